@@ -1601,6 +1601,14 @@ class LanceDataset(pa.dataset.Dataset):
             ds._ds = self._ds.checkout_version(version)
         return ds
 
+    def read_transaction(self) -> Optional[Transaction]:
+        """
+        Read the transaction that created the currently checked out version.
+
+        Returns None if no transaction file was written for this version.
+        """
+        return self._ds.read_transaction()
+
     def restore(self):
         """
         Restore the currently checked out version as the latest version of the dataset.
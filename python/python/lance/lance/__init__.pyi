@@ -257,6 +257,7 @@ class _Dataset:
     def version(self) -> int: ...
     def latest_version(self) -> int: ...
     def checkout_version(self, version: int | str) -> _Dataset: ...
+    def read_transaction(self) -> Optional[Transaction]: ...
     def restore(self): ...
     def cleanup_old_versions(
         self,
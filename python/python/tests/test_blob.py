@@ -216,3 +216,44 @@ def test_take_deleted_blob(tmp_path, dataset_with_blobs):
 def test_scan_blob(tmp_path, dataset_with_blobs):
     ds = dataset_with_blobs.scanner(filter="idx = 2").to_table()
     assert ds.num_rows == 1
+
+
+def test_blob_transaction_round_trip(tmp_path, dataset_with_blobs):
+    # The first version was written with a blob overwrite
+    first = dataset_with_blobs.checkout_version(1).read_transaction()
+    assert isinstance(first.blobs_op, lance.LanceOperation.Overwrite)
+
+    values = pa.array([b"grault", b"garply"], pa.large_binary())
+    idx = pa.array([6, 7], pa.uint64())
+    table = pa.table(
+        [values, idx],
+        schema=pa.schema(
+            [
+                pa.field(
+                    "blobs", pa.large_binary(), metadata={"lance-encoding:blob": "true"}
+                ),
+                pa.field("idx", pa.uint64()),
+            ]
+        ),
+    )
+    written = lance.fragment.write_fragments(
+        table, dataset_with_blobs, mode="append", return_transaction=True
+    )
+    assert isinstance(written.blobs_op, lance.LanceOperation.Append)
+
+    txn = lance.Transaction(
+        read_version=dataset_with_blobs.version,
+        operation=written.operation,
+        blobs_op=lance.LanceOperation.Append(written.blobs_op.fragments),
+    )
+    result = lance.LanceDataset.commit_batch(dataset_with_blobs, [txn])
+    ds = result["dataset"]
+    assert result["merged"].blobs_op == txn.blobs_op
+
+    read_back = ds.read_transaction()
+    assert read_back.operation == txn.operation
+    assert read_back.blobs_op == txn.blobs_op
+    assert ds.count_rows() == 8
+    blobs = ds.take_blobs("blobs", indices=[6, 7])
+    with blobs[0] as f:
+        assert f.read() == b"grault"
@@ -1325,6 +1325,22 @@ def test_restore_with_commit(tmp_path: Path):
     assert tbl == table
 
 
+def test_read_restore_transaction(tmp_path: Path):
+    table = pa.Table.from_pydict({"a": range(100), "b": range(100)})
+    base_dir = tmp_path / "test"
+
+    lance.write_dataset(table, base_dir)
+    dataset = lance.write_dataset(table, base_dir, mode="append")
+    dataset.checkout_version(1).restore()
+
+    dataset = lance.dataset(base_dir)
+    assert dataset.version == 3
+    transaction = dataset.read_transaction()
+    assert isinstance(transaction, lance.Transaction)
+    assert transaction.operation == lance.LanceOperation.Restore(1)
+    assert dataset.to_table() == table
+
+
 def test_merge_insert_with_commit():
     table = pa.table({"id": range(10), "updated": [False] * 10})
     dataset = lance.write_dataset(table, "memory://test")
@@ -1243,6 +1243,14 @@ impl Dataset {
         }
     }
 
+    /// Read the transaction that created the current version
+    fn read_transaction(self_: PyRef<'_, Self>) -> PyResult<Option<PyLance<Transaction>>> {
+        let transaction = RT
+            .block_on(Some(self_.py()), self_.ds.read_transaction())?
+            .map_err(|err| PyIOError::new_err(err.to_string()))?;
+        Ok(transaction.map(PyLance))
+    }
+
     /// Restore the current version
     fn restore(&mut self) -> PyResult<()> {
         let mut new_self = self.ds.as_ref().clone();
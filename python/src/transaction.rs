@@ -9,7 +9,7 @@ use lance::dataset::transaction::{
 use lance::datatypes::Schema;
 use lance_table::format::{DataFile, Fragment, Index};
 use pyo3::exceptions::PyValueError;
-use pyo3::types::PySet;
+use pyo3::types::{PyDict, PySet};
 use pyo3::{intern, prelude::*};
 use pyo3::{Bound, FromPyObject, PyAny, PyResult, Python};
 use uuid::Uuid;
@@ -241,6 +241,12 @@ impl<'py> IntoPyObject<'py> for PyLance<&Operation> {
                     .expect("Failed to get DataReplacement class");
                 cls.call1((replacements,))
             }
+            Operation::Restore { version } => {
+                let cls = namespace
+                    .getattr("Restore")
+                    .expect("Failed to get Restore class");
+                cls.call1((version,))
+            }
             _ => todo!(),
         }
     }
@@ -285,14 +291,17 @@ impl<'py> IntoPyObject<'py> for PyLance<&Transaction> {
             .map(|op| PyLance(op).into_pyobject(py))
             .transpose()?;
 
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("read_version", read_version)?;
+        kwargs.set_item("operation", operation)?;
+        kwargs.set_item("uuid", uuid)?;
+        kwargs.set_item("blobs_op", blobs_op)?;
+
         let cls = namespace
             .getattr("Transaction")
             .expect("Failed to get Transaction class");
         // Unwrap due to infallible
-        Ok(cls
-            .call1((read_version, operation, uuid, blobs_op))?
-            .into_pyobject(py)
-            .unwrap())
+        Ok(cls.call((), Some(&kwargs))?.into_pyobject(py).unwrap())
     }
 }
 
@@ -101,6 +101,7 @@ impl DataTypeExt for DataType {
                 | FixedSizeList(_, _)
                 | FixedSizeBinary(_)
                 | Duration(_)
+                | Interval(_)
                 | Timestamp(_, _)
                 | Date32
                 | Date64
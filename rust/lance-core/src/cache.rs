@@ -5,9 +5,10 @@
 
 use std::any::{Any, TypeId};
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::{
-    atomic::{AtomicU64, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, RwLock,
 };
 
 use futures::{Future, FutureExt};
@@ -46,9 +47,87 @@ impl SizedRecord {
     }
 }
 
+/// Entries that are kept outside of the LRU cache, see [`LanceCache::pin`]
+#[derive(Default)]
+struct PinnedEntries {
+    patterns: Vec<String>,
+    entries: HashMap<(String, TypeId), SizedRecord>,
+}
+
+impl PinnedEntries {
+    fn is_pinned(&self, key: &str) -> bool {
+        self.patterns.iter().any(|pattern| key.contains(pattern))
+    }
+
+    fn size_bytes(&self) -> usize {
+        self.entries
+            .values()
+            .map(|v| (v.size_accessor)(&v.record))
+            .sum()
+    }
+}
+
+/// A hard limit on the bytes held by one or more caches
+///
+/// See [`LanceCache::with_hard_limit`].  The limit can be shared by several caches
+/// so that they have one budget between them.
+#[derive(Debug)]
+pub struct CacheMemoryLimit {
+    limit_bytes: u64,
+    used_bytes: AtomicU64,
+}
+
+impl CacheMemoryLimit {
+    pub fn new(limit_bytes: usize) -> Arc<Self> {
+        Arc::new(Self {
+            limit_bytes: limit_bytes as u64,
+            used_bytes: AtomicU64::new(0),
+        })
+    }
+
+    pub fn limit_bytes(&self) -> usize {
+        self.limit_bytes as usize
+    }
+
+    /// The number of bytes currently held by the caches sharing this limit
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes.load(Ordering::Acquire) as usize
+    }
+
+    fn try_reserve(&self, num_bytes: u64) -> bool {
+        self.used_bytes
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(num_bytes)
+                    .filter(|total| *total <= self.limit_bytes)
+            })
+            .is_ok()
+    }
+
+    fn release(&self, num_bytes: u64) {
+        self.used_bytes.fetch_sub(num_bytes, Ordering::AcqRel);
+    }
+
+    fn out_of_memory(&self, key: &str, num_bytes: u64) -> crate::Error {
+        crate::Error::OutOfMemory {
+            message: format!(
+                "caching {} ({} bytes) would exceed the hard cache memory limit of {} bytes ({} bytes in use)",
+                key,
+                num_bytes,
+                self.limit_bytes,
+                self.used_bytes()
+            ),
+            location: location!(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct LanceCache {
     cache: Arc<Cache<(String, TypeId), SizedRecord>>,
+    pinned: Arc<RwLock<PinnedEntries>>,
+    // Set once the first pattern is pinned so lookups can skip the lock until then
+    has_pins: Arc<AtomicBool>,
+    hard_limit: Option<Arc<CacheMemoryLimit>>,
     prefix: String,
     hits: Arc<AtomicU64>,
     misses: Arc<AtomicU64>,
@@ -64,10 +143,12 @@ impl std::fmt::Debug for LanceCache {
 
 impl DeepSizeOf for LanceCache {
     fn deep_size_of_children(&self, _: &mut Context) -> usize {
-        self.cache
+        let cached = self
+            .cache
             .iter()
             .map(|(_, v)| (v.size_accessor)(&v.record))
-            .sum()
+            .sum::<usize>();
+        cached + self.pinned.read().unwrap().size_bytes()
     }
 }
 
@@ -82,6 +163,42 @@ impl LanceCache {
             .build();
         Self {
             cache: Arc::new(cache),
+            pinned: Arc::new(RwLock::new(PinnedEntries::default())),
+            has_pins: Arc::new(AtomicBool::new(false)),
+            hard_limit: None,
+            prefix: String::new(),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Create a cache that never evicts and instead errors when `limit` is exceeded
+    ///
+    /// Unlike [`Self::with_capacity`], which is a soft limit that evicts the least
+    /// recently used entries to make room, entries are only removed from this cache
+    /// when they are invalidated.  An insert that would take the bytes held by the
+    /// caches sharing `limit` over the limit is rejected with
+    /// [`crate::Error::OutOfMemory`] by the `try_insert_*` methods and by
+    /// `get_or_insert_with_key`.  The infallible `insert_*` methods skip caching
+    /// the entry instead.
+    ///
+    /// Pinned entries are kept outside of the cache and do not count towards the limit.
+    pub fn with_hard_limit(limit: Arc<CacheMemoryLimit>) -> Self {
+        let listener_limit = limit.clone();
+        let cache = Cache::builder()
+            .weigher(|_, v: &SizedRecord| {
+                (v.size_accessor)(&v.record).try_into().unwrap_or(u32::MAX)
+            })
+            .eviction_listener(move |_key, v: SizedRecord, _cause| {
+                listener_limit.release((v.size_accessor)(&v.record) as u64);
+            })
+            .support_invalidation_closures()
+            .build();
+        Self {
+            cache: Arc::new(cache),
+            pinned: Arc::new(RwLock::new(PinnedEntries::default())),
+            has_pins: Arc::new(AtomicBool::new(false)),
+            hard_limit: Some(limit),
             prefix: String::new(),
             hits: Arc::new(AtomicU64::new(0)),
             misses: Arc::new(AtomicU64::new(0)),
@@ -91,6 +208,9 @@ impl LanceCache {
     pub fn no_cache() -> Self {
         Self {
             cache: Arc::new(Cache::new(0)),
+            pinned: Arc::new(RwLock::new(PinnedEntries::default())),
+            has_pins: Arc::new(AtomicBool::new(false)),
+            hard_limit: None,
             prefix: String::new(),
             hits: Arc::new(AtomicU64::new(0)),
             misses: Arc::new(AtomicU64::new(0)),
@@ -107,6 +227,9 @@ impl LanceCache {
     pub fn with_key_prefix(&self, prefix: &str) -> Self {
         Self {
             cache: self.cache.clone(),
+            pinned: self.pinned.clone(),
+            has_pins: self.has_pins.clone(),
+            hard_limit: self.hard_limit.clone(),
             prefix: format!("{}{}/", self.prefix, prefix),
             hits: self.hits.clone(),
             misses: self.misses.clone(),
@@ -121,12 +244,59 @@ impl LanceCache {
         }
     }
 
+    /// Pin all entries whose key contains `pattern`, now and in the future
+    ///
+    /// Pinned entries are kept outside of the LRU cache and are never evicted.
+    /// They do not count towards the capacity of the cache, so they are kept
+    /// even if the cache is disabled.  Pins apply to the whole cache, not
+    /// just to keys under this cache's prefix.
+    ///
+    /// Pinned entries are still removed by [`Self::invalidate_prefix`] and
+    /// [`Self::clear`].
+    pub fn pin(&self, pattern: &str) {
+        let mut pinned = self.pinned.write().unwrap();
+        if pinned.patterns.iter().any(|p| p == pattern) {
+            return;
+        }
+        pinned.patterns.push(pattern.to_string());
+        self.has_pins.store(true, Ordering::Release);
+
+        // Move any entries that are already cached
+        let pattern = pattern.to_string();
+        for (key, value) in self.cache.iter() {
+            if key.0.contains(&pattern) {
+                pinned.entries.insert(key.as_ref().clone(), value);
+            }
+        }
+        self.cache
+            .invalidate_entries_if(move |(key, _typeid), _value| key.contains(&pattern))
+            .expect("Cache configured correctly");
+    }
+
+    fn is_pinned(&self, key: &str) -> bool {
+        // Patterns are never removed, so if nothing was ever pinned nothing is
+        self.has_pins.load(Ordering::Acquire) && self.pinned.read().unwrap().is_pinned(key)
+    }
+
+    fn get_pinned(&self, key: &(String, TypeId)) -> Option<SizedRecord> {
+        // Entries are only ever moved into `pinned` after a pattern is added
+        if !self.has_pins.load(Ordering::Acquire) {
+            return None;
+        }
+        self.pinned.read().unwrap().entries.get(key).cloned()
+    }
+
     /// Invalidate all entries in the cache that start with the given prefix
     ///
     /// The given prefix is appended to the existing prefix of the cache. If you
     /// want to invalidate all at the current prefix, pass an empty string.
     pub fn invalidate_prefix(&self, prefix: &str) {
         let full_prefix = format!("{}{}", self.prefix, prefix);
+        self.pinned
+            .write()
+            .unwrap()
+            .entries
+            .retain(|(key, _typeid), _value| !key.starts_with(&full_prefix));
         self.cache
             .invalidate_entries_if(move |(key, _typeid), _value| key.starts_with(&full_prefix))
             .expect("Cache configured correctly");
@@ -134,11 +304,11 @@ impl LanceCache {
 
     pub async fn size(&self) -> usize {
         self.cache.run_pending_tasks().await;
-        self.cache.entry_count() as usize
+        self.approx_size()
     }
 
     pub fn approx_size(&self) -> usize {
-        self.cache.entry_count() as usize
+        self.cache.entry_count() as usize + self.pinned.read().unwrap().entries.len()
     }
 
     pub async fn size_bytes(&self) -> usize {
@@ -147,10 +317,38 @@ impl LanceCache {
     }
 
     pub fn approx_size_bytes(&self) -> usize {
-        self.cache.weighted_size() as usize
+        self.cache.weighted_size() as usize + self.pinned.read().unwrap().size_bytes()
+    }
+
+    /// Reserve room for `record` in the hard limit, if there is one
+    async fn reserve(&self, key: &str, record: &SizedRecord) -> Result<()> {
+        let Some(limit) = &self.hard_limit else {
+            return Ok(());
+        };
+        let size = (record.size_accessor)(&record.record) as u64;
+        if limit.try_reserve(size) {
+            return Ok(());
+        }
+        // Invalidated entries are only released once pending tasks have run
+        self.cache.run_pending_tasks().await;
+        if limit.try_reserve(size) {
+            Ok(())
+        } else {
+            Err(limit.out_of_memory(key, size))
+        }
     }
 
     async fn insert<T: DeepSizeOf + Send + Sync + 'static>(&self, key: &str, metadata: Arc<T>) {
+        if let Err(err) = self.try_insert(key, metadata).await {
+            tracing::debug!(target: "lance_cache::insert", "skipped caching: {}", err);
+        }
+    }
+
+    async fn try_insert<T: DeepSizeOf + Send + Sync + 'static>(
+        &self,
+        key: &str,
+        metadata: Arc<T>,
+    ) -> Result<()> {
         let key = self.get_key(key);
         let record = SizedRecord::new(metadata);
         tracing::trace!(
@@ -159,7 +357,17 @@ impl LanceCache {
             type_id = std::any::type_name::<T>(),
             size = (record.size_accessor)(&record.record),
         );
+        if self.is_pinned(&key) {
+            self.pinned
+                .write()
+                .unwrap()
+                .entries
+                .insert((key, TypeId::of::<T>()), record);
+            return Ok(());
+        }
+        self.reserve(&key, &record).await?;
         self.cache.insert((key, TypeId::of::<T>()), record).await;
+        Ok(())
     }
 
     pub async fn insert_unsized<T: DeepSizeOf + Send + Sync + 'static + ?Sized>(
@@ -172,8 +380,12 @@ impl LanceCache {
     }
 
     async fn get<T: DeepSizeOf + Send + Sync + 'static>(&self, key: &str) -> Option<Arc<T>> {
-        let key = self.get_key(key);
-        if let Some(metadata) = self.cache.get(&(key, TypeId::of::<T>())).await {
+        let key = (self.get_key(key), TypeId::of::<T>());
+        let metadata = match self.get_pinned(&key) {
+            Some(metadata) => Some(metadata),
+            None => self.cache.get(&key).await,
+        };
+        if let Some(metadata) = metadata {
             self.hits.fetch_add(1, Ordering::Relaxed);
             Some(metadata.record.clone().downcast::<T>().unwrap())
         } else {
@@ -207,9 +419,28 @@ impl LanceCache {
         let full_key = self.get_key(&key);
         let cache_key = (full_key, TypeId::of::<T>());
 
+        if let Some(metadata) = self.get_pinned(&cache_key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(metadata.record.clone().downcast::<T>().unwrap());
+        }
+        if self.is_pinned(&cache_key.0) {
+            // Concurrent loads of the same pinned entry are not deduplicated
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            let record = SizedRecord::new(Arc::new(loader(&key).await?));
+            let value = record.record.clone().downcast::<T>().unwrap();
+            self.pinned
+                .write()
+                .unwrap()
+                .entries
+                .insert(cache_key, record);
+            return Ok(value);
+        }
+
         // Use optionally_get_with to handle concurrent requests
         let hits = self.hits.clone();
         let misses = self.misses.clone();
+        let hard_limit = self.hard_limit.clone();
+        let limit_key = cache_key.0.clone();
 
         // Use oneshot channels to track both errors and whether init was run
         let (error_tx, error_rx) = tokio::sync::oneshot::channel();
@@ -219,7 +450,17 @@ impl LanceCache {
             let _ = init_run_tx.send(());
             misses.fetch_add(1, Ordering::Relaxed);
             match loader(&key).await {
-                Ok(value) => Some(SizedRecord::new(Arc::new(value))),
+                Ok(value) => {
+                    let record = SizedRecord::new(Arc::new(value));
+                    if let Some(limit) = hard_limit {
+                        let size = (record.size_accessor)(&record.record) as u64;
+                        if !limit.try_reserve(size) {
+                            let _ = error_tx.send(limit.out_of_memory(&limit_key, size));
+                            return None;
+                        }
+                    }
+                    Some(record)
+                }
                 Err(e) => {
                     let _ = error_tx.send(e);
                     None
@@ -259,12 +500,13 @@ impl LanceCache {
         CacheStats {
             hits: self.hits.load(Ordering::Relaxed),
             misses: self.misses.load(Ordering::Relaxed),
-            num_entries: self.cache.entry_count() as usize,
-            size_bytes: self.cache.weighted_size() as usize,
+            num_entries: self.approx_size(),
+            size_bytes: self.approx_size_bytes(),
         }
     }
 
     pub async fn clear(&self) {
+        self.pinned.write().unwrap().entries.clear();
         self.cache.invalidate_all();
         self.cache.run_pending_tasks().await;
         self.hits.store(0, Ordering::Relaxed);
@@ -280,6 +522,20 @@ impl LanceCache {
         self.insert(&cache_key.key(), metadata).boxed().await
     }
 
+    /// Like [`Self::insert_with_key`] but fails if the entry would exceed the
+    /// hard limit of the cache, see [`Self::with_hard_limit`]
+    pub async fn try_insert_with_key<K>(
+        &self,
+        cache_key: &K,
+        metadata: Arc<K::ValueType>,
+    ) -> Result<()>
+    where
+        K: CacheKey,
+        K::ValueType: DeepSizeOf + Send + Sync + 'static,
+    {
+        self.try_insert(&cache_key.key(), metadata).boxed().await
+    }
+
     pub async fn get_with_key<K>(&self, cache_key: &K) -> Option<Arc<K::ValueType>>
     where
         K: CacheKey,
@@ -313,6 +569,23 @@ impl LanceCache {
             .await
     }
 
+    /// Like [`Self::insert_unsized_with_key`] but fails if the entry would exceed
+    /// the hard limit of the cache, see [`Self::with_hard_limit`]
+    pub async fn try_insert_unsized_with_key<K>(
+        &self,
+        cache_key: &K,
+        metadata: Arc<K::ValueType>,
+    ) -> Result<()>
+    where
+        K: UnsizedCacheKey,
+        K::ValueType: DeepSizeOf + Send + Sync + 'static,
+    {
+        // In order to make the data Sized, we wrap in another pointer.
+        self.try_insert(&cache_key.key(), Arc::new(metadata))
+            .boxed()
+            .await
+    }
+
     pub async fn get_unsized_with_key<K>(&self, cache_key: &K) -> Option<Arc<K::ValueType>>
     where
         K: UnsizedCacheKey,
@@ -342,9 +615,9 @@ pub struct CacheStats {
     pub hits: u64,
     /// Number of times `get`, `get_unsized`, or `get_or_insert` did not find an item in the cache.
     pub misses: u64,
-    /// Number of entries currently in the cache.
+    /// Number of entries currently in the cache, including pinned entries.
     pub num_entries: usize,
-    /// Total size in bytes of all entries in the cache.
+    /// Total size in bytes of all entries in the cache, including pinned entries.
     pub size_bytes: usize,
 }
 
@@ -399,6 +672,89 @@ mod tests {
         assert_eq!(cache.size().await, 10);
     }
 
+    #[tokio::test]
+    async fn test_cache_hard_limit() {
+        let item_size = Arc::new(vec![0, 0, 0]).deep_size_of();
+        let limit = CacheMemoryLimit::new(2 * item_size);
+        let cache = LanceCache::with_hard_limit(limit.clone());
+        // Caches sharing the limit share the budget
+        let other = LanceCache::with_hard_limit(limit.clone()).with_key_prefix("other");
+
+        cache
+            .try_insert("a", Arc::new(vec![1, 1, 1]))
+            .await
+            .unwrap();
+        other
+            .try_insert("b", Arc::new(vec![2, 2, 2]))
+            .await
+            .unwrap();
+        assert_eq!(limit.used_bytes(), 2 * item_size);
+
+        // Over budget inserts fail instead of evicting
+        let err = cache
+            .try_insert("c", Arc::new(vec![3, 3, 3]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::OutOfMemory { .. }), "{}", err);
+        let err = cache
+            .get_or_insert("c".to_string(), |_key| async { Ok(vec![3, 3, 3]) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::OutOfMemory { .. }), "{}", err);
+        cache.insert("c", Arc::new(vec![3, 3, 3])).await;
+        assert!(cache.get::<Vec<i32>>("c").await.is_none());
+        assert!(cache.get::<Vec<i32>>("a").await.is_some());
+        assert!(other.get::<Vec<i32>>("b").await.is_some());
+        assert_eq!(limit.used_bytes(), 2 * item_size);
+
+        // Invalidating entries frees room
+        cache.invalidate_prefix("a");
+        cache
+            .try_insert("c", Arc::new(vec![3, 3, 3]))
+            .await
+            .unwrap();
+        assert_eq!(limit.used_bytes(), 2 * item_size);
+        cache.clear().await;
+        assert_eq!(limit.used_bytes(), item_size);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_entries() {
+        let item_size = Arc::new(vec![0, 0, 0]).deep_size_of();
+        let capacity = 10 * item_size;
+        let cache = LanceCache::with_capacity(capacity);
+        let index_cache = cache.with_key_prefix("uri").with_key_prefix("pinned-uuid");
+
+        // Entries cached before pinning are moved out of the LRU cache
+        index_cache.insert("a", Arc::new(vec![1, 1, 1])).await;
+        cache.pin("pinned-uuid");
+        index_cache.insert("b", Arc::new(vec![2, 2, 2])).await;
+        let loaded: Arc<Vec<i32>> = index_cache
+            .get_or_insert("c".to_string(), |_key| async { Ok(vec![3, 3, 3]) })
+            .await
+            .unwrap();
+        assert_eq!(*loaded, vec![3, 3, 3]);
+
+        // Cache pressure evicts everything else
+        for i in 0..100 {
+            cache
+                .insert(&format!("key_{}", i), Arc::new(vec![i, i, i]))
+                .await;
+        }
+        assert_eq!(cache.size().await, 10 + 3);
+        assert_eq!(cache.size_bytes().await, capacity + 3 * item_size);
+
+        for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+            let retrieved = index_cache.get::<Vec<i32>>(key).await.unwrap();
+            assert_eq!(*retrieved, vec![value; 3]);
+        }
+
+        // Pinned entries can still be invalidated explicitly
+        cache.invalidate_prefix("uri/");
+        assert!(index_cache.get::<Vec<i32>>("a").await.is_none());
+        assert_eq!(cache.size().await, 10);
+    }
+
     #[tokio::test]
     async fn test_cache_trait_objects() {
         #[derive(Debug, DeepSizeOf)]
@@ -7,7 +7,7 @@ use std::fmt::{self, Debug, Formatter};
 use std::sync::{Arc, LazyLock};
 
 use arrow_array::ArrayRef;
-use arrow_schema::{DataType, Field as ArrowField, Fields, TimeUnit};
+use arrow_schema::{DataType, Field as ArrowField, Fields, IntervalUnit, TimeUnit};
 use deepsize::DeepSizeOf;
 use lance_arrow::bfloat16::{
     is_bfloat16_field, ARROW_EXT_META_KEY, ARROW_EXT_NAME_KEY, BFLOAT16_EXT_NAME,
@@ -76,6 +76,14 @@ impl LogicalType {
     fn is_struct(&self) -> bool {
         self.0 == "struct"
     }
+
+    fn is_map(&self) -> bool {
+        self.0 == "map" || self.0 == "map:sorted"
+    }
+
+    fn is_map_sorted(&self) -> bool {
+        self.0 == "map:sorted"
+    }
 }
 
 impl From<&str> for LogicalType {
@@ -142,6 +150,9 @@ impl TryFrom<&DataType> for LogicalType {
                     .unwrap_or("-".to_string())
             ),
             DataType::Duration(tu) => format!("duration:{}", timeunit_to_str(tu)),
+            DataType::Interval(IntervalUnit::YearMonth) => "interval:year_month".to_string(),
+            DataType::Interval(IntervalUnit::DayTime) => "interval:day_time".to_string(),
+            DataType::Interval(IntervalUnit::MonthDayNano) => "interval:month_day_nano".to_string(),
             DataType::Struct(_) => "struct".to_string(),
             DataType::Dictionary(key_type, value_type) => {
                 format!(
@@ -174,6 +185,13 @@ impl TryFrom<&DataType> for LogicalType {
                 }
             }
             DataType::FixedSizeBinary(len) => format!("fixed_size_binary:{}", *len),
+            DataType::Map(_, keys_sorted) => {
+                if *keys_sorted {
+                    "map:sorted".to_string()
+                } else {
+                    "map".to_string()
+                }
+            }
             _ => {
                 return Err(Error::Schema {
                     message: format!("Unsupported data type: {:?}", dt),
@@ -219,6 +237,9 @@ impl TryFrom<&LogicalType> for DataType {
             "duration:ms" => Some(Duration(TimeUnit::Millisecond)),
             "duration:us" => Some(Duration(TimeUnit::Microsecond)),
             "duration:ns" => Some(Duration(TimeUnit::Nanosecond)),
+            "interval:year_month" => Some(Interval(IntervalUnit::YearMonth)),
+            "interval:day_time" => Some(Interval(IntervalUnit::DayTime)),
+            "interval:month_day_nano" => Some(Interval(IntervalUnit::MonthDayNano)),
             _ => None,
         } {
             Ok(t)
@@ -329,18 +350,16 @@ impl TryFrom<&LogicalType> for DataType {
                     }
                 }
                 "timestamp" => {
-                    if splits.len() != 3 {
+                    if splits.len() < 3 {
                         Err(Error::Schema {
                             message: format!("Unsupported timestamp type: {}", lt),
                             location: location!(),
                         })
                     } else {
                         let timeunit = parse_timeunit(splits[1])?;
-                        let tz: Option<Arc<str>> = if splits[2] == "-" {
-                            None
-                        } else {
-                            Some(splits[2].into())
-                        };
+                        // Fixed offset timezones (e.g. "+05:30") contain a colon
+                        let tz = splits[2..].join(":");
+                        let tz: Option<Arc<str>> = if tz == "-" { None } else { Some(tz.into()) };
                         Ok(Timestamp(timeunit, tz))
                     }
                 }
@@ -164,6 +164,10 @@ impl Field {
             lt if lt.is_struct() => {
                 DataType::Struct(self.children.iter().map(ArrowField::from).collect())
             }
+            lt if lt.is_map() => DataType::Map(
+                Arc::new(ArrowField::from(&self.children[0])),
+                lt.is_map_sorted(),
+            ),
             lt => DataType::try_from(lt).unwrap(),
         }
     }
@@ -653,7 +657,8 @@ impl Field {
                 Ok(cloned)
             }
             (DataType::List(_), DataType::List(_))
-            | (DataType::LargeList(_), DataType::LargeList(_)) => {
+            | (DataType::LargeList(_), DataType::LargeList(_))
+            | (DataType::Map(_, _), DataType::Map(_, _)) => {
                 let projected =
                     self.children[0].project_by_field(&other.children[0], on_type_mismatch)?;
                 let mut cloned = self.clone();
@@ -827,7 +832,8 @@ impl Field {
                 }
             }
             (DataType::List(_), DataType::List(_))
-            | (DataType::LargeList(_), DataType::LargeList(_)) => {
+            | (DataType::LargeList(_), DataType::LargeList(_))
+            | (DataType::Map(_, _), DataType::Map(_, _)) => {
                 self.children[0].merge(&other.children[0])?;
             }
             (
@@ -974,6 +980,7 @@ impl TryFrom<&ArrowField> for Field {
                 .collect::<Result<_>>()?,
             DataType::List(item) => vec![Self::try_from(item.as_ref())?],
             DataType::LargeList(item) => vec![Self::try_from(item.as_ref())?],
+            DataType::Map(entries, _) => vec![Self::try_from(entries.as_ref())?],
             _ => vec![],
         };
         let storage_class = field
@@ -998,7 +1005,9 @@ impl TryFrom<&ArrowField> for Field {
                 dt if dt.is_binary_like() => Some(Encoding::VarBinary),
                 DataType::Dictionary(_, _) => Some(Encoding::Dictionary),
                 // Use plain encoder to store the offsets of list.
-                DataType::List(_) | DataType::LargeList(_) => Some(Encoding::Plain),
+                DataType::List(_) | DataType::LargeList(_) | DataType::Map(_, _) => {
+                    Some(Encoding::Plain)
+                }
                 _ => None,
             },
             metadata: field.metadata().clone(),
@@ -1041,7 +1050,7 @@ mod tests {
     use super::*;
 
     use arrow_array::{DictionaryArray, StringArray, UInt32Array};
-    use arrow_schema::{Fields, TimeUnit};
+    use arrow_schema::{Fields, IntervalUnit, TimeUnit};
 
     #[test]
     fn arrow_field_to_field() {
@@ -1077,6 +1086,10 @@ mod tests {
                 "timestamp:s:America/New_York",
                 DataType::Timestamp(TimeUnit::Second, Some("America/New_York".into())),
             ),
+            (
+                "timestamp:us:+05:30",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("+05:30".into())),
+            ),
             ("time32:s", DataType::Time32(TimeUnit::Second)),
             ("time32:ms", DataType::Time32(TimeUnit::Millisecond)),
             ("time64:us", DataType::Time64(TimeUnit::Microsecond)),
@@ -1085,6 +1098,18 @@ mod tests {
             ("duration:ms", DataType::Duration(TimeUnit::Millisecond)),
             ("duration:us", DataType::Duration(TimeUnit::Microsecond)),
             ("duration:ns", DataType::Duration(TimeUnit::Nanosecond)),
+            (
+                "interval:year_month",
+                DataType::Interval(IntervalUnit::YearMonth),
+            ),
+            (
+                "interval:day_time",
+                DataType::Interval(IntervalUnit::DayTime),
+            ),
+            (
+                "interval:month_day_nano",
+                DataType::Interval(IntervalUnit::MonthDayNano),
+            ),
             ("fixed_size_binary:100", DataType::FixedSizeBinary(100)),
             (
                 "fixed_size_list:int32:10",
@@ -1153,6 +1178,30 @@ mod tests {
         assert_eq!(ArrowField::from(&field), arrow_field);
     }
 
+    #[test]
+    fn map_field() {
+        let entries = ArrowField::new(
+            "entries",
+            DataType::Struct(Fields::from(vec![
+                ArrowField::new("keys", DataType::Utf8, false),
+                ArrowField::new("values", DataType::Int32, true),
+            ])),
+            false,
+        );
+        for keys_sorted in [false, true] {
+            let arrow_field = ArrowField::new(
+                "map",
+                DataType::Map(Arc::new(entries.clone()), keys_sorted),
+                true,
+            );
+            let field = Field::try_from(&arrow_field).unwrap();
+            assert_eq!(field.children.len(), 1);
+            assert_eq!(field.children[0].children.len(), 2);
+            assert_eq!(&field.data_type(), arrow_field.data_type());
+            assert_eq!(ArrowField::from(&field), arrow_field);
+        }
+    }
+
     #[test]
     fn test_project_by_field_null_type() {
         let f1: Field = ArrowField::new("a", DataType::Null, true)
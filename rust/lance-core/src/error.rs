@@ -111,6 +111,9 @@ pub enum Error {
         minor_version: u16,
         location: Location,
     },
+    /// A memory budget, such as a cache's hard limit, would be exceeded
+    #[snafu(display("Out of memory: {message}, {location}"))]
+    OutOfMemory { message: String, location: Location },
 }
 
 impl Error {
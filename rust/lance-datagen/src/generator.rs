@@ -1557,6 +1557,69 @@ impl ArrayGenerator for JitterCentroidsGenerator {
         Some(ByteCount::from(self.dimension as u64 * 4))
     }
 }
+
+/// Generates random float32 vectors with an L2 norm of 1
+#[derive(Debug)]
+struct RandomUnitVectorGenerator {
+    dimension: u32,
+    data_field: Arc<Field>,
+    data_type: DataType,
+}
+
+impl RandomUnitVectorGenerator {
+    fn new(dimension: u32) -> Self {
+        let data_field = Arc::new(Field::new("item", DataType::Float32, true));
+        let data_type = DataType::FixedSizeList(data_field.clone(), dimension as i32);
+        Self {
+            dimension,
+            data_field,
+            data_type,
+        }
+    }
+}
+
+impl ArrayGenerator for RandomUnitVectorGenerator {
+    fn generate(
+        &mut self,
+        length: RowCount,
+        rng: &mut rand_xoshiro::Xoshiro256PlusPlus,
+    ) -> Result<Arc<dyn Array>, ArrowError> {
+        let mut values_builder =
+            Float32Builder::with_capacity(length.0 as usize * self.dimension as usize);
+        let dist = Uniform::new(-1.0_f32, 1.0_f32);
+        for _ in 0..length.0 {
+            let mut vector = (0..self.dimension)
+                .map(|_| rng.sample(dist))
+                .collect::<Vec<_>>();
+            // A vector of all zeros can't be normalized, it is astronomically unlikely but fall
+            // back to a basis vector just in case
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                vector.iter_mut().for_each(|v| *v /= norm);
+            } else if let Some(first) = vector.first_mut() {
+                *first = 1.0;
+            }
+            values_builder.append_slice(&vector);
+        }
+        let values = values_builder.finish();
+        let vectors = FixedSizeListArray::try_new(
+            self.data_field.clone(),
+            self.dimension as i32,
+            Arc::new(values),
+            None,
+        )?;
+        Ok(Arc::new(vectors))
+    }
+
+    fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    fn element_size_bytes(&self) -> Option<ByteCount> {
+        Some(ByteCount::from(self.dimension as u64 * 4))
+    }
+}
+
 #[derive(Debug)]
 struct RandomStructGenerator {
     fields: Fields,
@@ -2105,6 +2168,13 @@ pub mod array {
         cycle_vec(underlying, dimension)
     }
 
+    /// Create a generator of random float32 vectors that are normalized to unit length
+    ///
+    /// This is useful for testing cosine and dot product distances, which expect normalized data
+    pub fn rand_unit_vec(dimension: Dimension) -> Box<dyn ArrayGenerator> {
+        Box::new(RandomUnitVectorGenerator::new(dimension.0))
+    }
+
     /// Create a generator of randomly sampled time32 values covering the entire
     /// range of 1 day
     pub fn rand_time32(resolution: &TimeUnit) -> Box<dyn ArrayGenerator> {
@@ -2778,6 +2848,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rand_unit_vec() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(DEFAULT_SEED.0);
+        let mut gen = array::rand_unit_vec(Dimension::from(8));
+        assert_eq!(
+            gen.data_type(),
+            &DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), 8)
+        );
+        let arr = gen.generate(RowCount::from(100), &mut rng).unwrap();
+        assert_eq!(arr.len(), 100);
+
+        let values = arr
+            .as_fixed_size_list()
+            .values()
+            .as_primitive::<Float32Type>()
+            .values()
+            .to_vec();
+        for vector in values.chunks(8) {
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 0.0001);
+        }
+    }
+
     #[test]
     fn test_jitter_centroids() {
         let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(DEFAULT_SEED.0);
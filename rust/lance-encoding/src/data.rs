@@ -22,7 +22,8 @@ use std::{
 use arrow::array::{ArrayData, ArrayDataBuilder, AsArray};
 use arrow_array::{new_empty_array, new_null_array, Array, ArrayRef, OffsetSizeTrait, UInt64Array};
 use arrow_buffer::{
-    ArrowNativeType, BooleanBuffer, BooleanBufferBuilder, NullBuffer, ScalarBuffer,
+    bit_chunk_iterator::UnalignedBitChunk, ArrowNativeType, BooleanBuffer, BooleanBufferBuilder,
+    NullBuffer, ScalarBuffer,
 };
 use arrow_schema::DataType;
 use lance_arrow::DataTypeExt;
@@ -111,10 +112,25 @@ pub struct NullableDataBlock {
 }
 
 impl NullableDataBlock {
+    /// The number of values that are null according to the validity bitmap
+    pub fn null_count(&self) -> u64 {
+        let num_values = self.data.num_values() as usize;
+        let num_valid = UnalignedBitChunk::new(&self.nulls, 0, num_values).count_ones();
+        (num_values - num_valid) as u64
+    }
+
     fn into_arrow(self, data_type: DataType, validate: bool) -> Result<ArrayData> {
         let nulls = self.nulls.into_buffer();
-        let data = self.data.into_arrow(data_type, validate)?.into_builder();
-        let data = data.null_bit_buffer(Some(nulls));
+        let data = self.data.into_arrow(data_type, validate)?;
+        // The inner data can have its own nulls (e.g. a nullable fixed size list inside a
+        // nullable wrapper), a value is only valid if it is valid in both
+        let nulls = match data.nulls() {
+            Some(inner_nulls) => {
+                (&BooleanBuffer::new(nulls, 0, data.len()) & inner_nulls.inner()).into_inner()
+            }
+            None => nulls,
+        };
+        let data = data.into_builder().null_bit_buffer(Some(nulls));
         if validate {
             Ok(data.build()?)
         } else {
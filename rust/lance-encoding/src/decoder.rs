@@ -225,7 +225,7 @@ use futures::stream::{self, BoxStream};
 use futures::{FutureExt, StreamExt};
 use lance_arrow::DataTypeExt;
 use lance_core::cache::LanceCache;
-use lance_core::datatypes::{Field, Schema, BLOB_DESC_LANCE_FIELD};
+use lance_core::datatypes::{Field, LogicalType, Schema, BLOB_DESC_LANCE_FIELD};
 use log::{debug, trace, warn};
 use snafu::location;
 use tokio::sync::mpsc::error::SendError;
@@ -242,13 +242,17 @@ use crate::encodings::logical::primitive::StructuralPrimitiveFieldScheduler;
 use crate::encodings::logical::r#struct::{StructuralStructDecoder, StructuralStructScheduler};
 use crate::format::pb::{self, column_encoding};
 use crate::repdef::{CompositeRepDefUnraveler, RepDefUnraveler};
+use crate::utils::coalesce::DEFAULT_COALESCE_GAP_BYTES;
 use crate::v2::decoder::LogicalPageDecoder;
 use crate::v2::encodings::logical::list::OffsetPageInfo;
-use crate::v2::encodings::logical::r#struct::{SimpleStructDecoder, SimpleStructScheduler};
+use crate::v2::encodings::logical::r#struct::{
+    SimpleStructDecoder, SimpleStructScheduler, StructValidityScheduler,
+};
 use crate::v2::encodings::logical::{
     binary::BinaryFieldScheduler, blob::BlobFieldScheduler, list::ListFieldScheduler,
     primitive::PrimitiveFieldScheduler,
 };
+use crate::v2::encodings::physical::dictionary::DictionaryDecodeMode;
 use crate::version::LanceFileVersion;
 use crate::{BufferScheduler, EncodingsIo};
 
@@ -474,6 +478,15 @@ pub struct CoreFieldDecoderStrategy {
     pub validate_data: bool,
     pub decompressor_strategy: Arc<dyn DecompressionStrategy>,
     pub cache_repetition_index: bool,
+    /// The maximum number of bytes of a single column that can be requested but not yet
+    /// received.  `None` means there is no limit.  This only applies to 2.0 files.
+    pub io_budget_bytes: Option<u64>,
+    /// How dictionary encoded pages of top-level string and binary columns are decoded.  This
+    /// only applies to 2.0 files.
+    pub dictionary_decode_mode: DictionaryDecodeMode,
+    /// The requests made while scheduling a page are coalesced if they are no more than this
+    /// many bytes apart.  `None` disables coalescing.  This only applies to 2.0 files.
+    pub coalesce_gap_bytes: Option<u64>,
 }
 
 impl Default for CoreFieldDecoderStrategy {
@@ -482,6 +495,9 @@ impl Default for CoreFieldDecoderStrategy {
             validate_data: false,
             decompressor_strategy: Arc::new(DefaultDecompressionStrategy {}),
             cache_repetition_index: false,
+            io_budget_bytes: None,
+            dictionary_decode_mode: DictionaryDecodeMode::default(),
+            coalesce_gap_bytes: Some(DEFAULT_COALESCE_GAP_BYTES),
         }
     }
 }
@@ -493,6 +509,33 @@ impl CoreFieldDecoderStrategy {
         self
     }
 
+    /// Limit the bytes of each column that can be in-flight at once, see [`Self::io_budget_bytes`]
+    pub fn with_io_budget_bytes(mut self, io_budget_bytes: Option<u64>) -> Self {
+        self.io_budget_bytes = io_budget_bytes;
+        self
+    }
+
+    /// Decode top-level string and binary columns as dictionaries, see [`Self::dictionary_decode_mode`]
+    pub fn with_dictionary_decode_mode(
+        mut self,
+        dictionary_decode_mode: DictionaryDecodeMode,
+    ) -> Self {
+        self.dictionary_decode_mode = dictionary_decode_mode;
+        self
+    }
+
+    /// Coalesce nearby requests when scheduling a page, see [`Self::coalesce_gap_bytes`]
+    pub fn with_coalesce_gap_bytes(mut self, coalesce_gap_bytes: Option<u64>) -> Self {
+        self.coalesce_gap_bytes = coalesce_gap_bytes;
+        self
+    }
+
+    /// True if `field` is decoded as a dictionary when the dictionary decode mode is
+    /// [`DictionaryDecodeMode::Indices`]
+    fn decodes_as_dictionary(field: &Field) -> bool {
+        field.parent_id == -1 && field.data_type().is_binary_like() && !field.is_blob()
+    }
+
     /// This is just a sanity check to ensure there is no "wrapped encodings"
     /// that haven't been handled.
     fn ensure_values_encoded(column_info: &ColumnInfo, field_name: &str) -> Result<()> {
@@ -544,13 +587,21 @@ impl CoreFieldDecoderStrategy {
             file_buffers: buffers,
             positions_and_sizes: &column.buffer_offsets_and_sizes,
         };
+        let dictionary_decode_mode = if Self::decodes_as_dictionary(field) {
+            self.dictionary_decode_mode
+        } else {
+            DictionaryDecodeMode::Values
+        };
         Ok(Box::new(PrimitiveFieldScheduler::new(
             column.index,
             field.data_type(),
             column.page_infos.clone(),
             column_buffers,
             self.validate_data,
-        )))
+            self.io_budget_bytes,
+            self.coalesce_gap_bytes,
+            dictionary_decode_mode,
+        )?))
     }
 
     /// Helper method to verify the page encoding of a struct header column
@@ -625,7 +676,10 @@ impl CoreFieldDecoderStrategy {
             Arc::from(inner_infos.into_boxed_slice()),
             offsets_column_buffers,
             self.validate_data,
-        )) as Arc<dyn crate::v2::decoder::FieldScheduler>;
+            self.io_budget_bytes,
+            self.coalesce_gap_bytes,
+            DictionaryDecodeMode::Values,
+        )?) as Arc<dyn crate::v2::decoder::FieldScheduler>;
         let items_field = match list_field.data_type() {
             DataType::List(inner) => inner,
             DataType::LargeList(inner) => inner,
@@ -714,7 +768,7 @@ impl CoreFieldDecoderStrategy {
                 column_infos.next_top_level();
                 Ok(scheduler)
             }
-            DataType::List(_) | DataType::LargeList(_) => {
+            DataType::List(_) | DataType::LargeList(_) | DataType::Map(_, _) => {
                 let child = field
                     .children
                     .first()
@@ -755,6 +809,18 @@ impl CoreFieldDecoderStrategy {
                         array_encoding: Some(pb::array_encoding::ArrayEncoding::List(..))
                     }
                 ) {
+                    if self.dictionary_decode_mode == DictionaryDecodeMode::Indices
+                        && Self::decodes_as_dictionary(field)
+                    {
+                        return Err(Error::NotSupported {
+                            source: format!(
+                                "Cannot decode the list encoded column {} as a dictionary",
+                                field.name
+                            )
+                            .into(),
+                            location: location!(),
+                        });
+                    }
                     let list_type = if matches!(data_type, DataType::Utf8 | DataType::Binary) {
                         DataType::List(Arc::new(ArrowField::new("item", DataType::UInt8, false)))
                     } else {
@@ -853,12 +919,27 @@ impl CoreFieldDecoderStrategy {
                         child_schedulers.push(Arc::from(field_scheduler));
                     }
 
+                    // The struct validity, if any, is stored in the (single) header page
+                    let header_page = &column_info.page_infos[0];
+                    let validity = match header_page.encoding.as_legacy().array_encoding.as_ref() {
+                        Some(pb::array_encoding::ArrayEncoding::Struct(simple_struct)) => {
+                            let page_buffers = PageBuffers {
+                                column_buffers: ColumnBuffers {
+                                    file_buffers: buffers,
+                                    positions_and_sizes: &column_info.buffer_offsets_and_sizes,
+                                },
+                                positions_and_sizes: &header_page.buffer_offsets_and_sizes,
+                            };
+                            StructValidityScheduler::from_encoding(simple_struct, &page_buffers)
+                        }
+                        _ => None,
+                    };
+
                     let fields = fields.clone();
-                    Ok(Box::new(SimpleStructScheduler::new(
-                        child_schedulers,
-                        fields,
-                        num_rows,
-                    )))
+                    Ok(Box::new(
+                        SimpleStructScheduler::new(child_schedulers, fields, num_rows)
+                            .with_validity(validity),
+                    ))
                 }
             }
             // TODO: Still need support for RLE
@@ -880,7 +961,7 @@ fn root_column(num_rows: u64) -> ColumnInfo {
             },
             encoding: PageEncoding::Legacy(pb::ArrayEncoding {
                 array_encoding: Some(pb::array_encoding::ArrayEncoding::Struct(
-                    pb::SimpleStruct {},
+                    pb::SimpleStruct::default(),
                 )),
             }),
             priority: 0, // not used in legacy scheduler
@@ -921,20 +1002,21 @@ impl RootDecoder {
 impl DecodeBatchScheduler {
     /// Creates a new decode scheduler with the expected schema and the column
     /// metadata of the file.
-    #[allow(clippy::too_many_arguments)]
+    ///
+    /// The I/O service, cache, and decode options are taken from `config`.  The
+    /// batch size and validation settings in `config` are not used by the scheduler.
     pub async fn try_new<'a>(
         schema: &'a Schema,
         column_indices: &[u32],
         column_infos: &[Arc<ColumnInfo>],
         file_buffer_positions_and_sizes: &'a Vec<(u64, u64)>,
         num_rows: u64,
-        _decoder_plugins: Arc<DecoderPlugins>,
-        io: Arc<dyn EncodingsIo>,
-        cache: Arc<LanceCache>,
         filter: &FilterExpression,
-        cache_repetition_index: bool,
+        config: &SchedulerDecoderConfig,
     ) -> Result<Self> {
         assert!(num_rows > 0);
+        let io = config.io.clone();
+        let cache = config.cache.clone();
         let buffers = FileBuffers {
             positions_and_sizes: file_buffer_positions_and_sizes,
         };
@@ -954,7 +1036,7 @@ impl DecodeBatchScheduler {
             let mut column_iter = ColumnInfoIter::new(column_infos.to_vec(), column_indices);
 
             let strategy = CoreFieldDecoderStrategy::default()
-                .with_cache_repetition_index(cache_repetition_index);
+                .with_cache_repetition_index(config.cache_repetition_index);
             let mut root_scheduler =
                 strategy.create_structural_field_scheduler(&root_field, &mut column_iter)?;
 
@@ -979,7 +1061,10 @@ impl DecodeBatchScheduler {
                 .collect::<Vec<_>>();
             let mut column_iter = ColumnInfoIter::new(columns, &adjusted_column_indices);
             let strategy = CoreFieldDecoderStrategy::default()
-                .with_cache_repetition_index(cache_repetition_index);
+                .with_cache_repetition_index(config.cache_repetition_index)
+                .with_io_budget_bytes(config.io_budget_bytes)
+                .with_dictionary_decode_mode(config.dictionary_decode_mode)
+                .with_coalesce_gap_bytes(config.coalesce_gap_bytes);
             let root_scheduler =
                 strategy.create_legacy_field_scheduler(&root_field, &mut column_iter, buffers)?;
 
@@ -1755,6 +1840,39 @@ pub struct SchedulerDecoderConfig {
     pub should_validate: bool,
     /// Whether to cache repetition indices for better performance
     pub cache_repetition_index: bool,
+    /// The maximum number of bytes of a single column that can be in-flight at once
+    pub io_budget_bytes: Option<u64>,
+    /// How dictionary encoded pages of top-level string and binary columns are decoded
+    ///
+    /// With [`DictionaryDecodeMode::Indices`] these columns are returned as
+    /// `Dictionary(UInt32, _)` arrays even though the schema asks for the value type.  This
+    /// is ignored for 2.1 files.
+    pub dictionary_decode_mode: DictionaryDecodeMode,
+    /// Requests made while scheduling a page of a 2.0 file are coalesced into a single request
+    /// if they are no more than this many bytes apart.  `None` disables coalescing.
+    pub coalesce_gap_bytes: Option<u64>,
+}
+
+// The schema of the decoded batches, top-level string and binary columns of 2.0 files
+// become dictionaries if they are decoded with `DictionaryDecodeMode::Indices`
+fn decoded_schema(
+    target_schema: &Schema,
+    is_structural: bool,
+    dictionary_decode_mode: DictionaryDecodeMode,
+) -> Result<Schema> {
+    let mut schema = target_schema.clone();
+    if is_structural || dictionary_decode_mode == DictionaryDecodeMode::Values {
+        return Ok(schema);
+    }
+    for field in schema.fields.iter_mut() {
+        if CoreFieldDecoderStrategy::decodes_as_dictionary(field) {
+            field.logical_type = LogicalType::try_from(&DataType::Dictionary(
+                Box::new(DataType::UInt32),
+                Box::new(field.data_type()),
+            ))?;
+        }
+    }
+    Ok(schema)
 }
 
 fn check_scheduler_on_drop(
@@ -1851,8 +1969,10 @@ fn create_scheduler_decoder(
 
     let (tx, rx) = mpsc::unbounded_channel();
 
+    let output_schema =
+        decoded_schema(&target_schema, is_structural, config.dictionary_decode_mode)?;
     let decode_stream = create_decode_stream(
-        &target_schema,
+        &output_schema,
         num_rows,
         config.batch_size,
         is_structural,
@@ -1867,11 +1987,8 @@ fn create_scheduler_decoder(
             &column_infos,
             &vec![],
             num_rows,
-            config.decoder_plugins,
-            config.io.clone(),
-            config.cache,
             &filter,
-            config.cache_repetition_index,
+            &config,
         )
         .await
         {
@@ -1978,11 +2095,8 @@ pub fn schedule_and_decode_blocking(
         &column_infos,
         &vec![],
         num_rows,
-        config.decoder_plugins,
-        config.io.clone(),
-        config.cache,
         &filter,
-        config.cache_repetition_index,
+        &config,
     ))?;
 
     // Schedule the requested rows
@@ -2005,8 +2119,10 @@ pub fn schedule_and_decode_blocking(
     {}
 
     // Create a decoder to decode the messages
+    let output_schema =
+        decoded_schema(&target_schema, is_structural, config.dictionary_decode_mode)?;
     let decode_iterator = create_decode_iterator(
-        &target_schema,
+        &output_schema,
         num_rows,
         config.batch_size,
         config.should_validate,
@@ -2523,17 +2639,25 @@ pub async fn decode_batch(
 
     let io_scheduler = Arc::new(BufferScheduler::new(batch.data.clone())) as Arc<dyn EncodingsIo>;
     let cache = cache.unwrap_or_else(|| Arc::new(LanceCache::with_capacity(128 * 1024 * 1024)));
+    let config = SchedulerDecoderConfig {
+        decoder_plugins,
+        batch_size: batch.num_rows as u32,
+        io: io_scheduler.clone(),
+        cache,
+        should_validate,
+        cache_repetition_index: false, // default to false for decode_batch
+        io_budget_bytes: None,
+        dictionary_decode_mode: DictionaryDecodeMode::default(),
+        coalesce_gap_bytes: Some(DEFAULT_COALESCE_GAP_BYTES),
+    };
     let mut decode_scheduler = DecodeBatchScheduler::try_new(
         batch.schema.as_ref(),
         &batch.top_level_columns,
         &batch.page_table,
         &vec![],
         batch.num_rows,
-        decoder_plugins,
-        io_scheduler.clone(),
-        cache,
         filter,
-        false, // cache_repetition_index - default to false for decode_batch
+        &config,
     )
     .await?;
     let (tx, rx) = unbounded_channel();
@@ -2575,4 +2699,73 @@ mod tests {
         let ranges = DecodeBatchScheduler::indices_to_ranges(&indices);
         assert_eq!(ranges, vec![1..4, 5..8, 9..10]);
     }
+
+    #[tokio::test]
+    async fn test_dictionary_decode_mode_indices() {
+        use arrow_array::{types::UInt32Type, Int32Array, StringArray};
+
+        use crate::encoder::{default_encoding_strategy, encode_batch, EncodingOptions};
+
+        // The first column is dictionary encoded on write (it has few distinct values) and
+        // the second is not
+        let num_rows = 1000;
+        let repeated = Arc::new(StringArray::from_iter_values(
+            (0..num_rows).map(|i| ["apple", "banana", "cherry"][i % 3]),
+        )) as ArrayRef;
+        let unique = Arc::new(StringArray::from_iter_values(
+            (0..num_rows).map(|i| format!("value-{}", i)),
+        )) as ArrayRef;
+        let ints = Arc::new(Int32Array::from_iter_values(0..num_rows as i32)) as ArrayRef;
+        let batch = RecordBatch::try_from_iter(vec![
+            ("repeated", repeated.clone()),
+            ("unique", unique.clone()),
+            ("ints", ints.clone()),
+        ])
+        .unwrap();
+        let schema = Arc::new(Schema::try_from(batch.schema().as_ref()).unwrap());
+        let encoded = encode_batch(
+            &batch,
+            schema.clone(),
+            default_encoding_strategy(LanceFileVersion::V2_0).as_ref(),
+            &EncodingOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let config = SchedulerDecoderConfig {
+            decoder_plugins: Arc::<DecoderPlugins>::default(),
+            batch_size: num_rows as u32,
+            io: Arc::new(BufferScheduler::new(encoded.data.clone())),
+            cache: Arc::new(LanceCache::with_capacity(128 * 1024 * 1024)),
+            should_validate: true,
+            cache_repetition_index: false,
+            io_budget_bytes: None,
+            dictionary_decode_mode: DictionaryDecodeMode::Indices,
+            coalesce_gap_bytes: Some(DEFAULT_COALESCE_GAP_BYTES),
+        };
+        let mut batches = schedule_and_decode(
+            encoded.page_table.clone(),
+            RequestedRows::Ranges(vec![0..num_rows as u64]),
+            FilterExpression::no_filter(),
+            encoded.top_level_columns.clone(),
+            schema,
+            config,
+        );
+        let decoded = batches.next().await.unwrap().task.await.unwrap();
+        assert!(batches.next().await.is_none());
+
+        let dict_type = DataType::Dictionary(Box::new(DataType::UInt32), Box::new(DataType::Utf8));
+        assert_eq!(decoded.schema().field(0).data_type(), &dict_type);
+        assert_eq!(decoded.schema().field(1).data_type(), &dict_type);
+        assert_eq!(decoded.schema().field(2).data_type(), &DataType::Int32);
+
+        // The dictionary of the encoded page is kept as-is
+        let decoded_repeated = decoded.column(0).as_dictionary::<UInt32Type>();
+        assert_eq!(decoded_repeated.values().len(), 3);
+        for (column, expected) in [(0, &repeated), (1, &unique)] {
+            let values = arrow_cast::cast(decoded.column(column), &DataType::Utf8).unwrap();
+            assert_eq!(&values, expected);
+        }
+        assert_eq!(decoded.column(2), &ints);
+    }
 }
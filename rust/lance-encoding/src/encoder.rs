@@ -353,7 +353,7 @@ impl StructuralEncodingStrategy {
             )?))
         } else {
             match data_type {
-                DataType::List(_) | DataType::LargeList(_) => {
+                DataType::List(_) | DataType::LargeList(_) | DataType::Map(_, _) => {
                     let child = field.children.first().expect("List should have a child");
                     let child_encoder = self.do_create_field_encoder(
                         _encoding_strategy_root,
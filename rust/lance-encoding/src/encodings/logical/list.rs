@@ -3,7 +3,7 @@
 
 use std::{ops::Range, sync::Arc};
 
-use arrow_array::{cast::AsArray, Array, ArrayRef, LargeListArray, ListArray};
+use arrow_array::{cast::AsArray, Array, ArrayRef, LargeListArray, ListArray, MapArray};
 use arrow_schema::DataType;
 use futures::future::BoxFuture;
 use lance_arrow::deepcopy::deep_copy_nulls;
@@ -27,6 +27,8 @@ use crate::{
 ///
 /// The values will have any garbage values removed and will be trimmed
 /// to only include the values that are actually used.
+///
+/// Maps are encoded as a list of their entries struct.
 pub struct ListStructuralEncoder {
     keep_original_array: bool,
     child: Box<dyn FieldEncoder>,
@@ -50,6 +52,19 @@ impl FieldEncoder for ListStructuralEncoder {
         row_number: u64,
         num_rows: u64,
     ) -> Result<Vec<EncodeTask>> {
+        let array = if let Some(map_arr) = array.as_map_opt() {
+            let DataType::Map(entries_field, _) = map_arr.data_type() else {
+                unreachable!()
+            };
+            Arc::new(ListArray::try_new(
+                entries_field.clone(),
+                map_arr.offsets().clone(),
+                Arc::new(map_arr.entries().clone()),
+                map_arr.nulls().cloned(),
+            )?)
+        } else {
+            array
+        };
         let values = if let Some(list_arr) = array.as_list_opt::<i32>() {
             let has_garbage_values = if self.keep_original_array {
                 repdef.add_offsets(list_arr.offsets().clone(), array.nulls().cloned())
@@ -217,6 +232,20 @@ impl StructuralDecodeArrayTask for StructuralListDecodeTask {
                     repdef,
                 })
             }
+            DataType::Map(entries_field, keys_sorted) => {
+                let (offsets, validity) = repdef.unravel_offsets::<i32>()?;
+                let map_array = MapArray::try_new(
+                    entries_field.clone(),
+                    offsets,
+                    array.as_struct().clone(),
+                    validity,
+                    *keys_sorted,
+                )?;
+                Ok(DecodedArray {
+                    array: Arc::new(map_array),
+                    repdef,
+                })
+            }
             _ => panic!("List decoder did not have a list field"),
         }
     }
@@ -227,7 +256,7 @@ mod tests {
 
     use std::{collections::HashMap, sync::Arc};
 
-    use arrow::array::{Int64Builder, LargeListBuilder, StringBuilder};
+    use arrow::array::{Int64Builder, LargeListBuilder, MapBuilder, StringBuilder};
     use arrow_array::{
         builder::{Int32Builder, ListBuilder},
         Array, ArrayRef, BooleanArray, DictionaryArray, LargeStringArray, ListArray, StructArray,
@@ -585,6 +614,41 @@ mod tests {
         .await;
     }
 
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_simple_map(
+        #[values(STRUCTURAL_ENCODING_MINIBLOCK, STRUCTURAL_ENCODING_FULLZIP)]
+        structural_encoding: &str,
+    ) {
+        let mut map_builder = MapBuilder::new(None, StringBuilder::new(), Int32Builder::new());
+        // {"a": 1, "b": null}, null, {}, {"c": 3}
+        map_builder.keys().append_value("a");
+        map_builder.values().append_value(1);
+        map_builder.keys().append_value("b");
+        map_builder.values().append_null();
+        map_builder.append(true).unwrap();
+        map_builder.append(false).unwrap();
+        map_builder.append(true).unwrap();
+        map_builder.keys().append_value("c");
+        map_builder.values().append_value(3);
+        map_builder.append(true).unwrap();
+        let map_array = map_builder.finish();
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            STRUCTURAL_ENCODING_META_KEY.to_string(),
+            structural_encoding.into(),
+        );
+
+        let test_cases = TestCases::default()
+            .with_range(0..4)
+            .with_range(1..3)
+            .with_indices(vec![0, 3])
+            .with_indices(vec![1])
+            .with_file_version(LanceFileVersion::V2_1);
+        check_round_trip_encoding_of_data(vec![Arc::new(map_array)], &test_cases, metadata).await;
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_simple_large_list() {
         let items_builder = Int32Builder::new();
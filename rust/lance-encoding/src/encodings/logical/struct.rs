@@ -4,6 +4,7 @@
 use std::{collections::BinaryHeap, ops::Range, sync::Arc};
 
 use crate::{
+    buffer::LanceBuffer,
     decoder::{
         DecodedArray, FilterExpression, LoadedPage, NextDecodeTask, PageEncoding,
         ScheduledScanLine, SchedulerContext, StructuralDecodeArrayTask, StructuralFieldDecoder,
@@ -13,7 +14,7 @@ use crate::{
     format::pb,
     repdef::RepDefBuilder,
 };
-use arrow_array::{cast::AsArray, Array, ArrayRef, StructArray};
+use arrow_array::{builder::BooleanBufferBuilder, cast::AsArray, Array, ArrayRef, StructArray};
 use arrow_schema::{DataType, Fields};
 use futures::{
     future::BoxFuture,
@@ -241,7 +242,9 @@ impl StructuralStructDecoder {
                     Box::new(Self::new(fields.clone(), should_validate, false))
                 }
             }
-            DataType::List(child_field) | DataType::LargeList(child_field) => {
+            DataType::List(child_field)
+            | DataType::LargeList(child_field)
+            | DataType::Map(child_field, _) => {
                 let child_decoder = Self::field_to_decoder(child_field, should_validate);
                 Box::new(StructuralListDecoder::new(
                     child_decoder,
@@ -250,7 +253,6 @@ impl StructuralStructDecoder {
             }
             DataType::RunEndEncoded(_, _) => todo!(),
             DataType::ListView(_) | DataType::LargeListView(_) => todo!(),
-            DataType::Map(_, _) => todo!(),
             DataType::Union(_, _) => todo!(),
             _ => Box::new(StructuralPrimitiveFieldDecoder::new(field, should_validate)),
         }
@@ -426,6 +428,8 @@ pub struct StructFieldEncoder {
     children: Vec<Box<dyn FieldEncoder>>,
     column_index: u32,
     num_rows_seen: u64,
+    // Only populated once the first null struct is seen
+    validity: Option<BooleanBufferBuilder>,
 }
 
 impl StructFieldEncoder {
@@ -435,6 +439,21 @@ impl StructFieldEncoder {
             children,
             column_index,
             num_rows_seen: 0,
+            validity: None,
+        }
+    }
+
+    fn record_validity(&mut self, struct_array: &StructArray) {
+        match (struct_array.nulls(), self.validity.as_mut()) {
+            (Some(nulls), Some(validity)) => validity.append_buffer(nulls.inner()),
+            (Some(nulls), None) => {
+                let mut validity = BooleanBufferBuilder::new(self.num_rows_seen as usize);
+                validity.append_n(self.num_rows_seen as usize, true);
+                validity.append_buffer(nulls.inner());
+                self.validity = Some(validity);
+            }
+            (None, Some(validity)) => validity.append_n(struct_array.len(), true),
+            (None, None) => {}
         }
     }
 }
@@ -448,8 +467,9 @@ impl FieldEncoder for StructFieldEncoder {
         row_number: u64,
         num_rows: u64,
     ) -> Result<Vec<EncodeTask>> {
-        self.num_rows_seen += array.len() as u64;
         let struct_array = array.as_struct();
+        self.record_validity(struct_array);
+        self.num_rows_seen += array.len() as u64;
         let child_tasks = self
             .children
             .iter_mut()
@@ -495,15 +515,26 @@ impl FieldEncoder for StructFieldEncoder {
             .collect::<FuturesOrdered<_>>();
         let num_rows_seen = self.num_rows_seen;
         let column_index = self.column_index;
+        // The validity, if any, is the only buffer in the header page
+        let (data, validity) = match self.validity.take() {
+            Some(mut validity) => (
+                vec![LanceBuffer::from(validity.finish().into_inner())],
+                Some(pb::Buffer {
+                    buffer_index: 0,
+                    buffer_type: pb::buffer::BufferType::Page as i32,
+                }),
+            ),
+            None => (Vec::new(), None),
+        };
         async move {
             let mut columns = Vec::new();
             // Add a column for the struct header
             let mut header = EncodedColumn::default();
             header.final_pages.push(EncodedPage {
-                data: Vec::new(),
+                data,
                 description: PageEncoding::Legacy(pb::ArrayEncoding {
                     array_encoding: Some(pb::array_encoding::ArrayEncoding::Struct(
-                        pb::SimpleStruct {},
+                        pb::SimpleStruct { validity },
                     )),
                 }),
                 num_rows: num_rows_seen,
@@ -600,6 +631,26 @@ mod tests {
         check_round_trip_encoding_of_data(vec![Arc::new(rows)], &test_cases, HashMap::new()).await;
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_nullable_struct_v2_0() {
+        // Rows 1 and 3 are null structs (with null children)
+        let fields = Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let a_vals = Int32Array::from(vec![Some(1), None, Some(3), None, Some(5)]);
+        let b_vals = Int32Array::from(vec![Some(6), None, None, None, Some(10)]);
+        let rows = StructArray::new(
+            fields,
+            vec![Arc::new(a_vals), Arc::new(b_vals)],
+            Some(NullBuffer::from(vec![true, false, true, false, true])),
+        );
+
+        let test_cases = TestCases::default().with_file_version(LanceFileVersion::V2_0);
+
+        check_round_trip_encoding_of_data(vec![Arc::new(rows)], &test_cases, HashMap::new()).await;
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_struct_list() {
         let data_type = DataType::Struct(Fields::from(vec![
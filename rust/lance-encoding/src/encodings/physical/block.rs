@@ -25,7 +25,9 @@ use arrow_buffer::ArrowNativeType;
 use lance_core::{Error, Result};
 use snafu::location;
 
-use std::io::Cursor;
+use bytes::Bytes;
+use std::io::{Cursor, Read};
+use std::ops::Range;
 use std::{io::Write, str::FromStr};
 use zstd::bulk::decompress_to_buffer;
 use zstd::stream::copy_decode;
@@ -88,7 +90,10 @@ impl FromStr for CompressionScheme {
             "zstd" => Ok(Self::Zstd),
             "lz4" => Ok(Self::Lz4),
             _ => Err(Error::invalid_input(
-                format!("Unknown compression scheme: {}", s),
+                format!(
+                    "Unknown compression scheme: {}, supported schemes are none, zstd and lz4",
+                    s
+                ),
                 location!(),
             )),
         }
@@ -148,6 +153,26 @@ impl ZstdBufferCompressor {
         }
     }
 
+    /// Decompress only the given (sorted, non-overlapping) byte ranges of the buffer
+    ///
+    /// The buffer is decompressed as a stream and bytes outside of the ranges are
+    /// discarded as they are decoded.  Unlike [`BufferCompressor::decompress`], the
+    /// full decompressed buffer is never held in memory.
+    pub fn decompress_ranges(
+        &self,
+        input_buf: &[u8],
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        const LENGTH_PREFIX_SIZE: usize = 8;
+        let compressed_data = if input_buf.is_empty() || self.is_raw_stream_format(input_buf) {
+            input_buf
+        } else {
+            &input_buf[LENGTH_PREFIX_SIZE..]
+        };
+        let decoder = zstd::stream::read::Decoder::with_buffer(compressed_data)?;
+        read_ranges(decoder, ranges)
+    }
+
     fn decompress_length_prefixed_zstd(
         &self,
         input_buf: &[u8],
@@ -168,6 +193,42 @@ impl ZstdBufferCompressor {
     }
 }
 
+// Read the given (sorted, non-overlapping) byte ranges from a stream, discarding the
+// bytes in between as they are read
+fn read_ranges(mut reader: impl Read, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+    let mut position = 0;
+    let mut decompressed = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        if range.start < position {
+            return Err(Error::Internal {
+                message: format!(
+                    "Cannot decompress the range {:?} after position {}, ranges must be sorted",
+                    range, position
+                ),
+                location: location!(),
+            });
+        }
+        let to_skip = (range.start - position) as u64;
+        let skipped = std::io::copy(&mut (&mut reader).take(to_skip), &mut std::io::sink())?;
+        if skipped != to_skip {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "The decompressed buffer is only {} bytes but the range {:?} was requested",
+                    position as u64 + skipped,
+                    range
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        let mut bytes = vec![0; range.end - range.start];
+        reader.read_exact(&mut bytes)?;
+        decompressed.push(Bytes::from(bytes));
+        position = range.end;
+    }
+    Ok(decompressed)
+}
+
 impl BufferCompressor for ZstdBufferCompressor {
     fn compress(&self, input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<()> {
         output_buf.write_all(&(input_buf.len() as u64).to_le_bytes())?;
@@ -299,6 +360,112 @@ impl GeneralBufferCompressor {
     }
 }
 
+/// One transform of a buffer, a layer of a [`BufferPipeline`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BufferLayer {
+    /// General purpose compression of the whole buffer
+    Compression(CompressionConfig),
+}
+
+impl BufferLayer {
+    /// Apply the transform, as the writer does
+    pub fn encode(&self, buffer: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Compression(config) => {
+                let mut output = Vec::new();
+                GeneralBufferCompressor::get_compressor(*config).compress(buffer, &mut output)?;
+                Ok(output)
+            }
+        }
+    }
+
+    /// Undo the transform
+    pub fn decode(&self, buffer: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Compression(config) => {
+                let mut output = Vec::new();
+                GeneralBufferCompressor::get_compressor(*config).decompress(buffer, &mut output)?;
+                Ok(output)
+            }
+        }
+    }
+}
+
+/// A buffer encoded with several layers of transforms, e.g. lz4 compressed and then zstd
+/// compressed
+///
+/// The layers are listed in the order they are undone when decoding, the same order as
+/// `Flat.layers` in the protobuf.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BufferPipeline {
+    layers: Vec<BufferLayer>,
+}
+
+impl BufferPipeline {
+    pub fn new(layers: Vec<BufferLayer>) -> Self {
+        Self { layers }
+    }
+
+    /// Parse the layers of a flat encoding
+    pub fn from_protobuf(layers: &[pb::BufferLayer]) -> Result<Self> {
+        let layers = layers
+            .iter()
+            .map(|layer| match layer.layer.as_ref() {
+                Some(pb::buffer_layer::Layer::Compression(compression)) => {
+                    let scheme = compression.scheme.parse::<CompressionScheme>()?;
+                    Ok(BufferLayer::Compression(CompressionConfig::new(
+                        scheme,
+                        compression.level,
+                    )))
+                }
+                None => Err(Error::InvalidInput {
+                    source: "A buffer layer has no transform".into(),
+                    location: location!(),
+                }),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { layers })
+    }
+
+    pub fn to_protobuf(&self) -> Vec<pb::BufferLayer> {
+        self.layers
+            .iter()
+            .map(|layer| pb::BufferLayer {
+                layer: Some(match layer {
+                    BufferLayer::Compression(config) => {
+                        pb::buffer_layer::Layer::Compression(pb::Compression {
+                            scheme: config.scheme.to_string(),
+                            level: config.level,
+                        })
+                    }
+                }),
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Apply the layers in reverse order, as the writer does
+    pub fn encode(&self, buffer: &[u8]) -> Result<Vec<u8>> {
+        let mut layers = self.layers.iter().rev();
+        let Some(first) = layers.next() else {
+            return Ok(buffer.to_vec());
+        };
+        layers.try_fold(first.encode(buffer)?, |buffer, layer| layer.encode(&buffer))
+    }
+
+    /// Undo the layers in order
+    pub fn decode(&self, buffer: &[u8]) -> Result<Vec<u8>> {
+        let mut layers = self.layers.iter();
+        let Some(first) = layers.next() else {
+            return Ok(buffer.to_vec());
+        };
+        layers.try_fold(first.decode(buffer)?, |buffer, layer| layer.decode(&buffer))
+    }
+}
+
 // An encoder which uses generic compression, such as zstd/lz4 to encode buffers
 #[derive(Debug)]
 pub struct CompressedBufferEncoder {
@@ -461,6 +628,90 @@ mod tests {
         );
     }
 
+    // Records the largest buffer that is read into at once
+    struct MaxReadTracker<R> {
+        inner: R,
+        max_read: usize,
+    }
+
+    impl<R: Read> Read for MaxReadTracker<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.max_read = self.max_read.max(buf.len());
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_zstd_decompress_ranges() {
+        // 4MiB of very compressible data
+        let data = (0..4 * 1024 * 1024)
+            .map(|i| (i / 4096) as u8)
+            .collect::<Vec<_>>();
+        let compressor = ZstdBufferCompressor::new(0);
+        let mut compressed = Vec::new();
+        compressor.compress(&data, &mut compressed).unwrap();
+        assert!(compressed.len() < data.len() / 100);
+
+        let ranges = [0..10, 5000..9000, (data.len() - 100)..data.len()];
+        let decompressed = compressor.decompress_ranges(&compressed, &ranges).unwrap();
+        for (range, bytes) in ranges.iter().zip(decompressed.iter()) {
+            assert_eq!(bytes.as_ref(), &data[range.clone()]);
+        }
+        // Only the requested bytes are kept
+        let total = decompressed.iter().map(|b| b.len()).sum::<usize>();
+        assert_eq!(total, 10 + 4000 + 100);
+
+        // The decompressed bytes are never read into a buffer much larger than a range
+        let mut tracker = MaxReadTracker {
+            inner: zstd::stream::read::Decoder::new(&compressed[8..]).unwrap(),
+            max_read: 0,
+        };
+        let decompressed = read_ranges(&mut tracker, &ranges).unwrap();
+        assert_eq!(decompressed[2].as_ref(), &data[ranges[2].clone()]);
+        assert!(tracker.max_read >= 4000);
+        assert!(tracker.max_read <= 64 * 1024, "{}", tracker.max_read);
+
+        // The raw stream format
+        let raw = zstd::encode_all(Cursor::new(&data[..100_000]), 0).unwrap();
+        let decompressed = compressor.decompress_ranges(&raw, &[100..200]).unwrap();
+        assert_eq!(decompressed[0].as_ref(), &data[100..200]);
+
+        assert!(compressor
+            .decompress_ranges(&raw, &[50..60, 0..10])
+            .is_err());
+        assert!(compressor.decompress_ranges(&raw, &[0..200_000]).is_err());
+    }
+
+    #[test]
+    fn test_buffer_pipeline() {
+        let values = (0..10_000_u32)
+            .flat_map(|v| (v % 100).to_le_bytes())
+            .collect::<Vec<_>>();
+        let pipeline = BufferPipeline::new(vec![
+            BufferLayer::Compression(CompressionConfig::new(CompressionScheme::Zstd, Some(3))),
+            BufferLayer::Compression(CompressionConfig::new(CompressionScheme::Lz4, None)),
+        ]);
+        let encoded = pipeline.encode(&values).unwrap();
+        assert!(encoded.len() < values.len());
+        assert_eq!(pipeline.decode(&encoded).unwrap(), values);
+        assert_eq!(
+            BufferPipeline::from_protobuf(&pipeline.to_protobuf()).unwrap(),
+            pipeline
+        );
+
+        // The layers are undone in order, the last one was applied first
+        let lz4 = BufferLayer::Compression(CompressionConfig::new(CompressionScheme::Lz4, None));
+        let zstd_only = BufferPipeline::new(vec![pipeline.layers[0]]);
+        assert_eq!(
+            lz4.decode(&zstd_only.decode(&encoded).unwrap()).unwrap(),
+            values
+        );
+
+        let empty = BufferPipeline::default();
+        assert_eq!(empty.encode(&values).unwrap(), values);
+        assert_eq!(empty.decode(&values).unwrap(), values);
+    }
+
     #[test]
     fn test_compression_scheme_from_str_invalid() {
         assert!(CompressionScheme::from_str("invalid").is_err());
@@ -739,10 +739,12 @@ pub(crate) mod tests {
     };
 
     use arrow_array::{
-        make_array, Array, ArrayRef, Decimal128Array, FixedSizeListArray, Int32Array,
+        make_array, types::IntervalMonthDayNano, Array, ArrayRef, Decimal128Array,
+        DurationMicrosecondArray, FixedSizeListArray, Int32Array, IntervalMonthDayNanoArray,
+        TimestampMicrosecondArray,
     };
     use arrow_buffer::{BooleanBuffer, NullBuffer};
-    use arrow_schema::{DataType, Field, TimeUnit};
+    use arrow_schema::{DataType, Field, IntervalUnit, TimeUnit};
     use lance_datagen::{array, gen, ArrayGeneratorExt, Dimension, RowCount};
     use rstest::rstest;
 
@@ -785,9 +787,10 @@ pub(crate) mod tests {
         DataType::Time32(TimeUnit::Second),
         DataType::Time64(TimeUnit::Nanosecond),
         DataType::Duration(TimeUnit::Second),
-        // The Interval type is supported by the reader but the writer works with Lance schema
-        // at the moment and Lance schema can't parse interval
-        // DataType::Interval(IntervalUnit::DayTime),
+        DataType::Duration(TimeUnit::Microsecond),
+        DataType::Interval(IntervalUnit::YearMonth),
+        DataType::Interval(IntervalUnit::DayTime),
+        DataType::Interval(IntervalUnit::MonthDayNano),
     ];
 
     #[test_log::test(tokio::test)]
@@ -825,6 +828,69 @@ pub(crate) mod tests {
         }
     }
 
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_timestamp_with_timezone(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+    ) {
+        // The physical decoders only see i64 values, the timezone must come from the schema
+        let timestamps = TimestampMicrosecondArray::from(vec![
+            Some(1_700_000_000_000_000),
+            None,
+            Some(1_700_000_000_000_001),
+            Some(0),
+        ])
+        .with_timezone("UTC");
+        let data_type = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+        assert_eq!(timestamps.data_type(), &data_type);
+
+        let test_cases = TestCases::default()
+            .with_range(0..2)
+            .with_range(1..4)
+            .with_indices(vec![0, 3])
+            .with_file_version(version);
+        check_round_trip_encoding_of_data(
+            vec![Arc::new(timestamps) as ArrayRef],
+            &test_cases,
+            HashMap::default(),
+        )
+        .await;
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_interval_and_duration(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+    ) {
+        // Month-day-nano intervals are 16 bytes wide, wider than any other fixed width primitive
+        let intervals = IntervalMonthDayNanoArray::from(vec![
+            Some(IntervalMonthDayNano::new(1, 2, 3)),
+            None,
+            Some(IntervalMonthDayNano::new(-1, 0, i64::MAX)),
+            Some(IntervalMonthDayNano::new(0, -30, 0)),
+        ]);
+        let durations =
+            DurationMicrosecondArray::from(vec![Some(1_000_000), Some(-5), None, Some(i64::MAX)]);
+
+        let test_cases = TestCases::default()
+            .with_range(0..2)
+            .with_range(1..4)
+            .with_indices(vec![0, 3])
+            .with_file_version(version);
+        check_round_trip_encoding_of_data(
+            vec![Arc::new(intervals) as ArrayRef],
+            &test_cases,
+            HashMap::default(),
+        )
+        .await;
+        check_round_trip_encoding_of_data(
+            vec![Arc::new(durations) as ArrayRef],
+            &test_cases,
+            HashMap::default(),
+        )
+        .await;
+    }
+
     static LARGE_TYPES: LazyLock<Vec<DataType>> = LazyLock::new(|| {
         vec![DataType::FixedSizeList(
             Arc::new(Field::new("", DataType::Int32, false)),
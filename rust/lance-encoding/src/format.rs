@@ -20,13 +20,16 @@ use pb::{
     full_zip_layout,
     nullable::{AllNull, NoNull, Nullability, SomeNull},
     page_layout::Layout,
-    AllNullLayout, ArrayEncoding, Binary, Bitpacked, BitpackedForNonNeg, Block, Dictionary,
-    FixedSizeBinary, FixedSizeList, Flat, Fsst, InlineBitpacking, MiniBlockLayout, Nullable,
-    OutOfLineBitpacking, PackedStruct, PackedStructFixedWidthMiniBlock, PageLayout, RepDefLayer,
-    Rle, Variable,
+    AdaptiveBitpacked, AllNullLayout, ArrayEncoding, Binary, Bitpacked, BitpackedForNonNeg, Block,
+    Delta, Dictionary, FixedSizeBinary, FixedSizeList, Flat, Fsst, InlineBitpacking,
+    MiniBlockLayout, Nullable, OutOfLineBitpacking, PackedStruct, PackedStructFixedWidthMiniBlock,
+    PageLayout, RepDefLayer, Rle, Variable,
 };
 
-use crate::{encodings::physical::block::CompressionConfig, repdef::DefinitionInterpretation};
+use crate::{
+    encodings::physical::block::{BufferPipeline, CompressionConfig},
+    repdef::DefinitionInterpretation,
+};
 
 use self::pb::Constant;
 
@@ -53,12 +56,14 @@ impl ProtobufUtils {
     pub fn basic_some_null_encoding(
         validity: ArrayEncoding,
         values: ArrayEncoding,
+        null_count: Option<u64>,
     ) -> ArrayEncoding {
         ArrayEncoding {
             array_encoding: Some(ArrayEncodingEnum::Nullable(Box::new(Nullable {
                 nullability: Some(Nullability::SomeNulls(Box::new(SomeNull {
                     validity: Some(Box::new(validity)),
                     values: Some(Box::new(values)),
+                    null_count,
                 }))),
             }))),
         }
@@ -98,16 +103,47 @@ impl ProtobufUtils {
                     scheme: compression_config.scheme.to_string(),
                     level: compression_config.level,
                 }),
+                big_endian: false,
+                layers: vec![],
             })),
         }
     }
 
+    /// A flat encoding of a buffer that was encoded with the layers of `pipeline`
+    pub fn flat_encoding_with_layers(
+        bits_per_value: u64,
+        buffer_index: u32,
+        pipeline: &BufferPipeline,
+    ) -> ArrayEncoding {
+        let mut encoding = Self::flat_encoding(bits_per_value, buffer_index, None);
+        if let Some(ArrayEncodingEnum::Flat(flat)) = encoding.array_encoding.as_mut() {
+            flat.layers = pipeline.to_protobuf();
+        }
+        encoding
+    }
+
     pub fn fsl_encoding(dimension: u64, items: ArrayEncoding, has_validity: bool) -> ArrayEncoding {
         ArrayEncoding {
             array_encoding: Some(ArrayEncodingEnum::FixedSizeList(Box::new(FixedSizeList {
                 dimension: dimension.try_into().unwrap(),
                 items: Some(Box::new(items)),
                 has_validity,
+                validity: None,
+            }))),
+        }
+    }
+
+    pub fn fsl_encoding_with_validity(
+        dimension: u64,
+        items: ArrayEncoding,
+        validity: ArrayEncoding,
+    ) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::FixedSizeList(Box::new(FixedSizeList {
+                dimension: dimension.try_into().unwrap(),
+                items: Some(Box::new(items)),
+                has_validity: false,
+                validity: Some(Box::new(validity)),
             }))),
         }
     }
@@ -131,6 +167,27 @@ impl ProtobufUtils {
         }
     }
 
+    pub fn adaptive_bitpacked(
+        uncompressed_bits_per_value: u64,
+        values_per_block: u64,
+        num_blocks: u64,
+        buffer_index: u32,
+        signed: bool,
+    ) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::AdaptiveBitpacked(AdaptiveBitpacked {
+                uncompressed_bits_per_value,
+                values_per_block,
+                num_blocks,
+                signed,
+                buffer: Some(pb::Buffer {
+                    buffer_index,
+                    buffer_type: BufferType::Page as i32,
+                }),
+            })),
+        }
+    }
+
     pub fn bitpacked_for_non_neg_encoding(
         compressed_bits_per_value: u64,
         uncompressed_bits_per_value: u64,
@@ -190,7 +247,41 @@ impl ProtobufUtils {
 
     pub fn rle(bits_per_value: u64) -> ArrayEncoding {
         ArrayEncoding {
-            array_encoding: Some(ArrayEncodingEnum::Rle(Rle { bits_per_value })),
+            array_encoding: Some(ArrayEncodingEnum::Rle(Rle {
+                bits_per_value,
+                values: None,
+                lengths: None,
+            })),
+        }
+    }
+
+    pub fn rle_with_buffers(
+        bits_per_value: u64,
+        values_buffer_index: u32,
+        lengths_buffer_index: u32,
+    ) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::Rle(Rle {
+                bits_per_value,
+                values: Some(pb::Buffer {
+                    buffer_index: values_buffer_index,
+                    buffer_type: BufferType::Page as i32,
+                }),
+                lengths: Some(pb::Buffer {
+                    buffer_index: lengths_buffer_index,
+                    buffer_type: BufferType::Page as i32,
+                }),
+            })),
+        }
+    }
+
+    pub fn delta(bits_per_value: u64, base: i64, deltas: ArrayEncoding) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::Delta(Box::new(Delta {
+                bits_per_value,
+                base,
+                deltas: Some(Box::new(deltas)),
+            }))),
         }
     }
 
@@ -282,6 +373,23 @@ impl ProtobufUtils {
                 FixedSizeBinary {
                     bytes: Some(Box::new(data)),
                     byte_width,
+                    validity: None,
+                },
+            ))),
+        }
+    }
+
+    pub fn fixed_size_binary_with_validity(
+        data: ArrayEncoding,
+        validity: ArrayEncoding,
+        byte_width: u32,
+    ) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::FixedSizeBinary(Box::new(
+                FixedSizeBinary {
+                    bytes: Some(Box::new(data)),
+                    byte_width,
+                    validity: Some(Box::new(validity)),
                 },
             ))),
         }
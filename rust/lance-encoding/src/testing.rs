@@ -18,14 +18,20 @@ use lance_datagen::{array, gen, ArrayGenerator, RowCount, Seed};
 use crate::{
     buffer::LanceBuffer,
     decoder::{
-        create_decode_stream, ColumnInfo, DecodeBatchScheduler, DecoderMessage, DecoderPlugins,
-        FilterExpression, PageInfo,
+        create_decode_stream, ColumnBuffers, ColumnInfo, DecodeBatchScheduler, DecoderMessage,
+        DecoderPlugins, FileBuffers, FilterExpression, PageBuffers, PageInfo, PageScheduler,
+        SchedulerDecoderConfig,
     },
     encoder::{
         default_encoding_strategy, ColumnIndexSequence, EncodedColumn, EncodedPage,
         EncodingOptions, FieldEncoder, OutOfLineBuffers, MIN_PAGE_BUFFER_ALIGNMENT,
     },
+    format::pb,
     repdef::RepDefBuilder,
+    utils::coalesce::DEFAULT_COALESCE_GAP_BYTES,
+    v2::encodings::physical::{
+        decoder_from_array_encoding_with_dictionary_mode, dictionary::DictionaryDecodeMode,
+    },
     version::LanceFileVersion,
     EncodingsIo,
 };
@@ -65,6 +71,59 @@ impl EncodingsIo for SimulatedScheduler {
     }
 }
 
+/// Create a scheduler for a page with the given `encoding` whose page buffers are
+/// `buffers`, along with an I/O that serves those buffers from memory
+pub(crate) fn schedule_single_page(
+    encoding: &pb::ArrayEncoding,
+    buffers: impl IntoIterator<Item = impl AsRef<[u8]>>,
+    data_type: &DataType,
+) -> Result<(Box<dyn PageScheduler>, Arc<dyn EncodingsIo>)> {
+    schedule_single_page_with_dictionary_mode(
+        encoding,
+        buffers,
+        data_type,
+        DictionaryDecodeMode::Values,
+    )
+}
+
+/// Like [`schedule_single_page`] but dictionary encoded pages are decoded according
+/// to `decode_mode`
+pub(crate) fn schedule_single_page_with_dictionary_mode(
+    encoding: &pb::ArrayEncoding,
+    buffers: impl IntoIterator<Item = impl AsRef<[u8]>>,
+    data_type: &DataType,
+    decode_mode: DictionaryDecodeMode,
+) -> Result<(Box<dyn PageScheduler>, Arc<dyn EncodingsIo>)> {
+    let mut data = BytesMut::new();
+    let mut positions_and_sizes = Vec::new();
+    for buffer in buffers {
+        let buffer = buffer.as_ref();
+        positions_and_sizes.push((data.len() as u64, buffer.len() as u64));
+        data.extend_from_slice(buffer);
+        data.extend(std::iter::repeat_n(
+            0,
+            pad_bytes::<TEST_ALIGNMENT>(data.len()),
+        ));
+    }
+    let page_buffers = PageBuffers {
+        column_buffers: ColumnBuffers {
+            file_buffers: FileBuffers {
+                positions_and_sizes: &[],
+            },
+            positions_and_sizes: &[],
+        },
+        positions_and_sizes: &positions_and_sizes,
+    };
+    let scheduler = decoder_from_array_encoding_with_dictionary_mode(
+        encoding,
+        &page_buffers,
+        data_type,
+        decode_mode,
+    )?;
+    let io = Arc::new(SimulatedScheduler::new(data.freeze())) as Arc<dyn EncodingsIo>;
+    Ok((scheduler, io))
+}
+
 fn column_indices_from_schema_helper(
     fields: &[FieldRef],
     column_indices: &mut Vec<u32>,
@@ -164,17 +223,25 @@ async fn test_decode(
     let lance_schema = lance_core::datatypes::Schema::try_from(schema).unwrap();
     let cache = Arc::new(LanceCache::with_capacity(128 * 1024 * 1024));
     let column_indices = column_indices_from_schema(schema, is_structural_encoding);
+    let config = SchedulerDecoderConfig {
+        decoder_plugins: Arc::<DecoderPlugins>::default(),
+        batch_size,
+        io,
+        cache,
+        should_validate: true,
+        cache_repetition_index: false, // default to false for tests
+        io_budget_bytes: None,
+        dictionary_decode_mode: DictionaryDecodeMode::default(),
+        coalesce_gap_bytes: Some(DEFAULT_COALESCE_GAP_BYTES),
+    };
     let decode_scheduler = DecodeBatchScheduler::try_new(
         &lance_schema,
         &column_indices,
         column_infos,
         &Vec::new(),
         num_rows,
-        Arc::<DecoderPlugins>::default(),
-        io,
-        cache,
         &FilterExpression::no_filter(),
-        false, // cache_repetition_index - default to false for tests
+        &config,
     )
     .await
     .unwrap();
@@ -5,3 +5,5 @@
 
 pub mod accumulation;
 pub mod bytepack;
+pub mod coalesce;
+pub mod io_budget;
@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Coalesce the small requests made while scheduling a page into fewer, larger requests.
+
+use std::{
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use futures::{
+    channel::oneshot,
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+use lance_core::{Error, Result};
+use snafu::location;
+
+use crate::EncodingsIo;
+
+/// The default coalescing gap
+///
+/// Reading a few KiB of unneeded bytes is much cheaper than an extra request to cloud storage.
+pub const DEFAULT_COALESCE_GAP_BYTES: u64 = 4 * 1024;
+
+// The data of the merged ranges, shared by all of the coalesced requests
+type SharedData = Shared<BoxFuture<'static, std::result::Result<Arc<Vec<Bytes>>, String>>>;
+
+// The merged data and, for each of a request's ranges, the index of the merged range that
+// holds it and the offset of the range within the merged range
+type Slices = (SharedData, Vec<(usize, Range<usize>)>);
+
+struct PendingRequest {
+    ranges: Vec<Range<u64>>,
+    tx: oneshot::Sender<Slices>,
+}
+
+impl std::fmt::Debug for PendingRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingRequest")
+            .field("ranges", &self.ranges)
+            .finish()
+    }
+}
+
+/// An I/O service that holds requests until [`CoalescingIo::flush`] and then submits them together
+///
+/// When flushed, the ranges of all held requests are sorted and ranges that are no more than
+/// `gap_bytes` apart are merged into a single range.  The merged ranges are submitted as one
+/// request (at the highest priority of the held requests) and each held request receives slices
+/// of the merged data.
+///
+/// Requests made after the flush are passed straight through.  Schedulers often make a second
+/// round of requests once the first round arrives and those must not wait for another flush.
+#[derive(Debug)]
+pub struct CoalescingIo {
+    inner: Arc<dyn EncodingsIo>,
+    gap_bytes: u64,
+    // None once flushed
+    pending: Mutex<Option<(Vec<PendingRequest>, u64)>>,
+}
+
+impl CoalescingIo {
+    /// Wrap `inner` so that requests no more than `gap_bytes` apart are coalesced
+    pub fn new(inner: Arc<dyn EncodingsIo>, gap_bytes: u64) -> Self {
+        Self {
+            inner,
+            gap_bytes,
+            pending: Mutex::new(Some((Vec::new(), u64::MAX))),
+        }
+    }
+
+    /// Submit all held requests, later requests are submitted immediately
+    pub fn flush(&self) {
+        let Some((pending, priority)) = self.pending.lock().unwrap().take() else {
+            return;
+        };
+        if pending.is_empty() {
+            return;
+        }
+
+        // (request index, range index, range) sorted by the start of the range
+        let mut all_ranges = pending
+            .iter()
+            .enumerate()
+            .flat_map(|(request_idx, request)| {
+                request
+                    .ranges
+                    .iter()
+                    .enumerate()
+                    .map(move |(range_idx, range)| (request_idx, range_idx, range.clone()))
+            })
+            .collect::<Vec<_>>();
+        all_ranges.sort_by_key(|(_, _, range)| range.start);
+
+        let mut merged: Vec<Range<u64>> = Vec::new();
+        // For each request, the index of the merged range that holds each of its ranges
+        let mut locations = pending
+            .iter()
+            .map(|request| vec![0; request.ranges.len()])
+            .collect::<Vec<_>>();
+        for (request_idx, range_idx, range) in all_ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end.saturating_add(self.gap_bytes) => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+            locations[request_idx][range_idx] = merged.len() - 1;
+        }
+
+        let data = self
+            .inner
+            .submit_request(merged.clone(), priority)
+            .map(|data| data.map(Arc::new).map_err(|err| err.to_string()))
+            .boxed()
+            .shared();
+        for (request, locations) in pending.into_iter().zip(locations) {
+            let slices = request
+                .ranges
+                .iter()
+                .zip(locations)
+                .map(|(range, merged_idx)| {
+                    let offset = merged[merged_idx].start;
+                    let slice = (range.start - offset) as usize..(range.end - offset) as usize;
+                    (merged_idx, slice)
+                })
+                .collect();
+            // The request may have been abandoned, that's fine
+            let _ = request.tx.send((data.clone(), slices));
+        }
+    }
+}
+
+impl EncodingsIo for CoalescingIo {
+    fn submit_request(
+        &self,
+        ranges: Vec<Range<u64>>,
+        priority: u64,
+    ) -> BoxFuture<'static, Result<Vec<Bytes>>> {
+        let mut pending = self.pending.lock().unwrap();
+        let Some((requests, min_priority)) = pending.as_mut() else {
+            return self.inner.submit_request(ranges, priority);
+        };
+        let (tx, rx) = oneshot::channel();
+        requests.push(PendingRequest { ranges, tx });
+        *min_priority = (*min_priority).min(priority);
+        async move {
+            let Ok((data, slices)) = rx.await else {
+                return Err(Error::Internal {
+                    message: "A coalesced I/O request was dropped before it was flushed".into(),
+                    location: location!(),
+                });
+            };
+            let data = data.await.map_err(|message| Error::IO {
+                source: message.into(),
+                location: location!(),
+            })?;
+            Ok(slices
+                .into_iter()
+                .map(|(merged_idx, slice)| data[merged_idx].slice(slice))
+                .collect())
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ops::Range,
+        sync::{Arc, Mutex},
+    };
+
+    use bytes::Bytes;
+    use futures::{future::BoxFuture, FutureExt};
+
+    use crate::EncodingsIo;
+
+    use super::CoalescingIo;
+
+    // Records the ranges of each request, the byte at each position is the position mod 256
+    #[derive(Debug, Default)]
+    struct TrackingIo {
+        requests: Mutex<Vec<Vec<Range<u64>>>>,
+    }
+
+    impl EncodingsIo for TrackingIo {
+        fn submit_request(
+            &self,
+            ranges: Vec<Range<u64>>,
+            _priority: u64,
+        ) -> BoxFuture<'static, lance_core::Result<Vec<Bytes>>> {
+            self.requests.lock().unwrap().push(ranges.clone());
+            let data = ranges
+                .into_iter()
+                .map(|r| Bytes::from(r.map(|i| i as u8).collect::<Vec<_>>()))
+                .collect();
+            std::future::ready(Ok(data)).boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalescing_io() {
+        let tracking = Arc::new(TrackingIo::default());
+        let io = CoalescingIo::new(tracking.clone(), 100);
+
+        let first = io.submit_request(vec![0..10, 500..510], 0);
+        let second = io.submit_request(vec![50..60], 1);
+        let third = io.submit_request(vec![700..720], 2);
+        assert!(tracking.requests.lock().unwrap().is_empty());
+        io.flush();
+        assert_eq!(
+            *tracking.requests.lock().unwrap(),
+            vec![vec![0..60, 500..510, 700..720]]
+        );
+
+        let expected = |range: Range<u64>| Bytes::from(range.map(|i| i as u8).collect::<Vec<_>>());
+        assert_eq!(
+            first.await.unwrap(),
+            vec![expected(0..10), expected(500..510)]
+        );
+        assert_eq!(second.await.unwrap(), vec![expected(50..60)]);
+        assert_eq!(third.await.unwrap(), vec![expected(700..720)]);
+
+        // Requests after the flush are submitted immediately
+        let fourth = io.submit_request(vec![60..70], 0);
+        assert_eq!(tracking.requests.lock().unwrap().len(), 2);
+        assert_eq!(fourth.await.unwrap(), vec![expected(60..70)]);
+    }
+}
@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! An I/O budget caps the number of bytes that can be in-flight at once.
+
+use std::{ops::Range, sync::Arc};
+
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt};
+use lance_core::Result;
+use tokio::sync::Semaphore;
+
+use crate::EncodingsIo;
+
+/// A budget of bytes that can be requested but not yet received
+///
+/// The budget is shared by all of the requests made through [`IoBudget::wrap`].  A request
+/// waits until enough of the budget is available before it is submitted and returns its
+/// share of the budget when the data arrives.  A single request larger than the budget
+/// waits for the entire budget.
+#[derive(Debug, Clone)]
+pub struct IoBudget {
+    budget_bytes: u32,
+    available: Arc<Semaphore>,
+}
+
+impl IoBudget {
+    /// Create a budget of `budget_bytes` (capped at `u32::MAX` bytes)
+    pub fn new(budget_bytes: u64) -> Self {
+        let budget_bytes = budget_bytes.clamp(1, u32::MAX as u64) as u32;
+        Self {
+            budget_bytes,
+            available: Arc::new(Semaphore::new(budget_bytes as usize)),
+        }
+    }
+
+    /// Wrap an I/O service so that requests made through it count against this budget
+    pub fn wrap(&self, io: Arc<dyn EncodingsIo>) -> Arc<dyn EncodingsIo> {
+        Arc::new(BudgetedIo {
+            inner: io,
+            budget: self.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct BudgetedIo {
+    inner: Arc<dyn EncodingsIo>,
+    budget: IoBudget,
+}
+
+impl EncodingsIo for BudgetedIo {
+    fn submit_request(
+        &self,
+        ranges: Vec<Range<u64>>,
+        priority: u64,
+    ) -> BoxFuture<'static, Result<Vec<Bytes>>> {
+        let num_bytes = ranges.iter().map(|r| r.end - r.start).sum::<u64>();
+        let permits = num_bytes.min(self.budget.budget_bytes as u64) as u32;
+        let available = self.budget.available.clone();
+        let inner = self.inner.clone();
+        async move {
+            // The semaphore is never closed
+            let _permit = available.acquire_many_owned(permits).await.unwrap();
+            inner.submit_request(ranges, priority).await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ops::Range,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+    };
+
+    use bytes::Bytes;
+    use futures::{future::BoxFuture, FutureExt};
+
+    use crate::EncodingsIo;
+
+    use super::IoBudget;
+
+    // Tracks the number of bytes in-flight, each request takes a few polls to complete
+    #[derive(Debug, Default)]
+    struct InFlightIo {
+        in_flight_bytes: Arc<AtomicU64>,
+        max_in_flight_bytes: Arc<AtomicU64>,
+    }
+
+    impl EncodingsIo for InFlightIo {
+        fn submit_request(
+            &self,
+            ranges: Vec<Range<u64>>,
+            _priority: u64,
+        ) -> BoxFuture<'static, lance_core::Result<Vec<Bytes>>> {
+            let num_bytes = ranges.iter().map(|r| r.end - r.start).sum::<u64>();
+            let in_flight_bytes = self.in_flight_bytes.clone();
+            let max_in_flight_bytes = self.max_in_flight_bytes.clone();
+            async move {
+                let in_flight = in_flight_bytes.fetch_add(num_bytes, Ordering::SeqCst) + num_bytes;
+                max_in_flight_bytes.fetch_max(in_flight, Ordering::SeqCst);
+                for _ in 0..5 {
+                    tokio::task::yield_now().await;
+                }
+                in_flight_bytes.fetch_sub(num_bytes, Ordering::SeqCst);
+                Ok(ranges
+                    .into_iter()
+                    .map(|r| Bytes::from(vec![0; (r.end - r.start) as usize]))
+                    .collect())
+            }
+            .boxed()
+        }
+    }
+
+    async fn max_in_flight_bytes(budget: Option<u64>) -> u64 {
+        let tracking = Arc::new(InFlightIo::default());
+        let io = tracking.clone() as Arc<dyn EncodingsIo>;
+        let io = match budget {
+            Some(budget) => IoBudget::new(budget).wrap(io),
+            None => io,
+        };
+        let requests = (0..10_u64)
+            .map(|i| io.submit_request(vec![i * 100..(i + 1) * 100], i))
+            .collect::<Vec<_>>();
+        for bytes in futures::future::try_join_all(requests).await.unwrap() {
+            assert_eq!(bytes[0].len(), 100);
+        }
+        tracking.max_in_flight_bytes.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_io_budget() {
+        assert_eq!(max_in_flight_bytes(None).await, 1000);
+        assert_eq!(max_in_flight_bytes(Some(250)).await, 200);
+        assert_eq!(max_in_flight_bytes(Some(100)).await, 100);
+
+        // A request larger than the budget still completes
+        let io = IoBudget::new(10).wrap(Arc::new(InFlightIo::default()));
+        let bytes = io.submit_request(vec![0..100], 0).await.unwrap();
+        assert_eq!(bytes[0].len(), 100);
+    }
+}
@@ -259,6 +259,14 @@ impl FieldEncodingStrategy for CoreFieldEncodingStrategy {
                         Err(Error::NotSupported { source: format!("cannot encode a dictionary column whose value type is a logical type ({})", value_type).into(), location: location!() })
                     }
                 }
+                DataType::Map(_, _) => Err(Error::NotSupported {
+                    source: format!(
+                        "cannot encode the map column {}, maps require file version 2.1 or later",
+                        field.name
+                    )
+                    .into(),
+                    location: location!(),
+                }),
                 _ => todo!("Implement encoding for field {}", field),
             }
         }
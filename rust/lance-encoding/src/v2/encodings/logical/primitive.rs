@@ -11,11 +11,16 @@ use futures::{future::BoxFuture, FutureExt};
 use log::trace;
 use snafu::location;
 
+use crate::data::DataBlock;
 use crate::decoder::{ColumnBuffers, PageBuffers};
 use crate::utils::accumulation::AccumulationQueue;
+use crate::utils::coalesce::CoalescingIo;
+use crate::utils::io_budget::IoBudget;
 use crate::v2::decoder::{FieldScheduler, LogicalPageDecoder, SchedulingJob};
 use crate::v2::encoder::ArrayEncodingStrategy;
-use crate::{data::DataBlock, v2::encodings::physical::decoder_from_array_encoding};
+use crate::v2::encodings::physical::{
+    decoder_from_array_encoding_with_dictionary_mode, dictionary::DictionaryDecodeMode,
+};
 use lance_core::{datatypes::Field, Error, Result};
 
 use crate::{
@@ -27,6 +32,7 @@ use crate::{
         EncodeTask, EncodedColumn, EncodedPage, EncodingOptions, FieldEncoder, OutOfLineBuffers,
     },
     repdef::RepDefBuilder,
+    EncodingsIo,
 };
 
 #[derive(Debug)]
@@ -48,20 +54,37 @@ struct PrimitivePage {
 #[derive(Debug)]
 pub struct PrimitiveFieldScheduler {
     data_type: DataType,
+    dictionary_value_type: Option<DataType>,
     page_schedulers: Vec<PrimitivePage>,
     num_rows: u64,
     should_validate: bool,
     column_index: u32,
+    io_budget: Option<IoBudget>,
+    coalesce_gap_bytes: Option<u64>,
 }
 
 impl PrimitiveFieldScheduler {
+    /// Create a scheduler for a single column
+    ///
+    /// If `io_budget_bytes` is set then no more than that many bytes of the column's pages
+    /// will be requested but not yet received at any one time.
+    ///
+    /// If `coalesce_gap_bytes` is set then the requests made while scheduling a page are
+    /// combined into a single request, merging ranges that are no more than that many bytes apart.
+    ///
+    /// If `dictionary_decode_mode` is [`DictionaryDecodeMode::Indices`] then `data_type` must
+    /// be a string or binary type and the column is decoded as `Dictionary(UInt32, data_type)`.
+    /// Pages that were not dictionary encoded are dictionary encoded after they are decoded.
     pub fn new(
         column_index: u32,
         data_type: DataType,
         pages: Arc<[PageInfo]>,
         buffers: ColumnBuffers,
         should_validate: bool,
-    ) -> Self {
+        io_budget_bytes: Option<u64>,
+        coalesce_gap_bytes: Option<u64>,
+        dictionary_decode_mode: DictionaryDecodeMode,
+    ) -> Result<Self> {
         let page_schedulers = pages
             .iter()
             .enumerate()
@@ -75,26 +98,37 @@ impl PrimitiveFieldScheduler {
                     column_buffers: buffers,
                     positions_and_sizes: &page.buffer_offsets_and_sizes,
                 };
-                let scheduler = decoder_from_array_encoding(
+                let scheduler = decoder_from_array_encoding_with_dictionary_mode(
                     page.encoding.as_legacy(),
                     &page_buffers,
                     &data_type,
-                );
-                PrimitivePage {
+                    dictionary_decode_mode,
+                )?;
+                Ok(PrimitivePage {
                     scheduler,
                     num_rows: page.num_rows,
                     page_index: page_index as u32,
-                }
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>>>()?;
         let num_rows = page_schedulers.iter().map(|p| p.num_rows).sum();
-        Self {
+        let (data_type, dictionary_value_type) = match dictionary_decode_mode {
+            DictionaryDecodeMode::Values => (data_type, None),
+            DictionaryDecodeMode::Indices => (
+                DataType::Dictionary(Box::new(DataType::UInt32), Box::new(data_type.clone())),
+                Some(data_type),
+            ),
+        };
+        Ok(Self {
             data_type,
+            dictionary_value_type,
             page_schedulers,
             num_rows,
             should_validate,
             column_index,
-        }
+            io_budget: io_budget_bytes.map(IoBudget::new),
+            coalesce_gap_bytes,
+        })
     }
 }
 
@@ -106,6 +140,8 @@ struct PrimitiveFieldSchedulingJob<'a> {
     range_idx: usize,
     range_offset: u64,
     global_row_offset: u64,
+    // The column's budgeted I/O, created on the first page and shared by the rest of the job
+    budgeted_io: Option<Arc<dyn EncodingsIo>>,
 }
 
 impl<'a> PrimitiveFieldSchedulingJob<'a> {
@@ -117,6 +153,7 @@ impl<'a> PrimitiveFieldSchedulingJob<'a> {
             range_idx: 0,
             range_offset: 0,
             global_row_offset: 0,
+            budgeted_io: None,
         }
     }
 }
@@ -183,14 +220,33 @@ impl SchedulingJob for PrimitiveFieldSchedulingJob<'_> {
         self.global_row_offset += cur_page.num_rows;
         self.page_idx += 1;
 
-        let physical_decoder = cur_page.scheduler.schedule_ranges(
-            &ranges_in_page,
-            context.io(),
-            priority.current_priority(),
-        );
+        let io = match &self.scheduler.io_budget {
+            Some(io_budget) => self
+                .budgeted_io
+                .get_or_insert_with(|| io_budget.wrap(context.io().clone()))
+                .clone(),
+            None => context.io().clone(),
+        };
+        let coalescing_io = self
+            .scheduler
+            .coalesce_gap_bytes
+            .map(|gap_bytes| Arc::new(CoalescingIo::new(io.clone(), gap_bytes)));
+        let io = match &coalescing_io {
+            Some(coalescing_io) => coalescing_io.clone() as Arc<dyn EncodingsIo>,
+            None => io,
+        };
+        let physical_decoder =
+            cur_page
+                .scheduler
+                .schedule_ranges(&ranges_in_page, &io, priority.current_priority());
+        // The page scheduler has made its first round of requests, send them as one
+        if let Some(coalescing_io) = coalescing_io {
+            coalescing_io.flush();
+        }
 
         let logical_decoder = PrimitiveFieldDecoder {
             data_type: self.scheduler.data_type.clone(),
+            dictionary_value_type: self.scheduler.dictionary_value_type.clone(),
             column_index: self.scheduler.column_index,
             unloaded_physical_decoder: Some(physical_decoder),
             physical_decoder: None,
@@ -243,6 +299,7 @@ impl FieldScheduler for PrimitiveFieldScheduler {
 
 pub struct PrimitiveFieldDecoder {
     data_type: DataType,
+    dictionary_value_type: Option<DataType>,
     unloaded_physical_decoder: Option<BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>>>,
     physical_decoder: Option<Arc<dyn PrimitivePageDecoder>>,
     should_validate: bool,
@@ -261,6 +318,7 @@ impl PrimitiveFieldDecoder {
     ) -> Self {
         Self {
             data_type,
+            dictionary_value_type: None,
             unloaded_physical_decoder: None,
             physical_decoder: Some(physical_decoder),
             should_validate,
@@ -288,6 +346,34 @@ struct PrimitiveFieldDecodeTask {
     should_validate: bool,
     physical_decoder: Arc<dyn PrimitivePageDecoder>,
     data_type: DataType,
+    // Set if the column is decoded with `DictionaryDecodeMode::Indices`
+    dictionary_value_type: Option<DataType>,
+}
+
+impl PrimitiveFieldDecodeTask {
+    // Pages that were dictionary encoded decode as `Dictionary(UInt8, _)` and other pages decode
+    // as plain values.  Either way we cast to the column's dictionary type.
+    fn decode_dictionary(
+        block: DataBlock,
+        value_type: &DataType,
+        data_type: &DataType,
+        should_validate: bool,
+    ) -> Result<ArrayRef> {
+        let is_dictionary = match &block {
+            DataBlock::Dictionary(_) => true,
+            DataBlock::Nullable(nullable) => {
+                matches!(nullable.data.as_ref(), DataBlock::Dictionary(_))
+            }
+            _ => false,
+        };
+        let block_type = if is_dictionary {
+            DataType::Dictionary(Box::new(DataType::UInt8), Box::new(value_type.clone()))
+        } else {
+            value_type.clone()
+        };
+        let array = make_array(block.into_arrow(block_type, should_validate)?);
+        Ok(arrow_cast::cast(&array, data_type)?)
+    }
 }
 
 impl DecodeArrayTask for PrimitiveFieldDecodeTask {
@@ -296,6 +382,15 @@ impl DecodeArrayTask for PrimitiveFieldDecodeTask {
             .physical_decoder
             .decode(self.rows_to_skip, self.rows_to_take)?;
 
+        if let Some(value_type) = &self.dictionary_value_type {
+            return Self::decode_dictionary(
+                block,
+                value_type,
+                &self.data_type,
+                self.should_validate,
+            );
+        }
+
         let array = make_array(block.into_arrow(self.data_type.clone(), self.should_validate)?);
 
         // This is a bit of a hack to work around https://github.com/apache/arrow-rs/issues/6302
@@ -361,6 +456,7 @@ impl LogicalPageDecoder for PrimitiveFieldDecoder {
             should_validate: self.should_validate,
             physical_decoder: self.physical_decoder.as_ref().unwrap().clone(),
             data_type: self.data_type.clone(),
+            dictionary_value_type: self.dictionary_value_type.clone(),
         });
 
         Ok(NextDecodeTask {
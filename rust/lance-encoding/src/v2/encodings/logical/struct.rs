@@ -8,13 +8,19 @@ use std::{
 };
 
 use crate::{
+    data::DataBlock,
     decoder::{
-        DecodeArrayTask, FilterExpression, MessageType, NextDecodeTask, PriorityRange,
-        ScheduledScanLine, SchedulerContext,
+        DecodeArrayTask, FilterExpression, MessageType, NextDecodeTask, PageBuffers, PageScheduler,
+        PrimitivePageDecoder, PriorityRange, ScheduledScanLine, SchedulerContext,
+    },
+    format::pb,
+    v2::{
+        decoder::{DecoderReady, FieldScheduler, LogicalPageDecoder, SchedulingJob},
+        encodings::physical::{bitmap::DenseBitmapScheduler, get_buffer},
     },
-    v2::decoder::{DecoderReady, FieldScheduler, LogicalPageDecoder, SchedulingJob},
 };
-use arrow_array::{ArrayRef, StructArray};
+use arrow_array::{ArrayRef, BooleanArray, StructArray};
+use arrow_buffer::NullBuffer;
 use arrow_schema::{DataType, Field, Fields};
 use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt, TryStreamExt};
 use lance_core::{Error, Result};
@@ -146,6 +152,7 @@ struct SimpleStructSchedulerJob<'a> {
     scheduler: &'a SimpleStructScheduler,
     /// A min-heap whose key is the # of rows currently scheduled
     children: BinaryHeap<SchedulingJobWithStatus<'a>>,
+    ranges: Vec<Range<u64>>,
     rows_scheduled: u64,
     num_rows: u64,
     initialized: bool,
@@ -155,6 +162,7 @@ impl<'a> SimpleStructSchedulerJob<'a> {
     fn new(
         scheduler: &'a SimpleStructScheduler,
         children: Vec<Box<dyn SchedulingJob + 'a>>,
+        ranges: Vec<Range<u64>>,
         num_rows: u64,
     ) -> Self {
         let children = children
@@ -171,6 +179,7 @@ impl<'a> SimpleStructSchedulerJob<'a> {
         Self {
             scheduler,
             children,
+            ranges,
             rows_scheduled: 0,
             num_rows,
             initialized: false,
@@ -186,12 +195,18 @@ impl SchedulingJob for SimpleStructSchedulerJob<'_> {
     ) -> Result<ScheduledScanLine> {
         let mut decoders = Vec::new();
         if !self.initialized {
-            // Send info to the decoder thread so it knows a struct is here.  In the future we will also
-            // send validity info here.
-            let struct_decoder = Box::new(SimpleStructDecoder::new(
-                self.scheduler.child_fields.clone(),
-                self.num_rows,
-            ));
+            // Send info to the decoder thread so it knows a struct is here, along with the
+            // struct's validity if it is nullable
+            let mut struct_decoder =
+                SimpleStructDecoder::new(self.scheduler.child_fields.clone(), self.num_rows);
+            if let Some(validity) = &self.scheduler.validity {
+                struct_decoder = struct_decoder.with_validity(validity.bitmap.schedule_ranges(
+                    &self.ranges,
+                    context.io(),
+                    priority.current_priority(),
+                ));
+            }
+            let struct_decoder = Box::new(struct_decoder);
             #[allow(deprecated)]
             let struct_decoder = context.locate_decoder(struct_decoder);
             decoders.push(MessageType::DecoderReady(struct_decoder));
@@ -244,6 +259,7 @@ pub struct SimpleStructScheduler {
     children: Vec<Arc<dyn FieldScheduler>>,
     child_fields: Fields,
     num_rows: u64,
+    validity: Option<StructValidityScheduler>,
 }
 
 impl SimpleStructScheduler {
@@ -261,8 +277,35 @@ impl SimpleStructScheduler {
             children,
             child_fields,
             num_rows,
+            validity: None,
         }
     }
+
+    /// Use the given validity for the struct, by default the struct is never null
+    pub fn with_validity(mut self, validity: Option<StructValidityScheduler>) -> Self {
+        self.validity = validity;
+        self
+    }
+}
+
+/// A scheduler for the validity of a nullable struct
+///
+/// The validity is stored as a bitmap in the struct header column.
+#[derive(Debug)]
+pub struct StructValidityScheduler {
+    bitmap: Box<dyn PageScheduler>,
+}
+
+impl StructValidityScheduler {
+    /// Create a scheduler for the validity described by the header page's encoding
+    ///
+    /// Returns `None` if the struct has no nulls
+    pub fn from_encoding(encoding: &pb::SimpleStruct, buffers: &PageBuffers) -> Option<Self> {
+        let (buffer_offset, _) = get_buffer(encoding.validity.as_ref()?, buffers);
+        Some(Self {
+            bitmap: Box::new(DenseBitmapScheduler::new(buffer_offset)),
+        })
+    }
 }
 
 impl FieldScheduler for SimpleStructScheduler {
@@ -285,6 +328,7 @@ impl FieldScheduler for SimpleStructScheduler {
         Ok(Box::new(SimpleStructSchedulerJob::new(
             self,
             child_schedulers,
+            ranges.to_vec(),
             num_rows,
         )))
     }
@@ -462,12 +506,27 @@ impl PartialOrd for WaitOrder<'_> {
     }
 }
 
+// The validity bitmap of a nullable struct
+struct ValidityState {
+    unloaded: Option<BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>>>,
+    decoder: Option<Box<dyn PrimitivePageDecoder>>,
+}
+
+impl std::fmt::Debug for ValidityState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidityState")
+            .field("loaded", &self.decoder.is_some())
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct SimpleStructDecoder {
     children: Vec<ChildState>,
     child_fields: Fields,
     data_type: DataType,
     num_rows: u64,
+    validity: Option<ValidityState>,
 }
 
 impl SimpleStructDecoder {
@@ -482,10 +541,28 @@ impl SimpleStructDecoder {
             child_fields,
             data_type,
             num_rows,
+            validity: None,
         }
     }
 
+    /// Decode the struct validity from the given (scheduled) bitmap decoder
+    fn with_validity(
+        mut self,
+        validity: BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>>,
+    ) -> Self {
+        self.validity = Some(ValidityState {
+            unloaded: Some(validity),
+            decoder: None,
+        });
+        self
+    }
+
     async fn do_wait_for_loaded(&mut self, loaded_need: u64) -> Result<()> {
+        if let Some(validity) = self.validity.as_mut() {
+            if let Some(unloaded) = validity.unloaded.take() {
+                validity.decoder = Some(unloaded.await?);
+            }
+        }
         let mut wait_orders = self
             .children
             .iter_mut()
@@ -542,6 +619,16 @@ impl LogicalPageDecoder for SimpleStructDecoder {
     }
 
     fn drain(&mut self, num_rows: u64) -> Result<NextDecodeTask> {
+        let validity = match self.validity.as_ref() {
+            Some(validity) => {
+                let decoder = validity.decoder.as_ref().ok_or_else(|| Error::Internal {
+                    message: "Struct validity drained before it was loaded".to_string(),
+                    location: location!(),
+                })?;
+                Some(decoder.decode(self.rows_drained(), num_rows)?)
+            }
+            None => None,
+        };
         let child_tasks = self
             .children
             .iter_mut()
@@ -553,6 +640,7 @@ impl LogicalPageDecoder for SimpleStructDecoder {
             task: Box::new(SimpleStructDecodeTask {
                 children: child_tasks,
                 child_fields: self.child_fields.clone(),
+                validity,
             }),
             num_rows,
         })
@@ -608,6 +696,7 @@ impl CompositeDecodeTask {
 struct SimpleStructDecodeTask {
     children: Vec<CompositeDecodeTask>,
     child_fields: Fields,
+    validity: Option<DataBlock>,
 }
 
 impl DecodeArrayTask for SimpleStructDecodeTask {
@@ -617,10 +706,17 @@ impl DecodeArrayTask for SimpleStructDecodeTask {
             .into_iter()
             .map(|child| child.decode())
             .collect::<Result<Vec<_>>>()?;
+        let nulls = self
+            .validity
+            .map(|validity| -> Result<NullBuffer> {
+                let validity = BooleanArray::from(validity.into_arrow(DataType::Boolean, false)?);
+                Ok(NullBuffer::new(validity.values().clone()))
+            })
+            .transpose()?;
         Ok(Arc::new(StructArray::try_new(
             self.child_fields,
             child_arrays,
-            None,
+            nulls,
         )?))
     }
 }
@@ -1,37 +1,57 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
+use std::ops::Range;
+
 use arrow_schema::DataType;
+use bytes::Bytes;
 use lance_arrow::DataTypeExt;
+use lance_core::{Error, Result};
+use object_store::{path::Path, ObjectStore};
+use snafu::location;
 
 use crate::{
     buffer::LanceBuffer,
-    decoder::{PageBuffers, PageScheduler},
-    encodings::physical::block::{CompressionConfig, CompressionScheme},
+    decoder::{ColumnInfo, PageBuffers, PageScheduler},
+    encodings::physical::block::{BufferPipeline, CompressionConfig, CompressionScheme},
     format::pb::{self, PackedStruct},
     v2::encodings::physical::{
-        basic::BasicPageScheduler, binary::BinaryPageScheduler, bitmap::DenseBitmapScheduler,
-        dictionary::DictionaryPageScheduler, fixed_size_list::FixedListScheduler,
-        fsst::FsstPageScheduler, packed_struct::PackedStructPageScheduler,
+        adaptive_bitpack::AdaptiveBitpackedScheduler,
+        basic::BasicPageScheduler,
+        binary::BinaryPageScheduler,
+        bitmap::{DenseBitmapScheduler, SubBytePackedScheduler},
+        constant::{check_constant_value, ConstantScheduler},
+        delta::DeltaScheduler,
+        dictionary::{DictionaryDecodeMode, DictionaryPageScheduler},
+        fixed_size_list::FixedListScheduler,
+        fsst::FsstPageScheduler,
+        packed_struct::PackedStructPageScheduler,
+        rle::{RleBitmapScheduler, RleScheduler},
         value::ValuePageScheduler,
     },
 };
 
+pub mod adaptive_bitpack;
 pub mod basic;
 pub mod binary;
 pub mod bitmap;
 pub mod bitpack;
 pub mod block;
+pub mod constant;
+pub mod delta;
 pub mod dictionary;
 pub mod fixed_size_binary;
 pub mod fixed_size_list;
 pub mod fsst;
+#[cfg(test)]
+mod fuzz_test;
 pub mod packed_struct;
+pub mod rle;
 pub mod value;
 
 // Translate a protobuf buffer description into a position in the file.  This could be a page
 // buffer, a column buffer, or a file buffer.
-fn get_buffer(buffer_desc: &pb::Buffer, buffers: &PageBuffers) -> (u64, u64) {
+pub(crate) fn get_buffer(buffer_desc: &pb::Buffer, buffers: &PageBuffers) -> (u64, u64) {
     let index = buffer_desc.buffer_index as usize;
 
     match pb::buffer::BufferType::try_from(buffer_desc.buffer_type).unwrap() {
@@ -43,49 +63,189 @@ fn get_buffer(buffer_desc: &pb::Buffer, buffers: &PageBuffers) -> (u64, u64) {
     }
 }
 
+/// Read the raw bytes of a buffer without decoding them
+///
+/// The bytes are returned exactly as they are stored in the file (e.g. still compressed) using
+/// a single request.  This does not go through the I/O scheduler and is meant for debugging
+/// tools that inspect the on-disk layout.
+pub async fn read_raw_buffer(
+    buffer_desc: &pb::Buffer,
+    buffers: &PageBuffers<'_, '_, '_>,
+    object_store: &dyn ObjectStore,
+    path: &Path,
+) -> Result<Bytes> {
+    let (position, size) = get_buffer(buffer_desc, buffers);
+    Ok(object_store
+        .get_range(path, position..(position + size))
+        .await?)
+}
+
+/// The positions and sizes of the buffers that are read to decode `range` of the rows of
+/// `column`, a column of a file with `num_rows` rows
+///
+/// Only the column metadata is used.  The plan is made at page granularity, the buffers of
+/// the column and every buffer of every page that overlaps the range are included.  The
+/// pages of some columns (e.g. the items of a list) count values and not rows.  Rows can't
+/// be mapped to those pages without reading the offsets so all of their pages are included.
+pub fn planned_reads(column: &ColumnInfo, num_rows: u64, range: Range<u64>) -> Vec<(u64, u64)> {
+    let pages_count_rows = column
+        .page_infos
+        .iter()
+        .map(|page| page.num_rows)
+        .sum::<u64>()
+        == num_rows;
+    let mut page_start = 0;
+    let mut pages = Vec::new();
+    for page in column.page_infos.iter() {
+        let page_end = page_start + page.num_rows;
+        if !pages_count_rows || (page_start < range.end && range.start < page_end) {
+            pages.push(page);
+        }
+        page_start = page_end;
+    }
+    column
+        .buffer_offsets_and_sizes
+        .iter()
+        .chain(
+            pages
+                .into_iter()
+                .flat_map(|page| page.buffer_offsets_and_sizes.iter()),
+        )
+        .filter(|(_, size)| *size > 0)
+        .copied()
+        .collect()
+}
+
 /// Convert a protobuf buffer encoding into a physical page scheduler
-fn get_buffer_decoder(encoding: &pb::Flat, buffers: &PageBuffers) -> Box<dyn PageScheduler> {
+fn get_buffer_decoder(
+    encoding: &pb::Flat,
+    buffers: &PageBuffers,
+) -> Result<Box<dyn PageScheduler>> {
     let (buffer_offset, buffer_size) = get_buffer(encoding.buffer.as_ref().unwrap(), buffers);
     let compression_config: CompressionConfig = if encoding.compression.is_none() {
         CompressionConfig::new(CompressionScheme::None, None)
     } else {
         let compression = encoding.compression.as_ref().unwrap();
-        CompressionConfig::new(
-            compression.scheme.as_str().parse().unwrap(),
-            compression.level,
-        )
+        CompressionConfig::new(compression.scheme.as_str().parse()?, compression.level)
     };
-    match encoding.bits_per_value {
+    if !encoding.layers.is_empty() && encoding.compression.is_some() {
+        return Err(Error::InvalidInput {
+            source: "A flat encoding can't have both compression and buffer layers".into(),
+            location: location!(),
+        });
+    }
+    if !encoding.layers.is_empty() && encoding.big_endian {
+        return Err(Error::InvalidInput {
+            source: "A flat encoding can't have both big-endian values and buffer layers".into(),
+            location: location!(),
+        });
+    }
+    if !encoding.layers.is_empty() && encoding.bits_per_value % 8 != 0 {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "Buffer layers need a multiple of 8 bits per value but the flat encoding has {}",
+                encoding.bits_per_value
+            )
+            .into(),
+            location: location!(),
+        });
+    }
+    let pipeline = BufferPipeline::from_protobuf(&encoding.layers)?;
+    Ok(match encoding.bits_per_value {
         1 => Box::new(DenseBitmapScheduler::new(buffer_offset)),
+        2 | 4 => Box::new(SubBytePackedScheduler::new(
+            buffer_offset,
+            encoding.bits_per_value,
+        )),
         bits_per_value => {
             if bits_per_value % 8 != 0 {
-                todo!(
-                    "bits_per_value ({}) that is not a multiple of 8",
-                    bits_per_value
-                );
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "Unsupported flat encoding with {} bits per value, only 1, 2, 4 and multiples of 8 are supported",
+                        bits_per_value
+                    )
+                    .into(),
+                    location: location!(),
+                });
             }
-            Box::new(ValuePageScheduler::new(
-                bits_per_value / 8,
-                buffer_offset,
-                buffer_size,
-                compression_config,
-            ))
+            Box::new(
+                ValuePageScheduler::new(
+                    bits_per_value / 8,
+                    buffer_offset,
+                    buffer_size,
+                    compression_config,
+                )
+                .with_big_endian(encoding.big_endian)
+                .with_pipeline(pipeline),
+            )
         }
+    })
+}
+
+fn get_rle_decoder(encoding: &pb::Rle, buffers: &PageBuffers) -> Result<Box<dyn PageScheduler>> {
+    // In 2.1 the runs are part of the miniblock and the encoding has no buffers
+    let (Some(values), Some(lengths)) = (encoding.values.as_ref(), encoding.lengths.as_ref())
+    else {
+        return Err(Error::InvalidInput {
+            source:
+                "RLE encoding without values and lengths buffers can't be decoded as a 2.0 page"
+                    .into(),
+            location: location!(),
+        });
+    };
+    let values = get_buffer(values, buffers);
+    let lengths = get_buffer(lengths, buffers);
+    match encoding.bits_per_value {
+        // A bitmap, usually the validity of a nullable page, is expanded into a dense bitmap
+        1 => Ok(Box::new(RleBitmapScheduler::new(values, lengths))),
+        bits_per_value if bits_per_value > 0 && bits_per_value % 8 == 0 => Ok(Box::new(
+            RleScheduler::new(bits_per_value, values, lengths),
+        )),
+        bits_per_value => Err(Error::InvalidInput {
+            source: format!(
+                "Unsupported RLE encoding with {} bits per value, only 1 and multiples of 8 are supported",
+                bits_per_value
+            )
+            .into(),
+            location: location!(),
+        }),
     }
 }
 
 fn get_bitpacked_buffer_decoder(
     encoding: &pb::Bitpacked,
     buffers: &PageBuffers,
-) -> Box<dyn PageScheduler> {
+) -> Result<Box<dyn PageScheduler>> {
+    // Values are unpacked into u64 so a corrupt width must not get to the scheduler
+    let compressed = encoding.compressed_bits_per_value;
+    let uncompressed = encoding.uncompressed_bits_per_value;
+    if uncompressed > 64 || compressed > uncompressed {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "Invalid bitpacked encoding: {} compressed bits per value and {} uncompressed bits per value \
+                 (the compressed width must not exceed the uncompressed width, which must be at most 64)",
+                compressed, uncompressed
+            )
+            .into(),
+            location: location!(),
+        });
+    }
+
+    // A column of all zeros may be written with no bits per value, there is nothing to read
+    if compressed == 0 {
+        return Ok(Box::new(bitpack::ZeroWidthBitpackedScheduler::new(
+            uncompressed,
+        )));
+    }
+
     let (buffer_offset, _buffer_size) = get_buffer(encoding.buffer.as_ref().unwrap(), buffers);
 
-    Box::new(bitpack::BitpackedScheduler::new(
-        encoding.compressed_bits_per_value,
-        encoding.uncompressed_bits_per_value,
+    Ok(Box::new(bitpack::BitpackedScheduler::new(
+        compressed,
+        uncompressed,
         buffer_offset,
         encoding.signed,
-    ))
+    )))
 }
 
 fn get_bitpacked_for_non_neg_buffer_decoder(
@@ -105,13 +265,18 @@ fn decoder_from_packed_struct(
     packed_struct: &PackedStruct,
     buffers: &PageBuffers,
     data_type: &DataType,
-) -> Box<dyn PageScheduler> {
+) -> Result<Box<dyn PageScheduler>> {
     let inner_encodings = &packed_struct.inner;
-    let fields = match data_type {
-        DataType::Struct(fields) => Some(fields),
-        _ => None,
-    }
-    .unwrap();
+    let DataType::Struct(fields) = data_type else {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "A packed struct encoding requires a struct data type but the data type is {}",
+                data_type
+            )
+            .into(),
+            location: location!(),
+        });
+    };
 
     let inner_datatypes = fields
         .iter()
@@ -122,27 +287,116 @@ fn decoder_from_packed_struct(
     for i in 0..fields.len() {
         let inner_encoding = &inner_encodings[i];
         let inner_datatype = inner_datatypes[i];
-        let inner_scheduler = decoder_from_array_encoding(inner_encoding, buffers, inner_datatype);
+        let inner_scheduler = decoder_from_array_encoding(inner_encoding, buffers, inner_datatype)?;
         inner_schedulers.push(inner_scheduler);
     }
 
     let packed_buffer = packed_struct.buffer.as_ref().unwrap();
     let (buffer_offset, _) = get_buffer(packed_buffer, buffers);
 
-    Box::new(PackedStructPageScheduler::new(
+    Ok(Box::new(PackedStructPageScheduler::new(
         inner_schedulers,
         data_type.clone(),
         buffer_offset,
+    )))
+}
+
+fn decoder_from_dictionary(
+    dictionary: &pb::Dictionary,
+    buffers: &PageBuffers,
+    data_type: &DataType,
+    decode_mode: DictionaryDecodeMode,
+) -> Result<Box<dyn PageScheduler>> {
+    let indices_encoding = dictionary.indices.as_ref().unwrap();
+    let items_encoding = dictionary.items.as_ref().unwrap();
+    let num_dictionary_items = dictionary.num_dictionary_items;
+
+    // We can get here in 2 ways.  The data is dictionary encoded and the user wants a dictionary or
+    // the data is dictionary encoded, as an optimization, and the user wants the value type.  Figure
+    // out the value type.
+    let value_type = if let DataType::Dictionary(_, value_type) = data_type {
+        value_type
+    } else {
+        data_type
+    };
+
+    // The indices may be stored narrower (e.g. bitpacked) than they decode to so the width comes
+    // from the indices encoding and not from `data_type`
+    let indices_type = match declared_bits_per_value(indices_encoding) {
+        // e.g. all indices are null, there is no width but also nothing to look up
+        None | Some(8) => DataType::UInt8,
+        Some(16) => DataType::UInt16,
+        Some(32) => DataType::UInt32,
+        Some(64) => DataType::UInt64,
+        Some(bits_per_value) => {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "Dictionary indices must decode to 8, 16, 32, or 64 bits per value but the indices encoding declares {} bits per value",
+                    bits_per_value
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+    };
+    let indices_scheduler = decoder_from_array_encoding(indices_encoding, buffers, &indices_type)?;
+
+    let items_scheduler = decoder_from_array_encoding(items_encoding, buffers, value_type)?;
+
+    let should_decode_dict = !data_type.is_dictionary();
+
+    Ok(Box::new(
+        DictionaryPageScheduler::new(
+            indices_scheduler.into(),
+            items_scheduler.into(),
+            num_dictionary_items,
+            should_decode_dict,
+        )
+        .with_indices_type(indices_type)
+        .with_decode_mode(decode_mode),
     ))
 }
 
+// The number of bits per value that an integer encoding decodes to, if it declares one
+fn declared_bits_per_value(encoding: &pb::ArrayEncoding) -> Option<u64> {
+    match encoding.array_encoding.as_ref()? {
+        pb::array_encoding::ArrayEncoding::Nullable(nullable) => {
+            match nullable.nullability.as_ref()? {
+                pb::nullable::Nullability::NoNulls(no_nulls) => {
+                    declared_bits_per_value(no_nulls.values.as_ref()?)
+                }
+                pb::nullable::Nullability::SomeNulls(some_nulls) => {
+                    declared_bits_per_value(some_nulls.values.as_ref()?)
+                }
+                pb::nullable::Nullability::AllNulls(_) => None,
+            }
+        }
+        pb::array_encoding::ArrayEncoding::Flat(flat) => Some(flat.bits_per_value),
+        pb::array_encoding::ArrayEncoding::Bitpacked(bitpacked) => {
+            Some(bitpacked.uncompressed_bits_per_value)
+        }
+        pb::array_encoding::ArrayEncoding::BitpackedForNonNeg(bitpacked) => {
+            Some(bitpacked.uncompressed_bits_per_value)
+        }
+        pb::array_encoding::ArrayEncoding::AdaptiveBitpacked(bitpacked) => {
+            Some(bitpacked.uncompressed_bits_per_value)
+        }
+        pb::array_encoding::ArrayEncoding::Rle(rle) => Some(rle.bits_per_value),
+        pb::array_encoding::ArrayEncoding::Delta(delta) => Some(delta.bits_per_value),
+        pb::array_encoding::ArrayEncoding::Constant(constant) => {
+            Some(constant.value.len() as u64 * 8)
+        }
+        _ => None,
+    }
+}
+
 /// Convert a protobuf array encoding into a physical page scheduler
 pub fn decoder_from_array_encoding(
     encoding: &pb::ArrayEncoding,
     buffers: &PageBuffers,
     data_type: &DataType,
-) -> Box<dyn PageScheduler> {
-    match encoding.array_encoding.as_ref().unwrap() {
+) -> Result<Box<dyn PageScheduler>> {
+    Ok(match encoding.array_encoding.as_ref().unwrap() {
         pb::array_encoding::ArrayEncoding::Nullable(basic) => {
             match basic.nullability.as_ref().unwrap() {
                 pb::nullable::Nullability::NoNulls(no_nulls) => Box::new(
@@ -150,20 +404,32 @@ pub fn decoder_from_array_encoding(
                         no_nulls.values.as_ref().unwrap(),
                         buffers,
                         data_type,
-                    )),
+                    )?),
                 ),
+                // The page has a validity bitmap but no nulls, there is no need to read it
+                pb::nullable::Nullability::SomeNulls(some_nulls)
+                    if some_nulls.null_count == Some(0) =>
+                {
+                    Box::new(BasicPageScheduler::new_non_nullable(
+                        decoder_from_array_encoding(
+                            some_nulls.values.as_ref().unwrap(),
+                            buffers,
+                            data_type,
+                        )?,
+                    ))
+                }
                 pb::nullable::Nullability::SomeNulls(some_nulls) => {
                     Box::new(BasicPageScheduler::new_nullable(
                         decoder_from_array_encoding(
                             some_nulls.validity.as_ref().unwrap(),
                             buffers,
                             data_type,
-                        ),
+                        )?,
                         decoder_from_array_encoding(
                             some_nulls.values.as_ref().unwrap(),
                             buffers,
                             data_type,
-                        ),
+                        )?,
                     ))
                 }
                 pb::nullable::Nullability::AllNulls(_) => {
@@ -172,30 +438,34 @@ pub fn decoder_from_array_encoding(
             }
         }
         pb::array_encoding::ArrayEncoding::Bitpacked(bitpacked) => {
-            get_bitpacked_buffer_decoder(bitpacked, buffers)
+            get_bitpacked_buffer_decoder(bitpacked, buffers)?
         }
-        pb::array_encoding::ArrayEncoding::Flat(flat) => get_buffer_decoder(flat, buffers),
+        pb::array_encoding::ArrayEncoding::Flat(flat) => get_buffer_decoder(flat, buffers)?,
         pb::array_encoding::ArrayEncoding::FixedSizeList(fixed_size_list) => {
             let item_encoding = fixed_size_list.items.as_ref().unwrap();
-            let item_scheduler = decoder_from_array_encoding(item_encoding, buffers, data_type);
-            Box::new(FixedListScheduler::new(
-                item_scheduler,
-                fixed_size_list.dimension,
-            ))
+            let item_scheduler = decoder_from_array_encoding(item_encoding, buffers, data_type)?;
+            let scheduler = FixedListScheduler::new(item_scheduler, fixed_size_list.dimension);
+            match fixed_size_list.validity.as_ref() {
+                Some(validity) => Box::new(
+                    scheduler
+                        .with_validity(decoder_from_array_encoding(validity, buffers, data_type)?),
+                ),
+                None => Box::new(scheduler),
+            }
         }
         // This is a column containing the list offsets.  This wrapper is superfluous at the moment
         // since we know it is a list based on the schema.  In the future there may be different ways
         // of storing the list offsets.
         pb::array_encoding::ArrayEncoding::List(list) => {
-            decoder_from_array_encoding(list.offsets.as_ref().unwrap(), buffers, data_type)
+            decoder_from_array_encoding(list.offsets.as_ref().unwrap(), buffers, data_type)?
         }
         pb::array_encoding::ArrayEncoding::Binary(binary) => {
             let indices_encoding = binary.indices.as_ref().unwrap();
             let bytes_encoding = binary.bytes.as_ref().unwrap();
 
             let indices_scheduler =
-                decoder_from_array_encoding(indices_encoding, buffers, data_type);
-            let bytes_scheduler = decoder_from_array_encoding(bytes_encoding, buffers, data_type);
+                decoder_from_array_encoding(indices_encoding, buffers, data_type)?;
+            let bytes_scheduler = decoder_from_array_encoding(bytes_encoding, buffers, data_type)?;
 
             let offset_type = match data_type {
                 DataType::LargeBinary | DataType::LargeUtf8 => DataType::Int64,
@@ -211,7 +481,7 @@ pub fn decoder_from_array_encoding(
         }
         pb::array_encoding::ArrayEncoding::Fsst(fsst) => {
             let inner =
-                decoder_from_array_encoding(fsst.binary.as_ref().unwrap(), buffers, data_type);
+                decoder_from_array_encoding(fsst.binary.as_ref().unwrap(), buffers, data_type)?;
 
             Box::new(FsstPageScheduler::new(
                 inner,
@@ -219,57 +489,89 @@ pub fn decoder_from_array_encoding(
             ))
         }
         pb::array_encoding::ArrayEncoding::Dictionary(dictionary) => {
-            let indices_encoding = dictionary.indices.as_ref().unwrap();
-            let items_encoding = dictionary.items.as_ref().unwrap();
-            let num_dictionary_items = dictionary.num_dictionary_items;
-
-            // We can get here in 2 ways.  The data is dictionary encoded and the user wants a dictionary or
-            // the data is dictionary encoded, as an optimization, and the user wants the value type.  Figure
-            // out the value type.
-            let value_type = if let DataType::Dictionary(_, value_type) = data_type {
-                value_type
-            } else {
-                data_type
-            };
-
-            // Note: we don't actually know the indices type here, passing down `data_type` works ok because
-            // the dictionary indices are always integers and we don't need the data_type to figure out how
-            // to decode integers.
-            let indices_scheduler =
-                decoder_from_array_encoding(indices_encoding, buffers, data_type);
-
-            let items_scheduler = decoder_from_array_encoding(items_encoding, buffers, value_type);
-
-            let should_decode_dict = !data_type.is_dictionary();
-
-            Box::new(DictionaryPageScheduler::new(
-                indices_scheduler.into(),
-                items_scheduler.into(),
-                num_dictionary_items,
-                should_decode_dict,
-            ))
+            decoder_from_dictionary(dictionary, buffers, data_type, DictionaryDecodeMode::Values)?
         }
         pb::array_encoding::ArrayEncoding::FixedSizeBinary(fixed_size_binary) => {
             let bytes_encoding = fixed_size_binary.bytes.as_ref().unwrap();
-            let bytes_scheduler = decoder_from_array_encoding(bytes_encoding, buffers, data_type);
-            let bytes_per_offset = match data_type {
-                DataType::LargeBinary | DataType::LargeUtf8 => 8,
-                DataType::Binary | DataType::Utf8 => 4,
-                _ => panic!("FixedSizeBinary only supports binary and utf8 types"),
+            let bytes_scheduler = decoder_from_array_encoding(bytes_encoding, buffers, data_type)?;
+            let byte_width = fixed_size_binary.byte_width;
+            let scheduler = match data_type {
+                DataType::LargeBinary | DataType::LargeUtf8 => {
+                    fixed_size_binary::FixedSizeBinaryPageScheduler::new(
+                        bytes_scheduler,
+                        byte_width,
+                        8,
+                    )
+                }
+                DataType::Binary | DataType::Utf8 => {
+                    fixed_size_binary::FixedSizeBinaryPageScheduler::new(
+                        bytes_scheduler,
+                        byte_width,
+                        4,
+                    )
+                }
+                // Decimals are stored as fixed width little-endian integers
+                DataType::Decimal128(..) | DataType::Decimal256(..) => {
+                    if data_type.primitive_width() != Some(byte_width as usize) {
+                        return Err(Error::InvalidInput {
+                            source: format!(
+                                "FixedSizeBinary with byte width {} cannot be decoded as {}",
+                                byte_width, data_type
+                            )
+                            .into(),
+                            location: location!(),
+                        });
+                    }
+                    fixed_size_binary::FixedSizeBinaryPageScheduler::new_fixed_width(
+                        bytes_scheduler,
+                        byte_width,
+                    )
+                }
+                _ => {
+                    return Err(Error::InvalidInput {
+                        source: format!(
+                        "FixedSizeBinary only supports binary, utf8 and decimal types but got {}",
+                        data_type
+                    )
+                        .into(),
+                        location: location!(),
+                    })
+                }
             };
-
-            Box::new(fixed_size_binary::FixedSizeBinaryPageScheduler::new(
-                bytes_scheduler,
-                fixed_size_binary.byte_width,
-                bytes_per_offset,
-            ))
+            match fixed_size_binary.validity.as_ref() {
+                Some(validity) => Box::new(
+                    scheduler
+                        .with_validity(decoder_from_array_encoding(validity, buffers, data_type)?),
+                ),
+                None => Box::new(scheduler),
+            }
         }
         pb::array_encoding::ArrayEncoding::PackedStruct(packed_struct) => {
-            decoder_from_packed_struct(packed_struct, buffers, data_type)
+            decoder_from_packed_struct(packed_struct, buffers, data_type)?
         }
         pb::array_encoding::ArrayEncoding::BitpackedForNonNeg(bitpacked) => {
             get_bitpacked_for_non_neg_buffer_decoder(bitpacked, buffers)
         }
+        pb::array_encoding::ArrayEncoding::Rle(rle) => get_rle_decoder(rle, buffers)?,
+        pb::array_encoding::ArrayEncoding::Constant(constant) => {
+            check_constant_value(&constant.value, data_type)?;
+            Box::new(ConstantScheduler::new(constant.value.clone()))
+        }
+        pb::array_encoding::ArrayEncoding::Delta(delta) => Box::new(DeltaScheduler::new(
+            decoder_from_array_encoding(delta.deltas.as_ref().unwrap(), buffers, data_type)?,
+            delta.bits_per_value,
+            delta.base,
+        )),
+        pb::array_encoding::ArrayEncoding::AdaptiveBitpacked(bitpacked) => {
+            let (buffer_offset, _) = get_buffer(bitpacked.buffer.as_ref().unwrap(), buffers);
+            Box::new(AdaptiveBitpackedScheduler::new(
+                bitpacked.uncompressed_bits_per_value,
+                bitpacked.values_per_block,
+                bitpacked.num_blocks,
+                bitpacked.signed,
+                buffer_offset,
+            ))
+        }
         // Currently there is no way to encode struct nullability and structs are encoded with a "header" column
         // (that has no data).  We never actually decode that column and so this branch is never actually encountered.
         //
@@ -277,14 +579,566 @@ pub fn decoder_from_array_encoding(
         pb::array_encoding::ArrayEncoding::Struct(_) => unreachable!(),
         // 2.1 only
         _ => unreachable!("Unsupported array encoding: {:?}", encoding),
+    })
+}
+
+/// Like [`decoder_from_array_encoding`] but pages of a `data_type` column that were dictionary
+/// encoded (as an optimization) are decoded according to `decode_mode`
+///
+/// With [`DictionaryDecodeMode::Indices`] the decoded pages have the type
+/// `Dictionary(UInt8, data_type)`.  The primitive field decoder casts these to
+/// `Dictionary(UInt32, data_type)`, so readers only ever see `UInt32` indices.  This lets
+/// operators like a group-by work on the indices and only materialize the values at the
+/// end.  Other encodings are unaffected.
+pub fn decoder_from_array_encoding_with_dictionary_mode(
+    encoding: &pb::ArrayEncoding,
+    buffers: &PageBuffers,
+    data_type: &DataType,
+    decode_mode: DictionaryDecodeMode,
+) -> Result<Box<dyn PageScheduler>> {
+    match encoding.array_encoding.as_ref() {
+        Some(pb::array_encoding::ArrayEncoding::Dictionary(dictionary)) => {
+            decoder_from_dictionary(dictionary, buffers, data_type, decode_mode)
+        }
+        _ => decoder_from_array_encoding(encoding, buffers, data_type),
+    }
+}
+
+/// Convert a protobuf array encoding into a page scheduler for only its validity bitmap
+///
+/// This is `None` unless the encoding is nullable with some nulls (and the encoding does
+/// not record a null count of zero).  The values buffer
+/// is never read by the returned scheduler so this is a cheap way to count nulls.  The
+/// decoded data block has one bit per value, set if the value is valid.
+pub fn validity_decoder_from_array_encoding(
+    encoding: &pb::ArrayEncoding,
+    buffers: &PageBuffers,
+) -> Result<Option<Box<dyn PageScheduler>>> {
+    let Some(pb::array_encoding::ArrayEncoding::Nullable(basic)) = encoding.array_encoding.as_ref()
+    else {
+        return Ok(None);
+    };
+    match basic.nullability.as_ref() {
+        Some(pb::nullable::Nullability::SomeNulls(some_nulls))
+            if some_nulls.null_count == Some(0) =>
+        {
+            Ok(None)
+        }
+        Some(pb::nullable::Nullability::SomeNulls(some_nulls)) => decoder_from_array_encoding(
+            some_nulls.validity.as_ref().unwrap(),
+            buffers,
+            &DataType::Boolean,
+        )
+        .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// A printable description of how an array is physically encoded
+///
+/// This is built from a protobuf array encoding by [`describe_encoding`] without creating
+/// any schedulers or reading any data.  It displays on a single line, for example
+/// `Nullable(validity=Flat(bits=1, buffer=page:0), values=Bitpacked(bits=8/32, signed, buffer=page:1))`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodingTree {
+    /// The name of the encoding, e.g. `Bitpacked`
+    pub name: String,
+    /// The parameters of the encoding, e.g. `bits=1` or `compression=zstd(level=3)`
+    pub properties: Vec<String>,
+    /// The buffers read directly by this encoding, e.g. `page:0`
+    pub buffers: Vec<String>,
+    /// The encodings nested in this one, each with its role (e.g. `indices`)
+    pub children: Vec<(String, EncodingTree)>,
+}
+
+impl EncodingTree {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            properties: Vec::new(),
+            buffers: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn property(mut self, property: impl Into<String>) -> Self {
+        self.properties.push(property.into());
+        self
+    }
+
+    fn compression(self, compression: Option<&pb::Compression>) -> Self {
+        match compression {
+            Some(pb::Compression {
+                scheme,
+                level: Some(level),
+            }) => self.property(format!("compression={}(level={})", scheme, level)),
+            Some(pb::Compression { scheme, .. }) => {
+                self.property(format!("compression={}", scheme))
+            }
+            None => self,
+        }
+    }
+
+    fn buffer(mut self, buffer: Option<&pb::Buffer>) -> Self {
+        let description = match buffer {
+            Some(buffer) => {
+                let buffer_type = pb::buffer::BufferType::try_from(buffer.buffer_type)
+                    .map(|buffer_type| buffer_type.as_str_name())
+                    .unwrap_or("unknown");
+                format!("{}:{}", buffer_type, buffer.buffer_index)
+            }
+            None => "missing".to_string(),
+        };
+        self.buffers.push(description);
+        self
+    }
+
+    fn child(mut self, role: impl Into<String>, encoding: Option<&pb::ArrayEncoding>) -> Self {
+        let child = match encoding {
+            Some(encoding) => describe_encoding(encoding),
+            None => Self::new("Missing"),
+        };
+        self.children.push((role.into(), child));
+        self
+    }
+}
+
+impl std::fmt::Display for EncodingTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        let mut parts = self.properties.clone();
+        match self.buffers.as_slice() {
+            [] => {}
+            [buffer] => parts.push(format!("buffer={}", buffer)),
+            buffers => parts.push(format!("buffers=[{}]", buffers.join(", "))),
+        }
+        parts.extend(
+            self.children
+                .iter()
+                .map(|(role, child)| format!("{}={}", role, child)),
+        );
+        if !parts.is_empty() {
+            write!(f, "({})", parts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe a protobuf array encoding as a printable tree
+///
+/// This follows the same structure as [`decoder_from_array_encoding`] but only looks at the
+/// protobuf so it never fails.  Encodings that are missing or unknown (e.g. written by a newer
+/// version) are described as `Missing` and `Unknown`.
+pub fn describe_encoding(encoding: &pb::ArrayEncoding) -> EncodingTree {
+    use pb::array_encoding::ArrayEncoding;
+    let Some(array_encoding) = encoding.array_encoding.as_ref() else {
+        return EncodingTree::new("Unknown");
+    };
+    match array_encoding {
+        ArrayEncoding::Nullable(nullable) => {
+            let tree = EncodingTree::new("Nullable");
+            match nullable.nullability.as_ref() {
+                Some(pb::nullable::Nullability::NoNulls(no_nulls)) => tree
+                    .property("no_nulls")
+                    .child("values", no_nulls.values.as_deref()),
+                Some(pb::nullable::Nullability::SomeNulls(some_nulls)) => tree
+                    .child("validity", some_nulls.validity.as_deref())
+                    .child("values", some_nulls.values.as_deref()),
+                Some(pb::nullable::Nullability::AllNulls(_)) => tree.property("all_nulls"),
+                None => tree.property("unknown"),
+            }
+        }
+        ArrayEncoding::Flat(flat) => {
+            let tree = EncodingTree::new("Flat").property(format!("bits={}", flat.bits_per_value));
+            let tree = if flat.big_endian {
+                tree.property("big_endian")
+            } else {
+                tree
+            };
+            let tree = if flat.layers.is_empty() {
+                tree
+            } else {
+                tree.property(format!(
+                    "layers=[{}]",
+                    flat.layers
+                        .iter()
+                        .map(|layer| match layer.layer.as_ref() {
+                            Some(pb::buffer_layer::Layer::Compression(compression)) => {
+                                compression.scheme.clone()
+                            }
+                            None => "unknown".to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            };
+            tree.compression(flat.compression.as_ref())
+                .buffer(flat.buffer.as_ref())
+        }
+        ArrayEncoding::Bitpacked(bitpacked) => {
+            let tree = EncodingTree::new("Bitpacked").property(format!(
+                "bits={}/{}",
+                bitpacked.compressed_bits_per_value, bitpacked.uncompressed_bits_per_value
+            ));
+            let tree = if bitpacked.signed {
+                tree.property("signed")
+            } else {
+                tree
+            };
+            tree.buffer(bitpacked.buffer.as_ref())
+        }
+        ArrayEncoding::BitpackedForNonNeg(bitpacked) => EncodingTree::new("BitpackedForNonNeg")
+            .property(format!(
+                "bits={}/{}",
+                bitpacked.compressed_bits_per_value, bitpacked.uncompressed_bits_per_value
+            ))
+            .buffer(bitpacked.buffer.as_ref()),
+        ArrayEncoding::FixedSizeList(fixed_size_list) => {
+            let tree = EncodingTree::new("FixedSizeList")
+                .property(format!("dimension={}", fixed_size_list.dimension))
+                .property(format!("has_validity={}", fixed_size_list.has_validity))
+                .child("items", fixed_size_list.items.as_deref());
+            match fixed_size_list.validity.as_deref() {
+                Some(validity) => tree.child("validity", Some(validity)),
+                None => tree,
+            }
+        }
+        ArrayEncoding::List(list) => EncodingTree::new("List")
+            .property(format!(
+                "null_offset_adjustment={}",
+                list.null_offset_adjustment
+            ))
+            .property(format!("num_items={}", list.num_items))
+            .child("offsets", list.offsets.as_deref()),
+        ArrayEncoding::Struct(simple_struct) => {
+            let tree = EncodingTree::new("Struct");
+            match simple_struct.validity.as_ref() {
+                Some(validity) => tree.buffer(Some(validity)),
+                None => tree,
+            }
+        }
+        ArrayEncoding::Binary(binary) => EncodingTree::new("Binary")
+            .property(format!("null_adjustment={}", binary.null_adjustment))
+            .child("indices", binary.indices.as_deref())
+            .child("bytes", binary.bytes.as_deref()),
+        ArrayEncoding::Dictionary(dictionary) => EncodingTree::new("Dictionary")
+            .property(format!("num_items={}", dictionary.num_dictionary_items))
+            .child("indices", dictionary.indices.as_deref())
+            .child("items", dictionary.items.as_deref()),
+        ArrayEncoding::Fsst(fsst) => EncodingTree::new("Fsst")
+            .property(format!("symbol_table={}B", fsst.symbol_table.len()))
+            .child("binary", fsst.binary.as_deref()),
+        ArrayEncoding::PackedStruct(packed_struct) => {
+            let tree = EncodingTree::new("PackedStruct").buffer(packed_struct.buffer.as_ref());
+            packed_struct
+                .inner
+                .iter()
+                .enumerate()
+                .fold(tree, |tree, (i, inner)| {
+                    tree.child(i.to_string(), Some(inner))
+                })
+        }
+        ArrayEncoding::FixedSizeBinary(fixed_size_binary) => {
+            let tree = EncodingTree::new("FixedSizeBinary")
+                .property(format!("byte_width={}", fixed_size_binary.byte_width))
+                .child("bytes", fixed_size_binary.bytes.as_deref());
+            match fixed_size_binary.validity.as_deref() {
+                Some(validity) => tree.child("validity", Some(validity)),
+                None => tree,
+            }
+        }
+        ArrayEncoding::Constant(constant) => {
+            EncodingTree::new("Constant").property(format!("value={}B", constant.value.len()))
+        }
+        ArrayEncoding::InlineBitpacking(bitpacking) => EncodingTree::new("InlineBitpacking")
+            .property(format!("bits={}", bitpacking.uncompressed_bits_per_value)),
+        ArrayEncoding::OutOfLineBitpacking(bitpacking) => EncodingTree::new("OutOfLineBitpacking")
+            .property(format!(
+                "bits={}/{}",
+                bitpacking.compressed_bits_per_value, bitpacking.uncompressed_bits_per_value
+            )),
+        ArrayEncoding::Variable(variable) => EncodingTree::new("Variable")
+            .property(format!("bits_per_offset={}", variable.bits_per_offset)),
+        ArrayEncoding::PackedStructFixedWidthMiniBlock(packed_struct) => {
+            EncodingTree::new("PackedStructFixedWidthMiniBlock")
+                .property(format!("bits={:?}", packed_struct.bits_per_values))
+                .child("flat", packed_struct.flat.as_deref())
+        }
+        ArrayEncoding::Block(block) => {
+            EncodingTree::new("Block").property(format!("scheme={}", block.scheme))
+        }
+        ArrayEncoding::Rle(rle) => EncodingTree::new("Rle")
+            .property(format!("bits={}", rle.bits_per_value))
+            .buffer(rle.values.as_ref())
+            .buffer(rle.lengths.as_ref()),
+        ArrayEncoding::GeneralMiniBlock(general) => EncodingTree::new("GeneralMiniBlock")
+            .compression(general.compression.as_ref())
+            .child("inner", general.inner.as_deref()),
+        ArrayEncoding::ByteStreamSplit(byte_stream_split) => EncodingTree::new("ByteStreamSplit")
+            .property(format!("bits={}", byte_stream_split.bits_per_value)),
+        ArrayEncoding::Delta(delta) => EncodingTree::new("Delta")
+            .property(format!("bits={}", delta.bits_per_value))
+            .property(format!("base={}", delta.base))
+            .child("deltas", delta.deltas.as_deref()),
+        ArrayEncoding::AdaptiveBitpacked(bitpacked) => {
+            let tree = EncodingTree::new("AdaptiveBitpacked")
+                .property(format!("bits={}", bitpacked.uncompressed_bits_per_value))
+                .property(format!("values_per_block={}", bitpacked.values_per_block))
+                .property(format!("blocks={}", bitpacked.num_blocks));
+            let tree = if bitpacked.signed {
+                tree.property("signed")
+            } else {
+                tree
+            };
+            tree.buffer(bitpacked.buffer.as_ref())
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        ops::Range,
+        sync::{Arc, Mutex},
+    };
+
+    use arrow_array::{Array, BooleanArray, Int32Array};
+    use arrow_schema::DataType;
+    use bytes::Bytes;
+    use futures::future::BoxFuture;
+    use object_store::{memory::InMemory, path::Path, ObjectStore, PutPayload};
+
     use crate::decoder::{ColumnBuffers, FileBuffers, PageBuffers};
-    use crate::format::pb;
-    use crate::v2::encodings::physical::get_buffer_decoder;
+    use crate::encodings::physical::block::{CompressionConfig, CompressionScheme};
+    use crate::format::{pb, ProtobufUtils};
+    use crate::testing::{schedule_single_page, SimulatedScheduler};
+    use crate::v2::encodings::physical::{
+        describe_encoding, get_buffer_decoder, read_raw_buffer,
+        validity_decoder_from_array_encoding,
+    };
+    use crate::EncodingsIo;
+
+    // Records every range requested from the inner scheduler
+    #[derive(Debug)]
+    struct TrackingScheduler {
+        inner: Arc<dyn EncodingsIo>,
+        requests: Mutex<Vec<Range<u64>>>,
+    }
+
+    impl EncodingsIo for TrackingScheduler {
+        fn submit_request(
+            &self,
+            ranges: Vec<Range<u64>>,
+            priority: u64,
+        ) -> BoxFuture<'static, lance_core::Result<Vec<Bytes>>> {
+            self.requests.lock().unwrap().extend(ranges.iter().cloned());
+            self.inner.submit_request(ranges, priority)
+        }
+    }
+
+    #[test]
+    fn test_rle_invalid_encodings() {
+        let buffers = [[0_u8; 4], [0_u8; 4]];
+
+        // 2.1 RLE encodings have no buffers, for both values and bitmaps
+        for bits_per_value in [1, 32] {
+            let encoding = ProtobufUtils::rle(bits_per_value);
+            let err = schedule_single_page(&encoding, buffers, &DataType::Int32)
+                .unwrap_err()
+                .to_string();
+            assert!(
+                err.contains("without values and lengths buffers"),
+                "{}",
+                err
+            );
+        }
+
+        for bits_per_value in [0, 12] {
+            let encoding = ProtobufUtils::rle_with_buffers(bits_per_value, 0, 1);
+            let err = schedule_single_page(&encoding, buffers, &DataType::Int32)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("Unsupported RLE encoding"), "{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_raw_buffer() {
+        let store = InMemory::new();
+        let path = Path::from("data.lance");
+        let data = (0..100_u8).collect::<Vec<_>>();
+        store
+            .put(&path, PutPayload::from(data.clone()))
+            .await
+            .unwrap();
+
+        let buffers = PageBuffers {
+            column_buffers: ColumnBuffers {
+                file_buffers: FileBuffers {
+                    positions_and_sizes: &[(90, 10)],
+                },
+                positions_and_sizes: &[(50, 20)],
+            },
+            positions_and_sizes: &[(0, 0), (10, 5)],
+        };
+        let cases = [
+            (pb::buffer::BufferType::Page, 1, 10..15),
+            (pb::buffer::BufferType::Page, 0, 0..0),
+            (pb::buffer::BufferType::Column, 0, 50..70),
+            (pb::buffer::BufferType::File, 0, 90..100),
+        ];
+        for (buffer_type, buffer_index, expected) in cases {
+            let buffer_desc = pb::Buffer {
+                buffer_index,
+                buffer_type: buffer_type as i32,
+            };
+            let bytes = read_raw_buffer(&buffer_desc, &buffers, &store, &path)
+                .await
+                .unwrap();
+            assert_eq!(bytes.as_ref(), &data[expected]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validity_only_decode() {
+        // 8 values, the 2nd and 4th are null
+        let validity = [0b1111_0101_u8];
+        let values = (0..8_i32).flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>();
+        let mut data = validity.to_vec();
+        data.extend_from_slice(&values);
+        let positions_and_sizes = [(0, 1), (1, values.len() as u64)];
+        let buffers = PageBuffers {
+            column_buffers: ColumnBuffers {
+                file_buffers: FileBuffers {
+                    positions_and_sizes: &[],
+                },
+                positions_and_sizes: &[],
+            },
+            positions_and_sizes: &positions_and_sizes,
+        };
+
+        let encoding = ProtobufUtils::basic_some_null_encoding(
+            ProtobufUtils::flat_encoding(1, 0, None),
+            ProtobufUtils::flat_encoding(32, 1, None),
+            None,
+        );
+        let scheduler = validity_decoder_from_array_encoding(&encoding, &buffers)
+            .unwrap()
+            .unwrap();
+
+        let io = Arc::new(TrackingScheduler {
+            inner: Arc::new(SimulatedScheduler::new(Bytes::from(data))),
+            requests: Mutex::new(Vec::new()),
+        });
+        let decoder = scheduler
+            .schedule_ranges(&[0..8], &(io.clone() as Arc<dyn EncodingsIo>), 0)
+            .await
+            .unwrap();
+        let validity = decoder.decode(0, 8).unwrap();
+        let validity = BooleanArray::from(validity.into_arrow(DataType::Boolean, true).unwrap());
+        assert_eq!(validity.len() - validity.true_count(), 2);
+        assert!(!validity.value(1));
+        assert!(!validity.value(3));
+
+        // Only the validity buffer was read
+        let requests = io.requests.lock().unwrap();
+        assert!(!requests.is_empty());
+        assert!(
+            requests.iter().all(|range| range.end <= 1),
+            "{:?}",
+            requests
+        );
+
+        // Encodings without some nulls have no validity to decode
+        let no_nulls =
+            ProtobufUtils::basic_no_null_encoding(ProtobufUtils::flat_encoding(32, 1, None));
+        assert!(validity_decoder_from_array_encoding(&no_nulls, &buffers)
+            .unwrap()
+            .is_none());
+        let all_nulls = ProtobufUtils::basic_all_null_encoding();
+        assert!(validity_decoder_from_array_encoding(&all_nulls, &buffers)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_bitpacked_invalid_widths() {
+        let schedule = |compressed, uncompressed| {
+            let encoding = ProtobufUtils::bitpacked_encoding(compressed, uncompressed, 0, false);
+            schedule_single_page(&encoding, [[0_u8; 8]], &DataType::UInt64)
+        };
+
+        assert!(schedule(3, 8).is_ok());
+        assert!(schedule(64, 64).is_ok());
+
+        // Compressed wider than uncompressed, uncompressed wider than 64, both wider than 64
+        for (compressed, uncompressed) in [(9, 8), (65, 128), (80, 64), (100, 100)] {
+            let err = schedule(compressed, uncompressed).unwrap_err();
+            assert!(
+                matches!(err, lance_core::Error::InvalidInput { .. }),
+                "{}",
+                err
+            );
+            let message = err.to_string();
+            assert!(
+                message.contains(&format!(
+                    "{} compressed bits per value and {} uncompressed bits per value",
+                    compressed, uncompressed
+                )),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bitpacked_zero_width() {
+        // The data buffer is empty since every value is zero
+        let encoding = ProtobufUtils::basic_no_null_encoding(ProtobufUtils::bitpacked_encoding(
+            0, 32, 0, true,
+        ));
+        let (scheduler, io) =
+            schedule_single_page(&encoding, [[0_u8; 0]], &DataType::Int32).unwrap();
+
+        let io = Arc::new(TrackingScheduler {
+            inner: io,
+            requests: Mutex::new(Vec::new()),
+        });
+        let decoder = scheduler
+            .schedule_ranges(&[0..10, 20..30], &(io.clone() as Arc<dyn EncodingsIo>), 0)
+            .await
+            .unwrap();
+        let values = decoder.decode(5, 12).unwrap();
+        let values = Int32Array::from(values.into_arrow(DataType::Int32, true).unwrap());
+        assert_eq!(values, Int32Array::from(vec![0; 12]));
+
+        // Nothing was read from the data buffer
+        assert!(io.requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_packed_struct_non_struct_data_type() {
+        let encoding = ProtobufUtils::packed_struct(
+            vec![
+                ProtobufUtils::flat_encoding(32, 0, None),
+                ProtobufUtils::flat_encoding(32, 0, None),
+            ],
+            0,
+        );
+        let err = schedule_single_page(&encoding, [[0_u8; 8]], &DataType::Int64).unwrap_err();
+        assert!(
+            matches!(err, lance_core::Error::InvalidInput { .. }),
+            "{}",
+            err
+        );
+        let message = err.to_string();
+        assert!(
+            message.contains("requires a struct data type"),
+            "{}",
+            message
+        );
+        assert!(message.contains("Int64"), "{}", message);
+    }
 
     #[test]
     fn test_get_buffer_decoder_for_compressed_buffer() {
@@ -299,6 +1153,93 @@ mod tests {
                     scheme: "zstd".to_string(),
                     level: Some(0),
                 }),
+                big_endian: false,
+                layers: vec![],
+            },
+            &PageBuffers {
+                column_buffers: ColumnBuffers {
+                    file_buffers: FileBuffers {
+                        positions_and_sizes: &[(0, 100)],
+                    },
+                    positions_and_sizes: &[],
+                },
+                positions_and_sizes: &[],
+            },
+        )
+        .unwrap();
+        assert_eq!(format!("{:?}", page_scheduler).as_str(), "ValuePageScheduler { bytes_per_value: 1, buffer_offset: 0, buffer_size: 100, compression_config: CompressionConfig { scheme: Zstd, level: Some(0) }, big_endian: false, pipeline: BufferPipeline { layers: [] } }");
+    }
+
+    #[test]
+    fn test_describe_encoding() {
+        let encoding = ProtobufUtils::basic_some_null_encoding(
+            ProtobufUtils::flat_encoding(1, 0, None),
+            ProtobufUtils::dict_encoding(
+                ProtobufUtils::bitpacked_encoding(4, 8, 1, false),
+                ProtobufUtils::fsst(
+                    ProtobufUtils::binary(
+                        ProtobufUtils::flat_encoding(32, 2, None),
+                        ProtobufUtils::flat_encoding(
+                            8,
+                            3,
+                            Some(CompressionConfig::new(CompressionScheme::Zstd, Some(3))),
+                        ),
+                        0,
+                    ),
+                    vec![0; 16],
+                ),
+                10,
+            ),
+            None,
+        );
+        let tree = describe_encoding(&encoding);
+        assert_eq!(tree.name, "Nullable");
+        assert_eq!(tree.children[1].1.name, "Dictionary");
+        assert_eq!(tree.children[1].1.children[0].1.buffers, vec!["page:1"]);
+        assert_eq!(
+            tree.to_string(),
+            "Nullable(\
+             validity=Flat(bits=1, buffer=page:0), \
+             values=Dictionary(num_items=10, \
+             indices=Bitpacked(bits=4/8, buffer=page:1), \
+             items=Fsst(symbol_table=16B, binary=Binary(null_adjustment=0, \
+             indices=Flat(bits=32, buffer=page:2), \
+             bytes=Flat(bits=8, compression=zstd(level=3), buffer=page:3)))))"
+        );
+
+        // Missing and unknown encodings are described rather than failing
+        let mut encoding = ProtobufUtils::fsl_encoding(4, ProtobufUtils::constant(vec![]), false);
+        let Some(pb::array_encoding::ArrayEncoding::FixedSizeList(fsl)) =
+            encoding.array_encoding.as_mut()
+        else {
+            unreachable!()
+        };
+        fsl.items = None;
+        assert_eq!(
+            describe_encoding(&encoding).to_string(),
+            "FixedSizeList(dimension=4, has_validity=false, items=Missing)"
+        );
+        assert_eq!(
+            describe_encoding(&pb::ArrayEncoding::default()).to_string(),
+            "Unknown"
+        );
+    }
+
+    #[test]
+    fn test_get_buffer_decoder_unknown_compression() {
+        let err = get_buffer_decoder(
+            &pb::Flat {
+                buffer: Some(pb::Buffer {
+                    buffer_index: 0,
+                    buffer_type: pb::buffer::BufferType::File as i32,
+                }),
+                bits_per_value: 8,
+                compression: Some(pb::Compression {
+                    scheme: "brotli".to_string(),
+                    level: None,
+                }),
+                big_endian: false,
+                layers: vec![],
             },
             &PageBuffers {
                 column_buffers: ColumnBuffers {
@@ -309,7 +1250,14 @@ mod tests {
                 },
                 positions_and_sizes: &[],
             },
+        )
+        .unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains("Unknown compression scheme: brotli"),
+            "{}",
+            message
         );
-        assert_eq!(format!("{:?}", page_scheduler).as_str(), "ValuePageScheduler { bytes_per_value: 1, buffer_offset: 0, buffer_size: 100, compression_config: CompressionConfig { scheme: Zstd, level: Some(0) } }");
+        assert!(message.contains("none, zstd and lz4"), "{}", message);
     }
 }
@@ -0,0 +1,450 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Block-adaptive bitpacking for integers in the 2.0 format
+//!
+//! The values are split into blocks of `values_per_block` values and each block is
+//! bitpacked with the smallest bit width that fits all of its values.  The buffer starts
+//! with a header of one byte per block, the bit width of that block, followed by the
+//! packed blocks.  Each block starts on a byte boundary.
+//!
+//! For example, with 4 values per block, `[1, 2, 3, 1, 1000, 7, 2, 1]` is stored as the
+//! header `[2, 10]`, the 1 byte block `[1, 2, 3, 1]` and the 5 byte block
+//! `[1000, 7, 2, 1]`.  A single bit width would need 10 bits for every value.
+
+use std::{ops::Range, sync::Arc};
+
+use arrow_schema::DataType;
+use futures::{future::BoxFuture, FutureExt};
+use snafu::location;
+
+use lance_core::{Error, Result};
+
+use crate::{
+    buffer::LanceBuffer,
+    data::{BlockInfo, DataBlock, FixedWidthDataBlock, OpaqueBlock},
+    decoder::{PageScheduler, PrimitivePageDecoder},
+    format::ProtobufUtils,
+    v2::encoder::{ArrayEncoder, EncodedArray},
+    EncodingsIo,
+};
+
+/// The number of values in each block written by [`AdaptiveBitpackedEncoder::default`]
+pub const DEFAULT_VALUES_PER_BLOCK: u64 = 1024;
+
+fn mask(bit_width: u64) -> u64 {
+    if bit_width == 64 {
+        u64::MAX
+    } else {
+        (1 << bit_width) - 1
+    }
+}
+
+// The number of bytes needed to pack `num_values` values of `bit_width` bits
+fn packed_size(num_values: u64, bit_width: u64) -> u64 {
+    (num_values * bit_width).div_ceil(8)
+}
+
+// Read the `bit_width` bit value that starts at bit `bit_position`, sign extending it if `signed`
+fn unpack_value(bytes: &[u8], bit_position: u64, bit_width: u64, signed: bool) -> u64 {
+    let first_byte = (bit_position / 8) as usize;
+    let last_byte = ((bit_position + bit_width).div_ceil(8) as usize).min(bytes.len());
+    let word = bytes[first_byte..last_byte]
+        .iter()
+        .enumerate()
+        .fold(0_u128, |word, (i, byte)| {
+            word | ((*byte as u128) << (8 * i))
+        });
+    let value = (word >> (bit_position % 8)) as u64 & mask(bit_width);
+    if signed && bit_width < 64 && (value >> (bit_width - 1)) & 1 == 1 {
+        value | !mask(bit_width)
+    } else {
+        value
+    }
+}
+
+/// A scheduler for integers that were bitpacked with a bit width per block
+///
+/// The header is read first and then only the bytes of the blocks that hold the
+/// requested rows are read.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveBitpackedScheduler {
+    uncompressed_bits_per_value: u64,
+    values_per_block: u64,
+    num_blocks: u64,
+    signed: bool,
+    buffer_offset: u64,
+}
+
+impl AdaptiveBitpackedScheduler {
+    pub fn new(
+        uncompressed_bits_per_value: u64,
+        values_per_block: u64,
+        num_blocks: u64,
+        signed: bool,
+        buffer_offset: u64,
+    ) -> Self {
+        Self {
+            uncompressed_bits_per_value,
+            values_per_block,
+            num_blocks,
+            signed,
+            buffer_offset,
+        }
+    }
+}
+
+// The part of a requested range that falls in a single block
+struct BlockSegment {
+    bit_width: u64,
+    // The position of the first value in the bytes read for the segment
+    bit_offset: u64,
+    num_values: u64,
+}
+
+impl PageScheduler for AdaptiveBitpackedScheduler {
+    fn schedule_ranges(
+        &self,
+        ranges: &[Range<u64>],
+        scheduler: &Arc<dyn EncodingsIo>,
+        top_level_row: u64,
+    ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
+        let header_range = self.buffer_offset..(self.buffer_offset + self.num_blocks);
+        let header = scheduler.submit_request(vec![header_range], top_level_row);
+
+        let io = scheduler.clone();
+        let params = *self;
+        let ranges = ranges.to_vec();
+        async move {
+            let header = header.await?.pop().unwrap();
+            let bit_widths = header.iter().map(|w| *w as u64).collect::<Vec<_>>();
+            if let Some(bit_width) = bit_widths
+                .iter()
+                .find(|w| **w > params.uncompressed_bits_per_value)
+            {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "Adaptive bitpacked block width {} is wider than {} bit values",
+                        bit_width, params.uncompressed_bits_per_value
+                    )
+                    .into(),
+                    location: location!(),
+                });
+            }
+
+            // Every block but the last is full so the position of a block only depends
+            // on the widths of the blocks before it
+            let mut block_starts = Vec::with_capacity(bit_widths.len());
+            let mut position = params.buffer_offset + params.num_blocks;
+            for bit_width in &bit_widths {
+                block_starts.push(position);
+                position += packed_size(params.values_per_block, *bit_width);
+            }
+
+            let mut segments = Vec::new();
+            let mut byte_ranges = Vec::new();
+            for range in ranges {
+                let mut row = range.start;
+                while row < range.end {
+                    let block = row / params.values_per_block;
+                    let block_first_row = block * params.values_per_block;
+                    let end = range.end.min(block_first_row + params.values_per_block);
+                    let Some(bit_width) = bit_widths.get(block as usize).copied() else {
+                        return Err(Error::InvalidInput {
+                            source: format!(
+                                "Row {} is past the last of the {} adaptive bitpacked blocks",
+                                row, params.num_blocks
+                            )
+                            .into(),
+                            location: location!(),
+                        });
+                    };
+                    let start_bit = (row - block_first_row) * bit_width;
+                    let end_bit = (end - block_first_row) * bit_width;
+                    // Blocks with a width of 0 are all zeros and have no bytes
+                    if bit_width > 0 {
+                        let block_start = block_starts[block as usize];
+                        byte_ranges.push(
+                            (block_start + start_bit / 8)..(block_start + end_bit.div_ceil(8)),
+                        );
+                    }
+                    segments.push(BlockSegment {
+                        bit_width,
+                        bit_offset: start_bit % 8,
+                        num_values: end - row,
+                    });
+                    row = end;
+                }
+            }
+
+            let packed = if byte_ranges.is_empty() {
+                Vec::new()
+            } else {
+                io.submit_request(byte_ranges, top_level_row).await?
+            };
+            let mut packed = packed.into_iter();
+
+            let bytes_per_value = params.uncompressed_bits_per_value / 8;
+            let num_values = segments.iter().map(|s| s.num_values).sum::<u64>();
+            let mut data = Vec::with_capacity((num_values * bytes_per_value) as usize);
+            for segment in segments {
+                if segment.bit_width == 0 {
+                    data.resize(
+                        data.len() + (segment.num_values * bytes_per_value) as usize,
+                        0,
+                    );
+                    continue;
+                }
+                let bytes = packed.next().unwrap();
+                for i in 0..segment.num_values {
+                    let value = unpack_value(
+                        &bytes,
+                        segment.bit_offset + i * segment.bit_width,
+                        segment.bit_width,
+                        params.signed,
+                    );
+                    data.extend_from_slice(&value.to_le_bytes()[..bytes_per_value as usize]);
+                }
+            }
+
+            Ok(Box::new(AdaptiveBitpackedDecoder {
+                bytes_per_value,
+                data: LanceBuffer::from(data),
+            }) as Box<dyn PrimitivePageDecoder>)
+        }
+        .boxed()
+    }
+}
+
+struct AdaptiveBitpackedDecoder {
+    bytes_per_value: u64,
+    data: LanceBuffer,
+}
+
+impl PrimitivePageDecoder for AdaptiveBitpackedDecoder {
+    fn decode(&self, rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let offset = (rows_to_skip * self.bytes_per_value) as usize;
+        let length = (num_rows * self.bytes_per_value) as usize;
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            bits_per_value: self.bytes_per_value * 8,
+            data: self.data.slice_with_length(offset, length),
+            num_values: num_rows,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
+// The number of bits needed to store `value` (with a sign bit if `signed`)
+fn bit_width(value: u64, signed: bool) -> u64 {
+    if value == 0 {
+        0
+    } else if !signed {
+        64 - value.leading_zeros() as u64
+    } else if (value as i64) < 0 {
+        65 - (!value).leading_zeros() as u64
+    } else {
+        65 - value.leading_zeros() as u64
+    }
+}
+
+// Pack the low `bit_width` bits of each value, starting from the least significant bit
+fn pack_block(values: &[u64], bit_width: u64, dst: &mut Vec<u8>) {
+    let mut word = 0_u128;
+    let mut num_bits = 0;
+    for value in values {
+        word |= ((*value & mask(bit_width)) as u128) << num_bits;
+        num_bits += bit_width;
+        while num_bits >= 8 {
+            dst.push(word as u8);
+            word >>= 8;
+            num_bits -= 8;
+        }
+    }
+    if num_bits > 0 {
+        dst.push(word as u8);
+    }
+}
+
+/// Encodes integers by bitpacking each block of values with its own bit width
+#[derive(Debug)]
+pub struct AdaptiveBitpackedEncoder {
+    values_per_block: u64,
+}
+
+impl AdaptiveBitpackedEncoder {
+    pub fn new(values_per_block: u64) -> Self {
+        assert!(values_per_block > 0);
+        Self { values_per_block }
+    }
+}
+
+impl Default for AdaptiveBitpackedEncoder {
+    fn default() -> Self {
+        Self::new(DEFAULT_VALUES_PER_BLOCK)
+    }
+}
+
+impl ArrayEncoder for AdaptiveBitpackedEncoder {
+    fn encode(
+        &self,
+        data: DataBlock,
+        data_type: &DataType,
+        buffer_index: &mut u32,
+    ) -> Result<EncodedArray> {
+        let data = match data {
+            DataBlock::FixedWidth(data) if matches!(data.bits_per_value, 8 | 16 | 32 | 64) => data,
+            _ => {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "Cannot encode a data block of type {} with AdaptiveBitpackedEncoder",
+                        data.name()
+                    )
+                    .into(),
+                    location: location!(),
+                })
+            }
+        };
+
+        let signed = data_type.is_signed_integer();
+        let bits_per_value = data.bits_per_value;
+        let values = data
+            .data
+            .chunks_exact((bits_per_value / 8) as usize)
+            .map(|chunk| {
+                let mut bytes = [0; 8];
+                bytes[..chunk.len()].copy_from_slice(chunk);
+                let value = u64::from_le_bytes(bytes);
+                if signed && bits_per_value < 64 && (value >> (bits_per_value - 1)) & 1 == 1 {
+                    value | !mask(bits_per_value)
+                } else {
+                    value
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let blocks = values.chunks(self.values_per_block as usize);
+        let num_blocks = blocks.len() as u64;
+        let mut header = Vec::with_capacity(num_blocks as usize);
+        let mut packed = Vec::new();
+        for block in blocks {
+            let block_width = block
+                .iter()
+                .map(|value| bit_width(*value, signed))
+                .max()
+                .unwrap_or(0);
+            header.push(block_width as u8);
+            pack_block(block, block_width, &mut packed);
+        }
+        header.extend_from_slice(&packed);
+
+        let encoding = ProtobufUtils::adaptive_bitpacked(
+            bits_per_value,
+            self.values_per_block,
+            num_blocks,
+            *buffer_index,
+            signed,
+        );
+        *buffer_index += 1;
+
+        let data = DataBlock::Opaque(OpaqueBlock {
+            buffers: vec![LanceBuffer::from(header)],
+            num_values: data.num_values,
+            block_info: BlockInfo::new(),
+        });
+        Ok(EncodedArray { data, encoding })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{
+        types::{Int64Type, Int8Type, UInt32Type},
+        Array, ArrowPrimitiveType, PrimitiveArray,
+    };
+
+    use crate::{data::DataBlock, testing::schedule_single_page, v2::encoder::ArrayEncoder};
+
+    use super::AdaptiveBitpackedEncoder;
+
+    // Returns the size of the encoded buffer
+    async fn check_round_trip<T: ArrowPrimitiveType>(values: PrimitiveArray<T>) -> usize {
+        let data_type = values.data_type().clone();
+        let num_rows = values.len() as u64;
+
+        let mut buffer_index = 0;
+        let encoded = AdaptiveBitpackedEncoder::new(100)
+            .encode(
+                DataBlock::from_array(values.clone()),
+                &data_type,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+        assert_eq!(buffer_index, 1);
+
+        let encoded_size = buffers.iter().map(|buffer| buffer.len()).sum::<usize>();
+        let (scheduler, io) = schedule_single_page(&encoding, buffers, &data_type).unwrap();
+
+        let ranges = [0..num_rows, 0..1, 95..205, (num_rows - 3)..num_rows];
+        for range in ranges {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = decoded.into_arrow(data_type.clone(), true).unwrap();
+            let expected = values.slice(range.start as usize, num_rows as usize);
+            assert_eq!(PrimitiveArray::<T>::from(decoded), expected);
+        }
+
+        // Multiple ranges and a partial decode
+        let decoder = scheduler
+            .schedule_ranges(&[2..4, 160..170], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(1, 5).unwrap();
+        let decoded = PrimitiveArray::<T>::from(decoded.into_arrow(data_type, true).unwrap());
+        let expected = [3, 160, 161, 162, 163]
+            .iter()
+            .map(|row| values.value(*row))
+            .collect::<Vec<_>>();
+        assert_eq!(decoded.values().to_vec(), expected);
+
+        encoded_size
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_bitpack_round_trip() {
+        // Mostly small values with one block of large values and one block of zeros
+        let skewed = PrimitiveArray::<UInt32Type>::from_iter_values((0..1050).map(|i| match i {
+            300..400 => u32::MAX - i,
+            500..600 => 0,
+            _ => i % 7,
+        }));
+        let encoded_size = check_round_trip(skewed).await;
+        // 11 bytes of header, 8 blocks of 3 bit values, 1 block of 32 bit values, the block
+        // of zeros (which takes no space) and a last block of 50 3 bit values
+        assert_eq!(encoded_size, 11 + 8 * 38 + 400 + 19);
+
+        // Signed values of varying magnitude
+        let signed = PrimitiveArray::<Int64Type>::from_iter_values((0..1000_i64).map(|i| {
+            let magnitude = 1_i64 << ((i / 100) * 6);
+            if i % 2 == 0 {
+                magnitude + i
+            } else {
+                -magnitude - i
+            }
+        }));
+        check_round_trip(signed).await;
+
+        let bytes = PrimitiveArray::<Int8Type>::from_iter_values((0..300).map(|i| {
+            if i < 100 {
+                (i % 3) as i8
+            } else {
+                i8::MIN + (i % 5) as i8
+            }
+        }));
+        check_round_trip(bytes).await;
+    }
+}
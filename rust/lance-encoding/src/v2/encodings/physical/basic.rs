@@ -200,6 +200,7 @@ impl ArrayEncoder for BasicEncoder {
                 Ok(EncodedArray { data, encoding })
             }
             DataBlock::Nullable(nullable) => {
+                let null_count = nullable.null_count();
                 let validity_buffer_index = *buffer_index;
                 *buffer_index += 1;
 
@@ -211,8 +212,11 @@ impl ArrayEncoder for BasicEncoder {
                 let encoded_values =
                     self.values_encoder
                         .encode(*nullable.data, data_type, buffer_index)?;
-                let encoding =
-                    ProtobufUtils::basic_some_null_encoding(validity_desc, encoded_values.encoding);
+                let encoding = ProtobufUtils::basic_some_null_encoding(
+                    validity_desc,
+                    encoded_values.encoding,
+                    Some(null_count),
+                );
                 let encoded = DataBlock::Nullable(NullableDataBlock {
                     data: Box::new(encoded_values.data),
                     nulls: nullable.nulls,
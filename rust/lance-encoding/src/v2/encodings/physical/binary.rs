@@ -50,7 +50,9 @@ impl IndicesNormalizer {
     }
 
     fn normalize(&self, val: u64) -> (bool, u64) {
-        if val >= self.null_adjustment {
+        // A null adjustment of 0 means the writer did not record any nulls in the
+        // indices (e.g. it was never written) and so every index is a valid offset
+        if self.null_adjustment > 0 && val >= self.null_adjustment {
             (false, val - self.null_adjustment)
         } else {
             (true, val)
@@ -81,6 +83,12 @@ impl IndicesNormalizer {
     }
 }
 
+/// Schedules binary data stored as a column of end offsets ("indices") and a column of bytes
+///
+/// Null rows are marked by adding `null_adjustment` to their index.  Writers set the adjustment
+/// to one more than the last offset so that null indices are always `>= null_adjustment` while
+/// valid indices (including those of empty strings) are always less.  An adjustment of 0 means
+/// it was absent and every row is treated as valid.
 #[derive(Debug)]
 pub struct BinaryPageScheduler {
     indices_scheduler: Arc<dyn PageScheduler>,
@@ -521,9 +529,74 @@ impl ArrayEncoder for BinaryEncoder {
 #[cfg(test)]
 mod tests {
     use arrow_array::StringArray;
+    use bytes::Bytes;
+
+    use crate::testing::SimulatedScheduler;
+    use crate::v2::encodings::physical::value::ValuePageScheduler;
 
     use super::*;
 
+    async fn decode_binary(indices: &[u64], bytes: &[u8], null_adjustment: u64) -> DataBlock {
+        let num_rows = indices.len() as u64;
+        let mut data = LanceBuffer::reinterpret_vec(indices.to_vec()).to_vec();
+        let bytes_offset = data.len() as u64;
+        data.extend_from_slice(bytes);
+
+        let indices_scheduler = Arc::new(ValuePageScheduler::new(
+            8,
+            0,
+            bytes_offset,
+            CompressionConfig::default(),
+        ));
+        let bytes_scheduler = Arc::new(ValuePageScheduler::new(
+            1,
+            bytes_offset,
+            bytes.len() as u64,
+            CompressionConfig::default(),
+        ));
+        let scheduler = BinaryPageScheduler::new(
+            indices_scheduler,
+            bytes_scheduler,
+            DataType::Int32,
+            null_adjustment,
+        );
+
+        let io = Arc::new(SimulatedScheduler::new(Bytes::from(data))) as Arc<dyn EncodingsIo>;
+        let decoder = scheduler
+            .schedule_ranges(&[0..num_rows], &io, 0)
+            .await
+            .unwrap();
+        decoder.decode(0, num_rows).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_decode_empty_vs_null_strings() {
+        // ["a", "", NULL, "bc"] where the NULL index is adjusted by 3 + 1
+        let decoded = decode_binary(&[1, 1, 5, 3], b"abc", 4).await;
+        let decoded = decoded.as_nullable().unwrap();
+        assert_eq!(
+            BooleanBuffer::new(decoded.nulls.into_buffer(), 0, 4),
+            BooleanBuffer::from(vec![true, true, false, true])
+        );
+        let strings = decoded.data.as_variable_width().unwrap();
+        assert_eq!(
+            strings.offsets,
+            LanceBuffer::reinterpret_vec(vec![0_i32, 1, 1, 1, 3])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decode_missing_null_adjustment() {
+        // ["a", "", "", "bc"] written without a null adjustment, nothing is null
+        let decoded = decode_binary(&[1, 1, 1, 3], b"abc", 0).await;
+        let strings = decoded.as_variable_width().unwrap();
+        assert_eq!(
+            strings.offsets,
+            LanceBuffer::reinterpret_vec(vec![0_i32, 1, 1, 1, 3])
+        );
+        assert_eq!(strings.data, LanceBuffer::from(b"abc".to_vec()));
+    }
+
     #[test]
     fn test_encode_indices_adjusts_nulls() {
         // Null entries in string arrays should be adjusted
@@ -123,18 +123,129 @@ impl PrimitivePageDecoder for BitmapDecoder {
     }
 }
 
+/// A physical scheduler for small integers densely packed with 2 or 4 bits per value
+///
+/// Values are packed least significant bits first, in the same order as a bitmap, so
+/// each byte holds 4 (2-bit) or 2 (4-bit) values.  Values are unpacked into 8-bit
+/// integers and are zero-extended (e.g. for small enums) so they can't be negative.
+#[derive(Debug, Clone, Copy)]
+pub struct SubBytePackedScheduler {
+    buffer_offset: u64,
+    bits_per_value: u64,
+}
+
+impl SubBytePackedScheduler {
+    pub fn new(buffer_offset: u64, bits_per_value: u64) -> Self {
+        debug_assert!(bits_per_value == 2 || bits_per_value == 4);
+        Self {
+            buffer_offset,
+            bits_per_value,
+        }
+    }
+}
+
+impl PageScheduler for SubBytePackedScheduler {
+    fn schedule_ranges(
+        &self,
+        ranges: &[Range<u64>],
+        scheduler: &Arc<dyn EncodingsIo>,
+        top_level_row: u64,
+    ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
+        let values_per_byte = 8 / self.bits_per_value;
+        let chunk_reqs = ranges
+            .iter()
+            .map(|range| {
+                debug_assert_ne!(range.start, range.end);
+                let start = self.buffer_offset + range.start / values_per_byte;
+                let end = self.buffer_offset + range.end.div_ceil(values_per_byte);
+                (
+                    start..end,
+                    range.start % values_per_byte,
+                    range.end - range.start,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let byte_ranges = chunk_reqs
+            .iter()
+            .map(|(range, _, _)| range.clone())
+            .collect::<Vec<_>>();
+        let bytes = scheduler.submit_request(byte_ranges, top_level_row);
+        let bits_per_value = self.bits_per_value;
+
+        async move {
+            let bytes = bytes.await?;
+            let chunks = bytes
+                .into_iter()
+                .zip(chunk_reqs)
+                .map(|(bytes, (_, value_offset, length))| BitmapData {
+                    data: bytes,
+                    bit_offset: value_offset * bits_per_value,
+                    length,
+                })
+                .collect::<Vec<_>>();
+            Ok(Box::new(SubBytePackedDecoder {
+                chunks,
+                bits_per_value,
+            }) as Box<dyn PrimitivePageDecoder>)
+        }
+        .boxed()
+    }
+}
+
+struct SubBytePackedDecoder {
+    chunks: Vec<BitmapData>,
+    bits_per_value: u64,
+}
+
+impl PrimitivePageDecoder for SubBytePackedDecoder {
+    fn decode(&self, rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let mask = (1_u8 << self.bits_per_value) - 1;
+        let mut rows_to_skip = rows_to_skip;
+        let mut values = Vec::with_capacity(num_rows as usize);
+
+        let mut rows_remaining = num_rows;
+        for chunk in &self.chunks {
+            if rows_remaining == 0 {
+                break;
+            }
+            if chunk.length <= rows_to_skip {
+                rows_to_skip -= chunk.length;
+            } else {
+                let num_vals_to_take = rows_remaining.min(chunk.length - rows_to_skip);
+                let start_bit = chunk.bit_offset + rows_to_skip * self.bits_per_value;
+                values.extend((0..num_vals_to_take).map(|idx| {
+                    let bit = start_bit + idx * self.bits_per_value;
+                    (chunk.data[(bit / 8) as usize] >> (bit % 8)) & mask
+                }));
+                rows_to_skip = 0;
+                rows_remaining -= num_vals_to_take;
+            }
+        }
+
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::from(values),
+            bits_per_value: 8,
+            num_values: num_rows,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use arrow_array::BooleanArray;
+    use arrow_array::{Array, BooleanArray, Int8Array};
     use arrow_schema::{DataType, Field};
     use bytes::Bytes;
     use rstest::rstest;
     use std::{collections::HashMap, sync::Arc};
 
     use crate::decoder::PrimitivePageDecoder;
+    use crate::format::ProtobufUtils;
     use crate::testing::{
-        check_round_trip_encoding_of_data, check_round_trip_encoding_random, TestCases,
+        check_round_trip_encoding_of_data, check_round_trip_encoding_random, schedule_single_page,
+        TestCases,
     };
     use crate::v2::encodings::physical::bitmap::BitmapData;
     use crate::version::LanceFileVersion;
@@ -223,4 +334,57 @@ mod tests {
         let result = decoder.decode(5, 1);
         assert!(result.is_ok());
     }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_sub_byte_packed_int8(#[values(2, 4)] bits_per_value: u64) {
+        let num_rows = 101_u64;
+        let values =
+            Int8Array::from_iter_values((0..num_rows).map(|i| (i % (1 << bits_per_value)) as i8));
+
+        // Pack the values, least significant bits first
+        let mut packed = vec![0_u8; (num_rows * bits_per_value).div_ceil(8) as usize];
+        for (idx, value) in values.values().iter().enumerate() {
+            let bit = idx as u64 * bits_per_value;
+            packed[(bit / 8) as usize] |= (*value as u8) << (bit % 8);
+        }
+        let encoding = ProtobufUtils::flat_encoding(bits_per_value, 0, None);
+        let (scheduler, io) = schedule_single_page(&encoding, [packed], &DataType::Int8).unwrap();
+
+        for range in [0..num_rows, 1..2, 3..50, (num_rows - 3)..num_rows] {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = Int8Array::from(decoded.into_arrow(DataType::Int8, true).unwrap());
+            assert_eq!(
+                decoded,
+                values.slice(range.start as usize, num_rows as usize)
+            );
+        }
+
+        // Multiple ranges and a partial decode
+        let decoder = scheduler
+            .schedule_ranges(&[2..5, 97..100], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(1, 4).unwrap();
+        let decoded = Int8Array::from(decoded.into_arrow(DataType::Int8, true).unwrap());
+        let expected = [3, 4, 97, 98]
+            .iter()
+            .map(|i| values.value(*i))
+            .collect::<Vec<_>>();
+        assert_eq!(decoded.values().to_vec(), expected);
+    }
+
+    #[test]
+    fn test_unsupported_flat_width() {
+        let encoding = ProtobufUtils::flat_encoding(3, 0, None);
+        let err = schedule_single_page(&encoding, [[0_u8; 10]], &DataType::Int8)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("3 bits per value"), "{}", err);
+    }
 }
@@ -308,6 +308,7 @@ impl ArrayEncoder for BitpackedForNonNegArrayEncoder {
                 }
             }
             DataBlock::Nullable(nullable) => {
+                let null_count = nullable.null_count();
                 let validity_buffer_index = *buffer_index;
                 *buffer_index += 1;
 
@@ -334,8 +335,11 @@ impl ArrayEncoder for BitpackedForNonNegArrayEncoder {
                         });
                     }
                 }
-                let encoding =
-                    ProtobufUtils::basic_some_null_encoding(validity_desc, encoded_values.encoding);
+                let encoding = ProtobufUtils::basic_some_null_encoding(
+                    validity_desc,
+                    encoded_values.encoding,
+                    Some(null_count),
+                );
                 let encoded = DataBlock::Nullable(NullableDataBlock {
                     data: Box::new(encoded_values.data),
                     nulls: nullable.nulls,
@@ -903,6 +907,54 @@ impl PageScheduler for BitpackedScheduler {
     }
 }
 
+// A physical scheduler for bitpacked buffers with a compressed width of 0
+//
+// Every value is zero so nothing needs to be read from the buffer
+#[derive(Debug, Clone, Copy)]
+pub struct ZeroWidthBitpackedScheduler {
+    uncompressed_bits_per_value: u64,
+}
+
+impl ZeroWidthBitpackedScheduler {
+    pub fn new(uncompressed_bits_per_value: u64) -> Self {
+        Self {
+            uncompressed_bits_per_value,
+        }
+    }
+}
+
+impl PageScheduler for ZeroWidthBitpackedScheduler {
+    fn schedule_ranges(
+        &self,
+        _ranges: &[std::ops::Range<u64>],
+        _scheduler: &Arc<dyn crate::EncodingsIo>,
+        _top_level_row: u64,
+    ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
+        let uncompressed_bits_per_value = self.uncompressed_bits_per_value;
+        std::future::ready(Ok(Box::new(ZeroWidthBitpackedPageDecoder {
+            uncompressed_bits_per_value,
+        }) as Box<dyn PrimitivePageDecoder>))
+        .boxed()
+    }
+}
+
+#[derive(Debug)]
+struct ZeroWidthBitpackedPageDecoder {
+    uncompressed_bits_per_value: u64,
+}
+
+impl PrimitivePageDecoder for ZeroWidthBitpackedPageDecoder {
+    fn decode(&self, _rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let num_bytes = self.uncompressed_bits_per_value / 8 * num_rows;
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::from(vec![0; num_bytes as usize]),
+            bits_per_value: self.uncompressed_bits_per_value,
+            num_values: num_rows,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
 #[derive(Debug)]
 struct BitpackedPageDecoder {
     // bit offsets of the first value within each buffer
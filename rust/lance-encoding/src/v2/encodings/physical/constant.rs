@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Constant encoding for the 2.0 format
+//!
+//! A page where every value is the same is stored as just that value, inline in
+//! the encoding description.  There are no buffers so decoding never reads any
+//! data, the value is repeated once for each requested row.
+//!
+//! For example, `[7, 7, 7, 7]` is stored as the value `7` (in its little-endian
+//! fixed-width representation).
+
+use std::{ops::Range, sync::Arc};
+
+use arrow_schema::DataType;
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt};
+use snafu::location;
+
+use lance_core::{Error, Result};
+
+use crate::{
+    buffer::LanceBuffer,
+    data::{BlockInfo, DataBlock, FixedWidthDataBlock, OpaqueBlock},
+    decoder::{PageScheduler, PrimitivePageDecoder},
+    format::ProtobufUtils,
+    v2::encoder::{ArrayEncoder, EncodedArray},
+    EncodingsIo,
+};
+
+/// A physical scheduler for a page of a single repeated fixed-width value
+#[derive(Debug, Clone)]
+pub struct ConstantScheduler {
+    value: Bytes,
+}
+
+impl ConstantScheduler {
+    pub fn new(value: Bytes) -> Self {
+        Self { value }
+    }
+}
+
+impl PageScheduler for ConstantScheduler {
+    fn schedule_ranges(
+        &self,
+        _ranges: &[Range<u64>],
+        _scheduler: &Arc<dyn EncodingsIo>,
+        _top_level_row: u64,
+    ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
+        let value = self.value.clone();
+        std::future::ready(Ok(
+            Box::new(ConstantDecoder { value }) as Box<dyn PrimitivePageDecoder>
+        ))
+        .boxed()
+    }
+}
+
+struct ConstantDecoder {
+    value: Bytes,
+}
+
+impl PrimitivePageDecoder for ConstantDecoder {
+    fn decode(&self, _rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let mut data = Vec::with_capacity(self.value.len() * num_rows as usize);
+        for _ in 0..num_rows {
+            data.extend_from_slice(&self.value);
+        }
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            bits_per_value: self.value.len() as u64 * 8,
+            data: LanceBuffer::from(data),
+            num_values: num_rows,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
+/// Validate the value of a constant encoding for a column of type `data_type`
+///
+/// Only fixed-width types can be constant encoded, the decoder repeats the value as
+/// a fixed-width block.
+pub fn check_constant_value(value: &[u8], data_type: &DataType) -> Result<()> {
+    let expected_width = match data_type {
+        DataType::FixedSizeBinary(width) => Some(*width as usize),
+        // Booleans are bit-packed and can't be repeated byte by byte
+        DataType::Boolean => None,
+        _ if data_type.is_primitive() => data_type.primitive_width(),
+        _ => None,
+    };
+    let Some(expected_width) = expected_width else {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "Invalid constant encoding: {} is not a fixed-width type",
+                data_type
+            )
+            .into(),
+            location: location!(),
+        });
+    };
+    if value.len() == expected_width {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput {
+            source: format!(
+                "Invalid constant encoding: a {} byte value cannot be decoded as {}",
+                value.len(),
+                data_type
+            )
+            .into(),
+            location: location!(),
+        })
+    }
+}
+
+/// Encodes a page of fixed-width values that are all the same as that single value
+///
+/// Encoding fails if the values are not all equal.
+#[derive(Debug, Default)]
+pub struct ConstantEncoder {}
+
+impl ConstantEncoder {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ArrayEncoder for ConstantEncoder {
+    fn encode(
+        &self,
+        data: DataBlock,
+        _data_type: &DataType,
+        _buffer_index: &mut u32,
+    ) -> Result<EncodedArray> {
+        let data = match data {
+            DataBlock::FixedWidth(data) if data.bits_per_value % 8 == 0 && data.num_values > 0 => {
+                data
+            }
+            _ => {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "Cannot encode a data block of type {} with ConstantEncoder",
+                        data.name()
+                    )
+                    .into(),
+                    location: location!(),
+                })
+            }
+        };
+
+        let bytes_per_value = (data.bits_per_value / 8) as usize;
+        let mut values = data.data.chunks_exact(bytes_per_value);
+        let value = values.next().unwrap();
+        if values.any(|other| other != value) {
+            return Err(Error::InvalidInput {
+                source: "Cannot encode values that are not all equal with ConstantEncoder".into(),
+                location: location!(),
+            });
+        }
+
+        let encoding = ProtobufUtils::constant(value.to_vec());
+        let data = DataBlock::Opaque(OpaqueBlock {
+            buffers: vec![],
+            num_values: data.num_values,
+            block_info: BlockInfo::new(),
+        });
+        Ok(EncodedArray { data, encoding })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Array, Int32Array, Int64Array};
+    use arrow_schema::DataType;
+
+    use crate::{data::DataBlock, testing::schedule_single_page, v2::encoder::ArrayEncoder};
+
+    use super::ConstantEncoder;
+
+    #[tokio::test]
+    async fn test_constant_round_trip() {
+        let values = Int64Array::from(vec![42; 1000]);
+        let num_rows = values.len() as u64;
+
+        let mut buffer_index = 0;
+        let encoded = ConstantEncoder::new()
+            .encode(
+                DataBlock::from_array(values.clone()),
+                &DataType::Int64,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+        assert_eq!(buffer_index, 0);
+        assert!(buffers.is_empty());
+
+        // There is no data, any read would fail
+        let (scheduler, io) = schedule_single_page(&encoding, &buffers, &DataType::Int64).unwrap();
+
+        for range in [0..num_rows, 5..40, (num_rows - 1)..num_rows] {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = Int64Array::from(decoded.into_arrow(DataType::Int64, true).unwrap());
+            assert_eq!(
+                decoded,
+                values.slice(range.start as usize, num_rows as usize)
+            );
+        }
+
+        // Values that differ can't be encoded
+        let err = ConstantEncoder::new()
+            .encode(
+                DataBlock::from_array(Int32Array::from(vec![1, 1, 2])),
+                &DataType::Int32,
+                &mut buffer_index,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("not all equal"), "{}", err);
+
+        // The value must match the width of the column
+        let err = schedule_single_page(&encoding, &buffers, &DataType::Int32).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("8 byte value cannot be decoded as Int32"),
+            "{}",
+            err
+        );
+
+        // Only fixed-width columns can be constant encoded
+        for data_type in [DataType::Utf8, DataType::Boolean] {
+            let err = schedule_single_page(&encoding, &buffers, &data_type).unwrap_err();
+            assert!(
+                err.to_string().contains("is not a fixed-width type"),
+                "{}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Delta encoding for integers in the 2.0 format
+//!
+//! The first value of the page is stored as the base and every value is stored as
+//! the difference from the previous value (the first delta is always 0).  For example,
+//! `[100, 101, 103, 106]` is stored as base `100` and deltas `[0, 1, 2, 3]`.
+//!
+//! Sorted columns like ids and timestamps have small deltas which compress well with
+//! bitpacking so the deltas are themselves encoded with another encoding.
+
+use std::{ops::Range, sync::Arc};
+
+use arrow_buffer::ArrowNativeType;
+use arrow_schema::DataType;
+use futures::{future::BoxFuture, FutureExt};
+use num_traits::{WrappingAdd, WrappingSub};
+use snafu::location;
+
+use lance_core::{Error, Result};
+
+use crate::{
+    buffer::LanceBuffer,
+    data::{BlockInfo, DataBlock, FixedWidthDataBlock},
+    decoder::{PageScheduler, PrimitivePageDecoder},
+    format::ProtobufUtils,
+    v2::encoder::{ArrayEncoder, EncodedArray},
+    EncodingsIo,
+};
+
+use super::value::ExpandedValuesDecoder;
+
+/// A scheduler for delta encoded integers
+///
+/// Every value depends on all of the values before it so we always load the deltas from
+/// the start of the page up to the end of the last requested range.
+#[derive(Debug)]
+pub struct DeltaScheduler {
+    deltas_scheduler: Box<dyn PageScheduler>,
+    bits_per_value: u64,
+    base: i64,
+}
+
+impl DeltaScheduler {
+    pub fn new(deltas_scheduler: Box<dyn PageScheduler>, bits_per_value: u64, base: i64) -> Self {
+        Self {
+            deltas_scheduler,
+            bits_per_value,
+            base,
+        }
+    }
+}
+
+impl PageScheduler for DeltaScheduler {
+    fn schedule_ranges(
+        &self,
+        ranges: &[Range<u64>],
+        scheduler: &Arc<dyn EncodingsIo>,
+        top_level_row: u64,
+    ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
+        let num_deltas = ranges.last().map(|range| range.end).unwrap_or(0);
+        let deltas_decoder =
+            self.deltas_scheduler
+                .schedule_ranges(&[0..num_deltas], scheduler, top_level_row);
+
+        let bits_per_value = self.bits_per_value;
+        let base = self.base;
+        let ranges = ranges.to_vec();
+        async move {
+            let deltas = deltas_decoder.await?.decode(0, num_deltas)?;
+            let deltas = deltas.as_fixed_width().ok_or_else(|| Error::InvalidInput {
+                source: "Delta encoded deltas must be fixed width".into(),
+                location: location!(),
+            })?;
+            let data = match bits_per_value {
+                32 => prefix_sum::<i32>(deltas.data, base as i32, &ranges),
+                64 => prefix_sum::<i64>(deltas.data, base, &ranges),
+                _ => {
+                    return Err(Error::InvalidInput {
+                        source: format!(
+                            "Delta encoding does not support {} bits per value",
+                            bits_per_value
+                        )
+                        .into(),
+                        location: location!(),
+                    })
+                }
+            };
+            Ok(
+                Box::new(ExpandedValuesDecoder::new(bits_per_value / 8, data))
+                    as Box<dyn PrimitivePageDecoder>,
+            )
+        }
+        .boxed()
+    }
+}
+
+// Reconstruct the values from the start of the page and keep the ones in `ranges`
+fn prefix_sum<T: ArrowNativeType + WrappingAdd>(
+    mut deltas: LanceBuffer,
+    base: T,
+    ranges: &[Range<u64>],
+) -> LanceBuffer {
+    let deltas = deltas.borrow_to_typed_slice::<T>();
+    let num_values = ranges.iter().map(|r| r.end - r.start).sum::<u64>();
+    let mut values = Vec::with_capacity(num_values as usize);
+
+    let mut ranges = ranges.iter().peekable();
+    let mut value = base;
+    for (row, delta) in deltas.iter().enumerate() {
+        let Some(range) = ranges.peek() else {
+            break;
+        };
+        value = value.wrapping_add(delta);
+        if range.contains(&(row as u64)) {
+            values.push(value);
+        }
+        if row as u64 + 1 == range.end {
+            ranges.next();
+        }
+    }
+    LanceBuffer::reinterpret_vec(values)
+}
+
+/// Encodes 32 or 64 bit integers as deltas from the previous value
+///
+/// The deltas are encoded with the given encoder.
+#[derive(Debug)]
+pub struct DeltaEncoder {
+    deltas_encoder: Box<dyn ArrayEncoder>,
+}
+
+impl DeltaEncoder {
+    pub fn new(deltas_encoder: Box<dyn ArrayEncoder>) -> Self {
+        Self { deltas_encoder }
+    }
+}
+
+fn compute_deltas<T: ArrowNativeType + WrappingSub>(mut data: LanceBuffer) -> (T, LanceBuffer) {
+    let values = data.borrow_to_typed_slice::<T>();
+    let base = values.first().copied().unwrap_or_default();
+    let mut prev = base;
+    let deltas = values
+        .iter()
+        .map(|value| {
+            let delta = value.wrapping_sub(&prev);
+            prev = *value;
+            delta
+        })
+        .collect::<Vec<_>>();
+    (base, LanceBuffer::reinterpret_vec(deltas))
+}
+
+impl ArrayEncoder for DeltaEncoder {
+    fn encode(
+        &self,
+        data: DataBlock,
+        data_type: &DataType,
+        buffer_index: &mut u32,
+    ) -> Result<EncodedArray> {
+        let (data, base, deltas) = match data {
+            DataBlock::FixedWidth(data) if data.bits_per_value == 32 => {
+                let (base, deltas) = compute_deltas::<i32>(data.data.clone());
+                (data, base as i64, deltas)
+            }
+            DataBlock::FixedWidth(data) if data.bits_per_value == 64 => {
+                let (base, deltas) = compute_deltas::<i64>(data.data.clone());
+                (data, base, deltas)
+            }
+            _ => {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "Cannot encode a data block of type {} with DeltaEncoder",
+                        data.name()
+                    )
+                    .into(),
+                    location: location!(),
+                })
+            }
+        };
+
+        let deltas = DataBlock::FixedWidth(FixedWidthDataBlock {
+            bits_per_value: data.bits_per_value,
+            data: deltas,
+            num_values: data.num_values,
+            block_info: BlockInfo::new(),
+        });
+        let encoded_deltas = self
+            .deltas_encoder
+            .encode(deltas, data_type, buffer_index)?;
+        let encoding = ProtobufUtils::delta(data.bits_per_value, base, encoded_deltas.encoding);
+        Ok(EncodedArray {
+            data: encoded_deltas.data,
+            encoding,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{
+        types::{Int32Type, TimestampMicrosecondType},
+        Array, ArrowPrimitiveType, PrimitiveArray,
+    };
+
+    use crate::{
+        data::DataBlock, encodings::physical::value::ValueEncoder, testing::schedule_single_page,
+        v2::encoder::ArrayEncoder,
+    };
+
+    use super::DeltaEncoder;
+
+    async fn check_round_trip<T: ArrowPrimitiveType>(values: PrimitiveArray<T>) {
+        let data_type = values.data_type().clone();
+        let num_rows = values.len() as u64;
+
+        let mut buffer_index = 0;
+        let encoded = DeltaEncoder::new(Box::<ValueEncoder>::default())
+            .encode(
+                DataBlock::from_array(values.clone()),
+                &data_type,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+
+        let (scheduler, io) = schedule_single_page(&encoding, buffers, &data_type).unwrap();
+
+        let ranges = [0..num_rows, 0..1, 5..40, (num_rows - 3)..num_rows];
+        for range in ranges {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = decoded.into_arrow(data_type.clone(), true).unwrap();
+            let expected = values.slice(range.start as usize, num_rows as usize);
+            assert_eq!(PrimitiveArray::<T>::from(decoded), expected);
+        }
+
+        // Multiple ranges and a partial decode
+        let decoder = scheduler
+            .schedule_ranges(&[2..4, 60..70], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(1, 5).unwrap();
+        let decoded = PrimitiveArray::<T>::from(decoded.into_arrow(data_type, true).unwrap());
+        let expected = [3, 60, 61, 62, 63]
+            .iter()
+            .map(|row| values.value(*row))
+            .collect::<Vec<_>>();
+        assert_eq!(decoded.values().to_vec(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_delta_round_trip() {
+        // A monotonically increasing timestamp column with irregular gaps
+        let start = 1_700_000_000_000_000_i64;
+        let timestamps = PrimitiveArray::<TimestampMicrosecondType>::from_iter_values(
+            (0..1000_i64).map(|i| start + i * 1_000_000 + (i * i) % 997),
+        );
+        check_round_trip(timestamps).await;
+
+        // Deltas that wrap around
+        let ints = PrimitiveArray::<Int32Type>::from_iter_values((0..1000).map(|i| {
+            if i % 2 == 0 {
+                i32::MAX - i
+            } else {
+                i32::MIN + i
+            }
+        }));
+        check_round_trip(ints).await;
+    }
+}
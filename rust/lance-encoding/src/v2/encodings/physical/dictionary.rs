@@ -4,12 +4,13 @@
 use std::sync::Arc;
 use std::vec;
 
-use arrow_array::builder::{ArrayBuilder, StringBuilder};
+use arrow_array::builder::{ArrayBuilder, BooleanBufferBuilder, StringBuilder};
 use arrow_array::cast::AsArray;
 use arrow_array::types::UInt8Type;
 use arrow_array::{
     make_array, new_null_array, Array, ArrayRef, DictionaryArray, StringArray, UInt8Array,
 };
+use arrow_cast::CastOptions;
 use arrow_schema::DataType;
 use futures::{future::BoxFuture, FutureExt};
 use lance_arrow::DataTypeExt;
@@ -19,8 +20,8 @@ use std::collections::HashMap;
 
 use crate::buffer::LanceBuffer;
 use crate::data::{
-    BlockInfo, DataBlock, DictionaryDataBlock, FixedWidthDataBlock, NullableDataBlock,
-    VariableWidthBlock,
+    AllNullDataBlock, BlockInfo, DataBlock, DictionaryDataBlock, FixedWidthDataBlock,
+    NullableDataBlock, VariableWidthBlock,
 };
 use crate::format::ProtobufUtils;
 use crate::v2::decoder::LogicalPageDecoder;
@@ -31,6 +32,21 @@ use crate::{
     EncodingsIo,
 };
 
+/// How pages that were dictionary encoded as an optimization are decoded
+///
+/// This does not apply to data that was written as a dictionary, that data is always
+/// decoded as a dictionary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DictionaryDecodeMode {
+    /// Expand the indices into the dictionary values
+    #[default]
+    Values,
+    /// Return the indices along with the decoded dictionary, without expanding them
+    ///
+    /// The output is a `Dictionary(UInt8, _)` array and null values are null indices.
+    Indices,
+}
+
 #[derive(Debug)]
 pub struct DictionaryPageScheduler {
     indices_scheduler: Arc<dyn PageScheduler>,
@@ -40,6 +56,9 @@ pub struct DictionaryPageScheduler {
     // If true, decode the dictionary items.  If false, leave them dictionary encoded (e.g. the
     // output type is probably a dictionary type)
     should_decode_dict: bool,
+    decode_mode: DictionaryDecodeMode,
+    // The type the indices decode to
+    indices_type: DataType,
 }
 
 impl DictionaryPageScheduler {
@@ -54,8 +73,25 @@ impl DictionaryPageScheduler {
             items_scheduler,
             num_dictionary_items,
             should_decode_dict,
+            decode_mode: DictionaryDecodeMode::default(),
+            indices_type: DataType::UInt8,
         }
     }
+
+    /// Set the type the indices decode to (defaults to `UInt8`)
+    ///
+    /// This should come from the indices encoding since the indices may be stored with
+    /// fewer bits (e.g. bitpacked) than they decode to.
+    pub fn with_indices_type(mut self, indices_type: DataType) -> Self {
+        self.indices_type = indices_type;
+        self
+    }
+
+    /// Set how the dictionary is decoded if `should_decode_dict` is true
+    pub fn with_decode_mode(mut self, decode_mode: DictionaryDecodeMode) -> Self {
+        self.decode_mode = decode_mode;
+        self
+    }
 }
 
 impl PageScheduler for DictionaryPageScheduler {
@@ -72,6 +108,14 @@ impl PageScheduler for DictionaryPageScheduler {
         // We decode all the items during scheduling itself
         // These are used to rebuild the string later
 
+        // An empty dictionary can only be indexed by nulls so there is nothing to read
+        if self.num_dictionary_items == 0 {
+            return std::future::ready(Ok(
+                Box::new(AllNullPageDecoder) as Box<dyn PrimitivePageDecoder>
+            ))
+            .boxed();
+        }
+
         // Schedule indices for decoding
         let indices_page_decoder =
             self.indices_scheduler
@@ -86,8 +130,24 @@ impl PageScheduler for DictionaryPageScheduler {
         );
 
         let copy_size = self.num_dictionary_items as u64;
+        let indices_type = self.indices_type.clone();
 
-        if self.should_decode_dict {
+        if self.should_decode_dict && self.decode_mode == DictionaryDecodeMode::Indices {
+            tokio::spawn(async move {
+                let items_decoder = items_page_decoder.await?;
+                let decoded_dict = items_decoder.decode(0, copy_size)?.borrow_and_clone();
+
+                let indices_decoder = indices_page_decoder.await?;
+
+                Ok(Box::new(DictionaryIndicesPageDecoder {
+                    decoded_dict,
+                    indices_decoder,
+                    indices_type,
+                }) as Box<dyn PrimitivePageDecoder>)
+            })
+            .map(|join_handle| join_handle.unwrap())
+            .boxed()
+        } else if self.should_decode_dict {
             tokio::spawn(async move {
                 let items_decoder: Arc<dyn PrimitivePageDecoder> =
                     Arc::from(items_page_decoder.await?);
@@ -109,6 +169,7 @@ impl PageScheduler for DictionaryPageScheduler {
                 Ok(Box::new(DictionaryPageDecoder {
                     decoded_dict,
                     indices_decoder,
+                    indices_type,
                 }) as Box<dyn PrimitivePageDecoder>)
             })
             .map(|join_handle| join_handle.unwrap())
@@ -136,6 +197,17 @@ impl PageScheduler for DictionaryPageScheduler {
     }
 }
 
+// Decodes a page whose dictionary is empty, every value is null
+struct AllNullPageDecoder;
+
+impl PrimitivePageDecoder for AllNullPageDecoder {
+    fn decode(&self, _rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        Ok(DataBlock::AllNull(AllNullDataBlock {
+            num_values: num_rows,
+        }))
+    }
+}
+
 struct DirectDictionaryPageDecoder {
     decoded_dict: DataBlock,
     indices_decoder: Box<dyn PrimitivePageDecoder>,
@@ -156,9 +228,69 @@ impl PrimitivePageDecoder for DirectDictionaryPageDecoder {
     }
 }
 
+// Decode the indices of a page that was dictionary encoded as an optimization
+//
+// The indices may decode to any unsigned width but there are never more than 255 items
+// (plus the null index) so they are narrowed to u8.
+fn decode_u8_indices(indices: DataBlock, indices_type: &DataType) -> Result<UInt8Array> {
+    let indices = make_array(indices.into_arrow(indices_type.clone(), false)?);
+    if indices_type == &DataType::UInt8 {
+        return Ok(indices.as_primitive::<UInt8Type>().clone());
+    }
+    // Not safe, an index that doesn't fit in a u8 is an error and not a null
+    let cast_options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let indices = arrow_cast::cast_with_options(&indices, &DataType::UInt8, &cast_options)?;
+    Ok(indices.as_primitive::<UInt8Type>().clone())
+}
+
+// Decodes the indices of a string page that was dictionary encoded as an optimization
+//
+// These indices are offset by one, 0 is reserved for nulls.
+struct DictionaryIndicesPageDecoder {
+    decoded_dict: DataBlock,
+    indices_decoder: Box<dyn PrimitivePageDecoder>,
+    indices_type: DataType,
+}
+
+impl PrimitivePageDecoder for DictionaryIndicesPageDecoder {
+    fn decode(&self, rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let indices = self.indices_decoder.decode(rows_to_skip, num_rows)?;
+        let indices = decode_u8_indices(indices, &self.indices_type)?;
+
+        let mut validity = BooleanBufferBuilder::new(num_rows as usize);
+        let adjusted_indices = indices
+            .values()
+            .iter()
+            .map(|index| {
+                validity.append(*index != 0);
+                index.saturating_sub(1)
+            })
+            .collect::<Vec<_>>();
+
+        let dict = DataBlock::Dictionary(DictionaryDataBlock {
+            indices: FixedWidthDataBlock {
+                bits_per_value: 8,
+                data: LanceBuffer::from(adjusted_indices),
+                num_values: num_rows,
+                block_info: BlockInfo::new(),
+            },
+            dictionary: Box::new(self.decoded_dict.try_clone()?),
+        });
+        Ok(DataBlock::Nullable(NullableDataBlock {
+            data: Box::new(dict),
+            nulls: LanceBuffer::Borrowed(validity.finish().into_inner()),
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
 struct DictionaryPageDecoder {
     decoded_dict: Arc<dyn Array>,
     indices_decoder: Box<dyn PrimitivePageDecoder>,
+    indices_type: DataType,
 }
 
 impl PrimitivePageDecoder for DictionaryPageDecoder {
@@ -166,8 +298,7 @@ impl PrimitivePageDecoder for DictionaryPageDecoder {
         // Decode the indices
         let indices_data = self.indices_decoder.decode(rows_to_skip, num_rows)?;
 
-        let indices_array = make_array(indices_data.into_arrow(DataType::UInt8, false)?);
-        let indices_array = indices_array.as_primitive::<UInt8Type>();
+        let indices_array = decode_u8_indices(indices_data, &self.indices_type)?;
 
         let dictionary = self.decoded_dict.clone();
 
@@ -409,17 +540,35 @@ pub mod tests {
 
     use arrow_array::{
         builder::{LargeStringBuilder, StringBuilder},
-        ArrayRef, StringArray, UInt8Array,
+        cast::AsArray,
+        make_array,
+        types::UInt8Type,
+        Array, ArrayRef, StringArray, UInt16Array, UInt8Array,
     };
     use arrow_schema::{DataType, Field};
-    use std::{collections::HashMap, sync::Arc, vec};
+    use bytes::Bytes;
+    use futures::future::BoxFuture;
+    use std::{collections::HashMap, ops::Range, sync::Arc, vec};
 
     use crate::{
-        testing::{check_round_trip_encoding_of_data, check_round_trip_encoding_random, TestCases},
+        data::DataBlock,
+        encodings::physical::value::ValueEncoder,
+        format::ProtobufUtils,
+        testing::{
+            check_round_trip_encoding_of_data, check_round_trip_encoding_random,
+            schedule_single_page, schedule_single_page_with_dictionary_mode, TestCases,
+        },
+        v2::{
+            encoder::ArrayEncoder,
+            encodings::physical::{
+                basic::BasicEncoder, binary::BinaryEncoder, bitpack::BitpackedArrayEncoder,
+            },
+        },
         version::LanceFileVersion,
+        EncodingsIo,
     };
 
-    use super::encode_dict_indices_and_items;
+    use super::{encode_dict_indices_and_items, DictionaryDecodeMode, DictionaryEncoder};
 
     // These tests cover the case where we opportunistically convert some (or all) pages of
     // a string column into dictionaries (and decode on read)
@@ -563,6 +712,172 @@ pub mod tests {
         check_round_trip_encoding_of_data(arrs, &test_cases, HashMap::new()).await;
     }
 
+    // Fails the test if any I/O is submitted
+    #[derive(Debug)]
+    struct NoIo;
+
+    impl EncodingsIo for NoIo {
+        fn submit_request(
+            &self,
+            ranges: Vec<Range<u64>>,
+            _priority: u64,
+        ) -> BoxFuture<'static, lance_core::Result<Vec<Bytes>>> {
+            panic!("Unexpected I/O request for {:?}", ranges);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_empty_dictionary() {
+        let encoding = ProtobufUtils::dict_encoding(
+            ProtobufUtils::flat_encoding(8, 0, None),
+            ProtobufUtils::flat_encoding(8, 1, None),
+            0,
+        );
+        // Any read of the page buffers would panic
+        let io = Arc::new(NoIo) as Arc<dyn EncodingsIo>;
+
+        let dict_type = DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8));
+        for data_type in [DataType::Utf8, dict_type] {
+            let (scheduler, _) =
+                schedule_single_page(&encoding, [vec![0_u8; 100], vec![]], &data_type).unwrap();
+            let decoder = scheduler
+                .schedule_ranges(&[0..40, 60..100], &io, 0)
+                .await
+                .unwrap();
+            let decoded = decoder.decode(10, 50).unwrap();
+            let decoded = make_array(decoded.into_arrow(data_type.clone(), true).unwrap());
+            assert_eq!(decoded.data_type(), &data_type);
+            assert_eq!(decoded.len(), 50);
+            assert_eq!(decoded.null_count(), 50);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_dictionary_indices() {
+        let values = StringArray::from_iter((0..300).map(|i| match i % 4 {
+            0 => None,
+            1 => Some("apple"),
+            2 => Some("banana"),
+            _ => Some("cherry"),
+        }));
+        let num_rows = values.len() as u64;
+
+        let mut buffer_index = 0;
+        let encoded = DictionaryEncoder::new(
+            Box::new(BasicEncoder::new(Box::<ValueEncoder>::default())),
+            Box::new(BinaryEncoder::new(
+                Box::new(BasicEncoder::new(Box::<ValueEncoder>::default())),
+                None,
+            )),
+        )
+        .encode(
+            DataBlock::from_array(values.clone()),
+            &DataType::Utf8,
+            &mut buffer_index,
+        )
+        .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+
+        let (scheduler, io) = schedule_single_page_with_dictionary_mode(
+            &encoding,
+            buffers,
+            &DataType::Utf8,
+            DictionaryDecodeMode::Indices,
+        )
+        .unwrap();
+
+        let decoder = scheduler
+            .schedule_ranges(&[0..num_rows], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(10, 100).unwrap();
+
+        // The dictionary is not expanded, there is one index per row and one item per
+        // distinct value
+        let DataBlock::Nullable(nullable) = &decoded else {
+            panic!("Expected nullable dictionary indices");
+        };
+        let DataBlock::Dictionary(dict) = nullable.data.as_ref() else {
+            panic!("Expected dictionary indices");
+        };
+        assert_eq!(dict.indices.num_values, 100);
+        assert_eq!(dict.dictionary.num_values(), 3);
+
+        let dict_type = DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8));
+        let decoded = make_array(decoded.into_arrow(dict_type, true).unwrap());
+        let decoded = decoded.as_dictionary::<UInt8Type>();
+        assert_eq!(decoded.values().len(), 3);
+        assert_eq!(decoded.null_count(), 25);
+        let expanded = arrow_cast::cast(decoded, &DataType::Utf8).unwrap();
+        assert_eq!(expanded.as_string::<i32>(), &values.slice(10, 100));
+    }
+
+    #[tokio::test]
+    async fn test_decode_bitpacked_u16_indices() {
+        // 10 items, the indices (offset by one for nulls) are u16 bitpacked to 4 bits
+        let items = StringArray::from_iter_values((0..10).map(|i| format!("item-{}", i)));
+        let indices = UInt16Array::from_iter_values((0..200).map(|i| (i % 11) as u16));
+        let num_rows = indices.len() as u64;
+
+        let mut buffer_index = 0;
+        let encoded_indices = BitpackedArrayEncoder::new(4, false)
+            .encode(
+                DataBlock::from_array(indices.clone()),
+                &DataType::UInt16,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let encoded_items = BinaryEncoder::new(
+            Box::new(BasicEncoder::new(Box::<ValueEncoder>::default())),
+            None,
+        )
+        .encode(
+            DataBlock::from_array(items.clone()),
+            &DataType::Utf8,
+            &mut buffer_index,
+        )
+        .unwrap();
+        let (indices_buffers, indices_encoding) = encoded_indices.into_buffers();
+        let (items_buffers, items_encoding) = encoded_items.into_buffers();
+        let encoding = ProtobufUtils::dict_encoding(indices_encoding, items_encoding, 10);
+
+        let buffers = indices_buffers
+            .into_iter()
+            .chain(items_buffers)
+            .collect::<Vec<_>>();
+
+        let expected = StringArray::from_iter(indices.values().iter().map(|index| match index {
+            0 => None,
+            index => Some(items.value(*index as usize - 1)),
+        }));
+        for decode_mode in [DictionaryDecodeMode::Values, DictionaryDecodeMode::Indices] {
+            let (scheduler, io) = schedule_single_page_with_dictionary_mode(
+                &encoding,
+                &buffers,
+                &DataType::Utf8,
+                decode_mode,
+            )
+            .unwrap();
+            let decoder = scheduler
+                .schedule_ranges(&[0..num_rows], &io, 0)
+                .await
+                .unwrap();
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = match decode_mode {
+                DictionaryDecodeMode::Values => {
+                    make_array(decoded.into_arrow(DataType::Utf8, true).unwrap())
+                }
+                DictionaryDecodeMode::Indices => {
+                    let dict_type =
+                        DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8));
+                    let decoded = make_array(decoded.into_arrow(dict_type, true).unwrap());
+                    arrow_cast::cast(&decoded, &DataType::Utf8).unwrap()
+                }
+            };
+            assert_eq!(decoded.as_string::<i32>(), &expected);
+        }
+    }
+
     // These tests cover the case where the input is already dictionary encoded
 
     #[test_log::test(tokio::test)]
@@ -6,11 +6,12 @@ use std::sync::Arc;
 use arrow_buffer::ScalarBuffer;
 use arrow_schema::DataType;
 use futures::{future::BoxFuture, FutureExt};
-use lance_core::Result;
+use lance_core::{Error, Result};
+use snafu::location;
 
 use crate::{
     buffer::LanceBuffer,
-    data::{BlockInfo, DataBlock, FixedWidthDataBlock, VariableWidthBlock},
+    data::{BlockInfo, DataBlock, FixedWidthDataBlock, NullableDataBlock, VariableWidthBlock},
     decoder::{PageScheduler, PrimitivePageDecoder},
     format::ProtobufUtils,
     v2::encoder::{ArrayEncoder, EncodedArray},
@@ -18,11 +19,17 @@ use crate::{
 };
 
 /// A scheduler for fixed size binary data
+///
+/// If the encoding has an inline validity bitmap (see `FixedSizeBinary` in
+/// `encodings.proto`) then it is decoded alongside the bytes and the decoded
+/// blocks are nullable.
 #[derive(Debug)]
 pub struct FixedSizeBinaryPageScheduler {
     bytes_scheduler: Box<dyn PageScheduler>,
+    validity_scheduler: Option<Box<dyn PageScheduler>>,
     byte_width: u32,
-    bytes_per_offset: u32,
+    // None if the values are decoded as fixed width values instead of binary
+    bytes_per_offset: Option<u32>,
 }
 
 impl FixedSizeBinaryPageScheduler {
@@ -33,10 +40,30 @@ impl FixedSizeBinaryPageScheduler {
     ) -> Self {
         Self {
             bytes_scheduler,
+            validity_scheduler: None,
             byte_width,
-            bytes_per_offset,
+            bytes_per_offset: Some(bytes_per_offset),
         }
     }
+
+    /// Create a scheduler that decodes each value as a single fixed width value
+    ///
+    /// This is used for types that are stored as fixed width little-endian integers
+    /// (e.g. decimals) and so have no offsets.
+    pub fn new_fixed_width(bytes_scheduler: Box<dyn PageScheduler>, byte_width: u32) -> Self {
+        Self {
+            bytes_scheduler,
+            validity_scheduler: None,
+            byte_width,
+            bytes_per_offset: None,
+        }
+    }
+
+    /// Combine the bytes with an inline validity bitmap that has one bit per row
+    pub fn with_validity(mut self, validity_scheduler: Box<dyn PageScheduler>) -> Self {
+        self.validity_scheduler = Some(validity_scheduler);
+        self
+    }
 }
 
 impl PageScheduler for FixedSizeBinaryPageScheduler {
@@ -56,14 +83,23 @@ impl PageScheduler for FixedSizeBinaryPageScheduler {
         let bytes_page_decoder =
             self.bytes_scheduler
                 .schedule_ranges(&expanded_ranges, scheduler, top_level_row);
+        let validity_page_decoder = self
+            .validity_scheduler
+            .as_ref()
+            .map(|validity| validity.schedule_ranges(ranges, scheduler, top_level_row));
 
         let byte_width = self.byte_width as u64;
         let bytes_per_offset = self.bytes_per_offset;
 
         async move {
             let bytes_decoder = bytes_page_decoder.await?;
+            let validity_decoder = match validity_page_decoder {
+                Some(validity) => Some(validity.await?),
+                None => None,
+            };
             Ok(Box::new(FixedSizeBinaryDecoder {
                 bytes_decoder,
+                validity_decoder,
                 byte_width,
                 bytes_per_offset,
             }) as Box<dyn PrimitivePageDecoder>)
@@ -74,19 +110,58 @@ impl PageScheduler for FixedSizeBinaryPageScheduler {
 
 pub struct FixedSizeBinaryDecoder {
     bytes_decoder: Box<dyn PrimitivePageDecoder>,
+    validity_decoder: Option<Box<dyn PrimitivePageDecoder>>,
     byte_width: u64,
-    bytes_per_offset: u32,
+    bytes_per_offset: Option<u32>,
 }
 
 impl PrimitivePageDecoder for FixedSizeBinaryDecoder {
     fn decode(&self, rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let validity = self
+            .validity_decoder
+            .as_ref()
+            .map(|validity| validity.decode(rows_to_skip, num_rows))
+            .transpose()?;
+
         let rows_to_skip = rows_to_skip * self.byte_width;
         let num_bytes = num_rows * self.byte_width;
         let bytes = self.bytes_decoder.decode(rows_to_skip, num_bytes)?;
         let bytes = bytes.as_fixed_width().unwrap();
         debug_assert_eq!(bytes.bits_per_value, self.byte_width * 8);
 
-        let offsets_buffer = match self.bytes_per_offset {
+        let data = match self.bytes_per_offset {
+            None => DataBlock::FixedWidth(FixedWidthDataBlock {
+                bits_per_value: self.byte_width * 8,
+                data: bytes.data,
+                num_values: num_rows,
+                block_info: BlockInfo::new(),
+            }),
+            Some(bytes_per_offset) => self.variable_width(bytes.data, num_rows, bytes_per_offset),
+        };
+
+        match validity {
+            Some(validity) => {
+                let validity = validity
+                    .as_fixed_width()
+                    .ok_or_else(|| Error::InvalidInput {
+                        source: "Fixed size binary validity must be a bitmap".into(),
+                        location: location!(),
+                    })?;
+                Ok(DataBlock::Nullable(NullableDataBlock {
+                    data: Box::new(data),
+                    nulls: validity.data,
+                    block_info: BlockInfo::new(),
+                }))
+            }
+            None => Ok(data),
+        }
+    }
+}
+
+impl FixedSizeBinaryDecoder {
+    // Wraps the bytes with the offsets of equally sized values
+    fn variable_width(&self, data: LanceBuffer, num_rows: u64, bytes_per_offset: u32) -> DataBlock {
+        let offsets_buffer = match bytes_per_offset {
             8 => {
                 let offsets_vec = (0..(num_rows + 1))
                     .map(|i| i * self.byte_width)
@@ -104,15 +179,13 @@ impl PrimitivePageDecoder for FixedSizeBinaryDecoder {
             _ => panic!("Unsupported offsets type"),
         };
 
-        let string_data = DataBlock::VariableWidth(VariableWidthBlock {
-            bits_per_offset: (self.bytes_per_offset * 8) as u8,
-            data: bytes.data,
+        DataBlock::VariableWidth(VariableWidthBlock {
+            bits_per_offset: (bytes_per_offset * 8) as u8,
+            data,
             num_values: num_rows,
             offsets: LanceBuffer::from(offsets_buffer),
             block_info: BlockInfo::new(),
-        });
-
-        Ok(string_data)
+        })
     }
 }
 
@@ -120,6 +193,7 @@ impl PrimitivePageDecoder for FixedSizeBinaryDecoder {
 pub struct FixedSizeBinaryEncoder {
     bytes_encoder: Box<dyn ArrayEncoder>,
     byte_width: usize,
+    inline_validity: bool,
 }
 
 impl FixedSizeBinaryEncoder {
@@ -127,8 +201,18 @@ impl FixedSizeBinaryEncoder {
         Self {
             bytes_encoder,
             byte_width,
+            inline_validity: false,
         }
     }
+
+    /// Store the validity of nullable data as an inline bitmap
+    ///
+    /// Without this the data must not be nullable, the validity is expected to be
+    /// handled by an outer encoder (e.g. `BasicEncoder`).
+    pub fn with_inline_validity(mut self) -> Self {
+        self.inline_validity = true;
+        self
+    }
 }
 
 impl ArrayEncoder for FixedSizeBinaryEncoder {
@@ -138,7 +222,19 @@ impl ArrayEncoder for FixedSizeBinaryEncoder {
         _data_type: &DataType,
         buffer_index: &mut u32,
     ) -> Result<EncodedArray> {
-        let bytes_data = data.as_variable_width().unwrap();
+        let (bytes_data, nulls) = match data {
+            DataBlock::Nullable(nullable) if self.inline_validity => (
+                nullable.data.as_variable_width().unwrap(),
+                Some(nullable.nulls),
+            ),
+            data => (data.as_variable_width().unwrap(), None),
+        };
+        // The validity bitmap is the first buffer, the same as for `BasicEncoder`
+        let validity = nulls.map(|nulls| {
+            let validity_buffer_index = *buffer_index;
+            *buffer_index += 1;
+            (nulls, validity_buffer_index)
+        });
         let fixed_data = DataBlock::FixedWidth(FixedWidthDataBlock {
             bits_per_value: 8 * self.byte_width as u64,
             data: bytes_data.data,
@@ -151,13 +247,36 @@ impl ArrayEncoder for FixedSizeBinaryEncoder {
             &DataType::FixedSizeBinary(self.byte_width as i32),
             buffer_index,
         )?;
-        let encoding =
-            ProtobufUtils::fixed_size_binary(encoded_data.encoding, self.byte_width as u32);
-
-        Ok(EncodedArray {
-            data: encoded_data.data,
-            encoding,
-        })
+        match validity {
+            Some((nulls, validity_buffer_index)) => {
+                let validity = ProtobufUtils::flat_encoding(
+                    1,
+                    validity_buffer_index,
+                    /*compression=*/ None,
+                );
+                let encoding = ProtobufUtils::fixed_size_binary_with_validity(
+                    encoded_data.encoding,
+                    validity,
+                    self.byte_width as u32,
+                );
+                Ok(EncodedArray {
+                    data: DataBlock::Nullable(NullableDataBlock {
+                        data: Box::new(encoded_data.data),
+                        nulls,
+                        block_info: BlockInfo::new(),
+                    }),
+                    encoding,
+                })
+            }
+            None => {
+                let encoding =
+                    ProtobufUtils::fixed_size_binary(encoded_data.encoding, self.byte_width as u32);
+                Ok(EncodedArray {
+                    data: encoded_data.data,
+                    encoding,
+                })
+            }
+        }
     }
 }
 
@@ -166,16 +285,26 @@ mod tests {
     use std::{collections::HashMap, sync::Arc};
 
     use arrow::array::LargeStringBuilder;
-    use arrow_array::{Array, ArrayRef, FixedSizeBinaryArray, LargeStringArray, StringArray};
-    use arrow_buffer::Buffer;
+    use arrow_array::{
+        Array, ArrayRef, Decimal128Array, FixedSizeBinaryArray, LargeStringArray, StringArray,
+    };
+    use arrow_buffer::{Buffer, NullBuffer};
     use arrow_data::ArrayData;
     use arrow_schema::{DataType, Field};
 
     use crate::data::{DataBlock, FixedWidthDataBlock};
     use crate::decoder::PrimitivePageDecoder;
-    use crate::v2::encodings::physical::fixed_size_binary::FixedSizeBinaryDecoder;
+    use crate::encodings::physical::value::ValueEncoder;
+    use crate::format::ProtobufUtils;
+    use crate::v2::encoder::ArrayEncoder;
+    use crate::v2::encodings::physical::fixed_size_binary::{
+        FixedSizeBinaryDecoder, FixedSizeBinaryEncoder,
+    };
     use crate::{
-        testing::{check_round_trip_encoding_of_data, check_round_trip_encoding_random, TestCases},
+        testing::{
+            check_round_trip_encoding_of_data, check_round_trip_encoding_random,
+            schedule_single_page, TestCases,
+        },
         version::LanceFileVersion,
     };
 
@@ -299,6 +428,110 @@ mod tests {
         check_round_trip_encoding_of_data(arrs, &test_cases, HashMap::new()).await;
     }
 
+    #[tokio::test]
+    async fn test_fixed_size_binary_inline_validity() {
+        // Null rows still take up 3 bytes
+        let values = StringArray::from_iter_values((0..100).map(|i| format!("{:03}", i)));
+        let nulls = NullBuffer::from_iter((0..100).map(|i| i % 3 != 1 && i != 50));
+        let values = StringArray::new(
+            values.offsets().clone(),
+            values.values().clone(),
+            Some(nulls),
+        );
+        let num_rows = values.len() as u64;
+
+        let mut buffer_index = 0;
+        let encoded = FixedSizeBinaryEncoder::new(Box::<ValueEncoder>::default(), 3)
+            .with_inline_validity()
+            .encode(
+                DataBlock::from_array(values.clone()),
+                &DataType::Utf8,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+        assert_eq!(buffer_index, 2);
+
+        let (scheduler, io) = schedule_single_page(&encoding, buffers, &DataType::Utf8).unwrap();
+
+        for range in [0..num_rows, 1..2, 5..40, 48..53, (num_rows - 3)..num_rows] {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = StringArray::from(decoded.into_arrow(DataType::Utf8, true).unwrap());
+            let expected = values.slice(range.start as usize, num_rows as usize);
+            assert_eq!(decoded, expected);
+        }
+
+        // Multiple ranges and a partial decode
+        let decoder = scheduler
+            .schedule_ranges(&[2..4, 51..53], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(1, 3).unwrap();
+        let decoded = StringArray::from(decoded.into_arrow(DataType::Utf8, true).unwrap());
+        assert_eq!(
+            decoded,
+            StringArray::from(vec![Some("003"), Some("051"), None])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fixed_size_binary_decimal128() {
+        let data_type = DataType::Decimal128(38, 9);
+        let values = Decimal128Array::from_iter((0..100).map(|i| {
+            if i % 7 == 3 {
+                None
+            } else {
+                Some(i as i128 * 1_000_000_007 - 12_345_678_901_234_567_890)
+            }
+        }))
+        .with_precision_and_scale(38, 9)
+        .unwrap();
+        let num_rows = values.len() as u64;
+
+        // The values, 16 bytes each, followed by the validity bitmap
+        let bytes = values.values().inner().as_slice().to_vec();
+        let validity = values.nulls().unwrap().buffer().as_slice().to_vec();
+        let buffers = [bytes, validity];
+
+        let encoding = ProtobufUtils::fixed_size_binary_with_validity(
+            ProtobufUtils::flat_encoding(128, 0, None),
+            ProtobufUtils::flat_encoding(1, 1, None),
+            16,
+        );
+        let (scheduler, io) = schedule_single_page(&encoding, &buffers, &data_type).unwrap();
+
+        for range in [0..num_rows, 1..2, 5..40, (num_rows - 3)..num_rows] {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded =
+                Decimal128Array::from(decoded.into_arrow(data_type.clone(), true).unwrap());
+            let expected = values.slice(range.start as usize, num_rows as usize);
+            assert_eq!(decoded.data_type(), &data_type);
+            assert_eq!(decoded, expected);
+        }
+
+        // The byte width must match the decimal type
+        let err =
+            schedule_single_page(&encoding, &buffers, &DataType::Decimal256(38, 9)).unwrap_err();
+        assert!(err.to_string().contains("byte width 16"), "{}", err);
+
+        let test_cases = TestCases::default()
+            .with_range(0..2)
+            .with_range(5..40)
+            .with_indices(vec![0, 3, 99]);
+        check_round_trip_encoding_of_data(vec![Arc::new(values)], &test_cases, HashMap::new())
+            .await;
+    }
+
     struct FixedWidthCloningPageDecoder {
         data_block: FixedWidthDataBlock,
     }
@@ -339,6 +572,7 @@ mod tests {
         };
         let decoder = FixedSizeBinaryDecoder {
             bytes_decoder: Box::new(bytes_decoder),
+            validity_decoder: None,
             byte_width: byte_width as u64,
             bytes_per_offset: 4, // 32-bits offset binary
         };
@@ -5,11 +5,12 @@ use std::sync::Arc;
 
 use arrow_schema::DataType;
 use futures::{future::BoxFuture, FutureExt};
-use lance_core::Result;
+use lance_core::{Error, Result};
 use log::trace;
+use snafu::location;
 
 use crate::{
-    data::{DataBlock, FixedSizeListBlock},
+    data::{BlockInfo, DataBlock, FixedSizeListBlock, NullableDataBlock},
     decoder::{PageScheduler, PrimitivePageDecoder},
     format::ProtobufUtils,
     v2::encoder::{ArrayEncoder, EncodedArray},
@@ -19,9 +20,14 @@ use crate::{
 /// A scheduler for fixed size lists of primitive values
 ///
 /// This scheduler is, itself, primitive
+///
+/// If the encoding has a list-level validity bitmap (see `FixedSizeList` in
+/// `encodings.proto`) then it is decoded alongside the items and the decoded
+/// blocks are nullable.
 #[derive(Debug)]
 pub struct FixedListScheduler {
     items_scheduler: Box<dyn PageScheduler>,
+    validity_scheduler: Option<Box<dyn PageScheduler>>,
     dimension: u32,
 }
 
@@ -29,9 +35,16 @@ impl FixedListScheduler {
     pub fn new(items_scheduler: Box<dyn PageScheduler>, dimension: u32) -> Self {
         Self {
             items_scheduler,
+            validity_scheduler: None,
             dimension,
         }
     }
+
+    /// Combine the items with a validity bitmap that has one bit per list
+    pub fn with_validity(mut self, validity_scheduler: Box<dyn PageScheduler>) -> Self {
+        self.validity_scheduler = Some(validity_scheduler);
+        self
+    }
 }
 
 impl PageScheduler for FixedListScheduler {
@@ -56,11 +69,20 @@ impl PageScheduler for FixedListScheduler {
         let inner_page_decoder =
             self.items_scheduler
                 .schedule_ranges(&expanded_ranges, scheduler, top_level_row);
+        let validity_page_decoder = self
+            .validity_scheduler
+            .as_ref()
+            .map(|validity| validity.schedule_ranges(ranges, scheduler, top_level_row));
         let dimension = self.dimension;
         async move {
             let items_decoder = inner_page_decoder.await?;
+            let validity_decoder = match validity_page_decoder {
+                Some(validity) => Some(validity.await?),
+                None => None,
+            };
             Ok(Box::new(FixedListDecoder {
                 items_decoder,
+                validity_decoder,
                 dimension: dimension as u64,
             }) as Box<dyn PrimitivePageDecoder>)
         }
@@ -70,18 +92,42 @@ impl PageScheduler for FixedListScheduler {
 
 pub struct FixedListDecoder {
     items_decoder: Box<dyn PrimitivePageDecoder>,
+    validity_decoder: Option<Box<dyn PrimitivePageDecoder>>,
     dimension: u64,
 }
 
 impl PrimitivePageDecoder for FixedListDecoder {
     fn decode(&self, rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let validity = self
+            .validity_decoder
+            .as_ref()
+            .map(|validity| validity.decode(rows_to_skip, num_rows))
+            .transpose()?;
+
         let rows_to_skip = rows_to_skip * self.dimension;
         let num_child_rows = num_rows * self.dimension;
         let child_data = self.items_decoder.decode(rows_to_skip, num_child_rows)?;
-        Ok(DataBlock::FixedSizeList(FixedSizeListBlock {
+        let list_data = DataBlock::FixedSizeList(FixedSizeListBlock {
             child: Box::new(child_data),
             dimension: self.dimension,
-        }))
+        });
+
+        match validity {
+            Some(validity) => {
+                let validity = validity
+                    .as_fixed_width()
+                    .ok_or_else(|| Error::InvalidInput {
+                        source: "Fixed size list validity must be a bitmap".into(),
+                        location: location!(),
+                    })?;
+                Ok(DataBlock::Nullable(NullableDataBlock {
+                    data: Box::new(list_data),
+                    nulls: validity.data,
+                    block_info: BlockInfo::new(),
+                }))
+            }
+            None => Ok(list_data),
+        }
     }
 }
 
@@ -134,14 +180,18 @@ mod tests {
     use std::{collections::HashMap, sync::Arc};
 
     use arrow::datatypes::Int32Type;
-    use arrow_array::{FixedSizeListArray, Int32Array};
+    use arrow_array::{Array, FixedSizeListArray, Float32Array, Int32Array};
     use arrow_buffer::{BooleanBuffer, NullBuffer};
     use arrow_schema::{DataType, Field};
     use lance_datagen::{array, gen_array, ArrayGeneratorExt, RowCount};
     use rstest::rstest;
 
     use crate::{
-        testing::{check_round_trip_encoding_of_data, check_round_trip_encoding_random, TestCases},
+        format::ProtobufUtils,
+        testing::{
+            check_round_trip_encoding_of_data, check_round_trip_encoding_random,
+            schedule_single_page, TestCases,
+        },
         version::LanceFileVersion,
     };
 
@@ -269,4 +319,89 @@ mod tests {
 
         check_round_trip_encoding_of_data(vec![outer_list], &test_cases, HashMap::default()).await;
     }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_nullable_fsl_vectors(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+    ) {
+        // Embeddings where every 7th vector is missing
+        let num_vectors = 100;
+        let items = Arc::new(Float32Array::from_iter_values(
+            (0..num_vectors * 8).map(|i| i as f32),
+        ));
+        let items_field = Arc::new(Field::new("item", DataType::Float32, true));
+        let list_nulls = NullBuffer::new(BooleanBuffer::from_iter(
+            (0..num_vectors).map(|i| i % 7 != 0),
+        ));
+        let list = Arc::new(FixedSizeListArray::new(
+            items_field,
+            8,
+            items,
+            Some(list_nulls),
+        ));
+
+        let test_cases = TestCases::default()
+            .with_range(0..100)
+            .with_range(1..7)
+            .with_range(5..50)
+            .with_indices(vec![0, 1, 7, 99])
+            .with_indices(vec![14])
+            .with_file_version(version);
+
+        check_round_trip_encoding_of_data(vec![list], &test_cases, HashMap::default()).await;
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_fsl_list_validity() {
+        // [0, 1], NULL, [4, 5], [6, 7] with the list validity in the list encoding and,
+        // optionally, an outer validity where the third list is null
+        let list_validity = vec![0b1101_u8];
+        let outer_validity = vec![0b1011_u8];
+        let items = (0..8)
+            .flat_map(|i| (i as f32).to_le_bytes())
+            .collect::<Vec<_>>();
+        let buffers = [list_validity, outer_validity, items];
+
+        let items_field = Arc::new(Field::new("item", DataType::Float32, true));
+        let data_type = DataType::FixedSizeList(items_field, 2);
+        let list_encoding = ProtobufUtils::fsl_encoding_with_validity(
+            2,
+            ProtobufUtils::flat_encoding(32, 2, None),
+            ProtobufUtils::flat_encoding(1, 0, None),
+        );
+        let nullable_encoding = ProtobufUtils::basic_some_null_encoding(
+            ProtobufUtils::flat_encoding(1, 1, None),
+            list_encoding.clone(),
+            None,
+        );
+
+        for (encoding, expected_valid) in [
+            (list_encoding, [true, false, true, true]),
+            (nullable_encoding, [true, false, false, true]),
+        ] {
+            let (scheduler, io) = schedule_single_page(&encoding, &buffers, &data_type).unwrap();
+            for range in [0..4, 1..3, 3..4] {
+                let decoder = scheduler
+                    .schedule_ranges(&[range.clone()], &io, 0)
+                    .await
+                    .unwrap();
+                let num_rows = range.end - range.start;
+                let decoded = decoder.decode(0, num_rows).unwrap();
+                let decoded =
+                    FixedSizeListArray::from(decoded.into_arrow(data_type.clone(), true).unwrap());
+                assert_eq!(decoded.len() as u64, num_rows);
+                for (idx, row) in range.enumerate() {
+                    let row = row as usize;
+                    assert_eq!(decoded.is_valid(idx), expected_valid[row]);
+                    let values = decoded.value(idx);
+                    let values = values.as_any().downcast_ref::<Float32Array>().unwrap();
+                    assert_eq!(
+                        values.values().to_vec(),
+                        vec![(row * 2) as f32, (row * 2 + 1) as f32]
+                    );
+                }
+            }
+        }
+    }
 }
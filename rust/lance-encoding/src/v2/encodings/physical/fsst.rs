@@ -144,6 +144,10 @@ impl ArrayEncoder for FsstArrayEncoder {
                 (data, Some(nullable.nulls))
             }
             DataBlock::VariableWidth(variable) => (variable, None),
+            // There is nothing to compress, the page is written as plain binary
+            DataBlock::AllNull(_) => {
+                return self.inner_encoder.encode(data, data_type, buffer_index);
+            }
             _ => panic!("Expected variable width data block"),
         };
         assert_eq!(data.bits_per_offset, 32);
@@ -196,3 +200,50 @@ impl ArrayEncoder for FsstArrayEncoder {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::StringArray;
+    use arrow_schema::DataType;
+
+    use crate::{
+        data::DataBlock,
+        encodings::physical::value::ValueEncoder,
+        testing::schedule_single_page,
+        v2::{
+            encoder::ArrayEncoder,
+            encodings::physical::{basic::BasicEncoder, binary::BinaryEncoder},
+        },
+    };
+
+    use super::FsstArrayEncoder;
+
+    #[tokio::test]
+    async fn test_fsst_all_null() {
+        let values = StringArray::from(vec![None::<&str>; 100]);
+        let encoder = FsstArrayEncoder::new(Box::new(BinaryEncoder::new(
+            Box::new(BasicEncoder::new(Box::<ValueEncoder>::default())),
+            None,
+        )));
+
+        // There is nothing to compress, the page is encoded by the inner encoder
+        let mut buffer_index = 0;
+        let encoded = encoder
+            .encode(
+                DataBlock::from_array(values.clone()),
+                &DataType::Utf8,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+
+        let (scheduler, io) = schedule_single_page(&encoding, &buffers, &DataType::Utf8).unwrap();
+        let decoder = scheduler.schedule_ranges(&[10..60], &io, 0).await.unwrap();
+        let decoded = decoder
+            .decode(0, 50)
+            .unwrap()
+            .into_arrow(DataType::Utf8, true)
+            .unwrap();
+        assert_eq!(StringArray::from(decoded), values.slice(10, 50));
+    }
+}
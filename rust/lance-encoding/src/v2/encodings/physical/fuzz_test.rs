@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Randomized decode tests for the physical encodings
+//!
+//! Each case generates a random array (type, bit width, length and null pattern), encodes it
+//! into a page with one of the array encoders, and then decodes random ranges of the page with
+//! [`decoder_from_array_encoding`](super::decoder_from_array_encoding) and compares them
+//! against the input.  The random generator is seeded with a fixed seed so any failure can be
+//! reproduced from the case number.
+
+use std::{ops::Range, sync::Arc};
+
+use arrow_array::{
+    make_array, Array, ArrayRef, Float32Array, Float64Array, Int64Array, StringArray, UInt64Array,
+};
+use arrow_schema::DataType;
+use lance_datagen::DEFAULT_SEED;
+use rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+use crate::{
+    data::DataBlock,
+    encodings::physical::value::ValueEncoder,
+    testing::schedule_single_page,
+    v2::{
+        encoder::ArrayEncoder,
+        encodings::physical::{
+            basic::BasicEncoder,
+            binary::BinaryEncoder,
+            bitpack::{bitpack_params, BitpackedArrayEncoder},
+            dictionary::DictionaryEncoder,
+            fsst::FsstArrayEncoder,
+        },
+    },
+};
+
+// The number of random cases for each encoding
+const NUM_CASES: u64 = 25;
+const MAX_ROWS: usize = 2000;
+
+#[derive(Debug, Clone, Copy)]
+enum FuzzEncoding {
+    Flat,
+    Bitpacked,
+    Dictionary,
+    Binary,
+    Fsst,
+}
+
+// No nulls, all nulls, or nulls with a random probability
+fn random_validity(rng: &mut Xoshiro256PlusPlus, num_rows: usize) -> Vec<bool> {
+    match rng.gen_range(0..4) {
+        0 => vec![true; num_rows],
+        1 => vec![false; num_rows],
+        _ => {
+            let valid_probability = rng.gen::<f64>();
+            (0..num_rows)
+                .map(|_| rng.gen_bool(valid_probability))
+                .collect()
+        }
+    }
+}
+
+// Integers of a random type whose values use a random number of bits
+fn random_integers(rng: &mut Xoshiro256PlusPlus, validity: &[bool]) -> ArrayRef {
+    const TYPES: [DataType; 8] = [
+        DataType::Int8,
+        DataType::Int16,
+        DataType::Int32,
+        DataType::Int64,
+        DataType::UInt8,
+        DataType::UInt16,
+        DataType::UInt32,
+        DataType::UInt64,
+    ];
+    let data_type = TYPES[rng.gen_range(0..TYPES.len())].clone();
+    let bits = rng.gen_range(0..=data_type.primitive_width().unwrap() as u32 * 8);
+    // Shifting the random bits down keeps the sign, the values fit in `bits` bits
+    let array: ArrayRef = if data_type.is_signed_integer() {
+        Arc::new(Int64Array::from_iter(validity.iter().map(|valid| {
+            valid.then(|| match bits {
+                0 => 0,
+                bits => rng.gen::<i64>() >> (64 - bits),
+            })
+        })))
+    } else {
+        Arc::new(UInt64Array::from_iter(validity.iter().map(|valid| {
+            valid.then(|| match bits {
+                0 => 0,
+                bits => rng.gen::<u64>() >> (64 - bits),
+            })
+        })))
+    };
+    arrow_cast::cast(&array, &data_type).unwrap()
+}
+
+fn random_floats(rng: &mut Xoshiro256PlusPlus, validity: &[bool]) -> ArrayRef {
+    if rng.gen_bool(0.5) {
+        Arc::new(Float32Array::from_iter(
+            validity.iter().map(|valid| valid.then(|| rng.gen::<f32>())),
+        ))
+    } else {
+        Arc::new(Float64Array::from_iter(
+            validity.iter().map(|valid| valid.then(|| rng.gen::<f64>())),
+        ))
+    }
+}
+
+// Strings drawn from `cardinality` random values (unique values if `None`)
+//
+// The strings use a small alphabet so that larger pages are actually compressed by FSST
+fn random_strings(
+    rng: &mut Xoshiro256PlusPlus,
+    validity: &[bool],
+    cardinality: Option<usize>,
+) -> StringArray {
+    const ALPHABET: &[u8] = b"abcdefgh ";
+    let alphabet = &ALPHABET[..rng.gen_range(1..=ALPHABET.len())];
+    let max_len = rng.gen_range(0..=100);
+    let random_string = |rng: &mut Xoshiro256PlusPlus| {
+        let len = rng.gen_range(0..=max_len);
+        (0..len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())] as char)
+            .collect::<String>()
+    };
+    let values = cardinality.map(|cardinality| {
+        (0..cardinality)
+            .map(|_| random_string(rng))
+            .collect::<Vec<_>>()
+    });
+    StringArray::from_iter(validity.iter().map(|valid| {
+        valid.then(|| match &values {
+            Some(values) => values[rng.gen_range(0..values.len())].clone(),
+            None => random_string(rng),
+        })
+    }))
+}
+
+fn random_case(
+    rng: &mut Xoshiro256PlusPlus,
+    encoding: FuzzEncoding,
+) -> (Box<dyn ArrayEncoder>, ArrayRef) {
+    let num_rows = rng.gen_range(1..=MAX_ROWS);
+    let validity = random_validity(rng, num_rows);
+    let binary_encoder = || -> Box<dyn ArrayEncoder> {
+        Box::new(BinaryEncoder::new(
+            Box::new(BasicEncoder::new(Box::<ValueEncoder>::default())),
+            None,
+        ))
+    };
+    match encoding {
+        FuzzEncoding::Flat => {
+            let array = if rng.gen_bool(0.5) {
+                random_integers(rng, &validity)
+            } else {
+                random_floats(rng, &validity)
+            };
+            let encoder = BasicEncoder::new(Box::<ValueEncoder>::default());
+            (Box::new(encoder), array)
+        }
+        FuzzEncoding::Bitpacked => {
+            let array = random_integers(rng, &validity);
+            // All null arrays have no bit width, their values are never encoded
+            let (num_bits, signed) = bitpack_params(array.as_ref())
+                .map(|params| (params.num_bits, params.signed))
+                .unwrap_or((1, false));
+            let encoder = BasicEncoder::new(Box::new(BitpackedArrayEncoder::new(num_bits, signed)));
+            (Box::new(encoder), array)
+        }
+        FuzzEncoding::Dictionary => {
+            // Dictionary indices are a single byte and 0 is reserved for nulls
+            let cardinality = rng.gen_range(1..=255);
+            let array = random_strings(rng, &validity, Some(cardinality));
+            let encoder = DictionaryEncoder::new(
+                Box::new(BasicEncoder::new(Box::<ValueEncoder>::default())),
+                binary_encoder(),
+            );
+            (Box::new(encoder), Arc::new(array))
+        }
+        FuzzEncoding::Binary => {
+            const TYPES: [DataType; 4] = [
+                DataType::Utf8,
+                DataType::LargeUtf8,
+                DataType::Binary,
+                DataType::LargeBinary,
+            ];
+            let data_type = &TYPES[rng.gen_range(0..TYPES.len())];
+            let array = random_strings(rng, &validity, None);
+            let array = arrow_cast::cast(&array, data_type).unwrap();
+            (binary_encoder(), array)
+        }
+        FuzzEncoding::Fsst => {
+            // FSST only supports 32-bit offsets
+            let data_type = if rng.gen_bool(0.5) {
+                DataType::Utf8
+            } else {
+                DataType::Binary
+            };
+            let array = random_strings(rng, &validity, None);
+            let array = arrow_cast::cast(&array, &data_type).unwrap();
+            (Box::new(FsstArrayEncoder::new(binary_encoder())), array)
+        }
+    }
+}
+
+// Up to three sorted, non-empty and non-overlapping ranges of `0..num_rows`
+fn random_ranges(rng: &mut Xoshiro256PlusPlus, num_rows: u64) -> Vec<Range<u64>> {
+    let num_ranges = rng.gen_range(1..=3);
+    let mut bounds = (0..num_ranges * 2)
+        .map(|_| rng.gen_range(0..=num_rows))
+        .collect::<Vec<_>>();
+    bounds.sort_unstable();
+    let ranges = bounds
+        .chunks_exact(2)
+        .map(|bounds| bounds[0]..bounds[1])
+        .filter(|range| !range.is_empty())
+        .collect::<Vec<_>>();
+    if ranges.is_empty() {
+        vec![0..num_rows]
+    } else {
+        ranges
+    }
+}
+
+// Encode `array` into a page and check that random ranges of it decode to the input
+async fn check_case(
+    rng: &mut Xoshiro256PlusPlus,
+    encoder: &dyn ArrayEncoder,
+    array: ArrayRef,
+    case: &str,
+) {
+    let data_type = array.data_type().clone();
+    let mut buffer_index = 0;
+    let encoded = encoder
+        .encode(
+            DataBlock::from(array.clone()),
+            &data_type,
+            &mut buffer_index,
+        )
+        .unwrap_or_else(|err| panic!("{}: encoding failed: {}", case, err));
+    let (buffers, encoding) = encoded.into_buffers();
+
+    let (scheduler, io) = schedule_single_page(&encoding, buffers, &data_type)
+        .unwrap_or_else(|err| panic!("{}: invalid encoding {:?}: {}", case, encoding, err));
+
+    for _ in 0..4 {
+        let ranges = random_ranges(rng, array.len() as u64);
+        let scheduled = ranges
+            .iter()
+            .map(|range| array.slice(range.start as usize, (range.end - range.start) as usize))
+            .collect::<Vec<_>>();
+        let scheduled =
+            arrow_select::concat::concat(&scheduled.iter().map(|a| a.as_ref()).collect::<Vec<_>>())
+                .unwrap();
+
+        // Decode a random window of the scheduled rows
+        let rows_to_skip = rng.gen_range(0..scheduled.len());
+        let num_rows = rng.gen_range(1..=scheduled.len() - rows_to_skip);
+        let decoder = scheduler
+            .schedule_ranges(&ranges, &io, 0)
+            .await
+            .unwrap_or_else(|err| panic!("{}: scheduling {:?} failed: {}", case, ranges, err));
+        let decoded = decoder
+            .decode(rows_to_skip as u64, num_rows as u64)
+            .and_then(|decoded| decoded.into_arrow(data_type.clone(), true))
+            .unwrap_or_else(|err| {
+                panic!(
+                    "{}: decoding {} rows after {} of {:?} failed: {}",
+                    case, num_rows, rows_to_skip, ranges, err
+                )
+            });
+        let expected = scheduled.slice(rows_to_skip, num_rows);
+        assert_eq!(
+            &make_array(decoded),
+            &expected,
+            "{}: decoding {} rows after {} of {:?}",
+            case,
+            num_rows,
+            rows_to_skip,
+            ranges
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_decode_fuzz() {
+    for (seed_offset, encoding) in [
+        FuzzEncoding::Flat,
+        FuzzEncoding::Bitpacked,
+        FuzzEncoding::Dictionary,
+        FuzzEncoding::Binary,
+        FuzzEncoding::Fsst,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        // Each encoding has its own generator so that adding an encoding doesn't change the
+        // cases of the others
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(DEFAULT_SEED.0 + seed_offset as u64);
+        for case_idx in 0..NUM_CASES {
+            let (encoder, array) = random_case(&mut rng, encoding);
+            let case = format!(
+                "case {} of the {:?} encoding ({} rows of {}, {} nulls)",
+                case_idx,
+                encoding,
+                array.len(),
+                array.data_type(),
+                array.null_count()
+            );
+            check_case(&mut rng, encoder.as_ref(), array, &case).await;
+        }
+    }
+}
@@ -0,0 +1,393 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Run-length encoding for the 2.0 format
+//!
+//! A page is stored as two buffers.  The values buffer has one fixed-width value per
+//! run and the lengths buffer has the length of each run as a u8.  Runs longer than
+//! 255 values are split into multiple runs, the same as the 2.1 miniblock RLE.
+//!
+//! For example, `[1, 1, 1, 2, 2, 3, 3, 3, 3]` is stored as values `[1, 2, 3]` and
+//! lengths `[3, 2, 4]`.
+//!
+//! Bitmaps (1 bit per value), such as the validity of a nullable page, are stored
+//! with one byte (0 or 1) per run.  A column that is null for the first half and
+//! valid for the second half needs only a few bytes instead of a bit per row.
+
+use std::{ops::Range, sync::Arc};
+
+use arrow_buffer::{BooleanBuffer, BooleanBufferBuilder, Buffer};
+use arrow_schema::DataType;
+use futures::{future::BoxFuture, FutureExt};
+use log::trace;
+use snafu::location;
+
+use lance_core::{Error, Result};
+
+use crate::{
+    buffer::LanceBuffer,
+    data::{BlockInfo, DataBlock, FixedWidthDataBlock, OpaqueBlock},
+    decoder::{PageScheduler, PrimitivePageDecoder},
+    format::ProtobufUtils,
+    v2::encoder::{ArrayEncoder, EncodedArray},
+    EncodingsIo,
+};
+
+use super::value::ExpandedValuesDecoder;
+
+/// A physical scheduler for run-length encoded fixed-width values
+///
+/// Run lengths vary so there is no way to map a row to a byte offset without
+/// reading the lengths.  We always load the entire page and expand only the
+/// requested rows.
+#[derive(Debug, Clone, Copy)]
+pub struct RleScheduler {
+    bytes_per_value: u64,
+    values_buffer: (u64, u64),
+    lengths_buffer: (u64, u64),
+}
+
+impl RleScheduler {
+    /// Create a new scheduler, buffers are given as (offset, size)
+    pub fn new(bits_per_value: u64, values_buffer: (u64, u64), lengths_buffer: (u64, u64)) -> Self {
+        debug_assert_eq!(bits_per_value % 8, 0);
+        Self {
+            bytes_per_value: bits_per_value / 8,
+            values_buffer,
+            lengths_buffer,
+        }
+    }
+}
+
+impl PageScheduler for RleScheduler {
+    fn schedule_ranges(
+        &self,
+        ranges: &[Range<u64>],
+        scheduler: &Arc<dyn EncodingsIo>,
+        top_level_row: u64,
+    ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
+        let (values_offset, values_size) = self.values_buffer;
+        let (lengths_offset, lengths_size) = self.lengths_buffer;
+        trace!(
+            "Scheduling I/O for {} bytes of RLE values and {} run lengths",
+            values_size,
+            lengths_size
+        );
+        let bytes = scheduler.submit_request(
+            vec![
+                values_offset..(values_offset + values_size),
+                lengths_offset..(lengths_offset + lengths_size),
+            ],
+            top_level_row,
+        );
+
+        let bytes_per_value = self.bytes_per_value;
+        let ranges = ranges.to_vec();
+        async move {
+            let bytes = bytes.await?;
+            let data = expand_runs(&bytes[0], &bytes[1], bytes_per_value as usize, &ranges)?;
+            Ok(Box::new(ExpandedValuesDecoder::new(
+                bytes_per_value,
+                LanceBuffer::from(Buffer::from_vec(data)),
+            )) as Box<dyn PrimitivePageDecoder>)
+        }
+        .boxed()
+    }
+}
+
+/// A physical scheduler for a run-length encoded bitmap, such as a validity bitmap
+///
+/// The runs are expanded into a dense bitmap, like the one read by the
+/// [`super::bitmap::DenseBitmapScheduler`].
+#[derive(Debug, Clone, Copy)]
+pub struct RleBitmapScheduler {
+    values_buffer: (u64, u64),
+    lengths_buffer: (u64, u64),
+}
+
+impl RleBitmapScheduler {
+    /// Create a new scheduler, buffers are given as (offset, size)
+    pub fn new(values_buffer: (u64, u64), lengths_buffer: (u64, u64)) -> Self {
+        Self {
+            values_buffer,
+            lengths_buffer,
+        }
+    }
+}
+
+impl PageScheduler for RleBitmapScheduler {
+    fn schedule_ranges(
+        &self,
+        ranges: &[Range<u64>],
+        scheduler: &Arc<dyn EncodingsIo>,
+        top_level_row: u64,
+    ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
+        let (values_offset, values_size) = self.values_buffer;
+        let (lengths_offset, lengths_size) = self.lengths_buffer;
+        trace!("Scheduling I/O for {} RLE bitmap runs", lengths_size);
+        let bytes = scheduler.submit_request(
+            vec![
+                values_offset..(values_offset + values_size),
+                lengths_offset..(lengths_offset + lengths_size),
+            ],
+            top_level_row,
+        );
+
+        let ranges = ranges.to_vec();
+        async move {
+            let bytes = bytes.await?;
+            let expanded = expand_runs(&bytes[0], &bytes[1], 1, &ranges)?;
+            let bitmap = BooleanBuffer::from_iter(expanded.iter().map(|value| *value != 0));
+            Ok(Box::new(RleBitmapDecoder {
+                bitmap: bitmap.into_inner(),
+            }) as Box<dyn PrimitivePageDecoder>)
+        }
+        .boxed()
+    }
+}
+
+struct RleBitmapDecoder {
+    bitmap: Buffer,
+}
+
+impl PrimitivePageDecoder for RleBitmapDecoder {
+    fn decode(&self, rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let mut dest_builder = BooleanBufferBuilder::new(num_rows as usize);
+        let start = rows_to_skip as usize;
+        dest_builder.append_packed_range(start..start + num_rows as usize, &self.bitmap);
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::from(dest_builder.finish().into_inner()),
+            bits_per_value: 1,
+            num_values: num_rows,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
+// Expand the runs that overlap the (sorted, non-overlapping) row ranges
+fn expand_runs(
+    values: &[u8],
+    lengths: &[u8],
+    bytes_per_value: usize,
+    ranges: &[Range<u64>],
+) -> Result<Vec<u8>> {
+    let num_rows = ranges.iter().map(|r| r.end - r.start).sum::<u64>();
+    let mut expanded = Vec::with_capacity(num_rows as usize * bytes_per_value);
+
+    let mut runs = values
+        .chunks_exact(bytes_per_value)
+        .zip(lengths.iter().map(|len| *len as u64));
+    let mut run = runs.next();
+    let mut run_start = 0;
+    for range in ranges {
+        let mut row = range.start;
+        while row < range.end {
+            let Some((value, run_len)) = run else {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "RLE page has {} rows but row {} was requested",
+                        run_start, row
+                    )
+                    .into(),
+                    location: location!(),
+                });
+            };
+            let run_end = run_start + run_len;
+            if row >= run_end {
+                run_start = run_end;
+                run = runs.next();
+                continue;
+            }
+            let count = run_end.min(range.end) - row;
+            for _ in 0..count {
+                expanded.extend_from_slice(value);
+            }
+            row += count;
+        }
+    }
+    Ok(expanded)
+}
+
+/// Encodes fixed-width values, or a bitmap, as runs of repeated values
+#[derive(Debug, Default)]
+pub struct RleEncoder {}
+
+impl RleEncoder {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ArrayEncoder for RleEncoder {
+    fn encode(
+        &self,
+        data: DataBlock,
+        _data_type: &DataType,
+        buffer_index: &mut u32,
+    ) -> Result<EncodedArray> {
+        let data = match data {
+            DataBlock::FixedWidth(data)
+                if data.bits_per_value % 8 == 0 || data.bits_per_value == 1 =>
+            {
+                data
+            }
+            _ => {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "Cannot encode a data block of type {} with RleEncoder",
+                        data.name()
+                    )
+                    .into(),
+                    location: location!(),
+                })
+            }
+        };
+
+        // Each bit of a bitmap is run-length encoded as a byte
+        let unpacked;
+        let (data_bytes, bytes_per_value) = if data.bits_per_value == 1 {
+            let bitmap = BooleanBuffer::new(data.data.into_buffer(), 0, data.num_values as usize);
+            unpacked = bitmap.iter().map(u8::from).collect::<Vec<_>>();
+            (unpacked.as_slice(), 1)
+        } else {
+            (data.data.as_ref(), (data.bits_per_value / 8) as usize)
+        };
+        let mut values = Vec::new();
+        let mut lengths = Vec::new();
+        for value in data_bytes.chunks_exact(bytes_per_value) {
+            let continues_run =
+                values.ends_with(value) && lengths.last().is_some_and(|len: &u8| *len < u8::MAX);
+            if continues_run {
+                *lengths.last_mut().unwrap() += 1;
+            } else {
+                values.extend_from_slice(value);
+                lengths.push(1_u8);
+            }
+        }
+
+        let values_index = *buffer_index;
+        let lengths_index = *buffer_index + 1;
+        *buffer_index += 2;
+
+        let encoding =
+            ProtobufUtils::rle_with_buffers(data.bits_per_value, values_index, lengths_index);
+        let data = DataBlock::Opaque(OpaqueBlock {
+            buffers: vec![LanceBuffer::from(values), LanceBuffer::from(lengths)],
+            num_values: data.num_values,
+            block_info: BlockInfo::new(),
+        });
+        Ok(EncodedArray { data, encoding })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Array, Int32Array};
+    use arrow_schema::DataType;
+
+    use crate::{
+        buffer::LanceBuffer,
+        data::{BlockInfo, DataBlock, FixedWidthDataBlock},
+        format::ProtobufUtils,
+        testing::schedule_single_page,
+        v2::encoder::ArrayEncoder,
+    };
+
+    use super::RleEncoder;
+
+    #[tokio::test]
+    async fn test_rle_round_trip() {
+        // Sorted category ids, with one run longer than u8::MAX
+        let values = Int32Array::from_iter_values(
+            (0..10).flat_map(|category| std::iter::repeat_n(category, 30 * category as usize + 1)),
+        );
+        let num_rows = values.len() as u64;
+
+        let mut buffer_index = 0;
+        let encoded = RleEncoder::new()
+            .encode(
+                DataBlock::from_array(values.clone()),
+                &DataType::Int32,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+        assert_eq!(buffer_index, 2);
+        assert!(buffers[0].len() < values.len() * 4);
+
+        let (scheduler, io) = schedule_single_page(&encoding, buffers, &DataType::Int32).unwrap();
+
+        let ranges = [
+            0..num_rows,
+            5..40,
+            40..41,
+            100..300,
+            (num_rows - 3)..num_rows,
+        ];
+        for range in ranges {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = decoded.into_arrow(DataType::Int32, true).unwrap();
+            let expected = values.slice(range.start as usize, num_rows as usize);
+            assert_eq!(Int32Array::from(decoded), expected);
+        }
+
+        // Multiple ranges and a partial decode
+        let decoder = scheduler
+            .schedule_ranges(&[2..4, 60..70], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(1, 5).unwrap();
+        let decoded = Int32Array::from(decoded.into_arrow(DataType::Int32, true).unwrap());
+        let expected =
+            Int32Array::from_iter_values([3, 60, 61, 62, 63].iter().map(|row| values.value(*row)));
+        assert_eq!(decoded, expected);
+    }
+
+    #[tokio::test]
+    async fn test_rle_validity_round_trip() {
+        // Null for the first half and valid for the second half
+        let values =
+            Int32Array::from_iter(std::iter::repeat_n(None, 1000).chain((0..1000).map(Some)));
+        let num_rows = values.len() as u64;
+        let validity = DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::from(values.nulls().unwrap().inner().clone().into_inner()),
+            bits_per_value: 1,
+            num_values: num_rows,
+            block_info: BlockInfo::new(),
+        });
+
+        let mut buffer_index = 0;
+        let encoded_validity = RleEncoder::new()
+            .encode(validity, &DataType::Boolean, &mut buffer_index)
+            .unwrap();
+        let (mut buffers, validity_encoding) = encoded_validity.into_buffers();
+        // 4 runs each of 0 and 1, instead of 250 bytes for a dense bitmap
+        assert_eq!(buffers.iter().map(|b| b.len()).sum::<usize>(), 16);
+
+        buffers.push(LanceBuffer::from(values.values().inner().clone()));
+        let encoding = ProtobufUtils::basic_some_null_encoding(
+            validity_encoding,
+            ProtobufUtils::flat_encoding(32, buffer_index, None),
+            None,
+        );
+
+        let (scheduler, io) = schedule_single_page(&encoding, buffers, &DataType::Int32).unwrap();
+
+        for range in [0..num_rows, 990..1010, 3..7, 1500..num_rows] {
+            let decoder = scheduler
+                .schedule_ranges(&[range.clone()], &io, 0)
+                .await
+                .unwrap();
+            let num_rows = range.end - range.start;
+            let decoded = decoder.decode(0, num_rows).unwrap();
+            let decoded = decoded.into_arrow(DataType::Int32, true).unwrap();
+            let expected = values.slice(range.start as usize, num_rows as usize);
+            assert_eq!(Int32Array::from(decoded), expected);
+        }
+    }
+}
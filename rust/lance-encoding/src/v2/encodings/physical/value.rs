@@ -12,7 +12,8 @@ use std::sync::{Arc, Mutex};
 use crate::buffer::LanceBuffer;
 use crate::data::{BlockInfo, DataBlock, FixedWidthDataBlock};
 use crate::encodings::physical::block::{
-    CompressionConfig, CompressionScheme, GeneralBufferCompressor,
+    BufferPipeline, CompressionConfig, CompressionScheme, GeneralBufferCompressor,
+    ZstdBufferCompressor,
 };
 use crate::encodings::physical::value::ValueEncoder;
 use crate::format::ProtobufUtils;
@@ -24,8 +25,42 @@ use crate::{
 
 use lance_core::{Error, Result};
 
+/// A decoder for fixed-width values that were already reconstructed into a single buffer
+///
+/// Encodings that have to reconstruct the values of a page before they can be sliced
+/// (e.g. run-length and delta encoding) decode into this.
+pub(crate) struct ExpandedValuesDecoder {
+    bytes_per_value: u64,
+    data: LanceBuffer,
+}
+
+impl ExpandedValuesDecoder {
+    pub(crate) fn new(bytes_per_value: u64, data: LanceBuffer) -> Self {
+        Self {
+            bytes_per_value,
+            data,
+        }
+    }
+}
+
+impl PrimitivePageDecoder for ExpandedValuesDecoder {
+    fn decode(&self, rows_to_skip: u64, num_rows: u64) -> Result<DataBlock> {
+        let offset = (rows_to_skip * self.bytes_per_value) as usize;
+        let length = (num_rows * self.bytes_per_value) as usize;
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            bits_per_value: self.bytes_per_value * 8,
+            data: self.data.slice_with_length(offset, length),
+            num_values: num_rows,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
 /// Scheduler for a simple encoding where buffers of fixed-size items are stored as-is on disk
-#[derive(Debug, Clone, Copy)]
+///
+/// Values are assumed to be little-endian, like the in-memory arrow layout, unless
+/// [`Self::with_big_endian`] is used.
+#[derive(Debug, Clone)]
 pub struct ValuePageScheduler {
     // TODO: do we really support values greater than 2^32 bytes per value?
     // I think we want to, in theory, but will need to test this case.
@@ -33,6 +68,8 @@ pub struct ValuePageScheduler {
     buffer_offset: u64,
     buffer_size: u64,
     compression_config: CompressionConfig,
+    big_endian: bool,
+    pipeline: BufferPipeline,
 }
 
 impl ValuePageScheduler {
@@ -47,8 +84,28 @@ impl ValuePageScheduler {
             buffer_offset,
             buffer_size,
             compression_config,
+            big_endian: false,
+            pipeline: BufferPipeline::default(),
         }
     }
+
+    /// Set if the values are stored big-endian, they are byte-swapped when decoded
+    pub fn with_big_endian(mut self, big_endian: bool) -> Self {
+        self.big_endian = big_endian;
+        self
+    }
+
+    /// Set the layers the buffer was encoded with, they are undone when decoded
+    ///
+    /// Like a compressed buffer, the entire buffer is read and decoded for any request.
+    pub fn with_pipeline(mut self, pipeline: BufferPipeline) -> Self {
+        self.pipeline = pipeline;
+        self
+    }
+
+    fn is_compressed(&self) -> bool {
+        self.compression_config.scheme != CompressionScheme::None || !self.pipeline.is_empty()
+    }
 }
 
 impl PageScheduler for ValuePageScheduler {
@@ -59,17 +116,21 @@ impl PageScheduler for ValuePageScheduler {
         top_level_row: u64,
     ) -> BoxFuture<'static, Result<Box<dyn PrimitivePageDecoder>>> {
         let (mut min, mut max) = (u64::MAX, 0);
-        let byte_ranges = if self.compression_config.scheme == CompressionScheme::None {
-            ranges
-                .iter()
-                .map(|range| {
-                    let start = self.buffer_offset + (range.start * self.bytes_per_value);
-                    let end = self.buffer_offset + (range.end * self.bytes_per_value);
-                    min = min.min(start);
-                    max = max.max(end);
-                    start..end
-                })
-                .collect::<Vec<_>>()
+        let byte_ranges = if !self.is_compressed() {
+            let mut byte_ranges: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                let start = self.buffer_offset + (range.start * self.bytes_per_value);
+                let end = self.buffer_offset + (range.end * self.bytes_per_value);
+                min = min.min(start);
+                max = max.max(end);
+                match byte_ranges.last_mut() {
+                    // Adjacent ranges are read as one buffer so that a decode spanning
+                    // them does not need to copy
+                    Some(last) if last.end == start => last.end = end,
+                    _ => byte_ranges.push(start..end),
+                }
+            }
+            byte_ranges
         } else {
             min = self.buffer_offset;
             max = self.buffer_offset + self.buffer_size;
@@ -90,7 +151,7 @@ impl PageScheduler for ValuePageScheduler {
         let bytes = scheduler.submit_request(byte_ranges, top_level_row);
         let bytes_per_value = self.bytes_per_value;
 
-        let range_offsets = if self.compression_config.scheme != CompressionScheme::None {
+        let range_offsets = if self.is_compressed() {
             ranges
                 .iter()
                 .map(|range| {
@@ -104,6 +165,8 @@ impl PageScheduler for ValuePageScheduler {
         };
 
         let compression_config = self.compression_config;
+        let big_endian = self.big_endian;
+        let pipeline = self.pipeline.clone();
         async move {
             let bytes = bytes.await?;
 
@@ -113,6 +176,8 @@ impl PageScheduler for ValuePageScheduler {
                 uncompressed_data: Arc::new(Mutex::new(None)),
                 uncompressed_range_offsets: range_offsets,
                 compression_config,
+                big_endian,
+                pipeline,
             }) as Box<dyn PrimitivePageDecoder>)
         }
         .boxed()
@@ -125,15 +190,27 @@ struct ValuePageDecoder {
     uncompressed_data: Arc<Mutex<Option<Vec<Bytes>>>>,
     uncompressed_range_offsets: Vec<std::ops::Range<usize>>,
     compression_config: CompressionConfig,
+    big_endian: bool,
+    pipeline: BufferPipeline,
 }
 
 impl ValuePageDecoder {
     fn decompress(&self) -> Result<Vec<Bytes>> {
         // for compressed page, it is guaranteed that only one range is passed
-        let bytes_u8: Vec<u8> = self.data[0].to_vec();
-        let buffer_compressor = GeneralBufferCompressor::get_compressor(self.compression_config);
-        let mut uncompressed_bytes: Vec<u8> = Vec::new();
-        buffer_compressor.decompress(&bytes_u8, &mut uncompressed_bytes)?;
+        let uncompressed_bytes = if !self.pipeline.is_empty() {
+            self.pipeline.decode(&self.data[0])?
+        } else if self.compression_config.scheme == CompressionScheme::Zstd {
+            // Stream the decompression so we only hold on to the requested bytes, pages
+            // can be much larger than the ranges we need from them
+            return ZstdBufferCompressor::new(self.compression_config.level.unwrap_or(0))
+                .decompress_ranges(&self.data[0], &self.uncompressed_range_offsets);
+        } else {
+            let buffer_compressor =
+                GeneralBufferCompressor::get_compressor(self.compression_config);
+            let mut uncompressed_bytes: Vec<u8> = Vec::new();
+            buffer_compressor.decompress(&self.data[0], &mut uncompressed_bytes)?;
+            uncompressed_bytes
+        };
 
         let mut bytes_in_ranges: Vec<Bytes> =
             Vec::with_capacity(self.uncompressed_range_offsets.len());
@@ -167,7 +244,8 @@ impl ValuePageDecoder {
 
         for buf in buffers.into_iter() {
             let buf_len = buf.len() as u64;
-            if bytes_to_skip > buf_len {
+            // Buffers that are skipped entirely don't start a copy
+            if bytes_to_skip >= buf_len {
                 bytes_to_skip -= buf_len;
             } else {
                 let bytes_to_take_here = (buf_len - bytes_to_skip).min(bytes_to_take);
@@ -206,6 +284,15 @@ impl PrimitivePageDecoder for ValuePageDecoder {
         } else {
             self.decode_buffers(&self.data, bytes_to_skip, bytes_to_take)
         };
+        let data_buffer = if self.big_endian && self.bytes_per_value > 1 {
+            let mut swapped = data_buffer.to_vec();
+            swapped
+                .chunks_exact_mut(self.bytes_per_value as usize)
+                .for_each(|value| value.reverse());
+            LanceBuffer::from(swapped)
+        } else {
+            data_buffer
+        };
         Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
             bits_per_value: self.bytes_per_value * 8,
             data: data_buffer,
@@ -243,3 +330,188 @@ impl ArrayEncoder for ValueEncoder {
         Ok(EncodedArray { data, encoding })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, Int64Array};
+    use arrow_schema::DataType;
+    use bytes::Bytes;
+
+    use crate::{
+        data::DataBlock,
+        decoder::PageScheduler,
+        encodings::physical::{
+            block::{BufferLayer, BufferPipeline, CompressionConfig, CompressionScheme},
+            value::ValueEncoder,
+        },
+        format::{
+            pb::{self, array_encoding::ArrayEncoding as ArrayEncodingEnum},
+            ProtobufUtils,
+        },
+        testing::{schedule_single_page, SimulatedScheduler},
+        v2::{encoder::ArrayEncoder, encodings::physical::delta::DeltaEncoder},
+        EncodingsIo,
+    };
+
+    use super::ValuePageScheduler;
+
+    #[tokio::test]
+    async fn test_decode_adjacent_ranges() {
+        let data = Bytes::from(
+            (0..100_i32)
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>(),
+        );
+        // A single fetched buffer is only decoded in place if it is aligned
+        assert_eq!(data.as_ptr().align_offset(4), 0);
+        let io = Arc::new(SimulatedScheduler::new(data.clone())) as Arc<dyn EncodingsIo>;
+        let scheduler = ValuePageScheduler::new(
+            4,
+            0,
+            data.len() as u64,
+            CompressionConfig::new(CompressionScheme::None, None),
+        );
+        let decode = |ranges: Vec<std::ops::Range<u64>>, rows_to_skip: u64, num_rows: u64| {
+            let decoder = scheduler.schedule_ranges(&ranges, &io, 0);
+            let data = data.clone();
+            async move {
+                let decoded = decoder
+                    .await
+                    .unwrap()
+                    .decode(rows_to_skip, num_rows)
+                    .unwrap();
+                let decoded = decoded.as_fixed_width().unwrap().data;
+                let values = decoded
+                    .chunks_exact(4)
+                    .map(|v| i32::from_le_bytes(v.try_into().unwrap()))
+                    .collect::<Vec<_>>();
+                let is_zero_copy = decoded.as_ptr() >= data.as_ptr()
+                    && decoded.as_ptr() < data.as_ptr().wrapping_add(data.len());
+                (values, is_zero_copy)
+            }
+        };
+
+        // Adjacent ranges are fetched as one buffer and decoded straight from it
+        let (values, is_zero_copy) = decode(vec![10..20, 20..30], 5, 10).await;
+        assert_eq!(values, (15..25).collect::<Vec<_>>());
+        assert!(is_zero_copy);
+
+        // As are requests that skip entire ranges
+        let (values, is_zero_copy) = decode(vec![10..20, 40..50], 10, 5).await;
+        assert_eq!(values, (40..45).collect::<Vec<_>>());
+        assert!(is_zero_copy);
+
+        // Values from disjoint ranges must be copied
+        let (values, is_zero_copy) = decode(vec![10..20, 40..50], 5, 10).await;
+        assert_eq!(values, (15..20).chain(40..45).collect::<Vec<_>>());
+        assert!(!is_zero_copy);
+    }
+
+    #[tokio::test]
+    async fn test_decode_big_endian() {
+        let values = (0..100_i32).map(|v| v * 1000 - 7).collect::<Vec<_>>();
+        let data = values
+            .iter()
+            .flat_map(|v| v.to_be_bytes())
+            .collect::<Vec<_>>();
+        let encoding = ProtobufUtils::flat_encoding(32, 0, None);
+        let Some(ArrayEncodingEnum::Flat(mut flat)) = encoding.array_encoding else {
+            unreachable!()
+        };
+        flat.big_endian = true;
+        let encoding = pb::ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::Flat(flat)),
+        };
+        let (scheduler, io) = schedule_single_page(&encoding, [data], &DataType::Int32).unwrap();
+
+        let decoder = scheduler
+            .schedule_ranges(&[10..20, 40..50], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(5, 10).unwrap();
+        let decoded = Int32Array::from(decoded.into_arrow(DataType::Int32, true).unwrap());
+        let expected = values[15..20]
+            .iter()
+            .chain(&values[40..45])
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(decoded.values().to_vec(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_decode_delta_zstd() {
+        // Slowly increasing timestamps compress much better as deltas
+        let values = Int64Array::from_iter_values(
+            (0..10_000_i64).map(|v| 1_700_000_000_000 + v * 1000 + (v % 7) * 3 - 10),
+        );
+        let mut buffer_index = 0;
+        let encoded = DeltaEncoder::new(Box::<ValueEncoder>::default())
+            .encode(
+                DataBlock::from_array(values.clone()),
+                &DataType::Int64,
+                &mut buffer_index,
+            )
+            .unwrap();
+        let (buffers, encoding) = encoded.into_buffers();
+
+        // The deltas are stored as a zstd compressed flat buffer
+        let zstd = BufferPipeline::new(vec![BufferLayer::Compression(CompressionConfig::new(
+            CompressionScheme::Zstd,
+            Some(3),
+        ))]);
+        let data = zstd.encode(&buffers[0]).unwrap();
+        let raw = values
+            .values()
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert!(data.len() < zstd.encode(&raw).unwrap().len());
+        let Some(ArrayEncodingEnum::Delta(mut delta)) = encoding.array_encoding else {
+            unreachable!()
+        };
+        delta.deltas = Some(Box::new(ProtobufUtils::flat_encoding_with_layers(
+            64, 0, &zstd,
+        )));
+        let encoding = pb::ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::Delta(delta)),
+        };
+        let (scheduler, io) = schedule_single_page(&encoding, [&data], &DataType::Int64).unwrap();
+
+        let decoder = scheduler
+            .schedule_ranges(&[10..20, 5000..6000], &io, 0)
+            .await
+            .unwrap();
+        let decoded = decoder.decode(5, 500).unwrap();
+        let decoded = Int64Array::from(decoded.into_arrow(DataType::Int64, true).unwrap());
+        let expected = values.values()[15..20]
+            .iter()
+            .chain(&values.values()[5000..5495])
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(decoded.values().to_vec(), expected);
+
+        // Layers can't be combined with the compression or byte order of the flat encoding
+        let flat_with = |update: fn(&mut pb::Flat)| {
+            let encoding = ProtobufUtils::flat_encoding_with_layers(64, 0, &zstd);
+            let Some(ArrayEncodingEnum::Flat(mut flat)) = encoding.array_encoding else {
+                unreachable!()
+            };
+            update(&mut flat);
+            pb::ArrayEncoding {
+                array_encoding: Some(ArrayEncodingEnum::Flat(flat)),
+            }
+        };
+        let compressed = flat_with(|flat| {
+            flat.compression = Some(pb::Compression {
+                scheme: "zstd".to_string(),
+                level: None,
+            })
+        });
+        assert!(schedule_single_page(&compressed, [&data], &DataType::Int64).is_err());
+        let big_endian = flat_with(|flat| flat.big_endian = true);
+        let err = schedule_single_page(&big_endian, [&data], &DataType::Int64).unwrap_err();
+        assert!(err.to_string().contains("big-endian"), "{}", err);
+    }
+}
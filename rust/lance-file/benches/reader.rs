@@ -1,16 +1,19 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use arrow_array::{cast::AsArray, types::Int32Type, UInt32Array};
-use arrow_schema::DataType;
+use arrow_array::{cast::AsArray, types::Int32Type, RecordBatch, UInt32Array};
+use arrow_schema::{DataType, Field, Schema};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use futures::{FutureExt, StreamExt};
-use lance_datagen::ArrayGeneratorExt;
+use lance_core::datatypes::COMPRESSION_META_KEY;
+use lance_datagen::{ArrayGeneratorExt, ByteCount};
 use lance_encoding::decoder::{DecoderPlugins, FilterExpression};
+use lance_encoding::v2::encodings::physical::describe_encoding;
 use lance_file::{
     v2::{
-        reader::{FileReader, FileReaderOptions},
+        reader::{FileReader, FileReaderOptions, ReaderProjection},
         testing::test_cache,
         writer::{FileWriter, FileWriterOptions},
     },
@@ -229,17 +232,154 @@ fn bench_random_access(c: &mut Criterion) {
     }
 }
 
+/// Decode each column of a 2.0 file on its own and report the throughput by encoding
+///
+/// The columns are chosen to cover the common encodings.  Each encoding gets its own
+/// benchmark group, `decode_by_encoding/<encoding>` (e.g. `decode_by_encoding/Fsst`),
+/// measured in bytes of decoded data so criterion reports the throughput per encoding.
+/// The encoding is read from the column metadata, see [`describe_encoding`].
+fn bench_decode_by_encoding(c: &mut Criterion) {
+    const NUM_ROWS: u64 = 1024 * 1024;
+    let data = lance_datagen::gen()
+        .col("ints", lance_datagen::array::rand_type(&DataType::Int32))
+        .col(
+            "floats",
+            lance_datagen::array::rand_type(&DataType::Float64),
+        )
+        .col(
+            "dict_strings",
+            lance_datagen::array::cycle_utf8_literals(&["red", "green", "blue", "yellow"]),
+        )
+        .col(
+            "strings",
+            lance_datagen::array::rand_utf8(ByteCount::from(16), false),
+        )
+        .col(
+            "fsst_strings",
+            lance_datagen::array::rand_utf8(ByteCount::from(16), false),
+        )
+        .into_batch_rows(lance_datagen::RowCount::from(NUM_ROWS))
+        .unwrap();
+    // FSST is only used by default in 2.1 so it is requested explicitly
+    let fields = data
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| {
+            if field.name() == "fsst_strings" {
+                Field::clone(field).with_metadata(HashMap::from([(
+                    COMPRESSION_META_KEY.to_string(),
+                    "fsst".to_string(),
+                )]))
+            } else {
+                Field::clone(field)
+            }
+        })
+        .collect::<Vec<_>>();
+    let data =
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), data.columns().to_vec()).unwrap();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let test_path = tempdir.path();
+    let (object_store, base_path) = rt
+        .block_on(ObjectStore::from_uri(
+            test_path.as_os_str().to_str().unwrap(),
+        ))
+        .unwrap();
+    let file_path = base_path.child("foo.lance");
+    let object_writer = rt.block_on(object_store.create(&file_path)).unwrap();
+    let lance_schema: lance_core::datatypes::Schema = data.schema().as_ref().try_into().unwrap();
+    let mut writer = FileWriter::try_new(
+        object_writer,
+        lance_schema.clone(),
+        FileWriterOptions {
+            format_version: Some(LanceFileVersion::V2_0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    rt.block_on(writer.write_batch(&data)).unwrap();
+    rt.block_on(writer.finish()).unwrap();
+
+    let reader = Arc::new(rt.block_on(async {
+        let store_scheduler =
+            ScanScheduler::new(object_store.clone(), SchedulerConfig::default_for_testing());
+        let scheduler = store_scheduler
+            .open_file(&file_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        FileReader::try_open(
+            scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap()
+    }));
+
+    // Every column is primitive so the column index is the field index
+    let schema = data.schema();
+    for (column_index, field) in schema.fields().iter().enumerate() {
+        let column_info = &reader.metadata().column_infos[column_index];
+        let tree = describe_encoding(column_info.page_infos[0].encoding.as_legacy());
+        // Name the benchmark by the encoding under the validity wrapper
+        let encoding = match tree.children.iter().find(|(role, _)| role == "values") {
+            Some((_, values)) if tree.name == "Nullable" => values.name.clone(),
+            _ => tree.name.clone(),
+        };
+
+        let projection = ReaderProjection::from_column_names(
+            LanceFileVersion::V2_0,
+            &lance_schema,
+            &[field.name().as_str()],
+        )
+        .unwrap();
+        let mut group = c.benchmark_group(format!("decode_by_encoding/{}", encoding));
+        group.throughput(criterion::Throughput::Bytes(
+            data.column(column_index).get_buffer_memory_size() as u64,
+        ));
+        group.bench_function(field.name(), |b| {
+            b.iter(|| {
+                let reader = reader.clone();
+                let projection = projection.clone();
+                rt.block_on(async move {
+                    let mut stream = reader
+                        .read_tasks(
+                            lance_io::ReadBatchParams::RangeFull,
+                            16 * 1024,
+                            Some(projection),
+                            FilterExpression::no_filter(),
+                        )
+                        .unwrap()
+                        .map(|batch_task| batch_task.task)
+                        .buffer_unordered(16);
+                    let mut row_count = 0;
+                    while let Some(batch) = stream.next().await {
+                        row_count += batch.unwrap().num_rows();
+                    }
+                    assert_eq!(row_count as u64, NUM_ROWS);
+                    black_box(row_count);
+                });
+            })
+        });
+        group.finish();
+    }
+}
+
 #[cfg(target_os = "linux")]
 criterion_group!(
     name=benches;
     config = Criterion::default().significance_level(0.1).sample_size(10)
         .with_profiler(pprof::criterion::PProfProfiler::new(100, pprof::criterion::Output::Flamegraph(None)));
-    targets = bench_reader, bench_random_access);
+    targets = bench_reader, bench_random_access, bench_decode_by_encoding);
 
 // Non-linux version does not support pprof.
 #[cfg(not(target_os = "linux"))]
 criterion_group!(
     name=benches;
     config = Criterion::default().significance_level(0.1).sample_size(10);
-    targets = bench_reader, bench_random_access);
+    targets = bench_reader, bench_random_access, bench_decode_by_encoding);
 criterion_main!(benches);
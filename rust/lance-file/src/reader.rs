@@ -627,19 +627,25 @@ async fn read_dictionary_array(
 ) -> Result<ArrayRef> {
     let page_info = get_page_info(page_table, field, batch_id)?;
     let data_type = field.data_type();
+    let values = field
+        .dictionary
+        .as_ref()
+        .and_then(|dict| dict.values.as_ref())
+        .ok_or_else(|| Error::InvalidInput {
+            source: format!(
+                "The dictionary of field '{}' was not loaded, the dataset may have been \
+                 opened without populating dictionaries",
+                field.name
+            )
+            .into(),
+            location: location!(),
+        })?;
     let decoder = DictionaryDecoder::new(
         reader.object_reader.as_ref(),
         page_info.position,
         page_info.length,
         &data_type,
-        field
-            .dictionary
-            .as_ref()
-            .unwrap()
-            .values
-            .as_ref()
-            .unwrap()
-            .clone(),
+        values.clone(),
     );
     decoder.get(params.clone()).await
 }
@@ -9,19 +9,21 @@ use std::{
     sync::Arc,
 };
 
-use arrow_array::RecordBatchReader;
-use arrow_schema::Schema as ArrowSchema;
+use arrow_array::{make_array, new_empty_array, ArrayRef, RecordBatch, RecordBatchReader};
+use arrow_schema::{DataType, Schema as ArrowSchema};
 use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
 use bytes::{Bytes, BytesMut};
 use deepsize::{Context, DeepSizeOf};
 use futures::{stream::BoxStream, Stream, StreamExt};
 use lance_encoding::{
     decoder::{
-        schedule_and_decode, schedule_and_decode_blocking, ColumnInfo, DecoderPlugins,
-        FilterExpression, PageEncoding, PageInfo, ReadBatchTask, RequestedRows,
-        SchedulerDecoderConfig,
+        schedule_and_decode, schedule_and_decode_blocking, ColumnBuffers, ColumnInfo,
+        DecoderPlugins, FileBuffers, FilterExpression, PageBuffers, PageEncoding, PageInfo,
+        PageScheduler, ReadBatchTask, RequestedRows, SchedulerDecoderConfig,
     },
     encoder::EncodedBatch,
+    utils::coalesce::DEFAULT_COALESCE_GAP_BYTES,
+    v2::encodings::physical::{decoder_from_array_encoding, dictionary::DictionaryDecodeMode},
     version::LanceFileVersion,
     EncodingsIo,
 };
@@ -37,8 +39,10 @@ use lance_core::{
 };
 use lance_encoding::format::pb as pbenc;
 use lance_io::{
-    scheduler::FileScheduler,
+    object_store::ObjectStore,
+    scheduler::{FileScheduler, ScanScheduler, SchedulerConfig},
     stream::{RecordBatchStream, RecordBatchStreamAdapter},
+    utils::CachedFileSize,
     ReadBatchParams,
 };
 
@@ -311,12 +315,37 @@ impl ReaderProjection {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct FileReaderOptions {
     validate_on_decode: bool,
     /// Whether to cache repetition indices for better performance
     /// Default is false for backward compatibility
     pub cache_repetition_index: bool,
+    /// Requests made while scheduling a page are coalesced into a single request if they
+    /// are no more than this many bytes apart.  `None` disables coalescing.  This only
+    /// applies to 2.0 files.  Default is 4 KiB.
+    pub coalesce_gap_bytes: Option<u64>,
+    /// The maximum number of bytes of a single column that can be requested but not yet
+    /// received.  `None` means there is no limit.  This only applies to 2.0 files.  Default
+    /// is `None`.
+    pub io_budget_bytes: Option<u64>,
+    /// How pages of top-level string and binary columns that were dictionary encoded on
+    /// write are decoded.  With [`DictionaryDecodeMode::Indices`] those columns are read
+    /// as `Dictionary(UInt32, _)` arrays.  This only applies to 2.0 files.  Default is
+    /// [`DictionaryDecodeMode::Values`].
+    pub dictionary_decode_mode: DictionaryDecodeMode,
+}
+
+impl Default for FileReaderOptions {
+    fn default() -> Self {
+        Self {
+            validate_on_decode: false,
+            cache_repetition_index: false,
+            coalesce_gap_bytes: Some(DEFAULT_COALESCE_GAP_BYTES),
+            io_budget_bytes: None,
+            dictionary_decode_mode: DictionaryDecodeMode::default(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -827,38 +856,38 @@ impl FileReader {
         Ok(self.metadata.column_infos.to_vec())
     }
 
-    #[allow(clippy::too_many_arguments)]
+    // The options used to schedule and decode a single read
+    fn scheduler_config(&self, batch_size: u32) -> SchedulerDecoderConfig {
+        SchedulerDecoderConfig {
+            batch_size,
+            cache: self.cache.clone(),
+            decoder_plugins: self.decoder_plugins.clone(),
+            io: self.scheduler.clone(),
+            should_validate: self.options.validate_on_decode,
+            cache_repetition_index: self.options.cache_repetition_index,
+            io_budget_bytes: self.options.io_budget_bytes,
+            dictionary_decode_mode: self.options.dictionary_decode_mode,
+            coalesce_gap_bytes: self.options.coalesce_gap_bytes,
+        }
+    }
+
     fn do_read_range(
         column_infos: Vec<Arc<ColumnInfo>>,
-        io: Arc<dyn EncodingsIo>,
-        cache: Arc<LanceCache>,
         num_rows: u64,
-        decoder_plugins: Arc<DecoderPlugins>,
         range: Range<u64>,
-        batch_size: u32,
         projection: ReaderProjection,
         filter: FilterExpression,
-        should_validate: bool,
-        cache_repetition_index: bool,
+        config: SchedulerDecoderConfig,
     ) -> Result<BoxStream<'static, ReadBatchTask>> {
         debug!(
             "Reading range {:?} with batch_size {} from file with {} rows and {} columns into schema with {} columns",
             range,
-            batch_size,
+            config.batch_size,
             num_rows,
             column_infos.len(),
             projection.schema.fields.len(),
         );
 
-        let config = SchedulerDecoderConfig {
-            batch_size,
-            cache,
-            decoder_plugins,
-            io,
-            should_validate,
-            cache_repetition_index,
-        };
-
         let requested_rows = RequestedRows::Ranges(vec![range]);
 
         Ok(schedule_and_decode(
@@ -881,50 +910,30 @@ impl FileReader {
         // Create and initialize the stream
         Self::do_read_range(
             self.collect_columns_from_projection(&projection)?,
-            self.scheduler.clone(),
-            self.cache.clone(),
             self.num_rows,
-            self.decoder_plugins.clone(),
             range,
-            batch_size,
             projection,
             filter,
-            self.options.validate_on_decode,
-            self.options.cache_repetition_index,
+            self.scheduler_config(batch_size),
         )
     }
 
-    #[allow(clippy::too_many_arguments)]
     fn do_take_rows(
         column_infos: Vec<Arc<ColumnInfo>>,
-        io: Arc<dyn EncodingsIo>,
-        cache: Arc<LanceCache>,
-        decoder_plugins: Arc<DecoderPlugins>,
         indices: Vec<u64>,
-        batch_size: u32,
         projection: ReaderProjection,
         filter: FilterExpression,
-        should_validate: bool,
-        cache_repetition_index: bool,
+        config: SchedulerDecoderConfig,
     ) -> Result<BoxStream<'static, ReadBatchTask>> {
         debug!(
             "Taking {} rows spread across range {}..{} with batch_size {} from columns {:?}",
             indices.len(),
             indices[0],
             indices[indices.len() - 1],
-            batch_size,
+            config.batch_size,
             column_infos.iter().map(|ci| ci.index).collect::<Vec<_>>()
         );
 
-        let config = SchedulerDecoderConfig {
-            batch_size,
-            cache,
-            decoder_plugins,
-            io,
-            should_validate,
-            cache_repetition_index,
-        };
-
         let requested_rows = RequestedRows::Indices(indices);
 
         Ok(schedule_and_decode(
@@ -946,30 +955,19 @@ impl FileReader {
         // Create and initialize the stream
         Self::do_take_rows(
             self.collect_columns_from_projection(&projection)?,
-            self.scheduler.clone(),
-            self.cache.clone(),
-            self.decoder_plugins.clone(),
             indices,
-            batch_size,
             projection,
             FilterExpression::no_filter(),
-            self.options.validate_on_decode,
-            self.options.cache_repetition_index,
+            self.scheduler_config(batch_size),
         )
     }
 
-    #[allow(clippy::too_many_arguments)]
     fn do_read_ranges(
         column_infos: Vec<Arc<ColumnInfo>>,
-        io: Arc<dyn EncodingsIo>,
-        cache: Arc<LanceCache>,
-        decoder_plugins: Arc<DecoderPlugins>,
         ranges: Vec<Range<u64>>,
-        batch_size: u32,
         projection: ReaderProjection,
         filter: FilterExpression,
-        should_validate: bool,
-        cache_repetition_index: bool,
+        config: SchedulerDecoderConfig,
     ) -> Result<BoxStream<'static, ReadBatchTask>> {
         let num_rows = ranges.iter().map(|r| r.end - r.start).sum::<u64>();
         debug!(
@@ -978,19 +976,10 @@ impl FileReader {
             num_rows,
             ranges[0].start,
             ranges[ranges.len() - 1].end,
-            batch_size,
+            config.batch_size,
             column_infos.iter().map(|ci| ci.index).collect::<Vec<_>>()
         );
 
-        let config = SchedulerDecoderConfig {
-            batch_size,
-            cache,
-            decoder_plugins,
-            io,
-            should_validate,
-            cache_repetition_index,
-        };
-
         let requested_rows = RequestedRows::Ranges(ranges);
 
         Ok(schedule_and_decode(
@@ -1012,15 +1001,10 @@ impl FileReader {
     ) -> Result<BoxStream<'static, ReadBatchTask>> {
         Self::do_read_ranges(
             self.collect_columns_from_projection(&projection)?,
-            self.scheduler.clone(),
-            self.cache.clone(),
-            self.decoder_plugins.clone(),
             ranges,
-            batch_size,
             projection,
             filter,
-            self.options.validate_on_decode,
-            self.options.cache_repetition_index,
+            self.scheduler_config(batch_size),
         )
     }
 
@@ -1168,14 +1152,7 @@ impl FileReader {
             column_infos.iter().map(|ci| ci.index).collect::<Vec<_>>()
         );
 
-        let config = SchedulerDecoderConfig {
-            batch_size,
-            cache: self.cache.clone(),
-            decoder_plugins: self.decoder_plugins.clone(),
-            io: self.scheduler.clone(),
-            should_validate: self.options.validate_on_decode,
-            cache_repetition_index: self.options.cache_repetition_index,
-        };
+        let config = self.scheduler_config(batch_size);
 
         let requested_rows = RequestedRows::Indices(indices);
 
@@ -1208,14 +1185,7 @@ impl FileReader {
             column_infos.iter().map(|ci| ci.index).collect::<Vec<_>>()
         );
 
-        let config = SchedulerDecoderConfig {
-            batch_size,
-            cache: self.cache.clone(),
-            decoder_plugins: self.decoder_plugins.clone(),
-            io: self.scheduler.clone(),
-            should_validate: self.options.validate_on_decode,
-            cache_repetition_index: self.options.cache_repetition_index,
-        };
+        let config = self.scheduler_config(batch_size);
 
         let requested_rows = RequestedRows::Ranges(ranges);
 
@@ -1248,14 +1218,7 @@ impl FileReader {
             projection.schema.fields.len(),
         );
 
-        let config = SchedulerDecoderConfig {
-            batch_size,
-            cache: self.cache.clone(),
-            decoder_plugins: self.decoder_plugins.clone(),
-            io: self.scheduler.clone(),
-            should_validate: self.options.validate_on_decode,
-            cache_repetition_index: self.options.cache_repetition_index,
-        };
+        let config = self.scheduler_config(batch_size);
 
         let requested_rows = RequestedRows::Ranges(vec![range]);
 
@@ -1431,6 +1394,234 @@ pub fn describe_encoding(page: &pbfile::column_metadata::Page) -> String {
     }
 }
 
+/// Decodes a single column of a 2.0 file into an Arrow array
+///
+/// The data type of the column is taken from `schema`, which is expected to be the
+/// schema the file was written with.  In 2.0 files each field is stored in its own
+/// column, in pre-order, so `column_index` is the pre-order index of the field.  Only
+/// leaf (non-nested) fields can be decoded this way.
+///
+/// Only the buffers belonging to the requested column are read.
+pub async fn decode_column(
+    object_store: &Arc<ObjectStore>,
+    path: &Path,
+    schema: &Schema,
+    column_index: u32,
+) -> Result<ArrayRef> {
+    let field = schema
+        .fields_pre_order()
+        .nth(column_index as usize)
+        .ok_or_else(|| Error::InvalidInput {
+            source: format!(
+                "column index {} is out of range for a schema with {} fields",
+                column_index,
+                schema.fields_pre_order().count()
+            )
+            .into(),
+            location: location!(),
+        })?;
+    let data_type = field.data_type();
+    if data_type.is_nested() {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "cannot decode column {} ({}) because it has the nested type {}",
+                column_index, field.name, data_type
+            )
+            .into(),
+            location: location!(),
+        });
+    }
+
+    let (metadata, io) = open_for_page_decode(object_store, path).await?;
+    let pages = column_page_schedulers(&metadata, column_index, &data_type)?;
+    let num_rows = pages.iter().map(|(num_rows, _)| num_rows).sum::<u64>();
+    decode_column_rows(&pages, 0..num_rows, &io, &data_type).await
+}
+
+/// Streams the rows of a 2.0 file as batches of up to `batch_size` rows
+///
+/// This is a minimal reader for tools and tests: the pages of each column are decoded
+/// directly with the physical page schedulers, without the decoders and the I/O
+/// scheduling of [`FileReader`].  `schema` is expected to be the schema the file was
+/// written with and, as in [`decode_column`], all of its fields must be leaf fields.
+///
+/// The batches are read in order and each batch only reads the pages it overlaps.
+pub async fn stream_file(
+    object_store: &Arc<ObjectStore>,
+    path: &Path,
+    schema: &Schema,
+    batch_size: u32,
+) -> Result<Pin<Box<dyn RecordBatchStream>>> {
+    if batch_size == 0 {
+        return Err(Error::InvalidInput {
+            source: "the batch size must be greater than 0".into(),
+            location: location!(),
+        });
+    }
+    if let Some(field) = schema.fields.iter().find(|f| f.data_type().is_nested()) {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "cannot stream the file because the field {} has the nested type {}",
+                field.name,
+                field.data_type()
+            )
+            .into(),
+            location: location!(),
+        });
+    }
+
+    let (metadata, io) = open_for_page_decode(object_store, path).await?;
+    let data_types = schema
+        .fields
+        .iter()
+        .map(|field| field.data_type())
+        .collect::<Vec<_>>();
+    let columns = data_types
+        .iter()
+        .enumerate()
+        .map(|(column_index, data_type)| {
+            column_page_schedulers(&metadata, column_index as u32, data_type)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let arrow_schema = Arc::new(ArrowSchema::from(schema));
+    let columns = Arc::new(columns);
+    let data_types = Arc::new(data_types);
+    let num_rows = metadata.num_rows;
+    let batch_schema = arrow_schema.clone();
+    let batches = futures::stream::iter((0..num_rows).step_by(batch_size as usize))
+        .then(move |start| {
+            let rows = start..(start + batch_size as u64).min(num_rows);
+            let columns = columns.clone();
+            let data_types = data_types.clone();
+            let io = io.clone();
+            let schema = batch_schema.clone();
+            async move {
+                let mut arrays = Vec::with_capacity(columns.len());
+                for (pages, data_type) in columns.iter().zip(data_types.iter()) {
+                    arrays.push(decode_column_rows(pages, rows.clone(), &io, data_type).await?);
+                }
+                Ok(RecordBatch::try_new(schema, arrays)?)
+            }
+        })
+        .boxed();
+    Ok(Box::pin(RecordBatchStreamAdapter::new(
+        arrow_schema,
+        batches,
+    )))
+}
+
+// Open `path` and read its metadata for decoding pages with the physical page schedulers
+async fn open_for_page_decode(
+    object_store: &Arc<ObjectStore>,
+    path: &Path,
+) -> Result<(CachedFileMetadata, Arc<dyn EncodingsIo>)> {
+    let scan_scheduler = ScanScheduler::new(
+        object_store.clone(),
+        SchedulerConfig::max_bandwidth(object_store),
+    );
+    let file_scheduler = scan_scheduler
+        .open_file(path, &CachedFileSize::unknown())
+        .await?;
+    let metadata = FileReader::read_all_metadata(&file_scheduler).await?;
+    Ok((metadata, Arc::new(LanceEncodingsIo(file_scheduler))))
+}
+
+// The scheduler of each page of a column stored with the 2.0 encodings, along with the
+// number of rows in the page
+fn column_page_schedulers(
+    metadata: &CachedFileMetadata,
+    column_index: u32,
+    data_type: &DataType,
+) -> Result<Vec<(u64, Box<dyn PageScheduler>)>> {
+    let column_info = metadata
+        .column_infos
+        .get(column_index as usize)
+        .ok_or_else(|| Error::InvalidInput {
+            source: format!(
+                "column index {} is out of range for a file with {} columns",
+                column_index,
+                metadata.column_infos.len()
+            )
+            .into(),
+            location: location!(),
+        })?;
+
+    let file_buffers = metadata
+        .file_buffers
+        .iter()
+        .map(|buf| (buf.position, buf.size))
+        .collect::<Vec<_>>();
+    column_info
+        .page_infos
+        .iter()
+        .map(|page_info| {
+            let PageEncoding::Legacy(encoding) = &page_info.encoding else {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "cannot decode column {} because it is not stored with the 2.0 encodings",
+                        column_index
+                    )
+                    .into(),
+                    location: location!(),
+                });
+            };
+            let buffers = PageBuffers {
+                column_buffers: ColumnBuffers {
+                    file_buffers: FileBuffers {
+                        positions_and_sizes: &file_buffers,
+                    },
+                    positions_and_sizes: &column_info.buffer_offsets_and_sizes,
+                },
+                positions_and_sizes: &page_info.buffer_offsets_and_sizes,
+            };
+            let page_scheduler = decoder_from_array_encoding(encoding, &buffers, data_type)?;
+            Ok((page_info.num_rows, page_scheduler))
+        })
+        .collect()
+}
+
+// Decode `rows` of a column from the pages that overlap them
+async fn decode_column_rows(
+    pages: &[(u64, Box<dyn PageScheduler>)],
+    rows: Range<u64>,
+    io: &Arc<dyn EncodingsIo>,
+    data_type: &DataType,
+) -> Result<ArrayRef> {
+    let mut arrays = Vec::new();
+    let mut page_start = 0;
+    for (num_rows, page_scheduler) in pages {
+        let page_end = page_start + num_rows;
+        let start = rows.start.max(page_start);
+        let end = rows.end.min(page_end);
+        if start < end {
+            let page_rows = start - page_start..end - page_start;
+            arrays.push(decode_page_rows(page_scheduler.as_ref(), page_rows, io, data_type).await?);
+        }
+        page_start = page_end;
+    }
+    if arrays.is_empty() {
+        return Ok(new_empty_array(data_type));
+    }
+    let arrays = arrays.iter().map(|arr| arr.as_ref()).collect::<Vec<_>>();
+    Ok(arrow_select::concat::concat(&arrays)?)
+}
+
+// Decode `rows`, relative to the start of the page, of a single page
+async fn decode_page_rows(
+    page_scheduler: &dyn PageScheduler,
+    rows: Range<u64>,
+    io: &Arc<dyn EncodingsIo>,
+    data_type: &DataType,
+) -> Result<ArrayRef> {
+    let num_rows = rows.end - rows.start;
+    let page_decoder = page_scheduler.schedule_ranges(&[rows], io, 0).await?;
+    let data = page_decoder
+        .decode(0, num_rows)?
+        .into_arrow(data_type.clone(), true)?;
+    Ok(make_array(data))
+}
+
 pub trait EncodedBatchReaderExt {
     fn try_from_mini_lance(
         bytes: Bytes,
@@ -1539,10 +1730,12 @@ pub mod tests {
     use std::{collections::BTreeMap, pin::Pin, sync::Arc};
 
     use arrow_array::{
-        types::{Float64Type, Int32Type},
+        cast::AsArray,
+        types::{Float64Type, Int32Type, UInt32Type},
         RecordBatch, UInt32Array,
     };
     use arrow_schema::{DataType, Field, Fields, Schema as ArrowSchema};
+    use arrow_select::concat::concat_batches;
     use bytes::Bytes;
     use futures::{prelude::stream::TryStreamExt, StreamExt};
     use lance_arrow::RecordBatchExt;
@@ -1551,6 +1744,7 @@ pub mod tests {
     use lance_encoding::{
         decoder::{decode_batch, DecodeBatchScheduler, DecoderPlugins, FilterExpression},
         encoder::{default_encoding_strategy, encode_batch, EncodedBatch, EncodingOptions},
+        v2::encodings::physical::dictionary::DictionaryDecodeMode,
         version::LanceFileVersion,
     };
     use lance_io::{stream::RecordBatchStream, utils::CachedFileSize};
@@ -1559,7 +1753,10 @@ pub mod tests {
     use tokio::sync::mpsc;
 
     use crate::v2::{
-        reader::{EncodedBatchReaderExt, FileReader, FileReaderOptions, ReaderProjection},
+        reader::{
+            decode_column, stream_file, EncodedBatchReaderExt, FileReader, FileReaderOptions,
+            ReaderProjection,
+        },
         testing::{test_cache, write_lance_file, FsFixture, WrittenFile},
         writer::{EncodedBatchWriteExt, FileWriter, FileWriterOptions},
     };
@@ -2003,6 +2200,156 @@ pub mod tests {
         assert_eq!(batches[0].num_rows(), total_rows);
     }
 
+    #[tokio::test]
+    async fn test_dictionary_decode_mode() {
+        let fs = FsFixture::default();
+        // Few distinct values so the column is dictionary encoded on write
+        let reader = gen()
+            .col(
+                "strings",
+                array::cycle_utf8_literals(&["apple", "banana", "cherry"]),
+            )
+            .into_reader_rows(RowCount::from(1000), BatchCount::from(1));
+        let WrittenFile { data, .. } = write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(LanceFileVersion::V2_0),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        for (dictionary_decode_mode, expected_type) in [
+            (DictionaryDecodeMode::Values, DataType::Utf8),
+            (
+                DictionaryDecodeMode::Indices,
+                DataType::Dictionary(Box::new(DataType::UInt32), Box::new(DataType::Utf8)),
+            ),
+        ] {
+            let file_scheduler = fs
+                .scheduler
+                .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+                .await
+                .unwrap();
+            let file_reader = FileReader::try_open(
+                file_scheduler,
+                None,
+                Arc::<DecoderPlugins>::default(),
+                &test_cache(),
+                FileReaderOptions {
+                    dictionary_decode_mode,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+            let batches = file_reader
+                .read_tasks(
+                    lance_io::ReadBatchParams::RangeFull,
+                    1000,
+                    None,
+                    FilterExpression::no_filter(),
+                )
+                .unwrap()
+                .then(|task| task.task)
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            assert_eq!(batches.len(), 1);
+            let column = batches[0].column(0);
+            assert_eq!(column.data_type(), &expected_type);
+            let values = match dictionary_decode_mode {
+                DictionaryDecodeMode::Values => column.clone(),
+                DictionaryDecodeMode::Indices => {
+                    let dictionary = column.as_dictionary::<UInt32Type>();
+                    arrow_select::take::take(dictionary.values(), dictionary.keys(), None).unwrap()
+                }
+            };
+            assert_eq!(&values, data[0].column(0));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_column() {
+        let fs = FsFixture::default();
+        let reader = gen()
+            .col("ints", array::step::<Int32Type>())
+            .col("strings", array::rand_utf8(ByteCount::from(16), false))
+            .into_reader_rows(RowCount::from(100), BatchCount::from(5));
+        let WrittenFile { schema, data, .. } = write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(LanceFileVersion::V2_0),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        for column_index in 0..2 {
+            let expected = data
+                .iter()
+                .map(|batch| batch.column(column_index).as_ref())
+                .collect::<Vec<_>>();
+            let expected = arrow_select::concat::concat(&expected).unwrap();
+            let actual =
+                decode_column(&fs.object_store, &fs.tmp_path, &schema, column_index as u32)
+                    .await
+                    .unwrap();
+            assert_eq!(&actual, &expected);
+        }
+
+        let err = decode_column(&fs.object_store, &fs.tmp_path, &schema, 2)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_stream_file() {
+        let fs = FsFixture::default();
+        let reader = gen()
+            .col("ints", array::step::<Int32Type>())
+            .col("strings", array::rand_utf8(ByteCount::from(16), false))
+            .into_reader_rows(RowCount::from(100), BatchCount::from(5));
+        let WrittenFile { schema, data, .. } = write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(LanceFileVersion::V2_0),
+                // Flush a page for every batch
+                data_cache_bytes: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        let expected = concat_batches(&data[0].schema(), &data).unwrap();
+
+        // Batches that line up with the pages, and batches that span several pages
+        for batch_size in [100, 64, 1000] {
+            let batches = stream_file(&fs.object_store, &fs.tmp_path, &schema, batch_size)
+                .await
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            assert_eq!(batches.len(), 500_usize.div_ceil(batch_size as usize));
+            assert!(batches
+                .iter()
+                .all(|batch| batch.num_rows() <= batch_size as usize));
+            let actual = concat_batches(&expected.schema(), &batches).unwrap();
+            assert_eq!(actual, expected);
+        }
+
+        let err = stream_file(&fs.object_store, &fs.tmp_path, &schema, 0)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("batch size"), "{}", err);
+    }
+
     #[tokio::test]
     async fn test_blocking_take() {
         let fs = FsFixture::default();
@@ -2131,11 +2478,8 @@ pub mod tests {
             &column_infos,
             &vec![],
             total_rows as u64,
-            Arc::<DecoderPlugins>::default(),
-            file_reader.scheduler.clone(),
-            test_cache(),
             &FilterExpression::no_filter(),
-            false,
+            &file_reader.scheduler_config(total_rows as u32),
         )
         .await
         .unwrap();
@@ -33,6 +33,7 @@ use url::Url;
 
 use super::local::LocalObjectReader;
 mod list_retry;
+pub mod path_rewrite;
 pub mod providers;
 mod tracing;
 use crate::object_reader::SmallReader;
@@ -60,6 +61,7 @@ pub static DEFAULT_MAX_IOP_SIZE: std::sync::LazyLock<u64> = std::sync::LazyLock:
 
 pub const DEFAULT_DOWNLOAD_RETRY_COUNT: usize = 3;
 
+pub use path_rewrite::PathRewritingStoreWrapper;
 pub use providers::{ObjectStoreProvider, ObjectStoreRegistry};
 
 #[async_trait]
@@ -140,6 +142,34 @@ impl std::fmt::Display for ObjectStore {
 
 pub trait WrappingObjectStore: std::fmt::Debug + Send + Sync {
     fn wrap(&self, original: Arc<dyn OSObjectStore>) -> Arc<dyn OSObjectStore>;
+
+    /// The totals of the reads made through the stores created by this wrapper
+    ///
+    /// Wrappers that track IO can return their totals so callers can measure the
+    /// IO of an operation.  The default is `None`, the IO is not tracked.
+    fn io_counts(&self) -> Option<IoCounts> {
+        None
+    }
+}
+
+/// Totals of the reads made through an object store, see [`WrappingObjectStore::io_counts`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoCounts {
+    /// The number of read requests
+    pub read_iops: u64,
+    /// The number of bytes read
+    pub read_bytes: u64,
+}
+
+impl std::ops::Sub for IoCounts {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            read_iops: self.read_iops.saturating_sub(rhs.read_iops),
+            read_bytes: self.read_bytes.saturating_sub(rhs.read_bytes),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -163,6 +193,10 @@ impl WrappingObjectStore for ChainedWrappingObjectStore {
             .iter()
             .fold(original, |acc, wrapper| wrapper.wrap(acc))
     }
+
+    fn io_counts(&self) -> Option<IoCounts> {
+        self.wrappers.iter().find_map(|wrapper| wrapper.io_counts())
+    }
 }
 
 /// Parameters to create an [ObjectStore]
@@ -396,6 +430,11 @@ impl ObjectStore {
         self.max_iop_size
     }
 
+    /// Number of times a failed download is retried
+    pub fn download_retry_count(&self) -> usize {
+        self.download_retry_count
+    }
+
     pub fn io_parallelism(&self) -> usize {
         std::env::var("LANCE_IO_THREADS")
             .map(|val| val.parse::<usize>().unwrap())
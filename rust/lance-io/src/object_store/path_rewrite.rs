@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Wrappers around object_store that rewrite the paths of requests
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, PutMultipartOpts, PutOptions,
+    PutPayload, PutResult, Result as OSResult,
+};
+
+use super::WrappingObjectStore;
+
+/// A function mapping the path of a request to the path used in the target store
+pub type PathRewriteFn = Arc<dyn Fn(&Path) -> Path + Send + Sync>;
+
+/// Wraps object stores so that the path of every request is rewritten before it reaches
+/// the store, e.g. to redirect a prefix to a new location during a migration.
+///
+/// Only the paths of requests are rewritten.  The paths in the results of `list`
+/// requests are the paths of the target store, there is no mapping back.
+#[derive(Clone)]
+pub struct PathRewritingStoreWrapper {
+    rewrite: PathRewriteFn,
+}
+
+impl PathRewritingStoreWrapper {
+    pub fn new(rewrite: impl Fn(&Path) -> Path + Send + Sync + 'static) -> Self {
+        Self {
+            rewrite: Arc::new(rewrite),
+        }
+    }
+}
+
+impl std::fmt::Debug for PathRewritingStoreWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PathRewritingStoreWrapper")
+            .finish_non_exhaustive()
+    }
+}
+
+impl WrappingObjectStore for PathRewritingStoreWrapper {
+    fn wrap(
+        &self,
+        original: Arc<dyn object_store::ObjectStore>,
+    ) -> Arc<dyn object_store::ObjectStore> {
+        Arc::new(PathRewritingObjectStore {
+            target: original,
+            rewrite: self.rewrite.clone(),
+        })
+    }
+}
+
+pub struct PathRewritingObjectStore {
+    target: Arc<dyn object_store::ObjectStore>,
+    rewrite: PathRewriteFn,
+}
+
+impl PathRewritingObjectStore {
+    fn rewrite(&self, path: &Path) -> Path {
+        (self.rewrite)(path)
+    }
+}
+
+impl std::fmt::Debug for PathRewritingObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PathRewritingObjectStore")
+            .field("target", &self.target)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Display for PathRewritingObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("PathRewritingObjectStore({})", self.target))
+    }
+}
+
+#[async_trait::async_trait]
+#[deny(clippy::missing_trait_methods)]
+impl object_store::ObjectStore for PathRewritingObjectStore {
+    async fn put(&self, location: &Path, bytes: PutPayload) -> OSResult<PutResult> {
+        self.target.put(&self.rewrite(location), bytes).await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        bytes: PutPayload,
+        opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        self.target
+            .put_opts(&self.rewrite(location), bytes, opts)
+            .await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> OSResult<Box<dyn MultipartUpload>> {
+        self.target.put_multipart(&self.rewrite(location)).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        self.target
+            .put_multipart_opts(&self.rewrite(location), opts)
+            .await
+    }
+
+    async fn get(&self, location: &Path) -> OSResult<GetResult> {
+        self.target.get(&self.rewrite(location)).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        self.target.get_opts(&self.rewrite(location), options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<u64>) -> OSResult<Bytes> {
+        self.target.get_range(&self.rewrite(location), range).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> OSResult<Vec<Bytes>> {
+        self.target
+            .get_ranges(&self.rewrite(location), ranges)
+            .await
+    }
+
+    async fn head(&self, location: &Path) -> OSResult<ObjectMeta> {
+        self.target.head(&self.rewrite(location)).await
+    }
+
+    async fn delete(&self, location: &Path) -> OSResult<()> {
+        self.target.delete(&self.rewrite(location)).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, OSResult<Path>>,
+    ) -> BoxStream<'a, OSResult<Path>> {
+        let locations = locations
+            .map(|location| location.map(|location| self.rewrite(&location)))
+            .boxed();
+        self.target.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        let prefix = prefix.map(|prefix| self.rewrite(prefix));
+        self.target.list(prefix.as_ref())
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        let prefix = prefix.map(|prefix| self.rewrite(prefix));
+        self.target
+            .list_with_offset(prefix.as_ref(), &self.rewrite(offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        let prefix = prefix.map(|prefix| self.rewrite(prefix));
+        self.target.list_with_delimiter(prefix.as_ref()).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> OSResult<()> {
+        self.target
+            .copy(&self.rewrite(from), &self.rewrite(to))
+            .await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> OSResult<()> {
+        self.target
+            .rename(&self.rewrite(from), &self.rewrite(to))
+            .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> OSResult<()> {
+        self.target
+            .rename_if_not_exists(&self.rewrite(from), &self.rewrite(to))
+            .await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> OSResult<()> {
+        self.target
+            .copy_if_not_exists(&self.rewrite(from), &self.rewrite(to))
+            .await
+    }
+}
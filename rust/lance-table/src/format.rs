@@ -12,8 +12,8 @@ mod manifest;
 pub use fragment::*;
 pub use index::Index;
 pub use manifest::{
-    is_detached_version, DataStorageFormat, Manifest, SelfDescribingFileReader, WriterVersion,
-    DETACHED_VERSION_MASK,
+    decode_fragment_count, is_detached_version, DataStorageFormat, Manifest, ManifestHeader,
+    SelfDescribingFileReader, WriterVersion, DETACHED_VERSION_MASK,
 };
 
 use lance_core::{Error, Result};
@@ -114,6 +114,11 @@ impl DataFile {
         self.file_major_version == 0 && self.file_minor_version < 3
     }
 
+    /// The version of the Lance file format this data file was written with.
+    pub fn file_version(&self) -> Result<LanceFileVersion> {
+        LanceFileVersion::try_from_major_minor(self.file_major_version, self.file_minor_version)
+    }
+
     pub fn validate(&self, base_path: &Path) -> Result<()> {
         if self.is_legacy_file() {
             if !self.fields.windows(2).all(|w| w[0] < w[1]) {
@@ -378,6 +383,11 @@ impl Fragment {
         self.files[0].is_legacy_file()
     }
 
+    /// The file format versions of the data files in this fragment, in the order of `files`
+    pub fn file_versions(&self) -> Result<Vec<LanceFileVersion>> {
+        self.files.iter().map(DataFile::file_version).collect()
+    }
+
     // Helper method to infer the Lance version from a set of fragments
     //
     // Returns None if there are no data files
@@ -392,17 +402,11 @@ impl Fragment {
         else {
             return Ok(None);
         };
-        let file_version = LanceFileVersion::try_from_major_minor(
-            sample_file.file_major_version,
-            sample_file.file_minor_version,
-        )?;
+        let file_version = sample_file.file_version()?;
         // Ensure all files match
         for frag in fragments {
             for file in &frag.files {
-                let this_file_version = LanceFileVersion::try_from_major_minor(
-                    file.file_major_version,
-                    file.file_minor_version,
-                )?;
+                let this_file_version = file.file_version()?;
                 if file_version != this_file_version {
                     return Err(Error::invalid_input(
                         format!(
@@ -535,6 +539,29 @@ mod tests {
         assert_eq!(fragment, fragment2);
     }
 
+    #[test]
+    fn test_file_versions() {
+        // Fragments written by older releases can mix file versions
+        let mut fragment = Fragment::new(0);
+        fragment.files = vec![
+            DataFile::new("a.lance", vec![0], vec![], 0, 1, None),
+            DataFile::new("b.lance", vec![1], vec![0], 0, 3, None),
+            DataFile::new("c.lance", vec![2], vec![0], 2, 1, None),
+        ];
+        assert_eq!(
+            fragment.file_versions().unwrap(),
+            vec![
+                LanceFileVersion::Legacy,
+                LanceFileVersion::V2_0,
+                LanceFileVersion::V2_1
+            ]
+        );
+        assert!(Fragment::try_infer_version(&[fragment.clone()]).is_err());
+
+        fragment.files[0].file_major_version = 3;
+        assert!(fragment.file_versions().is_err());
+    }
+
     #[test]
     fn test_to_json() {
         let mut fragment = Fragment::new(123);
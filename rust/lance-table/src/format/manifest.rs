@@ -18,7 +18,7 @@ use prost_types::Timestamp;
 
 use super::Fragment;
 use crate::feature_flags::{has_deprecated_v2_feature_flag, FLAG_MOVE_STABLE_ROW_IDS};
-use crate::format::pb;
+use crate::format::{pb, MAJOR_VERSION, MINOR_VERSION};
 use lance_core::cache::LanceCache;
 use lance_core::datatypes::{Schema, StorageClass};
 use lance_core::{Error, Result};
@@ -92,11 +92,21 @@ pub struct Manifest {
 
     /// Blob dataset version
     pub blob_dataset_version: Option<u64>,
+
+    /// The (major, minor) format version of the manifest file.
+    ///
+    /// This is the version the manifest was read with, or the version it will be written
+    /// with if it is new.  Manifests with a newer minor version can be read, fields this
+    /// library doesn't know about are ignored.
+    pub format_version: (u16, u16),
 }
 
 // We use the most significant bit to indicate that a transaction is detached
 pub const DETACHED_VERSION_MASK: u64 = 0x8000_0000_0000_0000;
 
+// The format version of the manifests written by this library
+const CURRENT_FORMAT_VERSION: (u16, u16) = (MAJOR_VERSION as u16, MINOR_VERSION as u16);
+
 pub fn is_detached_version(version: u64) -> bool {
     version & DETACHED_VERSION_MASK != 0
 }
@@ -143,6 +153,7 @@ impl Manifest {
             data_storage_format,
             config: HashMap::new(),
             blob_dataset_version,
+            format_version: CURRENT_FORMAT_VERSION,
         }
     }
 
@@ -176,6 +187,7 @@ impl Manifest {
             data_storage_format: previous.data_storage_format.clone(),
             config: previous.config.clone(),
             blob_dataset_version,
+            format_version: CURRENT_FORMAT_VERSION,
         }
     }
 
@@ -569,6 +581,8 @@ impl TryFrom<pb::Manifest> for Manifest {
             } else {
                 Some(p.blob_dataset_version)
             },
+            // Set by `read_manifest` from the footer of the manifest file
+            format_version: CURRENT_FORMAT_VERSION,
         })
     }
 }
@@ -617,6 +631,61 @@ impl From<&Manifest> for pb::Manifest {
     }
 }
 
+/// The schema, version and timestamp of a [`Manifest`].
+///
+/// This can be decoded from a serialized manifest without decoding the
+/// fragments, which is much cheaper for manifests with many fragments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestHeader {
+    /// Dataset schema.
+    pub schema: Schema,
+
+    /// Dataset version
+    pub version: u64,
+
+    /// The creation timestamp with nanosecond resolution as 128-bit integer
+    pub timestamp_nanos: u128,
+}
+
+impl ManifestHeader {
+    /// Decode the header from the bytes of a serialized `pb::Manifest`.
+    pub fn decode(manifest: &[u8]) -> Result<Self> {
+        Ok(Self::from(pb::ManifestHeader::decode(manifest)?))
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        let nanos = self.timestamp_nanos % 1_000_000_000;
+        let seconds = ((self.timestamp_nanos - nanos) / 1_000_000_000) as i64;
+        Utc.from_utc_datetime(
+            &DateTime::from_timestamp(seconds, nanos as u32)
+                .unwrap_or_default()
+                .naive_utc(),
+        )
+    }
+}
+
+/// Count the fragments of a serialized `pb::Manifest` without decoding them.
+pub fn decode_fragment_count(manifest: &[u8]) -> Result<usize> {
+    Ok(pb::ManifestFragments::decode(manifest)?.fragments.len())
+}
+
+impl From<pb::ManifestHeader> for ManifestHeader {
+    fn from(p: pb::ManifestHeader) -> Self {
+        let timestamp_nanos = p
+            .timestamp
+            .map(|ts| ts.seconds as u128 * 1e9 as u128 + ts.nanos as u128);
+        let fields_with_meta = FieldsWithMeta {
+            fields: Fields(p.fields),
+            metadata: p.metadata,
+        };
+        Self {
+            schema: Schema::from(fields_with_meta),
+            version: p.version,
+            timestamp_nanos: timestamp_nanos.unwrap_or(0),
+        }
+    }
+}
+
 #[async_trait]
 pub trait SelfDescribingFileReader {
     /// Open a file reader without any cached schema
@@ -805,6 +874,50 @@ mod tests {
         assert_eq!(manifest.max_field_id(), 43);
     }
 
+    #[test]
+    fn test_decode_header() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "a",
+            arrow_schema::DataType::Int64,
+            false,
+        )]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let fragments = (0..10_000)
+            .map(|id| Fragment::with_file_legacy(id, &format!("path{}", id), &schema, Some(10)))
+            .collect::<Vec<_>>();
+        let mut manifest = Manifest::new(
+            schema,
+            Arc::new(fragments),
+            DataStorageFormat::default(),
+            /*blob_dataset_version= */ None,
+        );
+        manifest.version = 42;
+        manifest.timestamp_nanos = 1_700_000_000_123_456_789;
+        let bytes = pb::Manifest::from(&manifest).encode_to_vec();
+
+        let header = ManifestHeader::decode(&bytes).unwrap();
+        let full = Manifest::try_from(pb::Manifest::decode(bytes.as_slice()).unwrap()).unwrap();
+
+        assert_eq!(header.schema, full.schema);
+        assert_eq!(header.version, 42);
+        assert_eq!(header.timestamp(), full.timestamp());
+        assert_eq!(full.fragments.len(), 10_000);
+        // The fragments are skipped, none of their bytes are kept by the header
+        let header_len = pb::ManifestHeader::decode(bytes.as_slice())
+            .unwrap()
+            .encoded_len();
+        let fragments_len = pb::ManifestFragments::decode(bytes.as_slice())
+            .unwrap()
+            .encoded_len();
+        assert!(
+            header_len + fragments_len <= bytes.len(),
+            "the header has {} of the {} bytes of the manifest, {} of which are fragments",
+            header_len,
+            bytes.len(),
+            fragments_len
+        );
+    }
+
     #[test]
     fn test_config() {
         let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
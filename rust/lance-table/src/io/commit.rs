@@ -22,10 +22,11 @@
 //! terms of a lock. The trait [CommitLock] can be implemented as a simpler
 //! alternative to [CommitHandler].
 
+use std::collections::HashMap;
 use std::io;
 use std::pin::Pin;
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::{fmt::Debug, fs::DirEntry};
 
 use futures::future::Either;
@@ -558,6 +559,23 @@ pub trait CommitHandler: Debug + Send + Sync {
     async fn delete(&self, _base_path: &Path) -> Result<()> {
         Ok(())
     }
+
+    /// Called when a commit fails because it conflicts with a transaction
+    /// that was committed concurrently.
+    ///
+    /// This is only for diagnostics, the commit error is still returned to the caller.
+    fn on_conflict(&self, _conflict: &ConflictSummary) {}
+}
+
+impl dyn CommitHandler {
+    /// A commit handler that writes to a temporary path and then renames it
+    ///
+    /// This works for local files and in-memory stores, regardless of the URL of the dataset.
+    /// Tests can use it with `DatasetBuilder::with_commit_handler` so they don't depend on
+    /// the commit handler inferred from the URL.
+    pub fn local_rename() -> Arc<dyn CommitHandler> {
+        Arc::new(RenameCommitHandler)
+    }
 }
 
 async fn default_resolve_version(
@@ -796,6 +814,152 @@ impl From<CommitError> for Error {
     }
 }
 
+/// A summary of the transaction that won a commit conflict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictSummary {
+    /// The version the conflicting transaction was committed as.
+    pub version: u64,
+    /// The uuid of the conflicting transaction.
+    pub uuid: String,
+    /// The name of the conflicting operation, e.g. "Append" or "Delete".
+    pub operation: String,
+}
+
+/// A callback that receives the transaction that won a commit conflict.
+#[derive(Clone)]
+pub struct ConflictCallback(Arc<dyn Fn(&ConflictSummary) + Send + Sync>);
+
+impl ConflictCallback {
+    pub fn new(callback: impl Fn(&ConflictSummary) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+}
+
+impl Debug for ConflictCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConflictCallback").finish()
+    }
+}
+
+/// Wraps another commit handler, calling a [ConflictCallback] on commit conflicts.
+#[derive(Debug)]
+pub struct ConflictCallbackCommitHandler {
+    inner: Arc<dyn CommitHandler>,
+    callback: ConflictCallback,
+}
+
+impl ConflictCallbackCommitHandler {
+    pub fn new(inner: Arc<dyn CommitHandler>, callback: ConflictCallback) -> Self {
+        Self { inner, callback }
+    }
+}
+
+#[async_trait::async_trait]
+impl CommitHandler for ConflictCallbackCommitHandler {
+    async fn resolve_latest_location(
+        &self,
+        base_path: &Path,
+        object_store: &ObjectStore,
+    ) -> Result<ManifestLocation> {
+        self.inner
+            .resolve_latest_location(base_path, object_store)
+            .await
+    }
+
+    async fn resolve_version_location(
+        &self,
+        base_path: &Path,
+        version: u64,
+        object_store: &dyn OSObjectStore,
+    ) -> Result<ManifestLocation> {
+        self.inner
+            .resolve_version_location(base_path, version, object_store)
+            .await
+    }
+
+    fn list_manifest_locations<'a>(
+        &self,
+        base_path: &Path,
+        object_store: &'a ObjectStore,
+        sorted_descending: bool,
+    ) -> BoxStream<'a, Result<ManifestLocation>> {
+        self.inner
+            .list_manifest_locations(base_path, object_store, sorted_descending)
+    }
+
+    async fn commit(
+        &self,
+        manifest: &mut Manifest,
+        indices: Option<Vec<Index>>,
+        base_path: &Path,
+        object_store: &ObjectStore,
+        manifest_writer: ManifestWriter,
+        naming_scheme: ManifestNamingScheme,
+    ) -> std::result::Result<ManifestLocation, CommitError> {
+        self.inner
+            .commit(
+                manifest,
+                indices,
+                base_path,
+                object_store,
+                manifest_writer,
+                naming_scheme,
+            )
+            .await
+    }
+
+    async fn delete(&self, base_path: &Path) -> Result<()> {
+        self.inner.delete(base_path).await
+    }
+
+    fn on_conflict(&self, conflict: &ConflictSummary) {
+        self.inner.on_conflict(conflict);
+        (self.callback.0)(conflict);
+    }
+}
+
+/// A set of commit handlers that can be selected by name.
+///
+/// This allows the commit handler of a dataset to be chosen by configuration,
+/// e.g. `DatasetBuilder::with_commit_handler_named`, instead of constructing a
+/// handler for every dataset.
+#[derive(Debug, Default)]
+pub struct CommitHandlerRegistry {
+    handlers: RwLock<HashMap<String, Arc<dyn CommitHandler>>>,
+}
+
+impl CommitHandlerRegistry {
+    /// Register a commit handler, replacing any handler with the same name.
+    pub fn insert(&self, name: &str, handler: Arc<dyn CommitHandler>) {
+        self.handlers
+            .write()
+            .expect("CommitHandlerRegistry lock poisoned")
+            .insert(name.into(), handler);
+    }
+
+    /// Get the commit handler registered with the given name.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn CommitHandler>> {
+        self.handlers
+            .read()
+            .expect("CommitHandlerRegistry lock poisoned")
+            .get(name)
+            .cloned()
+    }
+
+    /// The names of all registered commit handlers, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self
+            .handlers
+            .read()
+            .expect("CommitHandlerRegistry lock poisoned")
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
+
 /// Whether we have issued a warning about using the unsafe commit handler.
 static WARNED_ON_UNSAFE_COMMIT: AtomicBool = AtomicBool::new(false);
 
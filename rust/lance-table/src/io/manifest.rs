@@ -19,10 +19,10 @@ use lance_io::{
     object_store::ObjectStore,
     object_writer::ObjectWriter,
     traits::{WriteExt, Writer},
-    utils::read_message,
+    utils::{read_message, read_version},
 };
 
-use crate::format::{pb, DataStorageFormat, Index, Manifest, MAGIC};
+use crate::format::{pb, DataStorageFormat, Index, Manifest, MAGIC, MAJOR_VERSION};
 
 use super::commit::ManifestLocation;
 
@@ -66,6 +66,18 @@ pub async fn read_manifest(
             location!(),
         ));
     }
+    // Newer minor versions only add fields, which are ignored when decoding
+    let (major_version, minor_version) = read_version(&buf)?;
+    if major_version > MAJOR_VERSION as u16 {
+        return Err(Error::NotSupported {
+            source: format!(
+                "manifest {} has format version {}.{} but only major version {} is supported",
+                path, major_version, minor_version, MAJOR_VERSION
+            )
+            .into(),
+            location: location!(),
+        });
+    }
     let manifest_pos = LittleEndian::read_i64(&buf[buf.len() - 16..buf.len() - 8]) as usize;
     let manifest_len = file_size as usize - manifest_pos;
 
@@ -107,7 +119,9 @@ pub async fn read_manifest(
     }
 
     let proto = pb::Manifest::decode(buf)?;
-    Manifest::try_from(proto)
+    let mut manifest = Manifest::try_from(proto)?;
+    manifest.format_version = (major_version, minor_version);
+    Ok(manifest)
 }
 
 #[instrument(level = "debug", skip(object_store, manifest))]
@@ -239,8 +253,8 @@ mod test {
     use std::collections::HashMap;
 
     use crate::format::SelfDescribingFileReader;
+    use crate::format::{MAGIC, MAJOR_VERSION, MINOR_VERSION};
     use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
-    use lance_file::format::{MAGIC, MAJOR_VERSION, MINOR_VERSION};
     use lance_file::{reader::FileReader, writer::FileWriter};
     use rand::{distributions::Alphanumeric, Rng};
     use tokio::io::AsyncWriteExt;
@@ -15,7 +15,7 @@ use datafusion::{
 };
 use lance_core::datatypes::{OnMissing, OnTypeMismatch};
 
-use crate::Dataset;
+use crate::{dataset::scanner::Scanner, Dataset};
 
 #[async_trait]
 impl TableProvider for Dataset {
@@ -46,7 +46,7 @@ impl TableProvider for Dataset {
         _: &[Expr],
         limit: Option<usize>,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
-        let scanner = self.scan();
+        let scanner = Scanner::new(Arc::new(self.clone()));
 
         let schema_ref = self.schema();
         let projections = if let Some(projection) = projection {
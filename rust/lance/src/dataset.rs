@@ -6,6 +6,7 @@
 
 use arrow_array::{RecordBatch, RecordBatchReader};
 use byteorder::{ByteOrder, LittleEndian};
+use bytes::Bytes;
 use chrono::{prelude::*, Duration};
 use deepsize::DeepSizeOf;
 use futures::future::BoxFuture;
@@ -15,7 +16,7 @@ use futures::{FutureExt, Stream};
 use crate::session::caches::{DSMetadataCache, ManifestKey, TransactionKey};
 use crate::session::index_caches::DSIndexCache;
 use itertools::Itertools;
-use lance_core::datatypes::{OnMissing, OnTypeMismatch, Projectable, Projection};
+use lance_core::datatypes::{OnMissing, OnTypeMismatch, Projectable, Projection, StorageClass};
 use lance_core::traits::DatasetTakeRows;
 use lance_core::utils::address::RowAddress;
 use lance_core::utils::tracing::{
@@ -29,15 +30,17 @@ use lance_file::version::LanceFileVersion;
 use lance_index::DatasetIndexExt;
 use lance_io::object_store::{ObjectStore, ObjectStoreParams};
 use lance_io::object_writer::{ObjectWriter, WriteResult};
-use lance_io::traits::WriteExt;
-use lance_io::utils::{read_last_block, read_metadata_offset, read_struct};
+use lance_io::traits::{Reader, WriteExt};
+use lance_io::utils::{read_last_block, read_message, read_metadata_offset, read_struct};
 use lance_table::format::{
-    DataStorageFormat, Fragment, Index, Manifest, MAGIC, MAJOR_VERSION, MINOR_VERSION,
+    decode_fragment_count, pb::ManifestFragments, DataStorageFormat, Fragment, Index, Manifest,
+    RowIdMeta, MAGIC, MAJOR_VERSION, MINOR_VERSION,
 };
 use lance_table::io::commit::{
     migrate_scheme_to_v2, CommitConfig, CommitError, CommitHandler, CommitLock, ManifestLocation,
     ManifestNamingScheme,
 };
+use lance_table::io::deletion::deletion_file_path;
 use lance_table::io::manifest::{read_manifest, write_manifest};
 use object_store::path::Path;
 use prost::Message;
@@ -87,7 +90,7 @@ use crate::io::commit::{
     detect_overlapping_fragments, read_transaction_file,
 };
 use crate::session::Session;
-use crate::utils::temporal::{timestamp_to_nanos, utc_now, SystemTime};
+use crate::utils::temporal::{timestamp_to_nanos, Clock, SystemClock, SystemTime};
 use crate::{Error, Result};
 pub use blob::BlobFile;
 use hash_joiner::HashJoiner;
@@ -141,6 +144,75 @@ pub struct Dataset {
     // These are references to session caches, but with the dataset URI as a prefix.
     pub(crate) index_cache: Arc<DSIndexCache>,
     pub(crate) metadata_cache: Arc<DSMetadataCache>,
+
+    pub(crate) read_options: DatasetReadOptions,
+}
+
+/// The options of a [`Dataset`] that are set by the [`DatasetBuilder`]
+///
+/// These are kept when another version of the dataset is checked out.
+#[derive(Clone)]
+pub(crate) struct DatasetReadOptions {
+    /// The projection used by [`Dataset::scan`], see [`DatasetBuilder::with_default_projection`]
+    pub(crate) default_projection: Option<Vec<String>>,
+    /// Deletion files are not applied to reads, see [`DatasetBuilder::with_ignore_deletions`]
+    pub(crate) ignore_deletions: bool,
+    /// The columns the schema is projected to, see [`DatasetBuilder::with_schema_projection`]
+    pub(crate) schema_projection: Option<Vec<String>>,
+    /// The source of the current time, see [`DatasetBuilder::with_clock`]
+    pub(crate) clock: Arc<dyn Clock>,
+}
+
+impl Default for DatasetReadOptions {
+    fn default() -> Self {
+        Self {
+            default_projection: None,
+            ignore_deletions: false,
+            schema_projection: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+impl DatasetReadOptions {
+    /// Apply the schema projection, if there is one, to `manifest`
+    ///
+    /// Fails if the schema projection or the default projection has columns that are
+    /// not in the schema.
+    fn project_manifest(&self, manifest: Arc<Manifest>) -> Result<Arc<Manifest>> {
+        let check_columns = |schema: &Schema, columns: &[String], name: &str| {
+            let missing = columns
+                .iter()
+                .filter(|column| schema.field(column).is_none())
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                Ok(())
+            } else {
+                Err(Error::InvalidInput {
+                    source: format!(
+                        "The {} has columns that are not in the dataset schema: {:?}",
+                        name, missing
+                    )
+                    .into(),
+                    location: location!(),
+                })
+            }
+        };
+        let manifest = match &self.schema_projection {
+            Some(columns) => {
+                check_columns(&manifest.schema, columns, "schema projection")?;
+                let mut manifest = manifest.as_ref().clone();
+                manifest.schema = manifest.schema.project(columns)?;
+                manifest.local_schema = manifest.schema.retain_storage_class(StorageClass::Default);
+                Arc::new(manifest)
+            }
+            None => manifest,
+        };
+        if let Some(columns) = &self.default_projection {
+            check_columns(&manifest.schema, columns, "default projection")?;
+        }
+        Ok(manifest)
+    }
 }
 
 impl std::fmt::Debug for Dataset {
@@ -149,6 +221,7 @@ impl std::fmt::Debug for Dataset {
             .field("uri", &self.uri)
             .field("base", &self.base)
             .field("version", &self.manifest.version)
+            .field("manifest_path", &self.manifest_location.path)
             .field("cache_num_items", &self.session.approx_num_items())
             .finish()
     }
@@ -178,6 +251,47 @@ impl From<&Manifest> for Version {
     }
 }
 
+/// The fragments and indices that differ between two versions of a dataset.
+///
+/// See [`Dataset::diff`].  A fragment that was modified (e.g. rows were deleted
+/// from it) is listed as removed, with its old metadata, and added, with its new
+/// metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VersionDiff {
+    pub added_fragments: Vec<Fragment>,
+    pub removed_fragments: Vec<Fragment>,
+    pub added_indices: Vec<Index>,
+    pub removed_indices: Vec<Index>,
+}
+
+/// The referenced files of a dataset version that are missing or have the wrong size.
+///
+/// See [`Dataset::validate_integrity`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    /// The number of files that were checked.
+    pub num_files_checked: usize,
+    /// Files that are referenced but do not exist.
+    pub missing_files: Vec<Path>,
+    /// Files whose size differs from the size recorded in the manifest.
+    pub size_mismatches: Vec<FileSizeMismatch>,
+}
+
+impl IntegrityReport {
+    /// True if every referenced file exists and has the expected size.
+    pub fn is_ok(&self) -> bool {
+        self.missing_files.is_empty() && self.size_mismatches.is_empty()
+    }
+}
+
+/// A file whose size differs from the size recorded in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSizeMismatch {
+    pub path: Path,
+    pub expected_size: u64,
+    pub actual_size: u64,
+}
+
 /// Customize read behavior of a dataset.
 #[derive(Clone, Debug)]
 pub struct ReadParams {
@@ -365,7 +479,7 @@ impl Dataset {
     /// Check out the latest version of the dataset
     pub async fn checkout_latest(&mut self) -> Result<()> {
         let (manifest, manifest_location) = self.latest_manifest().await?;
-        self.manifest = manifest;
+        self.manifest = self.read_options.project_manifest(manifest)?;
         self.manifest_location = manifest_location;
         Ok(())
     }
@@ -399,6 +513,7 @@ impl Dataset {
             &manifest_location,
             &self.uri,
             self.session.as_ref(),
+            true,
         )
         .await?;
         Self::checkout_manifest(
@@ -409,6 +524,7 @@ impl Dataset {
             manifest_location,
             self.session.clone(),
             self.commit_handler.clone(),
+            self.read_options.clone(),
         )
     }
 
@@ -417,12 +533,11 @@ impl Dataset {
         self.checkout_by_version_number(version).await
     }
 
-    async fn load_manifest(
+    // Open the manifest file and read its last block
+    async fn open_manifest(
         object_store: &ObjectStore,
         manifest_location: &ManifestLocation,
-        uri: &str,
-        session: &Session,
-    ) -> Result<Manifest> {
+    ) -> Result<(Box<dyn Reader>, Bytes)> {
         let object_reader = if let Some(size) = manifest_location.size {
             object_store
                 .open_with_size(&manifest_location.path, size as usize)
@@ -453,8 +568,64 @@ impl Dataset {
                         location: location!(),
                     },
                 })?;
+        Ok((object_reader, last_block))
+    }
+
+    /// Count the fragments in the manifest without decoding them.
+    pub(crate) async fn count_manifest_fragments(
+        object_store: &ObjectStore,
+        manifest_location: &ManifestLocation,
+    ) -> Result<usize> {
+        let (object_reader, last_block) =
+            Self::open_manifest(object_store, manifest_location).await?;
         let offset = read_metadata_offset(&last_block)?;
 
+        let manifest_size = object_reader.size().await?;
+        if manifest_size - offset <= last_block.len() {
+            let offset_in_block = last_block.len() - (manifest_size - offset);
+            let message_len =
+                LittleEndian::read_u32(&last_block[offset_in_block..offset_in_block + 4]) as usize;
+            let message_data = &last_block[offset_in_block + 4..offset_in_block + 4 + message_len];
+            decode_fragment_count(message_data)
+        } else {
+            let fragments =
+                read_message::<ManifestFragments>(object_reader.as_ref(), offset).await?;
+            Ok(fragments.fragments.len())
+        }
+    }
+
+    async fn load_manifest(
+        object_store: &ObjectStore,
+        manifest_location: &ManifestLocation,
+        uri: &str,
+        session: &Session,
+        populate_dictionary: bool,
+    ) -> Result<Manifest> {
+        let (object_reader, last_block) =
+            Self::open_manifest(object_store, manifest_location).await?;
+        Self::decode_manifest(
+            object_reader.as_ref(),
+            &last_block,
+            manifest_location,
+            uri,
+            session,
+            populate_dictionary,
+        )
+        .await
+    }
+
+    /// Decode the manifest from the last block of the manifest file, reading the
+    /// rest of the file from `object_reader` only if it is needed.
+    async fn decode_manifest(
+        object_reader: &dyn Reader,
+        last_block: &Bytes,
+        manifest_location: &ManifestLocation,
+        uri: &str,
+        session: &Session,
+        populate_dictionary: bool,
+    ) -> Result<Manifest> {
+        let offset = read_metadata_offset(last_block)?;
+
         // If manifest is in the last block, we can decode directly from memory.
         let manifest_size = object_reader.size().await?;
         let mut manifest = if manifest_size - offset <= last_block.len() {
@@ -465,7 +636,7 @@ impl Dataset {
             let message_data = &last_block[offset_in_block + 4..offset_in_block + 4 + message_len];
             Manifest::try_from(lance_table::format::pb::Manifest::decode(message_data)?)
         } else {
-            read_struct(object_reader.as_ref(), offset).await
+            read_struct(object_reader, offset).await
         }?;
 
         if !can_read_dataset(manifest.reader_feature_flags) {
@@ -507,8 +678,8 @@ impl Dataset {
             }
         }
 
-        if manifest.should_use_legacy_format() {
-            populate_schema_dictionary(&mut manifest.schema, object_reader.as_ref()).await?;
+        if populate_dictionary && manifest.should_use_legacy_format() {
+            populate_schema_dictionary(&mut manifest.schema, object_reader).await?;
         }
 
         Ok(manifest)
@@ -523,7 +694,9 @@ impl Dataset {
         manifest_location: ManifestLocation,
         session: Arc<Session>,
         commit_handler: Arc<dyn CommitHandler>,
+        read_options: DatasetReadOptions,
     ) -> Result<Self> {
+        let manifest = read_options.project_manifest(manifest)?;
         let tags = Tags::new(
             object_store.clone(),
             commit_handler.clone(),
@@ -542,6 +715,7 @@ impl Dataset {
             tags,
             metadata_cache,
             index_cache,
+            read_options,
         })
     }
 
@@ -603,6 +777,30 @@ impl Dataset {
         &self.manifest_location
     }
 
+    /// Serialize the manifest of this version, e.g. to send it to another process
+    ///
+    /// The bytes can be loaded with [`DatasetBuilder::with_serialized_manifest`] to open
+    /// this version without reading the manifest from the object store.
+    pub fn serialized_manifest(&self) -> Result<Vec<u8>> {
+        Ok(lance_table::format::pb::Manifest::from(self.manifest.as_ref()).encode_to_vec())
+    }
+
+    /// Get the (major, minor) format version of the manifest file.
+    ///
+    /// This can be newer than the version this library writes if the dataset was written
+    /// by a newer library with a compatible format.
+    pub fn manifest_format_version(&self) -> (u16, u16) {
+        self.manifest.format_version
+    }
+
+    /// Get the path of the manifest file this dataset was loaded from.
+    ///
+    /// This is the manifest resolved by the commit handler, which is useful to
+    /// debug which version was actually opened.
+    pub fn manifest_path(&self) -> &Path {
+        &self.manifest_location.path
+    }
+
     // TODO: Cache this
     pub async fn blobs_dataset(&self) -> Result<Option<Arc<Self>>> {
         if let Some(blobs_version) = self.manifest.blob_dataset_version {
@@ -625,6 +823,7 @@ impl Dataset {
                 blob_manifest_location,
                 self.session.clone(),
                 self.commit_handler.clone(),
+                DatasetReadOptions::default(),
             )?;
             Ok(Some(Arc::new(blobs_dataset)))
         } else {
@@ -687,6 +886,155 @@ impl Dataset {
         Transaction::try_from(transaction).map(Some)
     }
 
+    /// The paths of all of the objects that make up this version of the dataset.
+    ///
+    /// This is the manifest, the transaction file, and the data, deletion, row id
+    /// and index files.  Everything but the index files comes from the manifest, the
+    /// index directories are listed to find their files.  No data is read.  The
+    /// paths are sorted and deduplicated.
+    pub async fn referenced_paths(&self) -> Result<Vec<Path>> {
+        let mut paths = vec![self.manifest_location.path.clone()];
+        if let Some(path) = &self.manifest.transaction_file {
+            paths.push(self.base.child("_transactions").child(path.as_str()));
+        }
+        for fragment in self.manifest.fragments.iter() {
+            for file in &fragment.files {
+                paths.push(self.data_dir().child(file.path.as_str()));
+            }
+            if let Some(deletion_file) = &fragment.deletion_file {
+                paths.push(deletion_file_path(&self.base, fragment.id, deletion_file));
+            }
+            if let Some(RowIdMeta::External(file)) = &fragment.row_id_meta {
+                paths.push(self.base.child(file.path.as_str()));
+            }
+        }
+        for index in self.load_indices().await?.iter() {
+            let index_dir = self.indices_dir().child(index.uuid.to_string());
+            let index_files = self
+                .object_store
+                .read_dir_all(&index_dir, None)
+                .map_ok(|meta| meta.location)
+                .try_collect::<Vec<_>>()
+                .await?;
+            paths.extend(index_files);
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    /// Check that every file referenced by this version exists and has the expected size.
+    ///
+    /// Each path in [`Self::referenced_paths`] is checked with a `head` request, no
+    /// data is read or decoded.  Sizes are only compared for files whose size is
+    /// recorded in the manifest (the manifest itself and data files written by recent
+    /// versions).  This is useful after copying a dataset or an interrupted upload.
+    pub async fn validate_integrity(&self) -> Result<IntegrityReport> {
+        let mut expected_sizes = HashMap::new();
+        if let Some(size) = self.manifest_location.size {
+            expected_sizes.insert(self.manifest_location.path.clone(), size);
+        }
+        for fragment in self.manifest.fragments.iter() {
+            for file in &fragment.files {
+                if let Some(size) = file.file_size_bytes.get() {
+                    expected_sizes.insert(self.data_dir().child(file.path.as_str()), size.get());
+                }
+            }
+        }
+
+        let paths = self.referenced_paths().await?;
+        let mut report = IntegrityReport {
+            num_files_checked: paths.len(),
+            ..Default::default()
+        };
+        let sizes = stream::iter(paths)
+            .map(|path| async move {
+                match self.object_store.inner.head(&path).await {
+                    Ok(meta) => Ok((path, Some(meta.size))),
+                    Err(object_store::Error::NotFound { .. }) => Ok((path, None)),
+                    Err(e) => Err(Error::from(e)),
+                }
+            })
+            .buffered(self.object_store.io_parallelism())
+            .try_collect::<Vec<_>>()
+            .await?;
+        for (path, size) in sizes {
+            match (size, expected_sizes.get(&path)) {
+                (None, _) => report.missing_files.push(path),
+                (Some(actual_size), Some(expected_size)) if actual_size != *expected_size => {
+                    report.size_mismatches.push(FileSizeMismatch {
+                        path,
+                        expected_size: *expected_size,
+                        actual_size,
+                    })
+                }
+                _ => {}
+            }
+        }
+        Ok(report)
+    }
+
+    /// Compare the fragments and indices of this version with those of `other`.
+    ///
+    /// Items in `other` but not in this version are added, items in this version
+    /// but not in `other` are removed.  To see what changed from version A to
+    /// version B, open both (e.g. with [`DatasetBuilder::with_version`]) and call
+    /// `a.diff(&b)`.  Only the manifests and index metadata are compared, no data
+    /// is read.
+    pub async fn diff(&self, other: &Self) -> Result<VersionDiff> {
+        // Look fragments up by id so large manifests don't need a quadratic
+        // scan.  The fragment itself is still compared so a fragment that was
+        // modified in place shows up as removed and added.
+        let fragments = self
+            .manifest
+            .fragments
+            .iter()
+            .map(|fragment| (fragment.id, fragment))
+            .collect::<HashMap<_, _>>();
+        let other_fragments = other
+            .manifest
+            .fragments
+            .iter()
+            .map(|fragment| (fragment.id, fragment))
+            .collect::<HashMap<_, _>>();
+        let added_fragments = other
+            .manifest
+            .fragments
+            .iter()
+            .filter(|fragment| fragments.get(&fragment.id) != Some(fragment))
+            .cloned()
+            .collect();
+        let removed_fragments = self
+            .manifest
+            .fragments
+            .iter()
+            .filter(|fragment| other_fragments.get(&fragment.id) != Some(fragment))
+            .cloned()
+            .collect();
+
+        let indices = self.load_indices().await?;
+        let other_indices = other.load_indices().await?;
+        let index_uuids = indices.iter().map(|i| i.uuid).collect::<HashSet<_>>();
+        let other_index_uuids = other_indices.iter().map(|i| i.uuid).collect::<HashSet<_>>();
+        let added_indices = other_indices
+            .iter()
+            .filter(|index| !index_uuids.contains(&index.uuid))
+            .cloned()
+            .collect();
+        let removed_indices = indices
+            .iter()
+            .filter(|index| !other_index_uuids.contains(&index.uuid))
+            .cloned()
+            .collect();
+
+        Ok(VersionDiff {
+            added_fragments,
+            removed_fragments,
+            added_indices,
+            removed_indices,
+        })
+    }
+
     /// Restore the currently checked out version of the dataset as the latest version.
     pub async fn restore(&mut self) -> Result<()> {
         let (latest_manifest, _) = self.latest_manifest().await?;
@@ -735,7 +1083,7 @@ impl Dataset {
         error_if_tagged_old_versions: Option<bool>,
     ) -> BoxFuture<Result<RemovalStats>> {
         info!(target: TRACE_DATASET_EVENTS, event=DATASET_CLEANING_EVENT, uri=&self.uri);
-        let before = utc_now() - older_than;
+        let before = self.read_options.clock.now() - older_than;
         cleanup::cleanup_old_versions(
             self,
             before,
@@ -908,8 +1256,17 @@ impl Dataset {
     }
 
     /// Create a Scanner to scan the dataset.
+    ///
+    /// If the dataset was loaded with a default projection, see
+    /// [`DatasetBuilder::with_default_projection`], the scanner starts with that
+    /// projection.  If some of its columns have been dropped since the dataset was
+    /// loaded then the scan fails, unless another projection is set.
     pub fn scan(&self) -> Scanner {
-        Scanner::new(Arc::new(self.clone()))
+        let mut scanner = Scanner::new(Arc::new(self.clone()));
+        if let Some(columns) = &self.read_options.default_projection {
+            scanner.with_default_projection(columns);
+        }
+        scanner
     }
 
     /// Count the number of rows in the dataset.
@@ -940,6 +1297,44 @@ impl Dataset {
         Ok(cnts.iter().sum())
     }
 
+    /// Plan the reads needed to read `row_range` of `columns`, without reading any data
+    ///
+    /// The range is in terms of the physical rows of the fragments, in order, so deleted
+    /// rows are counted.  The metadata of the data files is read if it is not cached yet.
+    /// See [`FileFragment::plan_reads`] for how the plan relates to the reads that are
+    /// actually made.
+    ///
+    /// This can be used to prefetch data or to populate an external cache.  Datasets with
+    /// legacy (v1) storage are not supported.
+    pub async fn plan_reads(
+        &self,
+        row_range: Range<u64>,
+        columns: &[&str],
+    ) -> Result<Vec<fragment::ReadRequest>> {
+        if self.is_legacy_storage() {
+            return Err(Error::NotSupported {
+                source: "planning reads is not supported for datasets with legacy storage".into(),
+                location: location!(),
+            });
+        }
+        let projection = self.schema().project(columns)?;
+        let mut reads = Vec::new();
+        let mut fragment_start = 0;
+        for fragment in self.get_fragments() {
+            if fragment_start >= row_range.end {
+                break;
+            }
+            let fragment_end = fragment_start + fragment.physical_rows().await? as u64;
+            if row_range.start < fragment_end {
+                let start = row_range.start.saturating_sub(fragment_start);
+                let end = row_range.end.min(fragment_end) - fragment_start;
+                reads.extend(fragment.plan_reads(start..end, &projection).await?);
+            }
+            fragment_start = fragment_end;
+        }
+        Ok(reads)
+    }
+
     /// Take rows by indices.
     #[instrument(skip_all, fields(num_rows=row_indices.len()))]
     pub async fn take(
@@ -1169,6 +1564,19 @@ impl Dataset {
         Some(FileFragment::new(dataset, fragment.clone()))
     }
 
+    /// The data storage version of the data files of each fragment, keyed by fragment id.
+    ///
+    /// Datasets written by older releases may contain fragments whose files are a
+    /// different version than [`Self::manifest`] records, this reports the version of
+    /// every file so they can be found and migrated.
+    pub fn data_storage_versions(&self) -> Result<BTreeMap<u64, Vec<LanceFileVersion>>> {
+        self.manifest
+            .fragments
+            .iter()
+            .map(|fragment| Ok((fragment.id, fragment.file_versions()?)))
+            .collect()
+    }
+
     pub fn fragments(&self) -> &Arc<Vec<Fragment>> {
         &self.manifest.fragments
     }
@@ -1228,7 +1636,7 @@ impl Dataset {
             .iter()
             .map(|frag| {
                 if let Some(frag) = frag {
-                    frag.get_deletion_vector().boxed()
+                    frag.read_deletion_vector().boxed()
                 } else {
                     std::future::ready(Ok(None)).boxed()
                 }
@@ -1523,6 +1931,7 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
                             &location,
                             &dataset.uri,
                             dataset.session.as_ref(),
+                            true,
                         )
                         .await?,
                     );
@@ -1560,6 +1969,7 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
                         location,
                         dataset.session(),
                         dataset.commit_handler.clone(),
+                        DatasetReadOptions::default(),
                     )?;
                     let object_store = dataset_version.object_store();
                     let path = dataset_version
@@ -1596,6 +2006,7 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
                 location,
                 dataset.session(),
                 dataset.commit_handler.clone(),
+                dataset.read_options.clone(),
             )
         } else {
             // If we didn't get the latest manifest, we can still return the dataset
@@ -1930,7 +2341,7 @@ mod tests {
     use crate::dataset::transaction::DataReplacementGroup;
     use crate::dataset::WriteMode::Overwrite;
     use crate::index::vector::VectorIndexParams;
-    use crate::utils::test::copy_test_data_to_tmp;
+    use crate::utils::test::{copy_test_data_to_tmp, DatagenExt, FragmentCount, FragmentRowCount};
 
     use arrow::array::{as_struct_array, AsArray, GenericListBuilder, GenericStringBuilder};
     use arrow::compute::concat_batches;
@@ -1952,7 +2363,7 @@ mod tests {
     };
     use lance_arrow::bfloat16::{self, ARROW_EXT_META_KEY, ARROW_EXT_NAME_KEY, BFLOAT16_EXT_NAME};
     use lance_core::datatypes::LANCE_STORAGE_CLASS_SCHEMA_META_KEY;
-    use lance_datagen::{array, gen, BatchCount, Dimension, RowCount};
+    use lance_datagen::{array, gen, BatchCount, ByteCount, Dimension, RowCount};
     use lance_file::v2::writer::FileWriter;
     use lance_file::version::LanceFileVersion;
     use lance_index::scalar::inverted::{
@@ -2115,156 +2526,2189 @@ mod tests {
         .await
         .unwrap();
 
-        // check dataset empty
-        assert_eq!(result.count_rows(None).await.unwrap(), 0);
-        // Since the dataset is empty, will return None.
-        assert_eq!(result.manifest.max_fragment_id(), None);
+        // check dataset empty
+        assert_eq!(result.count_rows(None).await.unwrap(), 0);
+        // Since the dataset is empty, will return None.
+        assert_eq!(result.manifest.max_fragment_id(), None);
+
+        // append rows to dataset
+        let mut write_params = WriteParams {
+            max_rows_per_file: 40,
+            max_rows_per_group: 10,
+            data_storage_version: Some(data_storage_version),
+            ..Default::default()
+        };
+        // We should be able to append even if the metadata doesn't exactly match.
+        let schema_with_meta = Arc::new(
+            schema
+                .as_ref()
+                .clone()
+                .with_metadata([("key".to_string(), "value".to_string())].into()),
+        );
+        let batches = vec![RecordBatch::try_new(
+            schema_with_meta,
+            vec![Arc::new(Int32Array::from_iter_values(0..10))],
+        )
+        .unwrap()];
+        write_params.mode = WriteMode::Append;
+        let batches = RecordBatchIterator::new(batches.into_iter().map(Ok), schema.clone());
+        Dataset::write(batches, test_uri, Some(write_params))
+            .await
+            .unwrap();
+
+        let expected_batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..10))],
+        )
+        .unwrap();
+
+        // get actual dataset
+        let actual_ds = Dataset::open(test_uri).await.unwrap();
+        // confirm schema is same
+        let actual_schema = ArrowSchema::from(actual_ds.schema());
+        assert_eq!(&actual_schema, schema.as_ref());
+        // check num rows is 10
+        assert_eq!(actual_ds.count_rows(None).await.unwrap(), 10);
+        // Max fragment id is still 0 since we only have 1 fragment.
+        assert_eq!(actual_ds.manifest.max_fragment_id(), Some(0));
+        // check expected batch is correct
+        let actual_batches = actual_ds
+            .scan()
+            .try_into_stream()
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        // sort
+        let actual_batch = concat_batches(&schema, &actual_batches).unwrap();
+        let idx_arr = actual_batch.column_by_name("i").unwrap();
+        let sorted_indices = sort_to_indices(idx_arr, None, None).unwrap();
+        let struct_arr: StructArray = actual_batch.into();
+        let sorted_arr = arrow_select::take::take(&struct_arr, &sorted_indices, None).unwrap();
+        let expected_struct_arr: StructArray = expected_batch.into();
+        assert_eq!(&expected_struct_arr, as_struct_array(sorted_arr.as_ref()));
+    }
+
+    #[rstest]
+    #[lance_test_macros::test(tokio::test)]
+    async fn test_create_with_empty_iter(
+        #[values(LanceFileVersion::Legacy, LanceFileVersion::Stable)]
+        data_storage_version: LanceFileVersion,
+    ) {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int32,
+            false,
+        )]));
+        let reader = RecordBatchIterator::new(vec![].into_iter().map(Ok), schema.clone());
+        // check schema of reader and original is same
+        assert_eq!(schema.as_ref(), reader.schema().as_ref());
+        let write_params = Some(WriteParams {
+            data_storage_version: Some(data_storage_version),
+            ..Default::default()
+        });
+        let result = Dataset::write(reader, test_uri, write_params)
+            .await
+            .unwrap();
+
+        // check dataset empty
+        assert_eq!(result.count_rows(None).await.unwrap(), 0);
+        // Since the dataset is empty, will return None.
+        assert_eq!(result.manifest.max_fragment_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_load_manifest_iops() {
+        // Need to use in-memory for accurate IOPS tracking.
+        use crate::utils::test::IoTrackingStore;
+
+        // Use consistent session so memory store can be reused.
+        let session = Arc::new(Session::default());
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int32,
+            false,
+        )]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..10_i32))],
+        )
+        .unwrap();
+        let batches = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let _original_ds = Dataset::write(
+            batches,
+            "memory://test",
+            Some(WriteParams {
+                store_params: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper.clone()),
+                    ..Default::default()
+                }),
+                session: Some(session.clone()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        *io_stats.lock().unwrap() = Default::default();
+
+        let _dataset = DatasetBuilder::from_uri("memory://test")
+            .with_read_params(ReadParams {
+                store_options: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper),
+                    ..Default::default()
+                }),
+                session: Some(session),
+                ..Default::default()
+            })
+            .load()
+            .await
+            .unwrap();
+
+        let stats = io_stats.lock().unwrap();
+
+        // There should be only two IOPS:
+        // 1. List _versions directory to get the latest manifest location
+        // 2. Read the manifest file. (The manifest is small enough to be read in one go.
+        //    Larger manifests would result in more IOPS.)
+        assert_eq!(stats.metadata_iops, 1);
+        assert_eq!(stats.read_iops, 1);
+    }
+
+    #[tokio::test]
+    async fn test_coalesced_page_reads() {
+        // Need to use in-memory for accurate IOPS tracking.
+        use crate::utils::test::IoTrackingStore;
+
+        let session = Arc::new(Session::default());
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int32,
+            true,
+        )]));
+        // Nulls mean each page has a validity buffer and a values buffer
+        let values = Int32Array::from_iter((0..1000).map(|i| (i % 3 != 0).then_some(i)));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(values.clone())]).unwrap();
+        let batches = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let dataset = Dataset::write(
+            batches,
+            "memory://coalesce",
+            Some(WriteParams {
+                data_storage_version: Some(LanceFileVersion::V2_0),
+                store_params: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper),
+                    ..Default::default()
+                }),
+                session: Some(session),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        // The first scan caches the file metadata
+        dataset.scan().try_into_batch().await.unwrap();
+        *io_stats.lock().unwrap() = Default::default();
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch.column(0).as_primitive::<Int32Type>(), &values);
+
+        // The two adjacent buffers of the page are read with a single request
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        let data_reads = stats
+            .requests
+            .iter()
+            .filter(|request| request.path.as_ref().contains("data/"))
+            .collect::<Vec<_>>();
+        assert_eq!(data_reads.len(), 1, "{}", stats);
+        assert_eq!(data_reads[0].method, "get_range");
+    }
+
+    #[tokio::test]
+    async fn test_skip_validity_without_nulls() {
+        // Need to use in-memory for accurate IOPS tracking.
+        use crate::utils::test::IoTrackingStore;
+        use arrow_buffer::NullBuffer;
+        use lance_encoding::format::pb;
+        use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
+
+        // Write `values` as a 2.0 dataset and return it with the bytes of data files read by
+        // a scan
+        async fn data_bytes_read(uri: &str, values: Int32Array, nullable: bool) -> (Dataset, u64) {
+            let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+                "i",
+                DataType::Int32,
+                nullable,
+            )]));
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(values.clone())]).unwrap();
+            let batches = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+            let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+            let dataset = Dataset::write(
+                batches,
+                uri,
+                Some(WriteParams {
+                    data_storage_version: Some(LanceFileVersion::V2_0),
+                    store_params: Some(ObjectStoreParams {
+                        object_store_wrapper: Some(io_stats_wrapper),
+                        ..Default::default()
+                    }),
+                    session: Some(Arc::new(Session::default())),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+            // The first scan caches the file metadata
+            dataset.scan().try_into_batch().await.unwrap();
+            *io_stats.lock().unwrap() = Default::default();
+            let batch = dataset.scan().try_into_batch().await.unwrap();
+            assert_eq!(batch.column(0).as_primitive::<Int32Type>(), &values);
+
+            let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+            let bytes_read = stats
+                .requests
+                .iter()
+                .filter(|request| request.path.as_ref().contains("data/"))
+                .map(|request| request.num_bytes)
+                .sum();
+            (dataset, bytes_read)
+        }
+
+        // A validity bitmap without any nulls, the page is written with a validity buffer
+        let values = Int32Array::new(
+            (0..1000).collect::<Vec<_>>().into(),
+            Some(NullBuffer::new_valid(1000)),
+        );
+        let (dataset, with_validity) =
+            data_bytes_read("memory://with_validity", values, true).await;
+        let (_, without_validity) = data_bytes_read(
+            "memory://without_validity",
+            Int32Array::from_iter_values(0..1000),
+            false,
+        )
+        .await;
+
+        // The page is written with its validity buffer and records that there are no nulls
+        let data_file = &dataset.get_fragments()[0].metadata().files[0];
+        let scheduler = ScanScheduler::new(
+            dataset.object_store.clone(),
+            SchedulerConfig::default_for_testing(),
+        );
+        let file_scheduler = scheduler
+            .open_file(
+                &dataset.data_dir().child(data_file.path.as_str()),
+                &CachedFileSize::unknown(),
+            )
+            .await
+            .unwrap();
+        let metadata = lance_file::v2::reader::FileReader::read_all_metadata(&file_scheduler)
+            .await
+            .unwrap();
+        let encoding = metadata.column_infos[0].page_infos[0].encoding.as_legacy();
+        let Some(pb::array_encoding::ArrayEncoding::Nullable(nullable)) =
+            encoding.array_encoding.as_ref()
+        else {
+            panic!("Expected a nullable encoding, got {:?}", encoding);
+        };
+        let Some(pb::nullable::Nullability::SomeNulls(some_nulls)) = nullable.nullability.as_ref()
+        else {
+            panic!("Expected a page with a validity buffer, got {:?}", nullable);
+        };
+        assert!(some_nulls.validity.is_some());
+        assert_eq!(some_nulls.null_count, Some(0));
+
+        assert!(without_validity > 0);
+        // The validity buffer is not read since the page records that it has no nulls
+        assert_eq!(with_validity, without_validity);
+    }
+
+    #[tokio::test]
+    async fn test_load_newer_manifest_format_version() {
+        use lance_table::format::pb;
+        use tokio::io::AsyncWriteExt;
+
+        // Rewrite the manifest as a newer writer would, with a field we don't know about
+        async fn rewrite_manifest(dataset: &Dataset, major_version: i16, minor_version: i16) {
+            let mut message = pb::Manifest::from(dataset.manifest()).encode_to_vec();
+            prost::encoding::encode_key(1000, prost::encoding::WireType::Varint, &mut message);
+            prost::encoding::encode_varint(42, &mut message);
+            let mut writer =
+                ObjectWriter::new(&dataset.object_store, &dataset.manifest_location().path)
+                    .await
+                    .unwrap();
+            writer.write_u32_le(message.len() as u32).await.unwrap();
+            writer.write_all(&message).await.unwrap();
+            writer
+                .write_magics(0, major_version, minor_version, MAGIC)
+                .await
+                .unwrap();
+            writer.shutdown().await.unwrap();
+        }
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        assert_eq!(
+            dataset.manifest_format_version(),
+            (MAJOR_VERSION as u16, MINOR_VERSION as u16)
+        );
+
+        // The unknown field of a newer minor version is ignored
+        rewrite_manifest(&dataset, MAJOR_VERSION, MINOR_VERSION + 1).await;
+        let loaded = DatasetBuilder::from_uri(test_uri).load().await.unwrap();
+        assert_eq!(
+            loaded.manifest_format_version(),
+            (MAJOR_VERSION as u16, MINOR_VERSION as u16 + 1)
+        );
+        assert_eq!(loaded.schema(), dataset.schema());
+        assert_eq!(loaded.count_rows(None).await.unwrap(), 10);
+
+        // A newer major version can't be read
+        rewrite_manifest(&dataset, MAJOR_VERSION + 1, 0).await;
+        let err = DatasetBuilder::from_uri(test_uri).load().await.unwrap_err();
+        assert!(matches!(err, Error::NotSupported { .. }), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_open_or_create() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int32,
+            false,
+        )]));
+
+        // Creates an empty dataset if it does not exist
+        let mut dataset = DatasetBuilder::from_uri(test_uri)
+            .open_or_create(schema.clone(), WriteParams::default())
+            .await
+            .unwrap();
+        assert_eq!(dataset.version().version, 1);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 0);
+        assert_eq!(&ArrowSchema::from(dataset.schema()), schema.as_ref());
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..10_i32))],
+        )
+        .unwrap();
+        let batches = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+        dataset.append(batches, None).await.unwrap();
+
+        // Opens the existing dataset without overwriting it
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .open_or_create(schema.clone(), WriteParams::default())
+            .await
+            .unwrap();
+        assert_eq!(dataset.version().version, 2);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_manifest_path() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        let naming_scheme = dataset.manifest_location().naming_scheme;
+        assert_eq!(
+            dataset.manifest_path(),
+            &naming_scheme.manifest_path(&dataset.base, 1)
+        );
+
+        let batches = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(10), BatchCount::from(1));
+        dataset.append(batches, None).await.unwrap();
+
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_version(1)
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(
+            dataset.manifest_path(),
+            &naming_scheme.manifest_path(&dataset.base, 1)
+        );
+        assert!(format!("{:?}", dataset).contains(dataset.manifest_path().as_ref()));
+    }
+
+    #[tokio::test]
+    async fn test_pinned_index() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(
+                test_uri,
+                FragmentCount::from(2),
+                FragmentRowCount::from(100),
+            )
+            .await
+            .unwrap();
+        dataset
+            .create_index(
+                &["i"],
+                IndexType::Scalar,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+        let uuid = dataset.load_indices().await.unwrap()[0].uuid;
+
+        // An index cache too small to hold anything
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_index_cache_size_bytes(1)
+            .with_pinned_index(uuid)
+            .load()
+            .await
+            .unwrap();
+        let count_filtered = || async {
+            dataset
+                .scan()
+                .filter("i = 10")
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap()
+                .num_rows()
+        };
+        assert_eq!(count_filtered().await, 1);
+        let stats = dataset.session.index_cache_stats().await;
+        assert!(stats.num_entries > 0);
+
+        // The pinned index is still cached on the next query
+        assert_eq!(count_filtered().await, 1);
+        let new_stats = dataset.session.index_cache_stats().await;
+        assert!(new_stats.hits > stats.hits);
+        assert_eq!(new_stats.num_entries, stats.num_entries);
+    }
+
+    #[tokio::test]
+    async fn test_eager_index_load() {
+        use crate::utils::test::{IoStats, IoTrackingStore};
+        use std::sync::Mutex;
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(
+                test_uri,
+                FragmentCount::from(2),
+                FragmentRowCount::from(100),
+            )
+            .await
+            .unwrap();
+        dataset
+            .create_index(
+                &["i"],
+                IndexType::Scalar,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+
+        // Each dataset gets a fresh session so nothing is cached from earlier loads
+        let open = |eager: bool| async move {
+            let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+            let mut builder = DatasetBuilder::from_uri(test_uri).with_read_params(ReadParams {
+                store_options: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper),
+                    ..Default::default()
+                }),
+                session: Some(Arc::new(Session::default())),
+                ..Default::default()
+            });
+            if eager {
+                builder = builder.with_eager_index_load();
+            }
+            let dataset = builder.load().await.unwrap();
+            std::mem::take(&mut *io_stats.lock().unwrap());
+            (dataset, io_stats)
+        };
+        // Reads of the manifest file made by the first query
+        let query_manifest_reads = |dataset: Dataset, io_stats: Arc<Mutex<IoStats>>| async move {
+            let batch = dataset
+                .scan()
+                .filter("i = 10")
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap();
+            assert_eq!(batch.num_rows(), 1);
+            let stats = io_stats.lock().unwrap();
+            stats
+                .requests
+                .iter()
+                .filter(|request| request.path.as_ref().contains("_versions"))
+                .count()
+        };
+
+        let (dataset, io_stats) = open(false).await;
+        assert!(query_manifest_reads(dataset, io_stats).await > 0);
+
+        let (dataset, io_stats) = open(true).await;
+        assert_eq!(query_manifest_reads(dataset, io_stats).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_skip_dictionary_population() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new(
+                "x",
+                DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+                false,
+            ),
+        ]));
+        let dictionary = Arc::new(StringArray::from(vec!["a", "b"]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![0, 1, 2])),
+                Arc::new(
+                    Int8DictionaryArray::try_new(Int8Array::from(vec![0, 1, 0]), dictionary)
+                        .unwrap(),
+                ),
+            ],
+        )
+        .unwrap();
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let write_params = WriteParams {
+            data_storage_version: Some(LanceFileVersion::Legacy),
+            ..Default::default()
+        };
+        let batches = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+        Dataset::write(batches, test_uri, Some(write_params))
+            .await
+            .unwrap();
+
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_skip_dictionary_population()
+            .load()
+            .await
+            .unwrap();
+        let field = dataset.schema().field("x").unwrap();
+        assert!(field.dictionary.as_ref().unwrap().values.is_none());
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 3);
+
+        // Other columns can still be read
+        let batch = dataset
+            .scan()
+            .project(&["i"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch.num_rows(), 3);
+
+        let err = dataset.scan().try_into_batch().await.unwrap_err();
+        assert!(err.to_string().contains("field 'x'"), "{}", err);
+
+        let dataset = DatasetBuilder::from_uri(test_uri).load().await.unwrap();
+        let field = dataset.schema().field("x").unwrap();
+        assert!(field.dictionary.as_ref().unwrap().values.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_validate_integrity() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(3), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        let report = dataset.validate_integrity().await.unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(
+            report.num_files_checked,
+            dataset.referenced_paths().await.unwrap().len()
+        );
+
+        // Delete one data file and truncate another
+        let fragments = dataset.get_fragments();
+        let deleted = &fragments[0].metadata().files[0];
+        let truncated = &fragments[1].metadata().files[0];
+        let data_dir = test_dir.path().join("data");
+        std::fs::remove_file(data_dir.join(&deleted.path)).unwrap();
+        let truncated_path = data_dir.join(&truncated.path);
+        let truncated_size = std::fs::metadata(&truncated_path).unwrap().len();
+        std::fs::File::options()
+            .write(true)
+            .open(&truncated_path)
+            .unwrap()
+            .set_len(truncated_size / 2)
+            .unwrap();
+
+        let report = dataset.validate_integrity().await.unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.missing_files,
+            vec![dataset.data_dir().child(deleted.path.as_str())]
+        );
+        assert_eq!(
+            report.size_mismatches,
+            vec![FileSizeMismatch {
+                path: dataset.data_dir().child(truncated.path.as_str()),
+                expected_size: truncated_size,
+                actual_size: truncated_size / 2,
+            }]
+        );
+
+        // The check can also be run when loading
+        DatasetBuilder::from_uri(test_uri).load().await.unwrap();
+        let err = DatasetBuilder::from_uri(test_uri)
+            .with_integrity_check()
+            .load()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::CorruptFile { .. }), "{:?}", err);
+        assert!(err
+            .to_string()
+            .contains("1 are missing and 1 have the wrong size"));
+    }
+
+    #[tokio::test]
+    async fn test_builder_field_id_check() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("a", array::step::<Int32Type>())
+            .col("b", array::step::<Int32Type>())
+            .col("c", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        // The data files still reference the id of a dropped column
+        dataset.drop_columns(&["c"]).await.unwrap();
+        DatasetBuilder::from_uri(test_uri)
+            .with_field_id_check()
+            .load()
+            .await
+            .unwrap();
+
+        let load_with = |manifest: Manifest| async move {
+            DatasetBuilder::from_uri(test_uri)
+                .with_manifest(manifest)
+                .with_field_id_check()
+                .load()
+                .await
+        };
+
+        let mut manifest = dataset.manifest.as_ref().clone();
+        manifest.schema.fields[1].id = manifest.schema.fields[0].id;
+        // Without the check the manifest loads
+        DatasetBuilder::from_uri(test_uri)
+            .with_manifest(manifest.clone())
+            .load()
+            .await
+            .unwrap();
+        let err = load_with(manifest).await.unwrap_err();
+        assert!(matches!(err, Error::CorruptFile { .. }), "{:?}", err);
+        assert!(
+            err.to_string()
+                .contains("the schema has duplicate field ids [0]"),
+            "{}",
+            err
+        );
+
+        let mut manifest = dataset.manifest.as_ref().clone();
+        Arc::make_mut(&mut manifest.fragments)[1].files[0].fields = vec![7, 8];
+        let err = load_with(manifest).await.unwrap_err();
+        assert!(matches!(err, Error::CorruptFile { .. }), "{:?}", err);
+        assert!(
+            err.to_string()
+                .contains("has field ids [7, 8] that are not in the schema"),
+            "{}",
+            err
+        );
+
+        let mut manifest = dataset.manifest.as_ref().clone();
+        let fragment = &mut Arc::make_mut(&mut manifest.fragments)[0];
+        let mut file = fragment.files[0].clone();
+        file.fields = vec![1];
+        fragment.files.push(file);
+        let err = load_with(manifest).await.unwrap_err();
+        assert!(matches!(err, Error::CorruptFile { .. }), "{:?}", err);
+        assert!(
+            err.to_string()
+                .contains("fragment 0 stores the field ids {1} in more than one data file"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_referenced_paths() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(3), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        dataset.delete("i < 5").await.unwrap();
+        dataset
+            .create_index(
+                &["i"],
+                IndexType::Scalar,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+
+        let paths = dataset.referenced_paths().await.unwrap();
+        let paths = paths.iter().map(|p| p.to_string()).collect::<HashSet<_>>();
+
+        assert!(paths.contains(&dataset.manifest_location().path.to_string()));
+        let transaction_file = dataset.manifest.transaction_file.as_ref().unwrap();
+        assert!(paths.contains(&format!(
+            "{}/_transactions/{}",
+            dataset.base, transaction_file
+        )));
+        for fragment in dataset.get_fragments() {
+            for file in &fragment.metadata().files {
+                assert!(paths.contains(&format!("{}/data/{}", dataset.base, file.path)));
+            }
+        }
+        let deletion_files = paths.iter().filter(|p| p.contains("/_deletions/")).count();
+        assert_eq!(deletion_files, 1);
+        let index_uuid = dataset.load_indices().await.unwrap()[0].uuid.to_string();
+        assert!(paths
+            .iter()
+            .any(|p| p.starts_with(&format!("{}/_indices/{}/", dataset.base, index_uuid))));
+
+        // Every referenced path exists
+        for path in dataset.referenced_paths().await.unwrap() {
+            assert!(
+                dataset.object_store.exists(&path).await.unwrap(),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diff() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        let batches = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(10), BatchCount::from(1));
+        dataset.append(batches, None).await.unwrap();
+        dataset
+            .create_index(
+                &["i"],
+                IndexType::Scalar,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+
+        let v1 = DatasetBuilder::from_uri(test_uri)
+            .with_version(1)
+            .load()
+            .await
+            .unwrap();
+        let v2 = DatasetBuilder::from_uri(test_uri)
+            .with_version(2)
+            .load()
+            .await
+            .unwrap();
+        let v3 = dataset;
+
+        // The append adds exactly the new fragment
+        let diff = v1.diff(&v2).await.unwrap();
+        assert_eq!(diff.added_fragments, vec![v2.manifest.fragments[2].clone()]);
+        assert!(diff.removed_fragments.is_empty());
+        assert!(diff.added_indices.is_empty());
+        assert!(diff.removed_indices.is_empty());
+
+        // Creating the index adds only the index
+        let diff = v2.diff(&v3).await.unwrap();
+        assert!(diff.added_fragments.is_empty());
+        assert!(diff.removed_fragments.is_empty());
+        assert_eq!(diff.added_indices.len(), 1);
+        assert_eq!(diff.added_indices[0].name, "i_idx");
+        assert!(diff.removed_indices.is_empty());
+
+        // Going backwards removes them
+        let diff = v3.diff(&v1).await.unwrap();
+        assert!(diff.added_fragments.is_empty());
+        assert_eq!(diff.removed_fragments.len(), 1);
+        assert_eq!(diff.removed_fragments[0].id, 2);
+        assert!(diff.added_indices.is_empty());
+        assert_eq!(diff.removed_indices.len(), 1);
+
+        assert_eq!(v3.diff(&v3).await.unwrap(), VersionDiff::default());
+    }
+
+    #[tokio::test]
+    async fn test_builder_default_projection() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut original = gen()
+            .col("a", array::step::<Int32Type>())
+            .col("b", array::step::<Int32Type>())
+            .col("c", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        // Create a second version
+        original.delete("a = 0").await.unwrap();
+
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_default_projection(vec!["c".to_string(), "a".to_string()])
+            .load()
+            .await
+            .unwrap();
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        let names = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["c", "a"]);
+
+        // An explicit projection overrides the default
+        let batch = dataset
+            .scan()
+            .project(&["b"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        let names = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["b"]);
+
+        // Checking out another version keeps the default
+        let version = dataset.checkout_version(1).await.unwrap();
+        let batch = version.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch.schema().field(0).name(), "c");
+        assert_eq!(batch.num_columns(), 2);
+
+        let err = DatasetBuilder::from_uri(test_uri)
+            .with_default_projection(vec!["a".to_string(), "x".to_string(), "y".to_string()])
+            .load()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(r#"["x", "y"]"#), "{}", err);
+
+        // Scans fail, rather than read every column, if a column of the default is dropped
+        let mut dataset = dataset;
+        dataset.drop_columns(&["c"]).await.unwrap();
+        let err = dataset.scan().try_into_batch().await.unwrap_err();
+        assert!(matches!(err, Error::InvalidInput { .. }), "{:?}", err);
+        assert!(err.to_string().contains("default projection"), "{}", err);
+        let batch = dataset
+            .scan()
+            .project(&["b"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch.num_columns(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_builder_ignore_deletions() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        dataset.delete("i % 2 = 0").await.unwrap();
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 10);
+
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_ignore_deletions()
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 20);
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(
+            batch["i"].as_primitive::<Int32Type>().values().to_vec(),
+            (0..20).collect::<Vec<_>>()
+        );
+        let batch = dataset
+            .take(&[0, 1], dataset.schema().clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            batch["i"].as_primitive::<Int32Type>().values().to_vec(),
+            vec![0, 1]
+        );
+
+        // The deleted rows can't be written back
+        let mut dataset = dataset;
+        let err = dataset.delete("i = 1").await.unwrap_err();
+        assert!(err.to_string().contains("ignore_deletions"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_builder_schema_projection() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        gen()
+            .col("a", array::step::<Int32Type>())
+            .col("b", array::step::<Int32Type>())
+            .col("c", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_schema_projection(vec!["c".to_string(), "a".to_string()])
+            .load()
+            .await
+            .unwrap();
+        let names = |schema: &Schema| {
+            schema
+                .fields
+                .iter()
+                .map(|field| field.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(dataset.schema()), vec!["c", "a"]);
+
+        // The pruned column is not scanned and can't be projected
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch.schema().fields().len(), 2);
+        assert!(batch.column_by_name("b").is_none());
+        assert_eq!(
+            batch["a"].as_primitive::<Int32Type>().values().to_vec(),
+            (0..20).collect::<Vec<_>>()
+        );
+        assert!(dataset.scan().project(&["b"]).is_err());
+        assert!(dataset
+            .take(&[0], ProjectionRequest::from_sql([("b", "b")]))
+            .await
+            .is_err());
+
+        // New versions would drop the hidden column
+        let mut dataset = dataset;
+        let err = dataset.delete("a = 1").await.unwrap_err();
+        assert!(err.to_string().contains("schema projection"), "{}", err);
+
+        let err = DatasetBuilder::from_uri(test_uri)
+            .with_schema_projection(vec!["a".to_string(), "x".to_string()])
+            .load()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(r#"["x"]"#), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_checkout_keeps_builder_options() {
+        use crate::utils::temporal::utc_now;
+        use crate::utils::test::ManualClock;
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut original = gen()
+            .col("a", array::step::<Int32Type>())
+            .col("b", array::step::<Int32Type>())
+            .col("c", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        original.delete("a < 5").await.unwrap();
+
+        let clock = Arc::new(ManualClock::new(
+            utc_now() + chrono::TimeDelta::try_days(30).unwrap(),
+        ));
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_schema_projection(vec!["c".to_string(), "a".to_string()])
+            .with_default_projection(vec!["a".to_string()])
+            .with_ignore_deletions()
+            .with_clock(clock.clone())
+            .load()
+            .await
+            .unwrap();
+
+        let check = |dataset: Dataset, version: u64| {
+            let clock = clock.clone();
+            async move {
+                assert_eq!(dataset.version().version, version);
+                let names = dataset
+                    .schema()
+                    .fields
+                    .iter()
+                    .map(|field| field.name.clone())
+                    .collect::<Vec<_>>();
+                assert_eq!(names, vec!["c", "a"]);
+                let batch = dataset.scan().try_into_batch().await.unwrap();
+                assert_eq!(batch.num_columns(), 1);
+                assert_eq!(
+                    batch["a"].as_primitive::<Int32Type>().values().to_vec(),
+                    (0..20).collect::<Vec<_>>()
+                );
+                assert_eq!(dataset.read_options.clock.now(), clock.now());
+            }
+        };
+        check(dataset.clone(), 2).await;
+
+        let mut dataset = dataset.checkout_version(1).await.unwrap();
+        check(dataset.clone(), 1).await;
+        dataset.checkout_latest().await.unwrap();
+        check(dataset, 2).await;
+    }
+
+    #[tokio::test]
+    async fn test_builder_fallback_uris() {
+        use crate::utils::test::{record_io_trace, IoStats, ReplayStore};
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().join("dataset");
+        let test_uri = test_uri.to_str().unwrap();
+        gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        // The primary is unreachable, replaying an empty trace fails every read
+        let trace_path = test_dir.path().join("empty_trace");
+        record_io_trace(&IoStats::default(), &trace_path).unwrap();
+        let faulty_params = ObjectStoreParams {
+            object_store_wrapper: Some(Arc::new(ReplayStore::load(&trace_path).unwrap())),
+            ..Default::default()
+        };
+        let primary_uri = format!("file-object-store://{}", test_uri);
+        let primary = || {
+            DatasetBuilder::from_uri(&primary_uri).with_read_params(ReadParams {
+                store_options: Some(faulty_params.clone()),
+                ..Default::default()
+            })
+        };
+        let primary_err = primary().load().await.unwrap_err();
+
+        let dataset = primary()
+            .with_fallback_uri_and_options(test_uri, ObjectStoreParams::default())
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(dataset.uri(), test_uri);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 10);
+
+        // Fallbacks share the options of the primary so this fallback is unreachable too, the
+        // error of the primary is returned
+        let err = primary()
+            .with_fallback_uris(vec![primary_uri.clone()])
+            .load()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            std::mem::discriminant(&err),
+            std::mem::discriminant(&primary_err),
+            "{:?}",
+            err
+        );
+
+        // Errors other than failing to read the dataset don't fall back
+        let schema = Schema::try_from(&ArrowSchema::new(vec![ArrowField::new(
+            "j",
+            DataType::Int32,
+            true,
+        )]))
+        .unwrap();
+        let err = DatasetBuilder::from_uri(test_uri)
+            .with_expected_schema(&schema)
+            .with_fallback_uris(vec![test_uri.to_string()])
+            .load()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::SchemaMismatch { .. }), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_builder_expected_schema() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        gen()
+            .col("a", array::step::<Int32Type>())
+            .col("b", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        let expected_schema =
+            |fields: Vec<ArrowField>| Schema::try_from(&ArrowSchema::new(fields)).unwrap();
+        let load = |schema: Schema, allow_extra_columns: bool| {
+            let mut builder = DatasetBuilder::from_uri(test_uri).with_expected_schema(&schema);
+            if allow_extra_columns {
+                builder = builder.with_allow_extra_columns();
+            }
+            builder.load()
+        };
+
+        // Field ids are not compared
+        let schema = expected_schema(vec![
+            ArrowField::new("a", DataType::Int32, true),
+            ArrowField::new("b", DataType::Int32, true),
+        ]);
+        load(schema, false).await.unwrap();
+
+        // Extra columns are only allowed with the flag
+        let schema = expected_schema(vec![ArrowField::new("b", DataType::Int32, true)]);
+        let err = load(schema.clone(), false).await.unwrap_err();
+        assert!(matches!(err, Error::SchemaMismatch { .. }), "{:?}", err);
+        assert!(err.to_string().contains("unexpected=[a]"), "{}", err);
+        load(schema, true).await.unwrap();
+
+        // Type differences and missing columns are always reported
+        let schema = expected_schema(vec![
+            ArrowField::new("a", DataType::Int64, true),
+            ArrowField::new("c", DataType::Int32, true),
+        ]);
+        let err = load(schema, true).await.unwrap_err();
+        assert!(matches!(err, Error::SchemaMismatch { .. }), "{:?}", err);
+        let message = err.to_string();
+        assert!(
+            message.contains("`a` should have type int64"),
+            "{}",
+            message
+        );
+        assert!(message.contains("missing=[c]"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_builder_hard_memory_limit() {
+        use crate::index::DatasetIndexInternalExt;
+        use lance_index::metrics::NoOpMetricsCollector;
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let columns = ["a", "b", "c", "d"];
+        let mut dataset = gen()
+            .col("a", array::step::<Int32Type>())
+            .col("b", array::step::<Int32Type>())
+            .col("c", array::step::<Int32Type>())
+            .col("d", array::step::<Int32Type>())
+            .into_dataset(
+                test_uri,
+                FragmentCount::from(2),
+                FragmentRowCount::from(1000),
+            )
+            .await
+            .unwrap();
+        for column in columns {
+            dataset
+                .create_index(
+                    &[column],
+                    IndexType::Scalar,
+                    None,
+                    &ScalarIndexParams::default(),
+                    false,
+                )
+                .await
+                .unwrap();
+        }
+
+        // Opens each index in turn, returning the bytes cached after each one
+        let open_indices = |limit: usize| async move {
+            let dataset = DatasetBuilder::from_uri(test_uri)
+                .with_hard_memory_limit(limit)
+                .load()
+                .await
+                .unwrap();
+            let indices = dataset.load_indices().await.unwrap();
+            let memory_limit = dataset.session().hard_memory_limit().unwrap().clone();
+            let mut used = Vec::new();
+            for column in columns {
+                let index = indices
+                    .iter()
+                    .find(|index| index.name == format!("{}_idx", column))
+                    .unwrap();
+                let opened = dataset
+                    .open_generic_index(column, &index.uuid.to_string(), &NoOpMetricsCollector)
+                    .await;
+                if let Err(err) = opened {
+                    return (used, Some(err));
+                }
+                used.push(memory_limit.used_bytes());
+            }
+            (used, None)
+        };
+
+        let (used, err) = open_indices(usize::MAX).await;
+        assert!(err.is_none());
+        assert!(used.windows(2).all(|w| w[0] < w[1]), "{:?}", used);
+
+        // A budget that fits exactly the first two indices
+        let (limited_used, err) = open_indices(used[1]).await;
+        assert_eq!(limited_used, used[..2]);
+        let err = err.unwrap();
+        assert!(matches!(err, Error::OutOfMemory { .. }), "{:?}", err);
+        assert!(
+            err.to_string().contains("hard cache memory limit"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_builder_trace_attributes() {
+        use std::sync::Mutex;
+
+        use tracing::{
+            field::{Field, Visit},
+            span,
+        };
+        use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+        // Captures the fields recorded on the `load` span
+        #[derive(Clone, Default)]
+        struct LoadSpanFields(Arc<Mutex<HashMap<String, String>>>);
+
+        impl Visit for LoadSpanFields {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .insert(field.name().to_string(), value.to_string());
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .insert(field.name().to_string(), format!("{:?}", value));
+            }
+        }
+
+        impl<S: tracing::Subscriber + for<'a> LookupSpan<'a>> Layer<S> for LoadSpanFields {
+            fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+                if ctx.span(id).is_some_and(|span| span.name() == "load") {
+                    values.record(&mut self.clone());
+                }
+            }
+        }
+
+        gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(
+                "memory://trace_attributes",
+                FragmentCount::from(1),
+                FragmentRowCount::from(10),
+            )
+            .await
+            .unwrap();
+
+        let fields = LoadSpanFields::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(fields.clone()));
+        DatasetBuilder::from_uri("memory://trace_attributes")
+            .with_trace_attributes(vec![
+                ("tenant_id".to_string(), "tenant-1".to_string()),
+                ("request_id".to_string(), "42".to_string()),
+            ])
+            .load()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fields.0.lock().unwrap().get("trace_attributes").unwrap(),
+            "tenant_id=tenant-1,request_id=42"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_builder_prefetch() {
+        // Need to use in-memory for accurate IOPS tracking.
+        use crate::utils::test::IoTrackingStore;
+
+        let session = Arc::new(Session::default());
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let store_params = ObjectStoreParams {
+            object_store_wrapper: Some(io_stats_wrapper),
+            ..Default::default()
+        };
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset_with_params(
+                "memory://prefetch",
+                FragmentCount::from(3),
+                FragmentRowCount::from(10),
+                Some(WriteParams {
+                    store_params: Some(store_params.clone()),
+                    session: Some(session.clone()),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        let builder = DatasetBuilder::from_uri("memory://prefetch").with_read_params(ReadParams {
+            store_options: Some(store_params),
+            session: Some(session),
+            ..Default::default()
+        });
+        *io_stats.lock().unwrap() = Default::default();
+        let loaded = builder.clone().prefetch().load().await.unwrap();
+        assert_eq!(loaded.manifest.as_ref(), dataset.manifest.as_ref());
+
+        // The manifest is only read by the prefetch
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        let manifest_reads = stats
+            .requests
+            .iter()
+            .filter(|request| {
+                request.path.as_ref().contains("_versions") && request.method.starts_with("get")
+            })
+            .count();
+        assert_eq!(manifest_reads, 1, "{}", stats);
+
+        // Errors from the prefetch are returned by load
+        let err = builder.with_version(42).prefetch().load().await;
+        assert!(err.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_builder_consistency_token() {
+        use crate::utils::test::IoTrackingStore;
+
+        let session = Arc::new(Session::default());
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let store_params = ObjectStoreParams {
+            object_store_wrapper: Some(io_stats_wrapper),
+            ..Default::default()
+        };
+        let write_params = WriteParams {
+            store_params: Some(store_params.clone()),
+            session: Some(session.clone()),
+            ..Default::default()
+        };
+        let mut writer = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset_with_params(
+                "memory://consistency_token",
+                FragmentCount::from(1),
+                FragmentRowCount::from(10),
+                Some(write_params.clone()),
+            )
+            .await
+            .unwrap();
+
+        let builder =
+            DatasetBuilder::from_uri("memory://consistency_token").with_read_params(ReadParams {
+                store_options: Some(store_params),
+                session: Some(session),
+                ..Default::default()
+            });
+        let (dataset, token) = builder.clone().load_with_token().await.unwrap();
+        assert_eq!(token.version, dataset.version().version);
+        assert_eq!(token.e_tag, dataset.manifest_location().e_tag);
+
+        // Checking the token does not read the manifest
+        *io_stats.lock().unwrap() = Default::default();
+        assert!(builder.clone().is_current(&token).await.unwrap());
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert_eq!(stats.read_iops, 0);
+
+        // A concurrent commit invalidates the token
+        let batches = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(10), BatchCount::from(1));
+        writer.append(batches, Some(write_params)).await.unwrap();
+        assert!(!builder.clone().is_current(&token).await.unwrap());
+
+        let (dataset, new_token) = builder.clone().load_with_token().await.unwrap();
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 20);
+        assert_eq!(new_token.version, token.version + 1);
+        assert!(builder.is_current(&new_token).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_builder_cached_manifest() {
+        use crate::utils::test::IoTrackingStore;
+
+        // The memory store answers conditional reads with "not modified"
+        let session = Arc::new(Session::default());
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let store_params = ObjectStoreParams {
+            object_store_wrapper: Some(io_stats_wrapper),
+            ..Default::default()
+        };
+        let write_params = WriteParams {
+            store_params: Some(store_params.clone()),
+            session: Some(session.clone()),
+            ..Default::default()
+        };
+        let mut writer = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset_with_params(
+                "memory://cached_manifest",
+                FragmentCount::from(2),
+                FragmentRowCount::from(10),
+                Some(write_params.clone()),
+            )
+            .await
+            .unwrap();
+
+        let builder =
+            DatasetBuilder::from_uri("memory://cached_manifest").with_read_params(ReadParams {
+                store_options: Some(store_params),
+                session: Some(session),
+                ..Default::default()
+            });
+        let (dataset, token) = builder.clone().load_with_token().await.unwrap();
+        assert!(token.e_tag.is_some());
+
+        let manifest_reads = || {
+            let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+            stats
+                .requests
+                .iter()
+                .filter(|request| {
+                    request.path.as_ref().contains("_versions") && request.method.starts_with("get")
+                })
+                .count()
+        };
+
+        // Not modified, the cached manifest is used and the manifest is not read
+        manifest_reads();
+        let loaded = builder
+            .clone()
+            .with_cached_manifest(dataset.manifest.as_ref().clone(), token.clone())
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(manifest_reads(), 0);
+        assert_eq!(loaded.manifest.as_ref(), dataset.manifest.as_ref());
+        assert_eq!(loaded.count_rows(None).await.unwrap(), 20);
+
+        // A new version is loaded from the store
+        let batches = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(10), BatchCount::from(1));
+        writer.append(batches, Some(write_params)).await.unwrap();
+        manifest_reads();
+        let loaded = builder
+            .clone()
+            .with_cached_manifest(dataset.manifest.as_ref().clone(), token.clone())
+            .load()
+            .await
+            .unwrap();
+        assert!(manifest_reads() > 0);
+        assert_eq!(loaded.version().version, token.version + 1);
+        assert_eq!(loaded.count_rows(None).await.unwrap(), 30);
+
+        // The same version but a token with a stale e-tag, the manifest is decoded from
+        // the conditional read and not read again
+        let stale_token = builder::ConsistencyToken {
+            e_tag: Some("stale".to_string()),
+            ..token.clone()
+        };
+        let loaded = builder
+            .with_version(token.version)
+            .with_cached_manifest(dataset.manifest.as_ref().clone(), stale_token)
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(manifest_reads(), 1);
+        assert_eq!(loaded.manifest.as_ref(), dataset.manifest.as_ref());
+    }
+
+    #[tokio::test]
+    async fn test_builder_path_rewriter() {
+        use crate::utils::test::IoTrackingStore;
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        // Move the data files to a new directory
+        std::fs::rename(
+            test_dir.path().join("data"),
+            test_dir.path().join("relocated"),
+        )
+        .unwrap();
+        let data_dir = dataset.data_dir();
+        let relocated_dir = dataset.base.child("relocated");
+        let scan_all = |dataset: Dataset| async move { dataset.scan().try_into_batch().await };
+
+        let moved = DatasetBuilder::from_uri(test_uri).load().await.unwrap();
+        assert!(scan_all(moved).await.is_err());
+
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let rewritten = DatasetBuilder::from_uri(test_uri)
+            .with_read_params(ReadParams {
+                store_options: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .with_path_rewriter(move |path: &Path| match path.prefix_match(&data_dir) {
+                Some(rest) => Path::from_iter(relocated_dir.parts().chain(rest)),
+                None => path.clone(),
+            })
+            .load()
+            .await
+            .unwrap();
+        io_stats.lock().unwrap().requests.clear();
+        let batch = scan_all(rewritten).await.unwrap();
+        assert_eq!(batch.num_rows(), 20);
+
+        // The wrapped store only sees the new location
+        let stats = io_stats.lock().unwrap();
+        assert!(!stats.requests.is_empty());
+        for request in &stats.requests {
+            assert!(
+                request.path.as_ref().contains("/relocated/"),
+                "unexpected read of {}",
+                request.path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_reads() {
+        use crate::dataset::fragment::FragReadConfig;
+        use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
+
+        let (dataset, io_stats) = gen()
+            .col("i", array::step::<Int32Type>())
+            .col("s", array::rand_utf8(ByteCount::from(16), false))
+            .into_tracked_ram_dataset(FragmentCount::from(2), FragmentRowCount::from(1000))
+            .await
+            .unwrap();
+
+        // Only the file of the second fragment is needed
+        let fragment = &dataset.get_fragments()[1];
+        let data_file = &fragment.metadata().files[0];
+        let metadata_reads = {
+            let scheduler = ScanScheduler::new(
+                dataset.object_store.clone(),
+                SchedulerConfig::default_for_testing(),
+            );
+            let file_scheduler = scheduler
+                .open_file(
+                    &dataset.data_dir().child(data_file.path.as_str()),
+                    &data_file.file_size_bytes,
+                )
+                .await
+                .unwrap();
+            std::mem::take(&mut *io_stats.lock().unwrap());
+            lance_file::v2::reader::FileReader::read_all_metadata(&file_scheduler)
+                .await
+                .unwrap();
+            std::mem::take(&mut *io_stats.lock().unwrap()).read_iops
+        };
+        assert!(metadata_reads > 0);
+
+        // On a cold cache planning reads the metadata of the file and none of its data
+        let plan = dataset.plan_reads(1100..1200, &["i"]).await.unwrap();
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert_eq!(stats.read_iops, metadata_reads);
+        assert!(stats
+            .requests
+            .iter()
+            .all(|request| request.path.filename() == Some(data_file.path.as_str())));
+        assert!(!plan.is_empty());
+        assert!(plan
+            .iter()
+            .all(|read| read.path.filename() == Some(data_file.path.as_str())));
+
+        // The metadata is now cached and planning does no IO
+        assert_eq!(dataset.plan_reads(1100..1200, &["i"]).await.unwrap(), plan);
+        assert_eq!(std::mem::take(&mut *io_stats.lock().unwrap()).read_iops, 0);
+
+        let projection = dataset.schema().project(&["i"]).unwrap();
+        fragment
+            .open(&projection, FragReadConfig::default())
+            .await
+            .unwrap()
+            .read_range(100..200, 1024)
+            .unwrap()
+            .buffered(1)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        let covered = |path: &Path, pos: u64| {
+            plan.iter().any(|read| {
+                &read.path == path && read.offset <= pos && pos < read.offset + read.size
+            })
+        };
+        let data_reads = stats
+            .requests
+            .iter()
+            .filter(|request| request.path.extension() == Some("lance"))
+            .collect::<Vec<_>>();
+        assert!(!data_reads.is_empty());
+        for request in data_reads {
+            // Small reads may be coalesced, so only check that each read starts and ends in
+            // a planned read
+            let range = request.range.clone().unwrap();
+            assert!(covered(&request.path, range.start), "{:?}", request);
+            assert!(covered(&request.path, range.end - 1), "{:?}", request);
+        }
+
+        // Legacy datasets can't be planned
+        let legacy = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_ram_dataset_with_params(
+                FragmentCount::from(1),
+                FragmentRowCount::from(10),
+                Some(WriteParams {
+                    data_storage_version: Some(LanceFileVersion::Legacy),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            legacy.plan_reads(0..10, &["i"]).await,
+            Err(Error::NotSupported { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_builder_metrics_callback() {
+        use crate::dataset::builder::OpenMetrics;
+        use crate::utils::test::IoTrackingStore;
+        use lance_io::object_store::IoCounts;
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        let metrics = Arc::new(std::sync::Mutex::new(Vec::<OpenMetrics>::new()));
+        let builder = {
+            let metrics = metrics.clone();
+            DatasetBuilder::from_uri(test_uri)
+                .with_metrics_callback(move |m| metrics.lock().unwrap().push(m))
+        };
+
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let dataset = builder
+            .clone()
+            .with_read_params(ReadParams {
+                store_options: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .load()
+            .await
+            .unwrap();
+        let reported = std::mem::take(&mut *metrics.lock().unwrap());
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].uri, test_uri);
+        assert_eq!(reported[0].version, dataset.version().version);
+        assert!(reported[0].latency > std::time::Duration::ZERO);
+        let stats = io_stats.lock().unwrap();
+        assert!(stats.read_iops > 0);
+        assert_eq!(
+            reported[0].io,
+            Some(IoCounts {
+                read_iops: stats.read_iops,
+                read_bytes: stats.read_bytes,
+            })
+        );
+
+        // Without a wrapper that tracks IO there are no IO counts
+        builder.load().await.unwrap();
+        let reported = std::mem::take(&mut *metrics.lock().unwrap());
+        assert_eq!(reported.len(), 1);
+        assert!(reported[0].latency > std::time::Duration::ZERO);
+        assert_eq!(reported[0].io, None);
+    }
+
+    #[tokio::test]
+    async fn test_builder_lazy() {
+        use crate::utils::test::IoTrackingStore;
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let lazy = DatasetBuilder::from_uri(test_uri)
+            .with_read_params(ReadParams {
+                store_options: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .lazy();
+        assert_eq!(lazy.uri(), test_uri);
+        assert!(!lazy.is_loaded());
+        assert_eq!(io_stats.lock().unwrap().read_iops, 0);
+
+        // The first use loads the dataset
+        let dataset = lazy.get().await.unwrap();
+        assert!(lazy.is_loaded());
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 20);
+        let read_iops = io_stats.lock().unwrap().read_iops;
+        assert!(read_iops > 0);
+
+        // Later uses reuse it
+        let again = lazy.get().await.unwrap();
+        assert!(Arc::ptr_eq(&dataset, &again));
+        assert_eq!(io_stats.lock().unwrap().read_iops, read_iops);
+
+        // Errors are only returned on use
+        let missing =
+            DatasetBuilder::from_uri(test_dir.path().join("missing").to_str().unwrap()).lazy();
+        assert!(missing.get().await.is_err());
+        assert!(!missing.is_loaded());
+    }
+
+    #[tokio::test]
+    async fn test_builder_clock() {
+        use crate::utils::temporal::utc_now;
+        use crate::utils::test::ManualClock;
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+
+        // A month from now
+        let clock = Arc::new(ManualClock::new(
+            utc_now() + chrono::TimeDelta::try_days(30).unwrap(),
+        ));
+        let mut dataset = DatasetBuilder::from_uri(test_uri)
+            .with_clock(clock.clone())
+            .load()
+            .await
+            .unwrap();
+
+        // New versions are stamped with the clock's time
+        let batches = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(10), BatchCount::from(1));
+        dataset.append(batches, None).await.unwrap();
+        assert_eq!(dataset.version().timestamp, clock.now());
+
+        // The first version is a month old according to the clock
+        let stats = dataset
+            .cleanup_old_versions(Duration::try_days(7).unwrap(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.old_versions, 1);
+        assert_eq!(dataset.versions().await.unwrap().len(), 1);
+
+        // A time before the Unix epoch can't be stored in the manifest
+        clock.set(DateTime::from_timestamp(-1, 0).unwrap());
+        let batches = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(10), BatchCount::from(1));
+        let err = dataset.append(batches, None).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidInput { .. }), "{}", err);
+        assert!(err.to_string().contains("Unix epoch"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_builder_download_retry_count() {
+        let retry_count = |builder: DatasetBuilder| async move {
+            let (object_store, _, _) = builder.build_object_store().await.unwrap();
+            object_store.download_retry_count()
+        };
+
+        let builder = DatasetBuilder::from_uri("memory://test");
+        assert_eq!(retry_count(builder.clone()).await, 3);
+        assert_eq!(
+            retry_count(builder.clone().with_download_retry_count(7)).await,
+            7
+        );
+
+        // The typed setting wins over the storage option, whichever is set last
+        let builder = DatasetBuilder::from_uri("memory://test")
+            .with_download_retry_count(7)
+            .with_storage_options(HashMap::from([
+                ("DOWNLOAD_RETRY_COUNT".to_string(), "1".to_string()),
+                ("allow_http".to_string(), "true".to_string()),
+            ]));
+        assert_eq!(retry_count(builder.clone()).await, 7);
+        let builder = builder
+            .with_storage_option("download_retry_count", "2")
+            .with_download_retry_count(5);
+        assert_eq!(retry_count(builder).await, 5);
+    }
+
+    #[tokio::test]
+    async fn test_builder_table_subpath() {
+        let test_dir = tempdir().unwrap();
+        let root = test_dir.path().to_str().unwrap().trim_end_matches('/');
+        gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(
+                &format!("{}/tables/t1", root),
+                FragmentCount::from(1),
+                FragmentRowCount::from(10),
+            )
+            .await
+            .unwrap();
+
+        for (table_root, subpath) in [
+            (root.to_string(), "tables/t1"),
+            (format!("{}/", root), "tables/t1"),
+            (root.to_string(), "/tables/t1"),
+            (format!("{}/", root), "/tables/t1/"),
+            (format!("{}/tables", root), "t1"),
+        ] {
+            let dataset = DatasetBuilder::from_uri(&table_root)
+                .with_table_subpath(subpath)
+                .load()
+                .await
+                .unwrap();
+            assert_eq!(dataset.count_rows(None).await.unwrap(), 10);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_version_and_tag() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        dataset.tags.create("tag1", 1).await.unwrap();
+
+        for builder in [
+            DatasetBuilder::from_uri(test_uri)
+                .with_version(1)
+                .with_tag("tag1"),
+            DatasetBuilder::from_uri(test_uri)
+                .with_tag("tag1")
+                .with_version(1),
+        ] {
+            let err = builder.load().await.unwrap_err();
+            assert!(
+                err.to_string().contains("ambiguous version specification"),
+                "{}",
+                err
+            );
+        }
+
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_tag("tag1")
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(dataset.version().version, 1);
+        let dataset = DatasetBuilder::from_uri(test_uri)
+            .with_version(1)
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(dataset.version().version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_serialized_manifest_round_trip() {
+        // Need to use in-memory for accurate IOPS tracking.
+        use crate::utils::test::IoTrackingStore;
 
-        // append rows to dataset
-        let mut write_params = WriteParams {
-            max_rows_per_file: 40,
-            max_rows_per_group: 10,
-            data_storage_version: Some(data_storage_version),
+        let session = Arc::new(Session::default());
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let store_params = ObjectStoreParams {
+            object_store_wrapper: Some(io_stats_wrapper),
             ..Default::default()
         };
-        // We should be able to append even if the metadata doesn't exactly match.
-        let schema_with_meta = Arc::new(
-            schema
-                .as_ref()
-                .clone()
-                .with_metadata([("key".to_string(), "value".to_string())].into()),
-        );
-        let batches = vec![RecordBatch::try_new(
-            schema_with_meta,
-            vec![Arc::new(Int32Array::from_iter_values(0..10))],
-        )
-        .unwrap()];
-        write_params.mode = WriteMode::Append;
-        let batches = RecordBatchIterator::new(batches.into_iter().map(Ok), schema.clone());
-        Dataset::write(batches, test_uri, Some(write_params))
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset_with_params(
+                "memory://serialized_manifest",
+                FragmentCount::from(3),
+                FragmentRowCount::from(10),
+                Some(WriteParams {
+                    store_params: Some(store_params.clone()),
+                    session: Some(session.clone()),
+                    ..Default::default()
+                }),
+            )
             .await
             .unwrap();
+        let serialized = dataset.serialized_manifest().unwrap();
 
-        let expected_batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![Arc::new(Int32Array::from_iter_values(0..10))],
-        )
-        .unwrap();
-
-        // get actual dataset
-        let actual_ds = Dataset::open(test_uri).await.unwrap();
-        // confirm schema is same
-        let actual_schema = ArrowSchema::from(actual_ds.schema());
-        assert_eq!(&actual_schema, schema.as_ref());
-        // check num rows is 10
-        assert_eq!(actual_ds.count_rows(None).await.unwrap(), 10);
-        // Max fragment id is still 0 since we only have 1 fragment.
-        assert_eq!(actual_ds.manifest.max_fragment_id(), Some(0));
-        // check expected batch is correct
-        let actual_batches = actual_ds
-            .scan()
-            .try_into_stream()
-            .await
+        *io_stats.lock().unwrap() = Default::default();
+        let loaded = DatasetBuilder::from_uri("memory://serialized_manifest")
+            .with_read_params(ReadParams {
+                store_options: Some(store_params),
+                session: Some(session),
+                ..Default::default()
+            })
+            .with_serialized_manifest(&serialized)
             .unwrap()
-            .try_collect::<Vec<_>>()
+            .load()
             .await
             .unwrap();
-        // sort
-        let actual_batch = concat_batches(&schema, &actual_batches).unwrap();
-        let idx_arr = actual_batch.column_by_name("i").unwrap();
-        let sorted_indices = sort_to_indices(idx_arr, None, None).unwrap();
-        let struct_arr: StructArray = actual_batch.into();
-        let sorted_arr = arrow_select::take::take(&struct_arr, &sorted_indices, None).unwrap();
-        let expected_struct_arr: StructArray = expected_batch.into();
-        assert_eq!(&expected_struct_arr, as_struct_array(sorted_arr.as_ref()));
+        assert_eq!(loaded.manifest.as_ref(), dataset.manifest.as_ref());
+        assert_eq!(loaded.count_rows(None).await.unwrap(), 30);
+
+        // The manifest is neither listed nor read
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert!(
+            !stats.requests.iter().any(|request| {
+                request.path.as_ref().contains("_versions")
+                    && (request.method.starts_with("list") || request.method.starts_with("get"))
+            }),
+            "{}",
+            stats
+        );
     }
 
-    #[rstest]
-    #[lance_test_macros::test(tokio::test)]
-    async fn test_create_with_empty_iter(
-        #[values(LanceFileVersion::Legacy, LanceFileVersion::Stable)]
-        data_storage_version: LanceFileVersion,
-    ) {
+    #[tokio::test]
+    async fn test_serialized_manifest_header() {
         let test_dir = tempdir().unwrap();
         let test_uri = test_dir.path().to_str().unwrap();
-        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
-            "i",
-            DataType::Int32,
-            false,
-        )]));
-        let reader = RecordBatchIterator::new(vec![].into_iter().map(Ok), schema.clone());
-        // check schema of reader and original is same
-        assert_eq!(schema.as_ref(), reader.schema().as_ref());
-        let write_params = Some(WriteParams {
-            data_storage_version: Some(data_storage_version),
-            ..Default::default()
-        });
-        let result = Dataset::write(reader, test_uri, write_params)
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(5), FragmentRowCount::from(10))
             .await
             .unwrap();
+        let serialized =
+            lance_table::format::pb::Manifest::from(dataset.manifest.as_ref()).encode_to_vec();
 
-        // check dataset empty
-        assert_eq!(result.count_rows(None).await.unwrap(), 0);
-        // Since the dataset is empty, will return None.
-        assert_eq!(result.manifest.max_fragment_id(), None);
+        let builder = DatasetBuilder::from_uri(test_uri)
+            .with_serialized_manifest_header(&serialized)
+            .unwrap();
+        let header = builder.manifest_header().unwrap();
+        assert_eq!(header.schema, dataset.manifest.schema);
+        assert_eq!(header.version, dataset.version().version);
+        assert_eq!(header.timestamp(), dataset.manifest.timestamp());
+
+        // Loading decodes the fragments
+        let loaded = builder.load().await.unwrap();
+        assert_eq!(loaded.manifest.as_ref(), dataset.manifest.as_ref());
+        assert_eq!(loaded.get_fragments().len(), 5);
+        assert_eq!(loaded.count_rows(None).await.unwrap(), 50);
     }
 
     #[tokio::test]
-    async fn test_load_manifest_iops() {
-        // Need to use in-memory for accurate IOPS tracking.
+    async fn test_builder_with_manifest() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(3), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        let manifest = dataset.manifest.as_ref().clone();
+
+        let loaded = DatasetBuilder::from_uri(test_uri)
+            .with_manifest(manifest.clone())
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(loaded.manifest.as_ref(), &manifest);
+        assert_eq!(loaded.count_rows(None).await.unwrap(), 30);
+
+        let loaded = DatasetBuilder::from_uri(test_uri)
+            .with_manifest_and_location(manifest.clone(), dataset.manifest_location().clone())
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(loaded.manifest.as_ref(), &manifest);
+
+        // The version must exist unless the location is given
+        let mut missing = manifest;
+        missing.version = 42;
+        let err = DatasetBuilder::from_uri(test_uri)
+            .with_manifest(missing)
+            .load()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::VersionNotFound { .. }), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_builder_with_serialized_manifest_io() {
         use crate::utils::test::IoTrackingStore;
 
-        // Use consistent session so memory store can be reused.
-        let session = Arc::new(Session::default());
-        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
-            "i",
-            DataType::Int32,
-            false,
-        )]));
-        let batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![Arc::new(Int32Array::from_iter_values(0..10_i32))],
-        )
-        .unwrap();
-        let batches = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
         let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
-        let _original_ds = Dataset::write(
-            batches,
-            "memory://test",
-            Some(WriteParams {
-                store_params: Some(ObjectStoreParams {
-                    object_store_wrapper: Some(io_stats_wrapper.clone()),
+        let store_params = ObjectStoreParams {
+            object_store_wrapper: Some(io_stats_wrapper),
+            ..Default::default()
+        };
+        let dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset_with_params(
+                "memory://serialized_manifest",
+                FragmentCount::from(3),
+                FragmentRowCount::from(10),
+                Some(WriteParams {
+                    store_params: Some(store_params.clone()),
                     ..Default::default()
                 }),
-                session: Some(session.clone()),
+            )
+            .await
+            .unwrap();
+        // V1 manifest paths are not found by the lookup of the version's location
+        assert_eq!(
+            dataset.manifest_location().naming_scheme,
+            ManifestNamingScheme::V1
+        );
+        let serialized =
+            lance_table::format::pb::Manifest::from(dataset.manifest.as_ref()).encode_to_vec();
+        let builder =
+            DatasetBuilder::from_uri("memory://serialized_manifest").with_read_params(ReadParams {
+                store_options: Some(store_params),
                 ..Default::default()
-            }),
-        )
-        .await
-        .unwrap();
+            });
 
-        io_stats.lock().unwrap().read_iops = 0;
+        // Nothing is read, only the location of the version is looked up
+        *io_stats.lock().unwrap() = Default::default();
+        let loaded = builder
+            .clone()
+            .with_serialized_manifest(&serialized)
+            .unwrap()
+            .load()
+            .await
+            .unwrap();
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert_eq!(loaded.manifest.as_ref(), dataset.manifest.as_ref());
+        assert_eq!(stats.read_iops, 0, "{}", stats);
+        assert_eq!(stats.metadata_iops, 1, "{}", stats);
 
-        let _dataset = DatasetBuilder::from_uri("memory://test")
-            .with_read_params(ReadParams {
-                store_options: Some(ObjectStoreParams {
-                    object_store_wrapper: Some(io_stats_wrapper),
+        // Only an explicit manifest is checked to exist
+        builder
+            .with_manifest(dataset.manifest.as_ref().clone())
+            .load()
+            .await
+            .unwrap();
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert_eq!(stats.read_iops, 0, "{}", stats);
+        assert_eq!(stats.metadata_iops, 2, "{}", stats);
+    }
+
+    #[tokio::test]
+    async fn test_builder_fragment_count() {
+        // Need to use in-memory for accurate IOPS tracking.
+        use crate::utils::test::IoTrackingStore;
+
+        let session = Arc::new(Session::default());
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let store_params = ObjectStoreParams {
+            object_store_wrapper: Some(io_stats_wrapper),
+            ..Default::default()
+        };
+        let mut dataset = gen()
+            .col("i", array::step::<Int32Type>())
+            .into_dataset_with_params(
+                "memory://fragment_count",
+                FragmentCount::from(20),
+                FragmentRowCount::from(10),
+                Some(WriteParams {
+                    max_rows_per_file: 10,
+                    store_params: Some(store_params.clone()),
+                    session: Some(session.clone()),
                     ..Default::default()
                 }),
-                session: Some(session),
-                ..Default::default()
-            })
-            .load()
+            )
             .await
             .unwrap();
+        // Removes the first fragment
+        dataset.delete("i < 10").await.unwrap();
 
-        let get_iops = || io_stats.lock().unwrap().read_iops;
+        let builder =
+            DatasetBuilder::from_uri("memory://fragment_count").with_read_params(ReadParams {
+                store_options: Some(store_params),
+                session: Some(session),
+                ..Default::default()
+            });
+        *io_stats.lock().unwrap() = Default::default();
+        assert_eq!(builder.clone().fragment_count().await.unwrap(), 19);
 
-        // There should be only two IOPS:
-        // 1. List _versions directory to get the latest manifest location
-        // 2. Read the manifest file. (The manifest is small enough to be read in one go.
-        //    Larger manifests would result in more IOPS.)
-        assert_eq!(get_iops(), 2);
+        // Only the _versions directory is listed and the manifest is read
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert_eq!(stats.metadata_iops, 1);
+        assert_eq!(stats.read_iops, 1);
+        assert!(
+            stats
+                .requests
+                .iter()
+                .all(|request| request.path.as_ref().contains("_versions")),
+            "{}",
+            stats
+        );
+
+        assert_eq!(
+            builder
+                .clone()
+                .with_version(1)
+                .fragment_count()
+                .await
+                .unwrap(),
+            20
+        );
+        let serialized =
+            lance_table::format::pb::Manifest::from(dataset.manifest.as_ref()).encode_to_vec();
+        assert_eq!(
+            builder
+                .with_serialized_manifest_header(&serialized)
+                .unwrap()
+                .fragment_count()
+                .await
+                .unwrap(),
+            19
+        );
     }
 
     #[rstest]
@@ -6148,6 +8592,76 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_scan_field_without_data_in_fragment() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let dataset = gen()
+            .col("a", array::step::<Int32Type>())
+            .col("b", array::step::<Int32Type>())
+            .into_dataset(test_uri, FragmentCount::from(2), FragmentRowCount::from(5))
+            .await
+            .unwrap();
+
+        // The second fragment's data file lists `b` but has no column for it, as if
+        // `b` was added to the other fragment's files later
+        let b_id = dataset.schema().field("b").unwrap().id;
+        let mut fragments = dataset
+            .get_fragments()
+            .into_iter()
+            .map(|fragment| fragment.metadata)
+            .collect::<Vec<_>>();
+        for data_file in &mut fragments[1].files {
+            for (field_id, column_index) in data_file
+                .fields
+                .iter()
+                .zip(data_file.column_indices.iter_mut())
+            {
+                if *field_id == b_id {
+                    *column_index = -1;
+                }
+            }
+        }
+        let schema = dataset.schema().clone();
+        let dataset = Dataset::commit(
+            WriteDestination::Dataset(Arc::new(dataset)),
+            Operation::Merge { fragments, schema },
+            Some(1),
+            None,
+            None,
+            Arc::new(Default::default()),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(
+            batch["a"].as_primitive::<Int32Type>(),
+            &Int32Array::from_iter_values(0..10)
+        );
+        let expected_b = Int32Array::from_iter((0..10).map(|i| (i < 5).then_some(i)));
+        assert_eq!(batch["b"].as_primitive::<Int32Type>(), &expected_b);
+
+        // Only the missing field
+        let batch = dataset
+            .scan()
+            .project(&["b"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch["b"].as_primitive::<Int32Type>(), &expected_b);
+
+        assert_eq!(
+            dataset
+                .count_rows(Some("b IS NULL".to_string()))
+                .await
+                .unwrap(),
+            5
+        );
+    }
+
     #[tokio::test]
     async fn test_datafile_partial_replacement() {
         let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
@@ -1,28 +1,145 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use super::refs::{Ref, Tags};
-use super::{ReadParams, WriteParams, DEFAULT_INDEX_CACHE_SIZE, DEFAULT_METADATA_CACHE_SIZE};
+use super::{
+    DatasetReadOptions, ReadParams, WriteMode, WriteParams, DEFAULT_INDEX_CACHE_SIZE,
+    DEFAULT_METADATA_CACHE_SIZE,
+};
+use arrow_array::RecordBatchIterator;
+use arrow_schema::SchemaRef;
+
 use crate::{
     error::{Error, Result},
     session::Session,
+    utils::temporal::{Clock, SystemClock},
     Dataset,
 };
+use lance_core::datatypes::{Schema, SchemaCompareOptions};
 use lance_core::utils::tracing::{DATASET_LOADING_EVENT, TRACE_DATASET_EVENTS};
 use lance_file::datatypes::populate_schema_dictionary;
+use lance_index::DatasetIndexExt;
 use lance_io::object_store::{
-    ObjectStore, ObjectStoreParams, StorageOptions, DEFAULT_CLOUD_IO_PARALLELISM,
+    ChainedWrappingObjectStore, IoCounts, ObjectStore, ObjectStoreParams,
+    PathRewritingStoreWrapper, StorageOptions, WrappingObjectStore, DEFAULT_CLOUD_IO_PARALLELISM,
 };
 use lance_table::{
-    format::Manifest,
-    io::commit::{commit_handler_from_url, CommitHandler},
+    format::{decode_fragment_count, pb, Manifest, ManifestHeader},
+    io::commit::{
+        commit_handler_from_url, CommitHandler, ConflictCallback, ConflictCallbackCommitHandler,
+        ConflictSummary, ManifestLocation,
+    },
 };
-use object_store::{aws::AwsCredentialProvider, path::Path, DynObjectStore};
+use object_store::{aws::AwsCredentialProvider, path::Path, DynObjectStore, GetOptions};
 use prost::Message;
 use snafu::location;
-use tracing::{info, instrument};
+use tokio::{sync::OnceCell, task::JoinHandle};
+use tracing::{field, info, instrument, Span};
 use url::Url;
+use uuid::Uuid;
+
+/// Identifies the manifest a [`Dataset`] was loaded from.
+///
+/// Returned by [`DatasetBuilder::load_with_token`] and checked with
+/// [`DatasetBuilder::is_current`] to tell if a newer version was committed since,
+/// without reading the manifest again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyToken {
+    /// The version of the manifest
+    pub version: u64,
+    /// The e-tag of the manifest file, if the object store reports one
+    ///
+    /// Manifests are immutable so a different e-tag for the same version means the
+    /// dataset was deleted and re-created.
+    pub e_tag: Option<String>,
+}
+
+/// Metrics about a successful [`DatasetBuilder::load`], see
+/// [`DatasetBuilder::with_metrics_callback`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenMetrics {
+    /// The URI the dataset was loaded from
+    pub uri: String,
+    /// The version of the loaded dataset
+    pub version: u64,
+    /// How long `load` took
+    pub latency: Duration,
+    /// The reads made while loading, if the object store wrapper tracks IO (see
+    /// [`WrappingObjectStore::io_counts`])
+    pub io: Option<IoCounts>,
+}
+
+/// A callback that receives the [`OpenMetrics`] of a load.
+#[derive(Clone)]
+struct MetricsCallback(Arc<dyn Fn(OpenMetrics) + Send + Sync>);
+
+impl std::fmt::Debug for MetricsCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetricsCallback").finish()
+    }
+}
+
+/// A manifest, and its location, being loaded on a background task.
+///
+/// Clones share the task, only the first to be loaded gets its result.
+#[derive(Clone)]
+struct PrefetchedManifest(Arc<Mutex<Option<JoinHandle<Result<(Manifest, ManifestLocation)>>>>>);
+
+impl PrefetchedManifest {
+    /// Wait for the prefetch, `None` if a clone already took it.
+    async fn join(self) -> Result<Option<(Manifest, ManifestLocation)>> {
+        let handle = self.0.lock().unwrap().take();
+        match handle {
+            Some(handle) => Ok(Some(handle.await??)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl std::fmt::Debug for PrefetchedManifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrefetchedManifest").finish_non_exhaustive()
+    }
+}
+
+/// A [`Dataset`] that is loaded on first use, see [`DatasetBuilder::lazy`].
+#[derive(Debug)]
+pub struct LazyDataset {
+    builder: DatasetBuilder,
+    dataset: OnceCell<Arc<Dataset>>,
+}
+
+impl LazyDataset {
+    /// The dataset, loaded by the first call
+    ///
+    /// Concurrent first calls share a single load.  If the load fails the error is
+    /// returned and the next call tries to load the dataset again.
+    pub async fn get(&self) -> Result<Arc<Dataset>> {
+        self.dataset
+            .get_or_try_init(|| async {
+                let dataset = Box::pin(self.builder.clone().load()).await?;
+                Ok::<_, Error>(Arc::new(dataset))
+            })
+            .await
+            .cloned()
+    }
+
+    /// Whether the dataset has been loaded
+    pub fn is_loaded(&self) -> bool {
+        self.dataset.initialized()
+    }
+
+    /// The URI of the dataset
+    pub fn uri(&self) -> &str {
+        &self.builder.table_uri
+    }
+}
+
 /// builder for loading a [`Dataset`].
 #[derive(Debug, Clone)]
 pub struct DatasetBuilder {
@@ -31,13 +148,60 @@ pub struct DatasetBuilder {
     /// Metadata cache size for the fragment metadata. If it is zero, metadata
     /// cache is disabled.
     metadata_cache_size_bytes: usize,
+    /// Hard limit on the bytes held by the index and metadata caches together.
+    hard_memory_limit: Option<usize>,
     /// Optional pre-loaded manifest to avoid loading it again.
     manifest: Option<Manifest>,
+    /// Location of `manifest`, trusted as is instead of being resolved on load.
+    manifest_location: Option<ManifestLocation>,
+    /// Check on load that the manifest file of `manifest` exists, see [`Self::with_manifest`].
+    validate_manifest_location: bool,
+    /// Header of a serialized manifest whose fragments have not been decoded yet.
+    manifest_header: Option<ManifestHeader>,
+    /// The serialized manifest for `manifest_header`, decoded fully on load.
+    serialized_manifest: Option<Vec<u8>>,
+    /// The manifest being loaded in the background by [`Self::prefetch`].
+    prefetched_manifest: Option<PrefetchedManifest>,
+    /// A manifest loaded earlier, reused by [`Self::load`] if it was not modified since.
+    cached_manifest: Option<(Manifest, ConsistencyToken)>,
     session: Option<Arc<Session>>,
+    pinned_indices: Vec<Uuid>,
+    /// Load the index metadata into the session cache on open, see [`Self::with_eager_index_load`].
+    eager_index_load: bool,
+    skip_dictionary_population: bool,
+    default_projection: Option<Vec<String>>,
+    /// Read deleted rows, see [`Self::with_ignore_deletions`].
+    ignore_deletions: bool,
+    /// The only columns of the loaded dataset, see [`Self::with_schema_projection`].
+    schema_projection: Option<Vec<String>>,
+    /// The schema the loaded manifest must be compatible with.
+    expected_schema: Option<Schema>,
+    /// Allow the loaded schema to have top-level columns not in `expected_schema`.
+    allow_extra_columns: bool,
+    /// Check that the referenced files exist after loading, see [`Dataset::validate_integrity`].
+    validate_integrity: bool,
+    /// Check the field ids of the manifest after loading, see [`Self::with_field_id_check`].
+    validate_field_ids: bool,
+    /// The source of the current time for the dataset, the system clock if not set.
+    clock: Option<Arc<dyn Clock>>,
     commit_handler: Option<Arc<dyn CommitHandler>>,
+    commit_handler_name: Option<String>,
+    on_conflict: Option<ConflictCallback>,
+    /// Called at the end of a successful load, see [`Self::with_metrics_callback`].
+    metrics_callback: Option<MetricsCallback>,
     options: ObjectStoreParams,
-    version: Option<Ref>,
+    /// Rewrites the paths of all requests, see [`Self::with_path_rewriter`].
+    path_rewriter: Option<Arc<PathRewritingStoreWrapper>>,
+    /// Overrides the `download_retry_count` storage option.
+    download_retry_count: Option<u32>,
+    version: Option<u64>,
+    tag: Option<String>,
+    /// Extra `key=value` pairs recorded on the span of [`Self::load`].
+    trace_attributes: Vec<(String, String)>,
     table_uri: String,
+    /// URIs tried, in order, if the dataset can't be loaded from `table_uri`, each with
+    /// its own object store options if they differ from `options`.
+    fallback_uris: Vec<(String, Option<ObjectStoreParams>)>,
 }
 
 impl DatasetBuilder {
@@ -45,12 +209,38 @@ impl DatasetBuilder {
         Self {
             index_cache_size_bytes: DEFAULT_INDEX_CACHE_SIZE,
             metadata_cache_size_bytes: DEFAULT_METADATA_CACHE_SIZE,
+            hard_memory_limit: None,
             table_uri: table_uri.as_ref().to_string(),
+            fallback_uris: Vec::new(),
             options: ObjectStoreParams::default(),
+            path_rewriter: None,
+            download_retry_count: None,
             commit_handler: None,
+            commit_handler_name: None,
+            on_conflict: None,
+            metrics_callback: None,
             session: None,
+            pinned_indices: Vec::new(),
+            eager_index_load: false,
+            skip_dictionary_population: false,
+            default_projection: None,
+            ignore_deletions: false,
+            schema_projection: None,
+            expected_schema: None,
+            allow_extra_columns: false,
+            validate_integrity: false,
+            validate_field_ids: false,
+            clock: None,
             version: None,
+            tag: None,
+            trace_attributes: Vec::new(),
             manifest: None,
+            manifest_location: None,
+            validate_manifest_location: false,
+            manifest_header: None,
+            serialized_manifest: None,
+            prefetched_manifest: None,
+            cached_manifest: None,
         }
     }
 }
@@ -79,6 +269,22 @@ impl DatasetBuilder {
         self
     }
 
+    /// Cap the memory held by the index and metadata caches, together, at `bytes`.
+    ///
+    /// The cache sizes are soft limits: when a cache is full the least recently used
+    /// entries are evicted to make room.  A hard limit never evicts.  Instead, loading
+    /// an index that would take the caches over the limit fails with
+    /// [`Error::OutOfMemory`], so hot entries stay cached and the memory used by the
+    /// dataset is bounded.  Entries that are cached opportunistically, such as
+    /// manifests, are simply not cached once the limit is reached.
+    ///
+    /// This replaces the cache sizes.  It is ignored if a session is given by
+    /// [`Self::with_session`].
+    pub fn with_hard_memory_limit(mut self, bytes: usize) -> Self {
+        self.hard_memory_limit = Some(bytes);
+        self
+    }
+
     /// Set the cache size for the file metadata. Set to zero to disable this cache.
     #[deprecated(
         since = "0.30.0",
@@ -100,14 +306,18 @@ impl DatasetBuilder {
     }
 
     /// Sets `version` for the builder using a version number
+    ///
+    /// This cannot be combined with [`Self::with_tag`], `load` fails if both are set.
     pub fn with_version(mut self, version: u64) -> Self {
-        self.version = Some(Ref::from(version));
+        self.version = Some(version);
         self
     }
 
     /// Sets `version` for the builder using a tag
+    ///
+    /// This cannot be combined with [`Self::with_version`], `load` fails if both are set.
     pub fn with_tag(mut self, tag: &str) -> Self {
-        self.version = Some(Ref::from(tag));
+        self.tag = Some(tag.to_string());
         self
     }
 
@@ -116,6 +326,26 @@ impl DatasetBuilder {
         self
     }
 
+    /// Use `<dyn CommitHandler>::local_rename()` regardless of the URL of the dataset
+    ///
+    /// This is a shortcut for [`Self::with_commit_handler`] with that handler and is
+    /// mostly useful for tests that should not depend on the commit handler inferred from the URL.
+    pub fn with_local_commit_handler(self) -> Self {
+        self.with_commit_handler(<dyn CommitHandler>::local_rename())
+    }
+
+    /// Use the commit handler registered under `name` in the session
+    ///
+    /// The handler is looked up in [`Session::commit_handler_registry`] of the session
+    /// given by [`Self::with_session`] when the dataset is loaded.  If no handler is
+    /// registered under `name`, a warning listing the registered names is logged and the
+    /// handler inferred from the URL is used instead.  A handler given directly, e.g. by
+    /// [`Self::with_commit_handler`], takes precedence.
+    pub fn with_commit_handler_named(mut self, name: impl AsRef<str>) -> Self {
+        self.commit_handler_name = Some(name.as_ref().to_string());
+        self
+    }
+
     /// Sets the s3 credentials refresh.
     /// This only applies to s3 storage.
     pub fn with_s3_credentials_refresh_offset(mut self, offset: Duration) -> Self {
@@ -144,15 +374,107 @@ impl DatasetBuilder {
         self
     }
 
+    /// Call `callback` when a write to the loaded dataset fails with a commit conflict.
+    ///
+    /// The callback receives a summary of the concurrent transaction that
+    /// conflicted with the write, which is useful for logging.  It wraps the
+    /// commit handler, so it applies to every commit made through the dataset.
+    pub fn with_on_conflict(
+        mut self,
+        callback: impl Fn(&ConflictSummary) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_conflict = Some(ConflictCallback::new(callback));
+        self
+    }
+
+    /// Call `callback` with the [`OpenMetrics`] of the load when it succeeds.
+    ///
+    /// The metrics include the latency of `load` and, if the object store wrapper
+    /// tracks IO (see [`WrappingObjectStore::io_counts`]), the number of reads and
+    /// bytes read.  The IO counts are the difference of the wrapper's totals before
+    /// and after the load, so they include any concurrent requests made through the
+    /// same wrapper.  This reports basic metrics without having to subscribe to the
+    /// `tracing` events of the load.
+    pub fn with_metrics_callback(
+        mut self,
+        callback: impl Fn(OpenMetrics) + Send + Sync + 'static,
+    ) -> Self {
+        self.metrics_callback = Some(MetricsCallback(Arc::new(callback)));
+        self
+    }
+
     /// Use a serialized manifest instead of loading it from the object store.
     ///
-    /// This is common when transferring a dataset across IPC boundaries.
-    pub fn with_serialized_manifest(mut self, manifest: &[u8]) -> Result<Self> {
-        let manifest = Manifest::try_from(lance_table::format::pb::Manifest::decode(manifest)?)?;
+    /// This is common when transferring a dataset across IPC boundaries.  Unlike
+    /// [`Self::with_manifest`], the manifest file is not checked to still exist, so
+    /// loading only resolves the location of the manifest's version.
+    pub fn with_serialized_manifest(self, manifest: &[u8]) -> Result<Self> {
+        let manifest = Manifest::try_from(pb::Manifest::decode(manifest)?)?;
+        let mut builder = self.with_manifest(manifest);
+        builder.validate_manifest_location = false;
+        Ok(builder)
+    }
+
+    /// Use a manifest already in memory instead of loading it from the object store.
+    ///
+    /// The manifest file for `manifest.version` must still exist, loading fails
+    /// otherwise.  Use [`Self::with_manifest_and_location`] if the location is
+    /// already known.
+    pub fn with_manifest(mut self, manifest: Manifest) -> Self {
         self.manifest = Some(manifest);
+        self.manifest_location = None;
+        self.validate_manifest_location = true;
+        self.manifest_header = None;
+        self.serialized_manifest = None;
+        self
+    }
+
+    /// Use a manifest already in memory, along with the location it was read from.
+    ///
+    /// The location is trusted as is, so loading does not touch the object store
+    /// to find the manifest file.
+    pub fn with_manifest_and_location(
+        mut self,
+        manifest: Manifest,
+        location: ManifestLocation,
+    ) -> Self {
+        self = self.with_manifest(manifest);
+        self.manifest_location = Some(location);
+        self
+    }
+
+    /// Reuse a manifest loaded earlier if it is still the manifest to load.
+    ///
+    /// `token` identifies `manifest`, see [`Self::load_with_token`].  If the version
+    /// to load is the same as the token's then [`Self::load`] reads the manifest
+    /// conditionally (`If-None-Match` with the token's e-tag).  If the store reports
+    /// it was not modified then `manifest` is used and no manifest bytes are
+    /// transferred, otherwise the manifest is decoded from the response.  Tokens
+    /// without an e-tag cannot be revalidated and the manifest is always loaded.
+    pub fn with_cached_manifest(mut self, manifest: Manifest, token: ConsistencyToken) -> Self {
+        self.cached_manifest = Some((manifest, token));
+        self
+    }
+
+    /// Use a serialized manifest, decoding only the schema, version, and timestamp
+    /// up front.
+    ///
+    /// The fragments are decoded when the dataset is loaded.  This makes
+    /// [`Self::manifest_header`] cheap for manifests with many fragments.
+    pub fn with_serialized_manifest_header(mut self, manifest: &[u8]) -> Result<Self> {
+        self.manifest_header = Some(ManifestHeader::decode(manifest)?);
+        self.serialized_manifest = Some(manifest.to_vec());
+        self.manifest = None;
+        self.manifest_location = None;
+        self.validate_manifest_location = false;
         Ok(self)
     }
 
+    /// The header given by [`Self::with_serialized_manifest_header`], if any.
+    pub fn manifest_header(&self) -> Option<&ManifestHeader> {
+        self.manifest_header.as_ref()
+    }
+
     /// Set options used to initialize storage backend
     ///
     /// Options may be passed in the HashMap or set as environment variables. See documentation of
@@ -180,6 +502,34 @@ impl DatasetBuilder {
         self
     }
 
+    /// Set the number of times a failed download is retried.
+    ///
+    /// This takes precedence over a `download_retry_count` storage option, no matter
+    /// which is set first.  Lance retries reads from cloud storage that fail while the
+    /// response body is streamed, which the object store client does not retry.  The
+    /// client's own retry policy (`client_max_retries` and `client_retry_timeout`)
+    /// still applies to every attempt, so a read can make up to
+    /// `(download_retry_count + 1) * (client_max_retries + 1)` requests.
+    pub fn with_download_retry_count(mut self, count: u32) -> Self {
+        self.download_retry_count = Some(count);
+        self
+    }
+
+    /// Rewrite the path of every request before it reaches the object store.
+    ///
+    /// This can redirect part of a dataset to a new location, e.g. to test a migration
+    /// that moves the data files to a different prefix, without changing the manifest.
+    /// Reads and writes both use the rewritten paths.  The rewrite is applied after
+    /// (outside of) any `object_store_wrapper` so that wrapper sees the rewritten paths.
+    /// Listing results are returned with the paths of the target store.
+    pub fn with_path_rewriter(
+        mut self,
+        rewrite: impl Fn(&Path) -> Path + Send + Sync + 'static,
+    ) -> Self {
+        self.path_rewriter = Some(Arc::new(PathRewritingStoreWrapper::new(rewrite)));
+        self
+    }
+
     /// Set options based on [ReadParams].
     pub fn with_read_params(mut self, read_params: ReadParams) -> Self {
         self = self
@@ -224,14 +574,245 @@ impl DatasetBuilder {
         self
     }
 
+    /// Never evict the index with the given UUID from the index cache.
+    ///
+    /// Pinned indices are cached in addition to `index_cache_size_bytes`; they
+    /// do not count towards it and are cached even if the index cache is
+    /// disabled.  The pin is applied to the session, so it also applies to any
+    /// other dataset sharing a session given by [`Self::with_session`].
+    pub fn with_pinned_index(mut self, uuid: Uuid) -> Self {
+        self.pinned_indices.push(uuid);
+        self
+    }
+
+    /// Load the metadata of the dataset's indices into the session cache when opening.
+    ///
+    /// By default the index metadata is read from the manifest file by the first
+    /// query (or other operation) that needs it.  With this set, [`Self::load`]
+    /// reads it so the first query doesn't pay for it.  This costs an extra read of
+    /// the manifest file at open time, even if the dataset has no indices or the
+    /// indices are never used.  The metadata is cached in the session, so the cost
+    /// is only paid once for datasets sharing a session.
+    pub fn with_eager_index_load(mut self) -> Self {
+        self.eager_index_load = true;
+        self
+    }
+
+    /// Read the current time from `clock` instead of the system clock.
+    ///
+    /// The clock is used by the loaded dataset (and the datasets created by its
+    /// commits), e.g. for the timestamps of new versions and the cutoff of
+    /// [`Dataset::cleanup_old_versions`].  This makes time-dependent behavior
+    /// reproducible in tests without sleeping.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Do not load the dictionaries of dictionary fields in legacy format datasets.
+    ///
+    /// This skips reading the dictionary values when opening the dataset, which is
+    /// useful when only the metadata is needed, e.g. when listing datasets in a
+    /// catalog.  The dictionary fields of the schema are left unpopulated and reading
+    /// those columns from the returned dataset will fail.
+    pub fn with_skip_dictionary_population(mut self) -> Self {
+        self.skip_dictionary_population = true;
+        self
+    }
+
+    /// Scan only these columns unless a scan sets its own projection.
+    ///
+    /// The columns are stored on the loaded [`Dataset`] and used as the initial
+    /// projection of [`Dataset::scan`].  `load` fails if any of the columns are
+    /// not in the dataset schema.
+    pub fn with_default_projection(mut self, columns: Vec<String>) -> Self {
+        self.default_projection = Some(columns);
+        self
+    }
+
+    /// Load the dataset with only these columns in its schema.
+    ///
+    /// The other columns are hidden entirely: they are not in [`Dataset::schema`], scans
+    /// don't return them and projecting them fails as if they didn't exist.  Unlike
+    /// [`Self::with_default_projection`] this can't be overridden by a scan.  `load` fails
+    /// if any of the columns are not in the dataset schema.
+    ///
+    /// The loaded dataset can't be modified since a new version based on the projected
+    /// schema would drop the hidden columns.
+    pub fn with_schema_projection(mut self, columns: Vec<String>) -> Self {
+        self.schema_projection = Some(columns);
+        self
+    }
+
+    /// Read the deleted rows of the dataset.
+    ///
+    /// Scans, takes, and row counts of the loaded [`Dataset`] ignore the deletion files and
+    /// return every physical row, **including rows that have been deleted**.  This is meant
+    /// for inspecting the raw fragment data and recovering accidentally deleted rows.  The
+    /// loaded dataset can't be modified since any changes would be based on the deleted rows.
+    ///
+    /// This is off by default.
+    pub fn with_ignore_deletions(mut self) -> Self {
+        self.ignore_deletions = true;
+        self
+    }
+
+    /// Fail to load unless the dataset schema is compatible with `schema`.
+    ///
+    /// The schemas are compatible if they have the same fields, in the same order,
+    /// with the same names, data types, and nullability.  Field ids and metadata are
+    /// not compared, so a schema built independently of the dataset (e.g. from an
+    /// Arrow schema) can be used.  Use [`Self::with_allow_extra_columns`] to also
+    /// accept datasets with additional top-level columns.
+    ///
+    /// If the schemas are not compatible `load` returns [`Error::SchemaMismatch`]
+    /// listing the differences.
+    pub fn with_expected_schema(mut self, schema: &Schema) -> Self {
+        self.expected_schema = Some(schema.clone());
+        self
+    }
+
+    /// Accept datasets that have top-level columns not in the expected schema.
+    ///
+    /// The columns of the expected schema must still be in the dataset, with the
+    /// same data types and nullability, but they may be in any order.  This has no
+    /// effect unless [`Self::with_expected_schema`] is set.
+    pub fn with_allow_extra_columns(mut self) -> Self {
+        self.allow_extra_columns = true;
+        self
+    }
+
+    /// Record these attributes on the tracing span of [`Self::load`].
+    ///
+    /// Use this to correlate a dataset open with the request that caused it, e.g. with
+    /// a tenant or request id.  The attributes are recorded, as `key=value` pairs
+    /// separated by commas, in the `trace_attributes` field of the `load` span so they
+    /// are exported along with the span (e.g. as an OpenTelemetry span attribute).
+    pub fn with_trace_attributes(mut self, attributes: Vec<(String, String)>) -> Self {
+        self.trace_attributes = attributes;
+        self
+    }
+
+    /// Fail to load if any file referenced by the dataset is missing or has the wrong size.
+    ///
+    /// After the manifest is loaded every referenced file is checked with
+    /// [`Dataset::validate_integrity`].  This makes a `head` request per file so it
+    /// is meant for occasional checks, e.g. after copying a dataset, and not for
+    /// every open.  If the check fails `load` returns [`Error::CorruptFile`].
+    pub fn with_integrity_check(mut self) -> Self {
+        self.validate_integrity = true;
+        self
+    }
+
+    /// Fail to load if the field ids of the manifest are inconsistent.
+    ///
+    /// The check fails if a field id is used by more than one field of the schema, if a
+    /// field of the schema is stored in more than one data file of a fragment, or if a
+    /// data file only has fields that are not in the schema.  Data files may still
+    /// reference the ids of dropped columns, as long as they also store a column of the
+    /// schema.  Any of these would make scans read the wrong column, so they are reported
+    /// as [`Error::CorruptFile`] for the manifest, the error used for all corrupt data.
+    /// The check only looks at the manifest and makes no requests.
+    pub fn with_field_id_check(mut self) -> Self {
+        self.validate_field_ids = true;
+        self
+    }
+
+    /// Load the dataset from one of `uris` if it can't be loaded from the table URI
+    ///
+    /// `load` tries the table URI and then each fallback, in order, and returns the first
+    /// dataset that loads.  Only errors that mean the dataset could not be read, i.e. not
+    /// found and IO errors, move on to the next URI.  Any other error (e.g. a schema that
+    /// does not match [`Self::with_expected_schema`]) is returned as is.  If no URI loads,
+    /// the error of the table URI is returned.  This is meant for replicas of the dataset,
+    /// e.g. in another region.
+    ///
+    /// The fallbacks use the same object store options (e.g. storage options) as the
+    /// table URI, see [`Self::with_fallback_uri_and_options`] to override them.
+    pub fn with_fallback_uris(mut self, uris: Vec<String>) -> Self {
+        self.fallback_uris
+            .extend(uris.into_iter().map(|uri| (uri, None)));
+        self
+    }
+
+    /// Like [`Self::with_fallback_uris`] but the fallback uses its own object store options
+    pub fn with_fallback_uri_and_options(
+        mut self,
+        uri: impl AsRef<str>,
+        options: ObjectStoreParams,
+    ) -> Self {
+        self.fallback_uris
+            .push((uri.as_ref().to_string(), Some(options)));
+        self
+    }
+
+    /// Append a relative path to the table URI
+    ///
+    /// This is useful when many tables are stored under a common root, e.g.
+    /// `DatasetBuilder::from_uri("s3://bucket/tables").with_table_subpath("my_table")`
+    /// loads `s3://bucket/tables/my_table`.  Leading and trailing slashes on the
+    /// subpath, and a trailing slash on the root, are handled so the result never
+    /// has a doubled or missing separator.
+    pub fn with_table_subpath(mut self, subpath: impl AsRef<str>) -> Self {
+        self.table_uri = join_table_subpath(&self.table_uri, subpath.as_ref());
+        self
+    }
+
     /// Build a lance object store for the given config
     pub async fn build_object_store(
-        self,
+        mut self,
     ) -> Result<(Arc<ObjectStore>, Path, Arc<dyn CommitHandler>)> {
-        let commit_handler = match self.commit_handler {
-            Some(commit_handler) => Ok(commit_handler),
-            None => commit_handler_from_url(&self.table_uri, &Some(self.options.clone())).await,
+        if let Some(count) = self.download_retry_count {
+            let storage_options = self
+                .options
+                .storage_options
+                .get_or_insert_with(HashMap::new);
+            storage_options.retain(|key, _| !key.eq_ignore_ascii_case("download_retry_count"));
+            storage_options.insert("download_retry_count".to_string(), count.to_string());
+        }
+        if let Some(path_rewriter) = self.path_rewriter.take() {
+            let path_rewriter = path_rewriter as Arc<dyn WrappingObjectStore>;
+            self.options.object_store_wrapper = Some(match self.options.object_store_wrapper {
+                Some(wrapper) => Arc::new(ChainedWrappingObjectStore::new(vec![
+                    wrapper,
+                    path_rewriter,
+                ])),
+                None => path_rewriter,
+            });
+        }
+
+        let commit_handler = match (self.commit_handler, &self.commit_handler_name) {
+            (Some(commit_handler), _) => Ok(commit_handler),
+            (None, Some(name)) => {
+                let registry = self
+                    .session
+                    .as_ref()
+                    .map(|s| s.commit_handler_registry())
+                    .unwrap_or_default();
+                match registry.get(name) {
+                    Some(commit_handler) => Ok(commit_handler),
+                    None => {
+                        log::warn!(
+                            "No commit handler is registered with the name '{}', the registered names are: {:?}, falling back to the commit handler for {}",
+                            name,
+                            registry.names(),
+                            self.table_uri
+                        );
+                        commit_handler_from_url(&self.table_uri, &Some(self.options.clone())).await
+                    }
+                }
+            }
+            (None, None) => {
+                commit_handler_from_url(&self.table_uri, &Some(self.options.clone())).await
+            }
         }?;
+        let commit_handler = match self.on_conflict {
+            Some(callback) => {
+                Arc::new(ConflictCallbackCommitHandler::new(commit_handler, callback))
+                    as Arc<dyn CommitHandler>
+            }
+            None => commit_handler,
+        };
 
         let storage_options = self
             .options
@@ -277,79 +858,331 @@ impl DatasetBuilder {
         }
     }
 
-    #[instrument(skip_all)]
-    pub async fn load(mut self) -> Result<Dataset> {
-        info!(target: TRACE_DATASET_EVENTS, event=DATASET_LOADING_EVENT, uri=self.table_uri);
-        let session = match self.session.as_ref() {
-            Some(session) => session.clone(),
-            None => Arc::new(Session::new(
+    // The version to load, given either by number or by tag
+    fn version_ref(&self) -> Result<Option<Ref>> {
+        match (self.version, self.tag.as_ref()) {
+            (Some(version), Some(tag)) => Err(Error::InvalidInput {
+                source: format!(
+                    "ambiguous version specification: both version {} and tag '{}' were set",
+                    version, tag
+                )
+                .into(),
+                location: location!(),
+            }),
+            (Some(version), None) => Ok(Some(Ref::from(version))),
+            (None, Some(tag)) => Ok(Some(Ref::from(tag.as_str()))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Count the fragments of the dataset without loading it
+    ///
+    /// Only the manifest is read and its fragments are counted without being decoded,
+    /// which is much cheaper than `load` followed by [`Dataset::get_fragments`] for
+    /// datasets with many fragments.  The version given by [`Self::with_version`] or
+    /// [`Self::with_tag`] is counted, the latest version otherwise.
+    pub async fn fragment_count(self) -> Result<usize> {
+        if let Some(manifest) = &self.manifest {
+            return Ok(manifest.fragments.len());
+        }
+        if let Some(serialized) = &self.serialized_manifest {
+            return decode_fragment_count(serialized);
+        }
+
+        let version_ref = self.version_ref()?;
+        let (object_store, base_path, commit_handler) = self.build_object_store().await?;
+        let manifest_location =
+            resolve_manifest_location(&object_store, &base_path, &commit_handler, version_ref)
+                .await?;
+        Dataset::count_manifest_fragments(&object_store, &manifest_location).await
+    }
+
+    /// Start loading the manifest in the background.
+    ///
+    /// The manifest location is resolved and the manifest is read and decoded on a
+    /// spawned task, so this must be called within a tokio runtime.  [`Self::load`]
+    /// then waits for that task instead of reading the manifest again, and returns
+    /// any error it hit.  This overlaps the latency of opening the dataset with other
+    /// setup work.
+    ///
+    /// The version, object store, and session must be configured before calling this,
+    /// changes to them afterwards do not apply to the prefetched manifest.  This does
+    /// nothing if a manifest was already given with [`Self::with_manifest`] or
+    /// [`Self::with_serialized_manifest`].
+    pub fn prefetch(mut self) -> Self {
+        if self.manifest.is_some() || self.serialized_manifest.is_some() {
+            return self;
+        }
+        // The manifest is loaded with the session used by `load`
+        let session = self.session();
+        self.session = Some(session.clone());
+        let builder = self.clone();
+        let handle = tokio::spawn(async move {
+            let table_uri = builder.table_uri.clone();
+            let version_ref = builder.version_ref()?;
+            let (object_store, base_path, commit_handler) = builder.build_object_store().await?;
+            let location =
+                resolve_manifest_location(&object_store, &base_path, &commit_handler, version_ref)
+                    .await?;
+            // Dictionaries are populated by `load`, as for any manifest given up front
+            let manifest =
+                Dataset::load_manifest(&object_store, &location, &table_uri, &session, false)
+                    .await?;
+            Ok::<_, Error>((manifest, location))
+        });
+        self.prefetched_manifest = Some(PrefetchedManifest(Arc::new(Mutex::new(Some(handle)))));
+        self
+    }
+
+    /// Defer loading the dataset until it is first used.
+    ///
+    /// Nothing is read until [`LazyDataset::get`] is called, which loads the dataset as
+    /// [`Self::load`] would and caches it for later calls.  Errors, including a missing
+    /// dataset, are only returned then.  Unlike [`Self::prefetch`], which starts loading
+    /// right away, a lazy dataset that is never used costs no IO.
+    pub fn lazy(self) -> LazyDataset {
+        LazyDataset {
+            builder: self,
+            dataset: OnceCell::new(),
+        }
+    }
+
+    /// Load the dataset and a token identifying the manifest that was loaded
+    pub async fn load_with_token(self) -> Result<(Dataset, ConsistencyToken)> {
+        let dataset = self.load().await?;
+        let location = dataset.manifest_location();
+        let token = ConsistencyToken {
+            version: location.version,
+            e_tag: location.e_tag.clone(),
+        };
+        Ok((dataset, token))
+    }
+
+    /// Check if `token` still identifies the latest version of the dataset
+    ///
+    /// This only resolves the location of the latest manifest (a single listing
+    /// for most commit handlers), the manifest itself is not read.  The e-tags are
+    /// only compared if both the token and the latest manifest have one.  The
+    /// version and tag set on the builder are ignored.
+    pub async fn is_current(self, token: &ConsistencyToken) -> Result<bool> {
+        let (object_store, base_path, commit_handler) = self.build_object_store().await?;
+        let latest = commit_handler
+            .resolve_latest_location(&base_path, &object_store)
+            .await
+            .map_err(|e| Error::DatasetNotFound {
+                source: Box::new(e),
+                path: base_path.to_string(),
+                location: location!(),
+            })?;
+        let same_e_tag = match (&token.e_tag, &latest.e_tag) {
+            (Some(token_e_tag), Some(latest_e_tag)) => token_e_tag == latest_e_tag,
+            _ => true,
+        };
+        Ok(latest.version == token.version && same_e_tag)
+    }
+
+    // The session given to the builder, or a new one for its cache settings
+    fn session(&self) -> Arc<Session> {
+        match (self.session.as_ref(), self.hard_memory_limit) {
+            (Some(session), _) => session.clone(),
+            (None, Some(limit)) => {
+                Arc::new(Session::with_hard_memory_limit(limit, Default::default()))
+            }
+            (None, None) => Arc::new(Session::new(
                 self.index_cache_size_bytes,
                 self.metadata_cache_size_bytes,
                 Default::default(),
             )),
+        }
+    }
+
+    // Try the table URI and then each fallback URI
+    async fn load_with_fallbacks(mut self) -> Result<Dataset> {
+        let fallback_uris = std::mem::take(&mut self.fallback_uris);
+
+        // A manifest given to the builder was read from the table URI, the fallbacks load
+        // their own
+        let mut fallback_builder = self.clone();
+        fallback_builder.manifest = None;
+        fallback_builder.manifest_location = None;
+        fallback_builder.manifest_header = None;
+        fallback_builder.serialized_manifest = None;
+        fallback_builder.prefetched_manifest = None;
+
+        let table_uri = self.table_uri.clone();
+        let err = match Box::pin(self.load()).await {
+            Ok(dataset) => return Ok(dataset),
+            Err(err) if should_try_fallback(&err) => err,
+            Err(err) => return Err(err),
         };
+        let mut errors = vec![format!("{}: {}", table_uri, err)];
+        for (uri, options) in fallback_uris {
+            let mut builder = fallback_builder.clone();
+            builder.table_uri = uri.clone();
+            if let Some(options) = options {
+                builder.options = options;
+            }
+            match Box::pin(builder.load()).await {
+                Ok(dataset) => {
+                    log::warn!(
+                        "Loaded the dataset from the fallback URI {} after failing to load it from: {}",
+                        uri,
+                        errors.join("; ")
+                    );
+                    return Ok(dataset);
+                }
+                Err(fallback_err) if should_try_fallback(&fallback_err) => {
+                    errors.push(format!("{}: {}", uri, fallback_err))
+                }
+                Err(fallback_err) => return Err(fallback_err),
+            }
+        }
+        log::warn!(
+            "The dataset could not be loaded from any of its URIs: {}",
+            errors.join("; ")
+        );
+        Err(err)
+    }
+
+    #[instrument(skip_all, fields(trace_attributes = field::Empty))]
+    pub async fn load(mut self) -> Result<Dataset> {
+        if !self.fallback_uris.is_empty() {
+            return self.load_with_fallbacks().await;
+        }
+        if let Some(callback) = self.metrics_callback.take() {
+            let start = Instant::now();
+            let uri = self.table_uri.clone();
+            let io_wrapper = self.options.object_store_wrapper.clone();
+            let io_before = io_wrapper.as_ref().and_then(|wrapper| wrapper.io_counts());
+            let dataset = Box::pin(self.load()).await?;
+            let io_after = io_wrapper.as_ref().and_then(|wrapper| wrapper.io_counts());
+            (callback.0)(OpenMetrics {
+                uri,
+                version: dataset.version().version,
+                latency: start.elapsed(),
+                io: io_after
+                    .zip(io_before)
+                    .map(|(after, before)| after - before),
+            });
+            return Ok(dataset);
+        }
+        if !self.trace_attributes.is_empty() {
+            let attributes = self
+                .trace_attributes
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(",");
+            Span::current().record("trace_attributes", attributes.as_str());
+        }
+        info!(target: TRACE_DATASET_EVENTS, event=DATASET_LOADING_EVENT, uri=self.table_uri);
+        if let Some(prefetched) = self.prefetched_manifest.take() {
+            if let Some((manifest, location)) = prefetched.join().await? {
+                self = self.with_manifest_and_location(manifest, location);
+            }
+        }
+        let session = self.session();
+        for uuid in &self.pinned_indices {
+            session.pin_index(&uuid.to_string());
+        }
 
-        let mut version: Option<u64> = None;
-        let cloned_ref = self.version.clone();
+        let cloned_ref = self.version_ref()?;
         let table_uri = self.table_uri.clone();
+        let skip_dictionary_population = self.skip_dictionary_population;
+        let default_projection = self.default_projection.take();
+        let ignore_deletions = self.ignore_deletions;
+        let schema_projection = self.schema_projection.take();
+        let expected_schema = self.expected_schema.take();
+        let allow_extra_columns = self.allow_extra_columns;
+        let validate_integrity = self.validate_integrity;
+        let validate_field_ids = self.validate_field_ids;
+        let eager_index_load = self.eager_index_load;
+        let clock = self.clock.take();
+        let cached_manifest = self.cached_manifest.take();
 
         // How do we detect which version scheme is in use?
 
-        let manifest = self.manifest.take();
+        let manifest = match self.serialized_manifest.take() {
+            Some(serialized) => Some(Manifest::try_from(pb::Manifest::decode(
+                serialized.as_slice(),
+            )?)?),
+            None => self.manifest.take(),
+        };
+        let manifest_location = self.manifest_location.take();
+        let validate_manifest_location = self.validate_manifest_location;
 
         let (object_store, base_path, commit_handler) = self.build_object_store().await?;
 
-        if let Some(r) = cloned_ref {
-            version = match r {
-                Ref::Version(v) => Some(v),
-                Ref::Tag(t) => {
-                    let tags = Tags::new(
-                        object_store.clone(),
-                        commit_handler.clone(),
-                        base_path.clone(),
-                    );
-                    Some(tags.get_version(t.as_str()).await?)
-                }
-            }
-        }
-
         let (manifest, location) = if let Some(mut manifest) = manifest {
-            let location = commit_handler
-                .resolve_version_location(&base_path, manifest.version, &object_store.inner)
-                .await?;
-            if manifest.schema.has_dictionary_types() && manifest.should_use_legacy_format() {
+            let location = match manifest_location {
+                Some(location) => location,
+                None => {
+                    let location = commit_handler
+                        .resolve_version_location(&base_path, manifest.version, &object_store.inner)
+                        .await?;
+                    if validate_manifest_location {
+                        check_manifest_exists(&object_store, &location).await?;
+                    }
+                    location
+                }
+            };
+            if !skip_dictionary_population
+                && manifest.schema.has_dictionary_types()
+                && manifest.should_use_legacy_format()
+            {
                 let reader = object_store.open(&location.path).await?;
                 populate_schema_dictionary(&mut manifest.schema, reader.as_ref()).await?;
             }
             (manifest, location)
         } else {
-            let manifest_location = match version {
-                Some(version) => {
-                    commit_handler
-                        .resolve_version_location(&base_path, version, &object_store.inner)
-                        .await?
+            let manifest_location =
+                resolve_manifest_location(&object_store, &base_path, &commit_handler, cloned_ref)
+                    .await?;
+
+            let manifest = match cached_manifest {
+                Some((cached, token))
+                    if token.e_tag.is_some() && token.version == manifest_location.version =>
+                {
+                    read_manifest_if_modified(
+                        &object_store,
+                        &manifest_location,
+                        &token,
+                        &table_uri,
+                        session.as_ref(),
+                        !skip_dictionary_population,
+                    )
+                    .await?
+                    .unwrap_or(cached)
+                }
+                _ => {
+                    Dataset::load_manifest(
+                        &object_store,
+                        &manifest_location,
+                        &table_uri,
+                        session.as_ref(),
+                        !skip_dictionary_population,
+                    )
+                    .await?
                 }
-                None => commit_handler
-                    .resolve_latest_location(&base_path, &object_store)
-                    .await
-                    .map_err(|e| Error::DatasetNotFound {
-                        source: Box::new(e),
-                        path: base_path.to_string(),
-                        location: location!(),
-                    })?,
             };
-
-            let manifest = Dataset::load_manifest(
-                &object_store,
-                &manifest_location,
-                &table_uri,
-                session.as_ref(),
-            )
-            .await?;
             (manifest, manifest_location)
         };
 
-        Dataset::checkout_manifest(
+        if validate_field_ids {
+            if let Some(problem) = check_field_ids(&manifest) {
+                return Err(Error::corrupt_file(
+                    location.path.clone(),
+                    format!("the manifest has inconsistent field ids, {}", problem),
+                    location!(),
+                ));
+            }
+        }
+
+        if let Some(expected_schema) = &expected_schema {
+            check_expected_schema(&manifest.schema, expected_schema, allow_extra_columns)?;
+        }
+
+        let dataset = Dataset::checkout_manifest(
             object_store,
             base_path,
             table_uri,
@@ -357,6 +1190,258 @@ impl DatasetBuilder {
             location,
             session,
             commit_handler,
-        )
+            DatasetReadOptions {
+                default_projection,
+                ignore_deletions,
+                schema_projection,
+                clock: clock.unwrap_or_else(|| Arc::new(SystemClock)),
+            },
+        )?;
+
+        if eager_index_load {
+            dataset.load_indices().await?;
+        }
+
+        if validate_integrity {
+            let report = dataset.validate_integrity().await?;
+            let first_bad_file = report
+                .missing_files
+                .first()
+                .or_else(|| report.size_mismatches.first().map(|m| &m.path));
+            if let Some(path) = first_bad_file {
+                return Err(Error::corrupt_file(
+                    path.clone(),
+                    format!(
+                        "the dataset failed the integrity check, of the {} referenced files {} are missing and {} have the wrong size",
+                        report.num_files_checked,
+                        report.missing_files.len(),
+                        report.size_mismatches.len()
+                    ),
+                    location!(),
+                ));
+            }
+        }
+        Ok(dataset)
+    }
+
+    /// Open the dataset if it exists, otherwise create an empty dataset with
+    /// the given schema.
+    ///
+    /// The object store, commit handler, and session configured on the builder
+    /// are used to create the dataset unless `write_params` sets its own. The
+    /// write mode is always [`WriteMode::Create`].
+    ///
+    /// This is only safe for a single writer. If two callers race to create the
+    /// same dataset, one of them will fail unless the commit handler guarantees
+    /// that only one of the creations can succeed.
+    pub async fn open_or_create(
+        self,
+        schema: SchemaRef,
+        mut write_params: WriteParams,
+    ) -> Result<Dataset> {
+        let table_uri = self.table_uri.clone();
+        if write_params.store_params.is_none() {
+            write_params.store_params = Some(self.options.clone());
+        }
+        if write_params.commit_handler.is_none() {
+            write_params.commit_handler = self.commit_handler.clone();
+        }
+        if write_params.session.is_none() {
+            write_params.session = self.session.clone();
+        }
+
+        match self.load().await {
+            Err(Error::DatasetNotFound { .. }) => {
+                write_params.mode = WriteMode::Create;
+                let reader = RecordBatchIterator::new(vec![], schema);
+                Dataset::write(reader, table_uri.as_str(), Some(write_params)).await
+            }
+            result => result,
+        }
+    }
+}
+
+// Check the dataset schema against the schema given to `with_expected_schema`
+fn check_expected_schema(
+    schema: &Schema,
+    expected: &Schema,
+    allow_extra_columns: bool,
+) -> Result<()> {
+    let options = SchemaCompareOptions::default();
+    if allow_extra_columns {
+        // The projection follows the order of the expected schema
+        let columns = expected
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        schema
+            .project_or_drop(&columns)?
+            .check_compatible(expected, &options)
+    } else {
+        schema.check_compatible(expected, &options)
+    }
+}
+
+// Describe the field ids of `manifest` that would make scans read the wrong column, see
+// [`DatasetBuilder::with_field_id_check`]
+fn check_field_ids(manifest: &Manifest) -> Option<String> {
+    let mut schema_ids = HashSet::new();
+    let duplicate_ids = manifest
+        .schema
+        .fields_pre_order()
+        .filter(|field| !schema_ids.insert(field.id))
+        .map(|field| field.id)
+        .collect::<BTreeSet<_>>();
+    if !duplicate_ids.is_empty() {
+        return Some(format!(
+            "the schema has duplicate field ids {:?}",
+            duplicate_ids
+        ));
+    }
+
+    for fragment in manifest.fragments.iter() {
+        let mut stored_ids = HashSet::new();
+        let mut duplicate_ids = BTreeSet::new();
+        for file in &fragment.files {
+            // Negative ids are tombstones of fields that were rewritten to another file
+            let ids = file
+                .fields
+                .iter()
+                .copied()
+                .filter(|id| *id >= 0)
+                .collect::<Vec<_>>();
+            if !ids.is_empty() && ids.iter().all(|id| !schema_ids.contains(id)) {
+                return Some(format!(
+                    "data file {} of fragment {} has field ids {:?} that are not in the schema",
+                    file.path, fragment.id, ids
+                ));
+            }
+            duplicate_ids.extend(
+                ids.into_iter()
+                    .filter(|id| schema_ids.contains(id) && !stored_ids.insert(*id)),
+            );
+        }
+        if !duplicate_ids.is_empty() {
+            return Some(format!(
+                "fragment {} stores the field ids {:?} in more than one data file",
+                fragment.id, duplicate_ids
+            ));
+        }
+    }
+    None
+}
+
+// Read the manifest at `location` conditionally on the e-tag of `token`, None if the
+// store reports it was not modified, otherwise the manifest decoded from the response
+async fn read_manifest_if_modified(
+    object_store: &ObjectStore,
+    location: &ManifestLocation,
+    token: &ConsistencyToken,
+    uri: &str,
+    session: &Session,
+    populate_dictionary: bool,
+) -> Result<Option<Manifest>> {
+    let options = GetOptions {
+        if_none_match: token.e_tag.clone(),
+        ..Default::default()
+    };
+    let data = match object_store.inner.get_opts(&location.path, options).await {
+        Err(object_store::Error::NotModified { .. }) => return Ok(None),
+        Ok(result) => result.bytes().await?,
+        Err(err) => return Err(err.into()),
+    };
+    // The whole manifest is in the response, the reader is only used to populate
+    // the dictionaries of legacy datasets
+    let object_reader = object_store
+        .open_with_size(&location.path, data.len())
+        .await?;
+    let manifest = Dataset::decode_manifest(
+        object_reader.as_ref(),
+        &data,
+        location,
+        uri,
+        session,
+        populate_dictionary,
+    )
+    .await?;
+    Ok(Some(manifest))
+}
+
+// Resolve the location of the manifest for `version_ref`, or of the latest manifest
+async fn resolve_manifest_location(
+    object_store: &Arc<ObjectStore>,
+    base_path: &Path,
+    commit_handler: &Arc<dyn CommitHandler>,
+    version_ref: Option<Ref>,
+) -> Result<ManifestLocation> {
+    let version = match version_ref {
+        Some(Ref::Version(version)) => Some(version),
+        Some(Ref::Tag(tag)) => {
+            let tags = Tags::new(
+                object_store.clone(),
+                commit_handler.clone(),
+                base_path.clone(),
+            );
+            Some(tags.get_version(tag.as_str()).await?)
+        }
+        None => None,
+    };
+    match version {
+        Some(version) => {
+            commit_handler
+                .resolve_version_location(base_path, version, &object_store.inner)
+                .await
+        }
+        None => commit_handler
+            .resolve_latest_location(base_path, object_store)
+            .await
+            .map_err(|e| Error::DatasetNotFound {
+                source: Box::new(e),
+                path: base_path.to_string(),
+                location: location!(),
+            }),
+    }
+}
+
+// Errors that mean the dataset could not be read from a URI, another replica may have it
+fn should_try_fallback(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::DatasetNotFound { .. }
+            | Error::NotFound { .. }
+            | Error::VersionNotFound { .. }
+            | Error::IO { .. }
+    )
+}
+
+// A resolved location only has a size once the manifest file has been found
+async fn check_manifest_exists(
+    object_store: &ObjectStore,
+    location: &ManifestLocation,
+) -> Result<()> {
+    if location.size.is_some() {
+        return Ok(());
+    }
+    match object_store.inner.head(&location.path).await {
+        Ok(_) => Ok(()),
+        Err(object_store::Error::NotFound { .. }) => Err(Error::VersionNotFound {
+            message: format!(
+                "Manifest for version {} not found at {}",
+                location.version, location.path
+            ),
+        }),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn join_table_subpath(root: &str, subpath: &str) -> String {
+    let subpath = subpath.trim_matches('/');
+    if subpath.is_empty() {
+        root.to_string()
+    } else if root.ends_with('/') {
+        format!("{}{}", root, subpath)
+    } else {
+        format!("{}/{}", root, subpath)
     }
 }
@@ -59,7 +59,7 @@ use std::{
 };
 use tracing::{info, instrument, Span};
 
-use crate::{utils::temporal::utc_now, Dataset};
+use crate::Dataset;
 
 use super::refs::TagContents;
 
@@ -259,7 +259,7 @@ impl<'a> CleanupTask<'a> {
         inspection: CleanupInspection,
     ) -> Result<RemovalStats> {
         let removal_stats = Mutex::new(RemovalStats::default());
-        let verification_threshold = utc_now()
+        let verification_threshold = self.dataset.read_options.clock.now()
             - TimeDelta::try_days(UNVERIFIED_THRESHOLD_DAYS).expect("TimeDelta::try_days");
         let unreferenced_paths = self
             .dataset
@@ -560,6 +560,7 @@ mod tests {
     use crate::{
         dataset::{builder::DatasetBuilder, ReadParams, WriteMode, WriteParams},
         index::vector::VectorIndexParams,
+        utils::temporal::utc_now,
     };
     use all_asserts::{assert_gt, assert_lt};
     use tempfile::{tempdir, TempDir};
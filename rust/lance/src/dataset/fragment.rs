@@ -20,7 +20,7 @@ use datafusion::logical_expr::Expr;
 use datafusion::scalar::ScalarValue;
 use futures::future::try_join_all;
 use futures::{join, stream, FutureExt, StreamExt, TryFutureExt, TryStreamExt};
-use lance_core::datatypes::{OnMissing, OnTypeMismatch, SchemaCompareOptions};
+use lance_core::datatypes::{Field, OnMissing, OnTypeMismatch, SchemaCompareOptions};
 use lance_core::utils::deletion::DeletionVector;
 use lance_core::utils::tokio::get_num_compute_intensive_cpus;
 use lance_core::{cache::CacheKey, datatypes::Schema, Error, Result};
@@ -42,6 +42,7 @@ use lance_table::utils::stream::{
     wrap_with_row_id_and_delete, ReadBatchFutStream, ReadBatchTask, ReadBatchTaskStream,
     RowIdAndDeletesConfig,
 };
+use object_store::path::Path;
 use snafu::location;
 
 use self::write::FragmentCreateBuilder;
@@ -49,7 +50,7 @@ use self::write::FragmentCreateBuilder;
 use super::hash_joiner::HashJoiner;
 use super::rowids::load_row_id_sequence;
 use super::scanner::Scanner;
-use super::statistics::FieldStatistics;
+use super::statistics::{EncodingStats, FieldStatistics};
 use super::updater::Updater;
 use super::{schema_evolution, NewColumnTransform, WriteParams};
 use crate::arrow::*;
@@ -68,6 +69,14 @@ pub struct FileFragment {
 
 const DEFAULT_BATCH_READ_SIZE: u32 = 1024;
 
+/// A read of `size` bytes at `offset` in the file at `path`, see [`FileFragment::plan_reads`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReadRequest {
+    pub path: Path,
+    pub offset: u64,
+    pub size: u64,
+}
+
 /// A trait for file readers to be implemented by both the v1 and v2 readers
 #[allow(clippy::len_without_is_empty)]
 pub trait GenericFileReader: std::fmt::Debug + Send + Sync {
@@ -110,6 +119,13 @@ pub trait GenericFileReader: std::fmt::Debug + Send + Sync {
     /// Update storage statistics (ignored by v1 reader)
     fn update_storage_stats(&self, field_stats: &mut HashMap<u32, FieldStatistics>);
 
+    /// Add the encodings of the file's pages to `stats` (ignored by v1 reader)
+    fn update_encoding_stats(&self, stats: &mut EncodingStats);
+
+    /// Plan the reads needed to read `range` of the projected columns, see
+    /// [`FileFragment::plan_reads`] (not supported by v1 reader)
+    fn plan_reads(&self, range: Range<u64>) -> Result<Vec<ReadRequest>>;
+
     // Helper functions to fallback to the legacy implementation while we
     // slowly migrate functionality over to the generic reader
 
@@ -269,6 +285,17 @@ impl GenericFileReader for V1Reader {
         // No-op for v1 files
     }
 
+    fn update_encoding_stats(&self, _stats: &mut EncodingStats) {
+        // No-op for v1 files, they have no encoding metadata
+    }
+
+    fn plan_reads(&self, _range: Range<u64>) -> Result<Vec<ReadRequest>> {
+        Err(Error::NotSupported {
+            source: "planning reads is not supported for legacy (v1) data files".into(),
+            location: location!(),
+        })
+    }
+
     fn clone_box(&self) -> Box<dyn GenericFileReader> {
         Box::new(self.clone())
     }
@@ -287,7 +314,7 @@ impl GenericFileReader for V1Reader {
 }
 
 mod v2_adapter {
-    use lance_encoding::decoder::FilterExpression;
+    use lance_encoding::{decoder::FilterExpression, v2::encodings::physical::planned_reads};
 
     use super::*;
 
@@ -459,6 +486,36 @@ mod v2_adapter {
             }
         }
 
+        fn update_encoding_stats(&self, stats: &mut EncodingStats) {
+            stats.add_columns(&self.reader.metadata().column_infos);
+        }
+
+        fn plan_reads(&self, range: Range<u64>) -> Result<Vec<ReadRequest>> {
+            let metadata = self.reader.metadata();
+            let projection = ReaderProjection::from_field_ids(
+                metadata.version(),
+                self.projection.as_ref(),
+                self.field_id_to_column_idx.as_ref(),
+            )?;
+            let path = self.file_scheduler.reader().path();
+            Ok(projection
+                .column_indices
+                .into_iter()
+                .flat_map(|column_idx| {
+                    planned_reads(
+                        &metadata.column_infos[column_idx as usize],
+                        metadata.num_rows,
+                        range.clone(),
+                    )
+                })
+                .map(|(offset, size)| ReadRequest {
+                    path: path.clone(),
+                    offset,
+                    size,
+                })
+                .collect())
+        }
+
         fn projection(&self) -> &Arc<Schema> {
             &self.projection
         }
@@ -569,6 +626,15 @@ impl GenericFileReader for NullReader {
         // No-op for null reader
     }
 
+    fn update_encoding_stats(&self, _stats: &mut EncodingStats) {
+        // No-op for null reader
+    }
+
+    fn plan_reads(&self, _range: Range<u64>) -> Result<Vec<ReadRequest>> {
+        // Nulls are generated, nothing is read
+        Ok(Vec::new())
+    }
+
     fn projection(&self) -> &Arc<Schema> {
         &self.schema
     }
@@ -774,6 +840,49 @@ impl FileFragment {
         Ok(())
     }
 
+    pub(crate) async fn update_encoding_stats(
+        &self,
+        stats: &mut EncodingStats,
+        dataset_schema: &Schema,
+        scan_scheduler: Arc<ScanScheduler>,
+    ) -> Result<()> {
+        for reader in self
+            .open_readers(
+                dataset_schema,
+                &FragReadConfig::default().with_scan_scheduler(scan_scheduler),
+            )
+            .await?
+        {
+            reader.update_encoding_stats(stats);
+        }
+        Ok(())
+    }
+
+    /// Plan the reads needed to read `range` (of the physical rows) of the `projection`
+    ///
+    /// Only the metadata of the data files is used, none of the data is read.  If the
+    /// metadata of a data file is not cached then its footer and column metadata are read
+    /// (and cached in the session), later plans do no IO.  The plan is made at page
+    /// granularity, see [`planned_reads`](lance_encoding::v2::encodings::physical::planned_reads).
+    /// Decoders may read less than a whole page, so the reads made when reading the range
+    /// are contained in, but may be smaller than, the planned reads.
+    ///
+    /// Only supported for data files with a data storage version of 2.0 or later.
+    pub async fn plan_reads(
+        &self,
+        range: Range<u64>,
+        projection: &Schema,
+    ) -> Result<Vec<ReadRequest>> {
+        let mut reads = Vec::new();
+        for reader in self
+            .open_readers(projection, &FragReadConfig::default())
+            .await?
+        {
+            reads.extend(reader.plan_reads(range.clone())?);
+        }
+        Ok(reads)
+    }
+
     pub fn dataset(&self) -> &Dataset {
         self.dataset.as_ref()
     }
@@ -797,6 +906,14 @@ impl FileFragment {
         self.metadata.files.len()
     }
 
+    /// The data storage version of each data file in this fragment.
+    ///
+    /// The versions are read from the manifest and are in the same order as the
+    /// data files in [`Self::metadata`].
+    pub fn data_storage_versions(&self) -> Result<Vec<LanceFileVersion>> {
+        self.metadata.file_versions()
+    }
+
     /// Gets the data file for a given field
     pub fn data_file_for_field(&self, field_id: u32) -> Option<&DataFile> {
         self.metadata
@@ -825,7 +942,7 @@ impl FileFragment {
         read_config: FragReadConfig,
     ) -> Result<FragmentReader> {
         let open_files = self.open_readers(projection, &read_config);
-        let deletion_vec_load = self.get_deletion_vector();
+        let deletion_vec_load = self.read_deletion_vector();
 
         let row_id_load = if self.dataset.manifest.uses_move_stable_row_ids() {
             futures::future::Either::Left(
@@ -967,6 +1084,22 @@ impl FileFragment {
                         }
                     }),
             ));
+            // A field can be listed in the data file without a column, it has no data in
+            // this file.  It is left out here so it is read as nulls, like a field that is
+            // not in any data file.
+            let mut field_ids = Vec::new();
+            for field in &schema_per_file.fields {
+                collect_fields_with_columns(
+                    field,
+                    &field_id_to_column_idx,
+                    file_metadata.version() >= LanceFileVersion::V2_1,
+                    &mut field_ids,
+                );
+            }
+            let schema_per_file = Arc::new(schema_per_file.project_by_ids(&field_ids, false));
+            if schema_per_file.fields.is_empty() {
+                return Ok(None);
+            }
             let reader = v2_adapter::Reader::new(
                 reader,
                 schema_per_file,
@@ -1039,7 +1172,13 @@ impl FileFragment {
     }
 
     /// Get the number of rows that have been deleted in this fragment.
+    ///
+    /// This is zero if the dataset was loaded with
+    /// [`DatasetBuilder::with_ignore_deletions`](crate::dataset::builder::DatasetBuilder::with_ignore_deletions).
     pub async fn count_deletions(&self) -> Result<usize> {
+        if self.dataset.read_options.ignore_deletions {
+            return Ok(0);
+        }
         match &self.metadata().deletion_file {
             Some(DeletionFile {
                 num_deleted_rows: Some(num_deleted),
@@ -1252,7 +1391,7 @@ impl FileFragment {
     /// If indices are out-of-bounds, this will return an error.
     pub async fn take(&self, indices: &[u32], projection: &Schema) -> Result<RecordBatch> {
         // Re-map the indices to row ids using the deletion vector
-        let deletion_vector = self.get_deletion_vector().await?;
+        let deletion_vector = self.read_deletion_vector().await?;
         let row_ids = if let Some(deletion_vector) = deletion_vector {
             // Naive case is O(N*M), where N = indices.len() and M = deletion_vector.len()
             // We can do better by sorting the deletion vector and using binary search
@@ -1317,6 +1456,17 @@ impl FileFragment {
         Ok(Some(deletion_vector))
     }
 
+    /// Get the deletion vector to apply when reading this fragment.
+    ///
+    /// This is `None` if the dataset was loaded with
+    /// [`DatasetBuilder::with_ignore_deletions`](crate::dataset::builder::DatasetBuilder::with_ignore_deletions).
+    pub(crate) async fn read_deletion_vector(&self) -> Result<Option<Arc<DeletionVector>>> {
+        if self.dataset.read_options.ignore_deletions {
+            return Ok(None);
+        }
+        self.get_deletion_vector().await
+    }
+
     /// Get the file metadata for this fragment, using the cache if available.
     async fn get_file_metadata(
         &self,
@@ -1768,6 +1918,27 @@ impl std::fmt::Display for FragmentReader {
     }
 }
 
+// Add the ids of the leaf fields under `field` that have a column in the data file to
+// `ids`.  In 2.0 files the parents of a field also need a column.  A packed struct is
+// stored in one column so it is added as a whole.
+fn collect_fields_with_columns(
+    field: &Field,
+    field_id_to_column_idx: &BTreeMap<u32, u32>,
+    is_structural: bool,
+    ids: &mut Vec<i32>,
+) {
+    let has_column = field_id_to_column_idx.contains_key(&(field.id as u32));
+    if field.children.is_empty() || field.is_packed_struct() {
+        if has_column {
+            ids.push(field.id);
+        }
+    } else if is_structural || has_column {
+        for child in &field.children {
+            collect_fields_with_columns(child, field_id_to_column_idx, is_structural, ids);
+        }
+    }
+}
+
 fn merge_batches(batches: &[RecordBatch]) -> Result<RecordBatch> {
     if batches.is_empty() {
         return Err(Error::io(
@@ -2352,7 +2523,7 @@ mod tests {
 
     use super::*;
     use crate::{
-        dataset::{transaction::Operation, InsertBuilder},
+        dataset::{transaction::Operation, InsertBuilder, WriteMode},
         session::Session,
         utils::test::{StatsHolder, TestDatasetGenerator},
     };
@@ -3381,6 +3552,70 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_data_storage_versions() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+
+        let make_batch = || {
+            gen()
+                .col("str", array::rand_type(&DataType::Utf8))
+                .col("int", array::rand_type(&DataType::Int32))
+                .into_batch_rows(RowCount::from(64))
+                .unwrap()
+        };
+        let dataset = Arc::new(
+            TestDatasetGenerator::new(vec![make_batch()], LanceFileVersion::Legacy)
+                .make_hostile(test_uri)
+                .await,
+        );
+
+        // Commits can't mix versions so the fragments are written without committing them
+        // and put together in a manifest of their own
+        let versions = [
+            LanceFileVersion::Legacy,
+            LanceFileVersion::V2_0,
+            LanceFileVersion::V2_1,
+        ];
+        let mut fragments = Vec::new();
+        for (id, version) in versions.into_iter().enumerate() {
+            let transaction = InsertBuilder::new(dataset.clone())
+                .with_params(&WriteParams {
+                    mode: WriteMode::Overwrite,
+                    data_storage_version: Some(version),
+                    ..Default::default()
+                })
+                .execute_uncommitted(vec![make_batch()])
+                .await
+                .unwrap();
+            let Operation::Overwrite {
+                fragments: written, ..
+            } = transaction.operation
+            else {
+                panic!("expected an overwrite, got {:?}", transaction.operation);
+            };
+            assert_eq!(written.len(), 1);
+            fragments.push(Fragment {
+                id: id as u64,
+                ..written[0].clone()
+            });
+        }
+        let mut manifest = dataset.manifest.as_ref().clone();
+        manifest.fragments = Arc::new(fragments);
+        let mixed = Dataset {
+            manifest: Arc::new(manifest),
+            ..dataset.as_ref().clone()
+        };
+
+        let reported = mixed.data_storage_versions().unwrap();
+        assert_eq!(reported.len(), versions.len());
+        for (fragment, version) in mixed.get_fragments().iter().zip(versions) {
+            let expected = vec![version; fragment.num_data_files()];
+            assert_eq!(fragment.data_storage_versions().unwrap(), expected);
+            assert_eq!(reported[&(fragment.id() as u64)], expected);
+        }
+    }
+
     #[tokio::test]
     async fn create_from_file_v2() {
         let test_dir = tempdir().unwrap();
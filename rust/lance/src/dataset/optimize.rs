@@ -102,6 +102,7 @@ use serde::{Deserialize, Serialize};
 use super::fragment::FileFragment;
 use super::index::DatasetIndexRemapperOptions;
 use super::rowids::load_row_id_sequences;
+use super::scanner::Scanner;
 use super::transaction::{Operation, RewriteGroup, RewrittenIndex, Transaction};
 use super::utils::make_rowaddr_capture_stream;
 use super::{write_fragments_internal, WriteMode, WriteParams};
@@ -673,7 +674,8 @@ async fn rewrite_files(
         .sum::<u64>();
     // If we aren't using move-stable row ids, then we need to remap indices.
     let needs_remapping = !dataset.manifest.uses_move_stable_row_ids();
-    let mut scanner = dataset.scan();
+    // Not `dataset.scan()`, which would only read the default projection
+    let mut scanner = Scanner::new(Arc::new(dataset.clone()));
     if let Some(batch_size) = options.batch_size {
         scanner.batch_size(batch_size);
     }
@@ -970,6 +972,7 @@ mod tests {
 
     use self::remapping::RemappedIndex;
     use super::*;
+    use crate::dataset::builder::DatasetBuilder;
     use crate::dataset::index::frag_reuse::cleanup_frag_reuse_index;
     use crate::dataset::optimize::remapping::{transpose_row_ids, transpose_row_ids_from_digest};
     use crate::dataset::WriteDestination;
@@ -1498,6 +1501,42 @@ mod tests {
         assert_eq!(scanned_data, data);
     }
 
+    #[tokio::test]
+    async fn test_compact_with_default_projection() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let original = lance_datagen::gen()
+            .col("a", lance_datagen::array::step::<Int32Type>())
+            .col("b", lance_datagen::array::step::<Int32Type>())
+            .into_dataset(
+                test_uri,
+                FragmentCount::from(4),
+                FragmentRowCount::from(100),
+            )
+            .await
+            .unwrap();
+        let expected = original.scan().try_into_batch().await.unwrap();
+
+        // Compaction must rewrite every column, not just the default projection
+        let mut dataset = DatasetBuilder::from_uri(test_uri)
+            .with_default_projection(vec!["a".to_string()])
+            .load()
+            .await
+            .unwrap();
+        let metrics = compact_files(&mut dataset, CompactionOptions::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(metrics.fragments_removed, 4);
+        assert_eq!(metrics.fragments_added, 1);
+
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch, expected.project(&[0]).unwrap());
+        let dataset = Dataset::open(test_uri).await.unwrap();
+        assert_eq!(dataset.get_fragments().len(), 1);
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch, expected);
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_compact_deletions(
@@ -387,6 +387,10 @@ pub struct Scanner {
     /// Mainly, if the result is returned strictly according to the batch_size,
     /// batching and waiting are required, and the performance will decrease.
     strict_batch_size: bool,
+
+    /// Why the default projection of the dataset could not be applied, planning fails
+    /// with this error unless another projection is set
+    default_projection_error: Option<String>,
 }
 
 fn escape_column_name(name: &str) -> String {
@@ -423,6 +427,14 @@ impl Scanner {
             include_deleted_rows: false,
             scan_stats_callback: None,
             strict_batch_size: false,
+            default_projection_error: None,
+        }
+    }
+
+    /// Start with the default projection of the dataset, see [`Dataset::scan`]
+    pub(crate) fn with_default_projection(&mut self, columns: &[String]) {
+        if let Err(err) = self.project(columns) {
+            self.default_projection_error = Some(err.to_string());
         }
     }
 
@@ -522,6 +534,7 @@ impl Scanner {
             .collect();
         self.projection_plan
             .project_from_expressions(&filtered_columns)?;
+        self.default_projection_error = None;
         Ok(self)
     }
 
@@ -1324,6 +1337,13 @@ impl Scanner {
     }
 
     fn validate_options(&self) -> Result<()> {
+        if let Some(err) = &self.default_projection_error {
+            return Err(Error::InvalidInput {
+                source: format!("The default projection of the dataset is invalid: {}", err).into(),
+                location: location!(),
+            });
+        }
+
         // Note: it's _ok_ (though maybe dubious) if the projection is empty and there is a vector search
         // or FTS because we might be projecting just the _distance / _score columns
         if self.projection_plan.physical_projection.is_empty()
@@ -6799,7 +6819,10 @@ mod test {
 
         impl IopsTracker {
             fn update(&mut self) {
-                let iops = self.io_stats.lock().unwrap().read_iops;
+                let iops = {
+                    let stats = self.io_stats.lock().unwrap();
+                    stats.read_iops + stats.metadata_iops
+                };
                 self.new_iops = iops - self.baseline;
                 self.baseline = iops;
             }
@@ -3,9 +3,16 @@
 
 //! Module for statistics related to the dataset.
 
-use std::{collections::HashMap, future::Future, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    future::Future,
+    sync::Arc,
+};
 
 use lance_core::Result;
+use lance_encoding::decoder::{ColumnInfo, PageEncoding};
+use lance_encoding::format::pb::page_layout::Layout;
+use lance_encoding::v2::encodings::physical::{describe_encoding, EncodingTree};
 use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
 
 use super::{fragment::FileFragment, Dataset};
@@ -26,11 +33,110 @@ pub struct DataStatistics {
     pub fields: Vec<FieldStatistics>,
 }
 
+/// How much of the data is stored with one encoding, see [`EncodingStats`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodingUsage {
+    /// Number of pages that use the encoding
+    pub num_pages: u64,
+    /// Amount of data in those pages (after compression, if any)
+    pub bytes_on_disk: u64,
+}
+
+/// The encodings used by the pages of the dataset's data files
+///
+/// A page is counted under each encoding in its encoding tree (see
+/// [`describe_encoding`]) and under its compression scheme, if any.  For example a
+/// nullable page of zstd compressed values counts towards `Nullable`, `Flat` and
+/// `zstd`.  So the bytes summed over all of the encodings are more than
+/// [`Self::bytes_on_disk`].
+///
+/// 2.1 pages are also counted under their layout (e.g. `MiniBlockLayout`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodingStats {
+    /// Usage by encoding name (e.g. `Bitpacked`) or compression scheme (e.g. `zstd`)
+    pub encodings: BTreeMap<String, EncodingUsage>,
+    /// Number of pages in the data files
+    pub num_pages: u64,
+    /// Amount of data in the pages of the data files
+    pub bytes_on_disk: u64,
+}
+
+impl EncodingStats {
+    /// Add the pages of the columns of a data file
+    pub(crate) fn add_columns(&mut self, columns: &[Arc<ColumnInfo>]) {
+        for page in columns.iter().flat_map(|column| column.page_infos.iter()) {
+            let num_bytes = page
+                .buffer_offsets_and_sizes
+                .iter()
+                .map(|(_, size)| size)
+                .sum::<u64>();
+            self.num_pages += 1;
+            self.bytes_on_disk += num_bytes;
+            for name in page_encoding_names(&page.encoding) {
+                let usage = self.encodings.entry(name).or_default();
+                usage.num_pages += 1;
+                usage.bytes_on_disk += num_bytes;
+            }
+        }
+    }
+}
+
+// The names of the encodings, layouts and compression schemes used by a page
+fn page_encoding_names(encoding: &PageEncoding) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    match encoding {
+        PageEncoding::Legacy(encoding) => {
+            add_encoding_names(&describe_encoding(encoding), &mut names)
+        }
+        PageEncoding::Structural(page_layout) => {
+            let (layout_name, encodings) = match page_layout.layout.as_ref() {
+                Some(Layout::MiniBlockLayout(mini_block)) => (
+                    "MiniBlockLayout",
+                    vec![
+                        mini_block.value_compression.as_ref(),
+                        mini_block.dictionary.as_ref(),
+                    ],
+                ),
+                Some(Layout::FullZipLayout(full_zip)) => {
+                    ("FullZipLayout", vec![full_zip.value_compression.as_ref()])
+                }
+                Some(Layout::AllNullLayout(_)) => ("AllNullLayout", vec![]),
+                None => ("Unknown", vec![]),
+            };
+            names.insert(layout_name.to_string());
+            for encoding in encodings.into_iter().flatten() {
+                add_encoding_names(&describe_encoding(encoding), &mut names);
+            }
+        }
+    }
+    names
+}
+
+fn add_encoding_names(tree: &EncodingTree, names: &mut BTreeSet<String>) {
+    names.insert(tree.name.clone());
+    for property in &tree.properties {
+        // e.g. `compression=zstd(level=3)`
+        if let Some(compression) = property.strip_prefix("compression=") {
+            let scheme = compression.split('(').next().unwrap_or(compression);
+            names.insert(scheme.to_string());
+        }
+    }
+    for (_, child) in &tree.children {
+        add_encoding_names(child, names);
+    }
+}
+
 pub trait DatasetStatisticsExt {
     /// Get statistics about the data in the dataset
     fn calculate_data_stats(
         self: &Arc<Self>,
     ) -> impl Future<Output = Result<DataStatistics>> + Send;
+
+    /// Get the encodings used by the data in the dataset, see [`EncodingStats`]
+    ///
+    /// Only the metadata of the data files is read, not the data.  Files with a data
+    /// storage version less than 2 have no encoding metadata and are not counted.
+    fn encoding_stats(self: &Arc<Self>) -> impl Future<Output = Result<EncodingStats>> + Send;
 }
 
 impl DatasetStatisticsExt for Dataset {
@@ -66,4 +172,89 @@ impl DatasetStatisticsExt for Dataset {
             fields: field_stats,
         })
     }
+
+    async fn encoding_stats(self: &Arc<Self>) -> Result<EncodingStats> {
+        let mut stats = EncodingStats::default();
+        if !self.is_legacy_storage() {
+            let scan_scheduler = ScanScheduler::new(
+                self.object_store.clone(),
+                SchedulerConfig::max_bandwidth(self.object_store.as_ref()),
+            );
+            for fragment in self.fragments().as_ref() {
+                let file_fragment = FileFragment::new(self.clone(), fragment.clone());
+                file_fragment
+                    .update_encoding_stats(&mut stats, self.schema(), scan_scheduler.clone())
+                    .await?;
+            }
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use arrow_array::{Int32Array, RecordBatch, RecordBatchIterator, StringArray};
+    use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+    use lance_core::datatypes::COMPRESSION_META_KEY;
+    use lance_file::version::LanceFileVersion;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::dataset::WriteParams;
+
+    #[tokio::test]
+    async fn test_encoding_stats() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new("s", DataType::Utf8, false).with_metadata(HashMap::from([(
+                COMPRESSION_META_KEY.to_string(),
+                "zstd".to_string(),
+            )])),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..100)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|i| format!("value_{}", i)),
+                )),
+            ],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let dataset = Dataset::write(
+            reader,
+            test_uri,
+            Some(WriteParams {
+                max_rows_per_file: 50,
+                data_storage_version: Some(LanceFileVersion::V2_0),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        let dataset = Arc::new(dataset);
+        assert_eq!(dataset.get_fragments().len(), 2);
+
+        let stats = dataset.encoding_stats().await.unwrap();
+        // At least one page for each column of each file
+        assert!(stats.num_pages >= 4, "{:?}", stats);
+        assert!(stats.bytes_on_disk > 0);
+
+        // The strings are zstd compressed binary, the integers are not compressed
+        let binary = &stats.encodings["Binary"];
+        let zstd = &stats.encodings["zstd"];
+        assert!(binary.num_pages >= 2);
+        assert_eq!(zstd.num_pages, binary.num_pages);
+        assert!(stats.encodings["Flat"].num_pages > zstd.num_pages);
+        for usage in stats.encodings.values() {
+            assert!(usage.num_pages <= stats.num_pages);
+            assert!(usage.bytes_on_disk <= stats.bytes_on_disk);
+        }
+    }
 }
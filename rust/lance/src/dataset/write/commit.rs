@@ -18,9 +18,10 @@ use crate::{
         commit_detached_transaction, commit_new_dataset, commit_transaction,
         refs::Tags,
         transaction::{Operation, Transaction},
-        ManifestWriteConfig, ReadParams,
+        DatasetReadOptions, ManifestWriteConfig, ReadParams,
     },
     session::Session,
+    utils::temporal::to_system_time,
     Dataset, Error, Result,
 };
 
@@ -281,6 +282,10 @@ impl<'a> CommitBuilder<'a> {
         let manifest_config = ManifestWriteConfig {
             use_move_stable_row_ids,
             storage_format: self.storage_format.map(DataStorageFormat::new),
+            timestamp: dest
+                .dataset()
+                .map(|dataset| to_system_time(dataset.read_options.clock.now()))
+                .transpose()?,
             ..Default::default()
         };
 
@@ -393,6 +398,7 @@ impl<'a> CommitBuilder<'a> {
                 tags,
                 index_cache,
                 metadata_cache,
+                read_options: DatasetReadOptions::default(),
             }),
         }
     }
@@ -550,10 +556,12 @@ mod tests {
 
         let reset_iops = || {
             io_stats.lock().unwrap().read_iops = 0;
+            io_stats.lock().unwrap().metadata_iops = 0;
             io_stats.lock().unwrap().write_iops = 0;
         };
         let get_new_iops = || {
-            let read_iops = io_stats.lock().unwrap().read_iops;
+            let read_iops =
+                io_stats.lock().unwrap().read_iops + io_stats.lock().unwrap().metadata_iops;
             let write_iops = io_stats.lock().unwrap().write_iops;
             reset_iops();
             (read_iops, write_iops)
@@ -656,7 +664,8 @@ mod tests {
         // This could be zero, if we decided to be optimistic. However, that
         // would mean two wasted write requests (txn + manifest) if there was
         // a conflict. We choose to be pessimistic for more consistent performance.
-        assert_eq!(io_stats.read_iops, 1);
+        assert_eq!(io_stats.metadata_iops, 1);
+        assert_eq!(io_stats.read_iops, 0);
         assert_eq!(io_stats.write_iops, 2);
         // We can't write them in parallel. The transaction file must exist before
         // we can write the manifest.
@@ -731,12 +740,13 @@ mod tests {
         // * 1 write manifest
         // For total of 3 + 2 * num_other_txns io requests. If we have caching enabled, we can skip 2 * num_other_txns
         // of those. We should be able to read in 5 hops.
+        assert_eq!(io_stats.metadata_iops, 1); // list versions
         if use_cache {
-            assert_eq!(io_stats.read_iops, 1); // Just list versions
+            assert_eq!(io_stats.read_iops, 0);
             assert_eq!(io_stats.num_hops, 3);
         } else {
             // We need to read the other manifests and transactions.
-            assert_eq!(io_stats.read_iops, 1 + num_other_txns * 2);
+            assert_eq!(io_stats.read_iops, num_other_txns * 2);
             // It's possible to read the txns for some versions before we
             // finish reading later versions and so the entire "read versions
             // and txs" may appear as 1 hop instead of 2.
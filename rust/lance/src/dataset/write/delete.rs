@@ -2,6 +2,7 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 use crate::{
+    dataset::scanner::Scanner,
     dataset::transaction::{Operation, Transaction},
     dataset::utils::make_rowaddr_capture_stream,
     Dataset,
@@ -66,7 +67,7 @@ async fn apply_deletions(
 
 pub async fn delete(ds: &mut Dataset, predicate: &str) -> Result<()> {
     // Create a single scanner for the entire dataset
-    let mut scanner = ds.scan();
+    let mut scanner = Scanner::new(Arc::new(ds.clone()));
     scanner
         .with_row_address()
         .filter(predicate)?
@@ -59,6 +59,7 @@ use crate::{
     datafusion::dataframe::SessionContextExt,
     dataset::{
         fragment::{FileFragment, FragReadConfig},
+        scanner::Scanner,
         transaction::{Operation, Transaction},
         write::{merge_insert::logical_plan::MergeInsertPlanner, open_writer},
     },
@@ -586,7 +587,7 @@ impl MergeInsertJob {
         // 5a - We also need to scan any new unindexed data and union it in
         let unindexed_fragments = self.dataset.unindexed_fragments(&index.name).await?;
         if !unindexed_fragments.is_empty() {
-            let mut builder = self.dataset.scan();
+            let mut builder = Scanner::new(self.dataset.clone());
             if add_row_addr {
                 builder.with_row_address();
             }
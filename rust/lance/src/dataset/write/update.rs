@@ -28,6 +28,7 @@ use roaring::RoaringTreemap;
 use snafu::{location, ResultExt};
 use std::future::Future;
 
+use crate::dataset::scanner::Scanner;
 use crate::dataset::transaction::{Operation, Transaction};
 use crate::{io::exec::Planner, Dataset};
 use crate::{Error, Result};
@@ -345,7 +346,8 @@ impl UpdateJob {
     }
 
     async fn execute_impl(self) -> Result<UpdateData> {
-        let mut scanner = self.dataset.scan();
+        // Not `self.dataset.scan()`, the rewritten rows need every column
+        let mut scanner = Scanner::new(self.dataset.clone());
         scanner.with_row_address();
 
         if let Some(expr) = &self.condition {
@@ -1112,8 +1112,8 @@ impl DatasetIndexInternalExt for Dataset {
         metrics.record_index_load();
 
         self.index_cache
-            .insert_unsized_with_key(&cache_key, index.clone())
-            .await;
+            .try_insert_unsized_with_key(&cache_key, index.clone())
+            .await?;
         Ok(index)
     }
 
@@ -1338,8 +1338,8 @@ impl DatasetIndexInternalExt for Dataset {
         let index = index?;
         metrics.record_index_load();
         self.index_cache
-            .insert_unsized_with_key(&cache_key, index.clone())
-            .await;
+            .try_insert_unsized_with_key(&cache_key, index.clone())
+            .await?;
         Ok(index)
     }
 
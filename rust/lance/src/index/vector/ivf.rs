@@ -223,8 +223,8 @@ impl IVFIndex {
                 let idx: Arc<dyn VectorIndex> = idx.into();
                 if write_cache {
                     self.index_cache
-                        .insert_unsized_with_key(&cache_key, idx.clone())
-                        .await;
+                        .try_insert_unsized_with_key(&cache_key, idx.clone())
+                        .await?;
                 }
                 idx
             }
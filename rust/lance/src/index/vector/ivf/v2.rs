@@ -297,8 +297,8 @@ impl<S: IvfSubIndex + 'static, Q: Quantization> IVFIndex<S, Q> {
                 });
                 if write_cache {
                     self.index_cache
-                        .insert_with_key(&cache_key, partition_entry.clone())
-                        .await;
+                        .try_insert_with_key(&cache_key, partition_entry.clone())
+                        .await?;
                 }
 
                 partition_entry
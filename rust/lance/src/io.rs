@@ -12,3 +12,148 @@ pub use lance_io::{
     object_store::{ObjectStore, ObjectStoreParams, ObjectStoreRegistry, WrappingObjectStore},
     stream::RecordBatchStream,
 };
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use arrow_array::{types::Int32Type, RecordBatchReader};
+    use futures::TryStreamExt;
+    use lance_core::{cache::LanceCache, datatypes::Schema};
+    use lance_datagen::{array, gen, ArrayGeneratorExt, BatchCount, RowCount};
+    use lance_encoding::{
+        decoder::{DecoderPlugins, FilterExpression},
+        version::LanceFileVersion,
+    };
+    use lance_file::v2::{
+        reader::{FileReader, FileReaderOptions},
+        writer::{FileWriter, FileWriterOptions},
+    };
+    use lance_io::{
+        scheduler::{ScanScheduler, SchedulerConfig},
+        utils::CachedFileSize,
+        ReadBatchParams,
+    };
+    use object_store::{path::Path, throttle::ThrottleConfig};
+
+    use super::{ObjectStore, WrappingObjectStore};
+    use crate::utils::test::{IoStats, IoTrackingStore, ThrottledStoreWrapper};
+
+    // Writes a 2.0 file with one column and a page per 1000 rows to a tracked, slow,
+    // in-memory store and opens it with `options`.  The column has nulls so each page
+    // has a validity buffer and a values buffer.  The stats are reset once the file is
+    // open so they only cover the reads that follow.
+    async fn open_tracked_file(options: FileReaderOptions) -> (FileReader, Arc<Mutex<IoStats>>) {
+        let (wrapper, stats) = IoTrackingStore::new_wrapper();
+        let throttle = ThrottledStoreWrapper {
+            config: ThrottleConfig {
+                wait_get_per_call: Duration::from_millis(10),
+                ..Default::default()
+            },
+        };
+        let mut object_store = ObjectStore::memory();
+        object_store.inner = wrapper.wrap(throttle.wrap(object_store.inner.clone()));
+        let object_store = Arc::new(object_store);
+        let path = Path::from("pages.lance");
+
+        let data = gen()
+            .col(
+                "ints",
+                array::step::<Int32Type>().with_nulls(&[true, false, false]),
+            )
+            .into_reader_rows(RowCount::from(1000), BatchCount::from(10));
+        let schema = Schema::try_from(data.schema().as_ref()).unwrap();
+        let mut writer = FileWriter::try_new(
+            object_store.create(&path).await.unwrap(),
+            schema,
+            FileWriterOptions {
+                format_version: Some(LanceFileVersion::V2_0),
+                // Flush a page for every batch
+                data_cache_bytes: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for batch in data {
+            writer.write_batch(&batch.unwrap()).await.unwrap();
+        }
+        writer.finish().await.unwrap();
+
+        let scheduler = ScanScheduler::new(object_store, SchedulerConfig::default_for_testing());
+        let file_scheduler = scheduler
+            .open_file(&path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &LanceCache::no_cache(),
+            options,
+        )
+        .await
+        .unwrap();
+        std::mem::take(&mut *stats.lock().unwrap());
+        (reader, stats)
+    }
+
+    async fn scan(reader: &FileReader) {
+        let batches = reader
+            .read_stream(
+                ReadBatchParams::RangeFull,
+                1000,
+                16,
+                FilterExpression::no_filter(),
+            )
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>(),
+            10_000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_reader_io_budget() {
+        // Without a budget the page requests overlap
+        let (reader, stats) = open_tracked_file(FileReaderOptions::default()).await;
+        scan(&reader).await;
+        let stats = std::mem::take(&mut *stats.lock().unwrap());
+        assert!(stats.read_iops >= 10);
+        assert!(stats.num_hops < stats.read_iops, "{:?}", stats);
+
+        // A budget smaller than a page only lets one request be in-flight at a time
+        let (reader, stats) = open_tracked_file(FileReaderOptions {
+            io_budget_bytes: Some(1),
+            ..Default::default()
+        })
+        .await;
+        scan(&reader).await;
+        let stats = std::mem::take(&mut *stats.lock().unwrap());
+        assert!(stats.read_iops >= 10);
+        assert_eq!(stats.num_hops, stats.read_iops, "{:?}", stats);
+    }
+
+    #[tokio::test]
+    async fn test_file_reader_coalescing() {
+        // The validity and values buffers of each page are read with a single request
+        let (reader, stats) = open_tracked_file(FileReaderOptions::default()).await;
+        scan(&reader).await;
+        let stats = std::mem::take(&mut *stats.lock().unwrap());
+        assert_eq!(stats.read_iops, 10, "{:?}", stats);
+
+        let (reader, stats) = open_tracked_file(FileReaderOptions {
+            coalesce_gap_bytes: None,
+            ..Default::default()
+        })
+        .await;
+        scan(&reader).await;
+        let stats = std::mem::take(&mut *stats.lock().unwrap());
+        assert_eq!(stats.read_iops, 20, "{:?}", stats);
+    }
+}
@@ -35,7 +35,8 @@ use lance_table::format::{
     WriterVersion, DETACHED_VERSION_MASK,
 };
 use lance_table::io::commit::{
-    CommitConfig, CommitError, CommitHandler, ManifestLocation, ManifestNamingScheme,
+    CommitConfig, CommitError, CommitHandler, ConflictSummary, ManifestLocation,
+    ManifestNamingScheme,
 };
 use rand::{thread_rng, Rng};
 use snafu::location;
@@ -639,6 +640,26 @@ pub(crate) async fn do_commit_detached_transaction(
     })
 }
 
+// A dataset loaded with `DatasetBuilder::with_ignore_deletions` reads deleted rows, a change
+// based on those reads could bring them back.  A dataset loaded with
+// `DatasetBuilder::with_schema_projection` has an incomplete schema, a new version based on
+// it would drop the hidden columns.
+fn check_can_commit(dataset: &Dataset) -> Result<()> {
+    if dataset.read_options.ignore_deletions {
+        return Err(Error::InvalidInput {
+            source: "The dataset was loaded with ignore_deletions and can't be modified".into(),
+            location: location!(),
+        });
+    }
+    if dataset.read_options.schema_projection.is_some() {
+        return Err(Error::InvalidInput {
+            source: "The dataset was loaded with a schema projection and can't be modified".into(),
+            location: location!(),
+        });
+    }
+    Ok(())
+}
+
 pub(crate) async fn commit_detached_transaction(
     dataset: &Dataset,
     object_store: &ObjectStore,
@@ -647,6 +668,7 @@ pub(crate) async fn commit_detached_transaction(
     write_config: &ManifestWriteConfig,
     commit_config: &CommitConfig,
 ) -> Result<(Manifest, ManifestLocation)> {
+    check_can_commit(dataset)?;
     let new_blob_version = if let Some(blob_op) = transaction.blobs_op.as_ref() {
         let blobs_dataset = dataset.blobs_dataset().await?.unwrap();
         let blobs_tx =
@@ -690,6 +712,7 @@ pub(crate) async fn commit_transaction(
     manifest_naming_scheme: ManifestNamingScheme,
     affected_rows: Option<&RowIdTreeMap>,
 ) -> Result<(Manifest, ManifestLocation)> {
+    check_can_commit(dataset)?;
     let new_blob_version = if let Some(blob_op) = transaction.blobs_op.as_ref() {
         let blobs_dataset = dataset.blobs_dataset().await?.unwrap();
         let blobs_tx =
@@ -766,7 +789,17 @@ pub(crate) async fn commit_transaction(
 
         // Check against committed transactions from oldest to latest
         for (other_version, other_transaction) in other_transactions.iter().rev() {
-            rebase.check_txn(other_transaction, *other_version)?;
+            let result = rebase.check_txn(other_transaction, *other_version);
+            if let Err(Error::CommitConflict { .. } | Error::RetryableCommitConflict { .. }) =
+                &result
+            {
+                commit_handler.on_conflict(&ConflictSummary {
+                    version: *other_version,
+                    uuid: other_transaction.uuid.clone(),
+                    operation: other_transaction.operation.name().to_string(),
+                });
+            }
+            result?;
         }
 
         transaction = rebase.finish(&dataset).await?;
@@ -1374,6 +1407,209 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_on_conflict_callback() {
+        let (tmpdir, dataset) = get_empty_dataset().await;
+        let test_uri = tmpdir.path().to_str().unwrap();
+
+        let conflicts = Arc::new(Mutex::new(Vec::new()));
+        let conflicts_ref = conflicts.clone();
+        let mut writer_a = crate::dataset::builder::DatasetBuilder::from_uri(test_uri)
+            .with_on_conflict(move |conflict| conflicts_ref.lock().unwrap().push(conflict.clone()))
+            .load()
+            .await
+            .unwrap();
+        let mut writer_b = writer_a.clone();
+        assert_eq!(writer_b.version().version, dataset.version().version);
+
+        // Both writers start from the same version, writer_a wins
+        writer_a
+            .update_config(vec![("key1".to_string(), "a".to_string())])
+            .await
+            .unwrap();
+        assert!(conflicts.lock().unwrap().is_empty());
+
+        let result = writer_b
+            .update_config(vec![("key1".to_string(), "b".to_string())])
+            .await;
+        assert!(
+            matches!(&result, &Err(Error::CommitConflict { .. })),
+            "{:?}",
+            result
+        );
+
+        let conflicts = conflicts.lock().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].version, writer_a.version().version);
+        assert_eq!(conflicts[0].operation, "UpdateConfig");
+    }
+
+    #[tokio::test]
+    async fn test_commit_handler_named() {
+        let (tmpdir, _) = get_empty_dataset().await;
+        let test_uri = tmpdir.path().to_str().unwrap();
+
+        let session = Arc::new(crate::session::Session::default());
+        let handler: Arc<dyn CommitHandler> = Arc::new(RenameCommitHandler);
+        session
+            .commit_handler_registry()
+            .insert("rename", handler.clone());
+
+        let mut dataset = crate::dataset::builder::DatasetBuilder::from_uri(test_uri)
+            .with_session(session.clone())
+            .with_commit_handler_named("rename")
+            .load()
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&dataset.commit_handler, &handler));
+        dataset
+            .update_config(vec![("key".to_string(), "value".to_string())])
+            .await
+            .unwrap();
+
+        // Unknown names fall back to the handler inferred from the URL
+        let mut dataset = crate::dataset::builder::DatasetBuilder::from_uri(test_uri)
+            .with_session(session)
+            .with_commit_handler_named("dynamodb")
+            .load()
+            .await
+            .unwrap();
+        assert!(!Arc::ptr_eq(&dataset.commit_handler, &handler));
+        assert_eq!(
+            format!("{:?}", dataset.commit_handler),
+            "RenameCommitHandler"
+        );
+        let version = dataset.version().version;
+        dataset
+            .update_config(vec![("key".to_string(), "other".to_string())])
+            .await
+            .unwrap();
+        assert_eq!(dataset.version().version, version + 1);
+    }
+
+    #[tokio::test]
+    async fn test_local_commit_handler() {
+        use lance_core::utils::testing::{ProxyObjectStore, ProxyObjectStorePolicy};
+
+        use crate::dataset::builder::DatasetBuilder;
+        use crate::dataset::ReadParams;
+        use crate::io::{ObjectStoreParams, WrappingObjectStore};
+
+        #[derive(Debug)]
+        struct ProxyWrapper(Arc<Mutex<ProxyObjectStorePolicy>>);
+
+        impl WrappingObjectStore for ProxyWrapper {
+            fn wrap(
+                &self,
+                original: Arc<dyn object_store::ObjectStore>,
+            ) -> Arc<dyn object_store::ObjectStore> {
+                Arc::new(ProxyObjectStore::new(original, self.0.clone()))
+            }
+        }
+
+        // Record the method and path of every request
+        let calls = Arc::new(Mutex::new(Vec::<(String, Path)>::new()));
+        let policy = Arc::new(Mutex::new(ProxyObjectStorePolicy::new()));
+        let recorded = calls.clone();
+        policy.lock().unwrap().set_before_policy(
+            "record",
+            Arc::new(move |method, path| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((method.to_string(), path.clone()));
+                Ok(())
+            }),
+        );
+        let store_params = ObjectStoreParams {
+            object_store_wrapper: Some(Arc::new(ProxyWrapper(policy))),
+            ..Default::default()
+        };
+        let session = Arc::new(crate::session::Session::default());
+
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int32,
+            false,
+        )]));
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1, 2]))])
+                .unwrap();
+        let test_uri = "memory://local_commit_handler";
+        Dataset::write(
+            RecordBatchIterator::new(vec![Ok(batch)], schema),
+            test_uri,
+            Some(WriteParams {
+                store_params: Some(store_params.clone()),
+                session: Some(session.clone()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        let builder = DatasetBuilder::from_uri(test_uri).with_read_params(ReadParams {
+            store_options: Some(store_params),
+            session: Some(session),
+            ..Default::default()
+        });
+        // Commits a config change and returns the new manifest path and the requests made
+        async fn commit_config(
+            mut dataset: Dataset,
+            calls: &Mutex<Vec<(String, Path)>>,
+        ) -> (Path, Vec<(String, Path)>) {
+            calls.lock().unwrap().clear();
+            dataset
+                .update_config(vec![("key".to_string(), "value".to_string())])
+                .await
+                .unwrap();
+            let calls = std::mem::take(&mut *calls.lock().unwrap());
+            (dataset.manifest_location().path.clone(), calls)
+        }
+
+        // The handler inferred for memory:// puts the manifest in place
+        let (manifest_path, requests) =
+            commit_config(builder.clone().load().await.unwrap(), &calls).await;
+        assert!(
+            requests
+                .iter()
+                .any(|(method, path)| method == "put" && path == &manifest_path),
+            "{:?}",
+            requests
+        );
+
+        // The local handler puts a staging file and renames it into place
+        let dataset = builder.with_local_commit_handler().load().await.unwrap();
+        assert_eq!(
+            format!("{:?}", dataset.commit_handler),
+            "RenameCommitHandler"
+        );
+        let (manifest_path, requests) = commit_config(dataset, &calls).await;
+        assert!(
+            !requests
+                .iter()
+                .any(|(method, path)| method == "put" && path == &manifest_path),
+            "{:?}",
+            requests
+        );
+        let (_, staged) = requests
+            .iter()
+            .find(|(method, path)| {
+                method == "put" && path.as_ref().contains("_versions/") && path != &manifest_path
+            })
+            .unwrap();
+        // The proxy store has no native rename-if-not-exists, so it copies and deletes
+        for renamed_with in ["copy_if_not_exists", "delete"] {
+            assert!(
+                requests
+                    .iter()
+                    .any(|(method, path)| method == renamed_with && path == staged),
+                "{:?}",
+                requests
+            );
+        }
+    }
+
     #[test]
     fn test_fix_schema() {
         // Manifest has a fragment with no fields in use
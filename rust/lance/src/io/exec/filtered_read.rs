@@ -431,7 +431,7 @@ impl FilteredReadStream {
         let deletion_vector = if include_deleted_rows {
             None
         } else {
-            file_fragment.get_deletion_vector().await?
+            file_fragment.read_deletion_vector().await?
         };
 
         let num_physical_rows = file_fragment.physical_rows().await? as u64;
@@ -5,10 +5,11 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use deepsize::DeepSizeOf;
-use lance_core::cache::LanceCache;
+use lance_core::cache::{CacheMemoryLimit, LanceCache};
 use lance_core::{Error, Result};
 use lance_index::IndexType;
 use lance_io::object_store::ObjectStoreRegistry;
+use lance_table::io::commit::CommitHandlerRegistry;
 use snafu::location;
 
 use crate::dataset::{DEFAULT_INDEX_CACHE_SIZE, DEFAULT_METADATA_CACHE_SIZE};
@@ -41,7 +42,12 @@ pub struct Session {
 
     pub(crate) index_extensions: HashMap<(IndexType, String), Arc<dyn IndexExtension>>,
 
+    /// The hard limit shared by the index and metadata caches, if there is one.
+    hard_memory_limit: Option<Arc<CacheMemoryLimit>>,
+
     store_registry: Arc<ObjectStoreRegistry>,
+
+    commit_handler_registry: Arc<CommitHandlerRegistry>,
 }
 
 impl DeepSizeOf for Session {
@@ -98,7 +104,32 @@ impl Session {
             index_cache: GlobalIndexCache(LanceCache::with_capacity(index_cache_size)),
             metadata_cache: GlobalMetadataCache(LanceCache::with_capacity(metadata_cache_size)),
             index_extensions: HashMap::new(),
+            hard_memory_limit: None,
             store_registry,
+            commit_handler_registry: Arc::new(CommitHandlerRegistry::default()),
+        }
+    }
+
+    /// Create a new session whose caches share a hard memory limit.
+    ///
+    /// The index and metadata caches together hold at most `limit_bytes`.
+    /// Unlike the cache sizes given to [`Self::new`], which evict the least
+    /// recently used entries when full, nothing is evicted.  Loading an index, or
+    /// other data cached through a fallible path, fails with
+    /// [`Error::OutOfMemory`] once the limit would be exceeded.  Entries only leave
+    /// the caches when they are invalidated.
+    pub fn with_hard_memory_limit(
+        limit_bytes: usize,
+        store_registry: Arc<ObjectStoreRegistry>,
+    ) -> Self {
+        let limit = CacheMemoryLimit::new(limit_bytes);
+        Self {
+            index_cache: GlobalIndexCache(LanceCache::with_hard_limit(limit.clone())),
+            metadata_cache: GlobalMetadataCache(LanceCache::with_hard_limit(limit.clone())),
+            index_extensions: HashMap::new(),
+            hard_memory_limit: Some(limit),
+            store_registry,
+            commit_handler_registry: Arc::new(CommitHandlerRegistry::default()),
         }
     }
 
@@ -164,11 +195,25 @@ impl Session {
             + self.index_extensions.len()
     }
 
+    /// The hard memory limit of the caches, if the session was created with
+    /// [`Self::with_hard_memory_limit`].
+    pub fn hard_memory_limit(&self) -> Option<&Arc<CacheMemoryLimit>> {
+        self.hard_memory_limit.as_ref()
+    }
+
     /// Get the object store registry.
     pub fn store_registry(&self) -> Arc<ObjectStoreRegistry> {
         self.store_registry.clone()
     }
 
+    /// Get the registry of named commit handlers.
+    ///
+    /// Handlers registered here can be selected with
+    /// [`crate::dataset::builder::DatasetBuilder::with_commit_handler_named`].
+    pub fn commit_handler_registry(&self) -> Arc<CommitHandlerRegistry> {
+        self.commit_handler_registry.clone()
+    }
+
     pub async fn metadata_cache_stats(&self) -> lance_core::cache::CacheStats {
         self.metadata_cache.0.stats().await
     }
@@ -176,6 +221,14 @@ impl Session {
     pub async fn index_cache_stats(&self) -> lance_core::cache::CacheStats {
         self.index_cache.0.stats().await
     }
+
+    /// Pin the index with the given UUID so it is never evicted from the index cache.
+    ///
+    /// Pinned index data is kept in addition to the index cache size, it does not
+    /// count towards that limit and is kept even if the index cache is disabled.
+    pub fn pin_index(&self, uuid: &str) {
+        self.index_cache.0.pin(uuid);
+    }
 }
 
 impl Default for Session {
@@ -186,7 +239,9 @@ impl Default for Session {
                 DEFAULT_METADATA_CACHE_SIZE,
             )),
             index_extensions: HashMap::new(),
+            hard_memory_limit: None,
             store_registry: Arc::new(ObjectStoreRegistry::default()),
+            commit_handler_registry: Arc::new(CommitHandlerRegistry::default()),
         }
     }
 }
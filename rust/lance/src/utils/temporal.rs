@@ -8,10 +8,13 @@
 use chrono::{DateTime, TimeZone, Utc};
 #[cfg(test)]
 use mock_instant::{SystemTime as NativeSystemTime, UNIX_EPOCH};
+use snafu::location;
 
 #[cfg(not(test))]
 use std::time::{SystemTime as NativeSystemTime, UNIX_EPOCH};
 
+use crate::{Error, Result};
+
 pub type SystemTime = NativeSystemTime;
 
 /// Mirror function that mimics DateTime<Utc>::now() with the exception that it
@@ -26,6 +29,43 @@ pub fn utc_now() -> DateTime<Utc> {
     Utc.from_utc_datetime(&naive)
 }
 
+/// A source of the current time
+///
+/// Datasets read "now" from a clock, see [`crate::dataset::builder::DatasetBuilder::with_clock`],
+/// so tests can control time without changing the (process-wide) system time.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A clock that reads the system time, the default
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        utc_now()
+    }
+}
+
+/// Convert the time of a [`Clock`] to a [`SystemTime`]
+///
+/// Returns an error if the time is before the Unix epoch or can't be represented in
+/// nanoseconds, which is how manifests store timestamps.
+pub fn to_system_time(time: DateTime<Utc>) -> Result<SystemTime> {
+    let nanos = time
+        .timestamp_nanos_opt()
+        .and_then(|nanos| u64::try_from(nanos).ok())
+        .ok_or_else(|| Error::InvalidInput {
+            source: format!(
+                "The clock time {} is out of range, it must be after the Unix epoch",
+                time
+            )
+            .into(),
+            location: location!(),
+        })?;
+    Ok(UNIX_EPOCH + std::time::Duration::from_nanos(nanos))
+}
+
 pub fn timestamp_to_nanos(timestamp: Option<SystemTime>) -> u128 {
     let timestamp = timestamp.unwrap_or_else(SystemTime::now);
     timestamp
@@ -1,34 +1,53 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
+use std::collections::{HashMap, VecDeque};
 use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
 use std::ops::Range;
 use std::sync::atomic::AtomicU16;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use arrow_array::types::{Float32Type, Int64Type};
 use arrow_array::{RecordBatch, RecordBatchIterator};
-use arrow_schema::Schema as ArrowSchema;
+use arrow_schema::{DataType, Schema as ArrowSchema};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use bytes::Bytes;
+use chrono::{DateTime, TimeDelta, Utc};
 use datafusion_physical_plan::ExecutionPlan;
-use futures::stream::BoxStream;
+use futures::stream::{self, BoxStream};
+use futures::{future, Future, FutureExt, StreamExt};
 use lance_arrow::RecordBatchExt;
-use lance_core::datatypes::Schema;
-use lance_datagen::{BatchCount, BatchGeneratorBuilder, ByteCount, RowCount};
+use lance_core::datatypes::{Field, Schema};
+use lance_datagen::{array, BatchCount, BatchGeneratorBuilder, ByteCount, Dimension, RowCount};
+use lance_file::v2;
 use lance_file::version::LanceFileVersion;
-use lance_io::object_store::WrappingObjectStore;
-use lance_table::format::Fragment;
+use lance_io::object_store::{
+    IoCounts, ObjectStore as LanceObjectStore, ObjectStoreParams, WrappingObjectStore,
+};
+use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
+use lance_io::utils::CachedFileSize;
+use lance_table::format::{DataFile, Fragment, RowIdMeta};
+use lance_table::rowids::{write_row_ids, RowIdSequence};
 use object_store::path::Path;
 use object_store::{
-    GetOptions, GetRange, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
-    PutMultipartOpts, PutOptions, PutPayload, PutResult, Result as OSResult, UploadPart,
+    GetOptions, GetRange, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result as OSResult,
+    UploadPart,
 };
 use rand::prelude::SliceRandom;
 use rand::{Rng, SeedableRng};
+use snafu::location;
 use tempfile::{tempdir, TempDir};
+use uuid::Uuid;
 
 use crate::dataset::fragment::write::FragmentCreateBuilder;
-use crate::dataset::transaction::Operation;
-use crate::dataset::WriteParams;
+use crate::dataset::transaction::{Operation, Transaction};
+use crate::dataset::{CommitBuilder, WriteParams, DATA_DIR};
+use crate::error::{Error, Result};
+use crate::session::Session;
+use crate::utils::temporal::Clock;
 use crate::Dataset;
 
 mod throttle_store;
@@ -44,8 +63,16 @@ pub struct TestDatasetGenerator {
     seed: Option<u64>,
     data: Vec<RecordBatch>,
     data_storage_version: LanceFileVersion,
+    field_id_mapping: Option<HashMap<FieldPath, i32>>,
+    store_params: Option<ObjectStoreParams>,
+    config: Option<HashMap<String, String>>,
+    row_id_seed: Option<u64>,
+    empty_fragment: bool,
 }
 
+/// The names of a field and its ancestors, starting from the top-level field.
+pub type FieldPath = Vec<String>;
+
 impl TestDatasetGenerator {
     /// Create a new dataset generator with the given data.
     ///
@@ -56,9 +83,39 @@ impl TestDatasetGenerator {
             data,
             seed: None,
             data_storage_version,
+            field_id_mapping: None,
+            store_params: None,
+            config: None,
+            row_id_seed: None,
+            empty_fragment: false,
         }
     }
 
+    /// Create a dataset generator with a single fragment of `num_columns`
+    /// scalar columns of mixed types, filled with random data.
+    ///
+    /// This is useful to measure metadata overhead at high column counts.
+    pub fn wide(num_columns: usize, rows: u64, data_storage_version: LanceFileVersion) -> Self {
+        const TYPES: [DataType; 6] = [
+            DataType::Int32,
+            DataType::Int64,
+            DataType::Float32,
+            DataType::Float64,
+            DataType::Utf8,
+            DataType::Boolean,
+        ];
+        let batch = (0..num_columns)
+            .fold(lance_datagen::gen(), |builder, i| {
+                builder.col(
+                    format!("col_{}", i),
+                    lance_datagen::array::rand_type(&TYPES[i % TYPES.len()]),
+                )
+            })
+            .into_batch_rows(RowCount::from(rows))
+            .unwrap();
+        Self::new(vec![batch], data_storage_version)
+    }
+
     /// Set the seed for the random number generator.
     ///
     /// If not set, a random seed will be generated on each call to [`Self::make_hostile`].
@@ -68,6 +125,121 @@ impl TestDatasetGenerator {
         self
     }
 
+    /// Use the given field ids instead of a random permutation.
+    ///
+    /// This is useful to reproduce a failure with a specific field id layout.
+    /// The mapping must assign a distinct id to every field (including nested
+    /// fields) and, like the random ids, be unsorted with at least one hole.
+    pub fn with_field_id_mapping(mut self, mapping: HashMap<FieldPath, i32>) -> Result<Self> {
+        let schema = Schema::try_from(self.data[0].schema().as_ref())?;
+        let paths = field_paths(&schema.fields, &[]);
+        let mut field_ids = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let id = mapping.get(path).ok_or_else(|| Error::InvalidInput {
+                source: format!("The field id mapping is missing the field {:?}", path).into(),
+                location: location!(),
+            })?;
+            if field_ids.contains(id) {
+                return Err(Error::InvalidInput {
+                    source: format!("The field id mapping assigns the id {} more than once", id)
+                        .into(),
+                    location: location!(),
+                });
+            }
+            field_ids.push(*id);
+        }
+        if mapping.len() != paths.len() {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "The field id mapping has {} entries but the schema has {} fields",
+                    mapping.len(),
+                    paths.len()
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        if let Some(violation) = check_field_ids(&field_ids) {
+            return Err(Error::InvalidInput {
+                source: format!("The field id mapping is not hostile: {:?}", violation).into(),
+                location: location!(),
+            });
+        }
+        self.field_id_mapping = Some(mapping);
+        Ok(self)
+    }
+
+    /// Write the dataset through the given object store params.
+    ///
+    /// Both the data files and the commit use these params, so an
+    /// `object_store_wrapper` (e.g. an [`IoTrackingStore`] or a
+    /// [`ThrottledStoreWrapper`]) sees every write made by [`Self::make_hostile`].
+    pub fn with_store_params(mut self, store_params: ObjectStoreParams) -> Self {
+        self.store_params = Some(store_params);
+        self
+    }
+
+    /// Commit the dataset with the given config values.
+    ///
+    /// The values are passed as `config_upsert_values` of the `Overwrite`
+    /// operation, so they can be read back with [`Dataset::config`].  Keys and
+    /// values must not be empty.
+    pub fn with_config(mut self, config: HashMap<String, String>) -> Result<Self> {
+        if let Some((key, value)) = config
+            .iter()
+            .find(|(key, value)| key.is_empty() || value.is_empty())
+        {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "The config must not have empty keys or values but has {:?} = {:?}",
+                    key, value
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        self.config = Some(config);
+        Ok(self)
+    }
+
+    /// Use stable row ids and give the fragments row id ranges in a shuffled order.
+    ///
+    /// The ranges still cover `0..num_rows` without overlapping, so row ids are
+    /// unique, but they do not increase with the fragment order.  The order is
+    /// reproducible from `seed`.  This needs at least two fragments, and the
+    /// dataset is at version 2 since the row ids are shuffled by a second commit.
+    pub fn with_shuffled_row_ids(mut self, seed: u64) -> Result<Self> {
+        if self.data.len() < 2 {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "Shuffled row ids need at least two fragments but there are {}",
+                    self.data.len()
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        self.row_id_seed = Some(seed);
+        Ok(self)
+    }
+
+    /// Add a fragment with no rows after the fragments of the data.
+    ///
+    /// The fragment has `physical_rows` of 0 and its fields are split across data
+    /// files like the other fragments, but the files are empty.  Since it holds no
+    /// data the empty fragment is exempt from the layout invariants, see
+    /// [`Self::why_not`].  Empty data files are only written in the 2.x format.
+    pub fn with_empty_fragment(mut self) -> Result<Self> {
+        if self.data_storage_version == LanceFileVersion::Legacy {
+            return Err(Error::InvalidInput {
+                source: "Empty fragments need a 2.x data storage version".into(),
+                location: location!(),
+            });
+        }
+        self.empty_fragment = true;
+        Ok(self)
+    }
+
     /// Make a new dataset that has a "hostile" layout.
     ///
     /// For this to be effective, there should be at least two top-level columns.
@@ -91,13 +263,15 @@ impl TestDatasetGenerator {
         // file. This prevents an infinite loop.
         let min_num_files = if self.data.len() > 1 { 1 } else { 2 };
 
+        // Share one session so every write reuses the same object store
+        let session = Arc::new(Session::default());
         let mut fragments = Vec::with_capacity(self.data.len());
         let mut id = 0;
 
         for batch in &self.data {
             loop {
                 let mut fragment = self
-                    .make_fragment(uri, batch, &schema, &mut rng, min_num_files)
+                    .make_fragment(uri, batch, &schema, &mut rng, min_num_files, &session)
                     .await;
 
                 let fields = field_structure(&fragment);
@@ -118,34 +292,61 @@ impl TestDatasetGenerator {
             }
         }
 
+        if self.empty_fragment {
+            // The layout of the empty fragment doesn't need to differ from the others
+            let batch = self.data[0].slice(0, 0);
+            let mut fragment = self
+                .make_fragment(uri, &batch, &schema, &mut rng, 1, &session)
+                .await;
+            fragment.id = id;
+            fragments.push(fragment);
+        }
+
         let operation = Operation::Overwrite {
             fragments,
             schema,
-            config_upsert_values: None,
+            config_upsert_values: self.config.clone(),
         };
 
-        Dataset::commit(
-            uri,
-            operation,
-            None,
-            Default::default(),
-            None,
-            Default::default(),
-            false,
-        )
-        .await
-        .unwrap()
+        let mut builder = CommitBuilder::new(uri)
+            .with_session(session)
+            .use_move_stable_row_ids(self.row_id_seed.is_some());
+        if let Some(store_params) = &self.store_params {
+            builder = builder.with_store_params(store_params.clone());
+        }
+        let dataset = builder
+            .execute(Transaction::new(0, operation, None, None))
+            .await
+            .unwrap();
+
+        match self.row_id_seed {
+            Some(seed) => shuffle_row_ids(dataset, seed).await,
+            None => dataset,
+        }
     }
 
     fn make_schema(&self, rng: &mut impl Rng) -> Schema {
         let arrow_schema = self.data[0].schema();
         let mut schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
 
+        if let Some(mapping) = &self.field_id_mapping {
+            // Validated by with_field_id_mapping
+            let field_ids = schema.fields_pre_order().map(|f| f.id).collect::<Vec<_>>();
+            let paths = field_paths(&schema.fields, &[]);
+            for (old_id, path) in field_ids.iter().zip(paths.iter()) {
+                let field = schema.mut_field_by_id(*old_id).unwrap();
+                field.id = mapping[path];
+            }
+            return schema;
+        }
+
         let field_ids = schema.fields_pre_order().map(|f| f.id).collect::<Vec<_>>();
         let mut new_ids = field_ids.clone();
-        // Add a hole
-        if new_ids.len() > 2 {
-            let hole_pos = rng.gen_range(1..new_ids.len() - 1);
+        // Add a hole by bumping every id from a random position onwards.  The
+        // first id is never bumped so the ids always start from the original
+        // minimum, and with only two ids the second id is bumped.
+        if new_ids.len() >= 2 {
+            let hole_pos = rng.gen_range(1..new_ids.len());
             for id in new_ids.iter_mut().skip(hole_pos) {
                 *id += 1;
             }
@@ -153,8 +354,8 @@ impl TestDatasetGenerator {
         // Randomize the order of ids
         loop {
             new_ids.shuffle(rng);
-            // In case we accidentally shuffled to the same order
-            if new_ids.len() == 1 || new_ids != field_ids {
+            // In case we accidentally shuffled back into sorted order
+            if check_field_ids(&new_ids).is_none() {
                 break;
             }
         }
@@ -173,6 +374,7 @@ impl TestDatasetGenerator {
         schema: &Schema,
         rng: &mut impl Rng,
         min_num_files: usize,
+        session: &Arc<Session>,
     ) -> Fragment {
         // Choose a random number of files.
         let num_files = if batch.num_columns() == 1 {
@@ -193,34 +395,34 @@ impl TestDatasetGenerator {
             .take(column_names.len())
             .collect::<Vec<_>>();
         file_assignments.shuffle(rng);
+        let mut columns_per_file = vec![Vec::new(); num_files];
+        for (name, file) in column_names.into_iter().zip(file_assignments) {
+            columns_per_file[file].push(name);
+        }
 
         // Write each as own fragment.
         let mut sub_fragments = Vec::with_capacity(num_files);
-        for file_id in 0..num_files {
-            let columns = column_names
-                .iter()
-                .zip(file_assignments.iter())
-                .filter_map(|(name, &file)| {
-                    if file == file_id {
-                        Some(name.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>();
+        for columns in columns_per_file {
             let file_schema = schema.project(&columns).unwrap();
             let file_arrow_schema = Arc::new(ArrowSchema::from(&file_schema));
             let data = batch.project_by_schema(file_arrow_schema.as_ref()).unwrap();
-            let reader = RecordBatchIterator::new(vec![Ok(data)], file_arrow_schema.clone());
-            let sub_frag = FragmentCreateBuilder::new(uri)
-                .schema(&file_schema)
-                .write_params(&WriteParams {
-                    data_storage_version: Some(self.data_storage_version),
-                    ..Default::default()
-                })
-                .write(reader, None)
-                .await
-                .unwrap();
+            let write_params = WriteParams {
+                data_storage_version: Some(self.data_storage_version),
+                store_params: self.store_params.clone(),
+                session: Some(session.clone()),
+                ..Default::default()
+            };
+            let sub_frag = if data.num_rows() == 0 {
+                write_empty_fragment(uri, &file_schema, &write_params).await
+            } else {
+                let reader = RecordBatchIterator::new(vec![Ok(data)], file_arrow_schema.clone());
+                FragmentCreateBuilder::new(uri)
+                    .schema(&file_schema)
+                    .write_params(&write_params)
+                    .write(reader, None)
+                    .await
+                    .unwrap()
+            };
 
             // The sub_fragment has it's own schema, with field ids that are local to
             // it. We need to remap the field ids to the global schema.
@@ -234,27 +436,242 @@ impl TestDatasetGenerator {
             .flat_map(|frag| frag.files.into_iter())
             .collect::<Vec<_>>();
 
-        // Make sure the field id order is distinct from the schema.
-        let schema_field_ids = schema.fields_pre_order().map(|f| f.id).collect::<Vec<_>>();
-        if files
-            .iter()
-            .flat_map(|file| file.fields.iter().cloned())
-            .collect::<Vec<_>>()
-            == schema_field_ids
-            && files.len() > 1
-        {
-            // Swap first two files
-            files.swap(0, 1);
-        }
-
-        Fragment {
+        let mut fragment = Fragment {
             id: 0,
             files,
             deletion_file: None,
             row_id_meta: None,
             physical_rows: Some(batch.num_rows()),
+        };
+
+        // Make sure the field id order is distinct from the schema.
+        let schema_field_ids = schema.fields_pre_order().map(|f| f.id).collect::<Vec<_>>();
+        if matches!(
+            check_field_order(&fragment, &schema_field_ids),
+            Some(HostileViolation::SchemaFieldOrder { .. })
+        ) {
+            // Swap first two files
+            fragment.files.swap(0, 1);
+        }
+
+        fragment
+    }
+
+    /// Returns true if the dataset satisfies all the invariants of a "hostile"
+    /// layout, as described in [`Self::make_hostile`].
+    pub fn is_hostile(dataset: &Dataset) -> bool {
+        Self::why_not(dataset).is_none()
+    }
+
+    /// Returns the first invariant of a "hostile" layout the dataset violates,
+    /// or `None` if the dataset is hostile.
+    ///
+    /// These are the same checks [`Self::make_hostile`] enforces. Single-column
+    /// datasets are exempt from the file layout checks, and a single fragment
+    /// only needs to be split into more than one file.  Fragments without rows
+    /// are ignored by the file layout checks.
+    pub fn why_not(dataset: &Dataset) -> Option<HostileViolation> {
+        let schema = dataset.schema();
+        let field_ids = schema.fields_pre_order().map(|f| f.id).collect::<Vec<_>>();
+        if let Some(violation) = check_field_ids(&field_ids) {
+            return Some(violation);
+        }
+
+        if schema.fields.len() <= 1 {
+            return None;
+        }
+
+        let fragments = dataset
+            .get_fragments()
+            .into_iter()
+            .filter(|frag| frag.metadata().physical_rows != Some(0))
+            .collect::<Vec<_>>();
+        let first_fields = fragments
+            .first()
+            .map(|frag| field_structure(frag.metadata()));
+        for (i, fragment) in fragments.iter().enumerate() {
+            let fragment = fragment.metadata();
+            if fragments.len() == 1 && fragment.files.len() < 2 {
+                return Some(HostileViolation::SingleFile {
+                    fragment_id: fragment.id,
+                });
+            }
+            if let Some(violation) = check_field_order(fragment, &field_ids) {
+                return Some(violation);
+            }
+            if i > 0 && first_fields.as_ref() == Some(&field_structure(fragment)) {
+                return Some(HostileViolation::SameLayout {
+                    fragment_id: fragment.id,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// An invariant of a "hostile" layout that a dataset does not satisfy.
+///
+/// See [`TestDatasetGenerator::why_not`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostileViolation {
+    /// The field ids are in sorted order.
+    SortedFieldIds,
+    /// The field ids are contiguous, there is no hole.
+    NoFieldIdHole,
+    /// The only fragment stores all of its fields in a single file.
+    SingleFile { fragment_id: u64 },
+    /// The fields across the data files are in the same order as the schema.
+    SchemaFieldOrder { fragment_id: u64 },
+    /// The fragment has the same arrangement of fields as the first fragment.
+    SameLayout { fragment_id: u64 },
+}
+
+impl Display for HostileViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SortedFieldIds => write!(f, "field ids are in sorted order"),
+            Self::NoFieldIdHole => write!(f, "field ids have no hole"),
+            Self::SingleFile { fragment_id } => {
+                write!(f, "fragment {} has a single data file", fragment_id)
+            }
+            Self::SchemaFieldOrder { fragment_id } => write!(
+                f,
+                "fragment {} stores fields in the same order as the schema",
+                fragment_id
+            ),
+            Self::SameLayout { fragment_id } => write!(
+                f,
+                "fragment {} has the same layout as the first fragment",
+                fragment_id
+            ),
+        }
+    }
+}
+
+/// The paths of the fields, and their children, in pre-order.
+fn field_paths(fields: &[Field], parent: &[String]) -> Vec<FieldPath> {
+    let mut paths = Vec::new();
+    for field in fields {
+        let mut path = parent.to_vec();
+        path.push(field.name.clone());
+        paths.push(path.clone());
+        paths.extend(field_paths(&field.children, &path));
+    }
+    paths
+}
+
+/// Check the field ids (in schema pre-order) are unsorted and have a hole.
+fn check_field_ids(field_ids: &[i32]) -> Option<HostileViolation> {
+    let mut sorted_ids = field_ids.to_vec();
+    sorted_ids.sort_unstable();
+    if field_ids.len() > 1 && field_ids == sorted_ids {
+        return Some(HostileViolation::SortedFieldIds);
+    }
+    if field_ids.len() > 1 && sorted_ids.windows(2).all(|w| w[1] - w[0] == 1) {
+        return Some(HostileViolation::NoFieldIdHole);
+    }
+    None
+}
+
+/// Check a multi-file fragment does not store its fields in schema order.
+fn check_field_order(fragment: &Fragment, schema_field_ids: &[i32]) -> Option<HostileViolation> {
+    let file_field_ids = fragment
+        .files
+        .iter()
+        .flat_map(|file| file.fields.iter().cloned())
+        .collect::<Vec<_>>();
+    if fragment.files.len() > 1 && file_field_ids == schema_field_ids {
+        Some(HostileViolation::SchemaFieldOrder {
+            fragment_id: fragment.id,
+        })
+    } else {
+        None
+    }
+}
+
+/// Write a fragment with a single data file that has the fields of `schema` and no rows.
+///
+/// [`FragmentCreateBuilder`] rejects empty data, so the file is written directly.
+async fn write_empty_fragment(uri: &str, schema: &Schema, params: &WriteParams) -> Fragment {
+    let (object_store, base_path) = LanceObjectStore::from_uri_and_params(
+        params.store_registry(),
+        uri,
+        &params.store_params.clone().unwrap_or_default(),
+    )
+    .await
+    .unwrap();
+    let filename = format!("{}.lance", Uuid::new_v4());
+    let object_writer = object_store
+        .create(&base_path.child(DATA_DIR).child(filename.as_str()))
+        .await
+        .unwrap();
+    let mut writer = v2::writer::FileWriter::try_new(
+        object_writer,
+        schema.clone(),
+        v2::writer::FileWriterOptions {
+            format_version: params.data_storage_version,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(writer.finish().await.unwrap(), 0);
+
+    let (major, minor) = writer.version().to_numbers();
+    let (fields, column_indices): (Vec<i32>, Vec<i32>) = writer
+        .field_id_to_column_indices()
+        .iter()
+        .map(|(field_id, column_index)| (*field_id as i32, *column_index as i32))
+        .unzip();
+    let mut fragment = Fragment::new(0);
+    fragment.files = vec![DataFile::new(
+        filename,
+        fields,
+        column_indices,
+        major,
+        minor,
+        None,
+    )];
+    fragment.physical_rows = Some(0);
+    fragment
+}
+
+/// Reassign the row ids of each fragment so the ranges are in a random order.
+async fn shuffle_row_ids(dataset: Dataset, seed: u64) -> Dataset {
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+    let mut fragments = dataset.manifest.fragments.as_ref().clone();
+    let mut order = (0..fragments.len()).collect::<Vec<_>>();
+    loop {
+        order.shuffle(&mut rng);
+        // In case we shuffled back into fragment order
+        if order.windows(2).any(|w| w[0] > w[1]) {
+            break;
         }
     }
+
+    let mut next_row_id = 0;
+    for index in order {
+        let fragment = &mut fragments[index];
+        let num_rows = fragment.physical_rows.unwrap() as u64;
+        let sequence = RowIdSequence::from(next_row_id..(next_row_id + num_rows));
+        fragment.row_id_meta = Some(RowIdMeta::Inline(write_row_ids(&sequence)));
+        next_row_id += num_rows;
+    }
+    // The ranges must cover the same row ids so they are still globally unique
+    assert_eq!(next_row_id, dataset.manifest.next_row_id);
+
+    let operation = Operation::Update {
+        removed_fragment_ids: vec![],
+        updated_fragments: fragments,
+        new_fragments: vec![],
+        fields_modified: vec![],
+        mem_wal_to_flush: None,
+    };
+    let read_version = dataset.manifest.version;
+    CommitBuilder::new(Arc::new(dataset))
+        .execute(Transaction::new(read_version, operation, None, None))
+        .await
+        .unwrap()
 }
 
 fn get_field_structure(dataset: &Dataset) -> Vec<Vec<Vec<i32>>> {
@@ -273,15 +690,285 @@ fn field_structure(fragment: &Fragment) -> Vec<Vec<i32>> {
         .collect::<Vec<_>>()
 }
 
+/// Flip the bits of `byte_range` in a page of a column, directly in the data file.
+///
+/// `column` is the name (or dotted path) of the field and `page` is the index of the page in
+/// the column of the fragment's data file.  `byte_range` is relative to the start of the
+/// page's first buffer and may not extend past the end of the page's last buffer.  Only the
+/// data file changes, the manifest and the file metadata still describe the original data.
+///
+/// This is only possible for 2.x data files and panics if the page can't be found.
+pub async fn corrupt_buffer(
+    dataset: &Dataset,
+    fragment_id: u64,
+    column: &str,
+    page: usize,
+    byte_range: Range<u64>,
+) {
+    let field_id = dataset
+        .schema()
+        .field(column)
+        .unwrap_or_else(|| panic!("The dataset has no field named {}", column))
+        .id;
+    let fragment = dataset
+        .get_fragment(fragment_id as usize)
+        .unwrap_or_else(|| panic!("The dataset has no fragment {}", fragment_id));
+    let data_file = fragment
+        .metadata()
+        .files
+        .iter()
+        .find(|file| file.fields.contains(&field_id))
+        .unwrap_or_else(|| panic!("Fragment {} does not store {}", fragment_id, column));
+    let field_index = data_file
+        .fields
+        .iter()
+        .position(|id| *id == field_id)
+        .unwrap();
+    let column_index = *data_file
+        .column_indices
+        .get(field_index)
+        .expect("Only 2.x data files have pages") as usize;
+    let path = dataset.data_dir().child(data_file.path.as_str());
+
+    let scheduler = ScanScheduler::new(
+        dataset.object_store.clone(),
+        SchedulerConfig::max_bandwidth(&dataset.object_store),
+    );
+    let file_scheduler = scheduler
+        .open_file(&path, &CachedFileSize::unknown())
+        .await
+        .unwrap();
+    let metadata = v2::reader::FileReader::read_all_metadata(&file_scheduler)
+        .await
+        .unwrap();
+    let buffers = &metadata.column_infos[column_index].page_infos[page].buffer_offsets_and_sizes;
+    let page_start = buffers.iter().map(|(offset, _)| *offset).min().unwrap();
+    let page_end = buffers
+        .iter()
+        .map(|(offset, size)| offset + size)
+        .max()
+        .unwrap();
+    assert!(
+        page_start + byte_range.end <= page_end,
+        "{:?} is outside of the {} bytes of page {} of {}",
+        byte_range,
+        page_end - page_start,
+        page,
+        column
+    );
+
+    let mut data = dataset
+        .object_store
+        .inner
+        .get(&path)
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap()
+        .to_vec();
+    let corrupt_range =
+        (page_start + byte_range.start) as usize..(page_start + byte_range.end) as usize;
+    for byte in &mut data[corrupt_range] {
+        *byte = !*byte;
+    }
+    dataset
+        .object_store
+        .inner
+        .put(&path, Bytes::from(data).into())
+        .await
+        .unwrap();
+}
+
 #[derive(Debug, Default)]
 pub struct IoStats {
     pub read_iops: u64,
     pub read_bytes: u64,
+    /// Number of metadata requests (`head` and `list*`). These are not counted
+    /// in `read_iops`.
+    pub metadata_iops: u64,
     pub write_iops: u64,
     pub write_bytes: u64,
+    /// Number of multipart uploads started.
+    pub multipart_uploads: u64,
+    /// Number of parts uploaded across all multipart uploads. Each part is also
+    /// counted in `write_iops`.
+    pub multipart_parts: u64,
+    /// Number of multipart uploads that were completed.
+    pub multipart_completed: u64,
+    /// Number of multipart uploads that were aborted.
+    pub multipart_aborted: u64,
+    /// Time spent in `put`, `put_opts`, `put_part`, `copy` and `rename` requests,
+    /// summed over the requests (so concurrent requests are counted separately).
+    ///
+    /// This is only measured if the store was created with write timing (see
+    /// [`StatsHolder::with_write_timing`]) and is zero otherwise.
+    pub total_write_latency: Duration,
+    /// The part of [`Self::total_write_latency`] spent in multipart `put_part`
+    /// requests.
+    pub multipart_part_latency: Duration,
     /// Number of disjoint periods where at least one IO is in-flight.
+    ///
+    /// This is unavailable, and always zero, if the store was created without hop
+    /// tracking (see [`StatsHolder::without_hop_tracking`]).
     pub num_hops: u64,
-    pub requests: Vec<IoRequestRecord>,
+    /// Individual read and metadata requests. If the store was created with a
+    /// `min_record_bytes` threshold, smaller requests (including all metadata
+    /// requests) are not recorded here but are still counted in the totals.
+    ///
+    /// If the store was created with a record capacity (see
+    /// [`StatsHolder::with_record_capacity`]) only the most recent records are
+    /// kept, older records are evicted.
+    pub requests: VecDeque<IoRequestRecord>,
+    /// Number of requests that were recorded, including any that have since been
+    /// evicted from [`Self::requests`].
+    pub recorded_requests: u64,
+    /// The metadata (size, etag, last modified) of the objects returned by `head`,
+    /// `get`, `get_opts` and `list*` requests, in the order they were returned.
+    ///
+    /// This is only recorded if the store was created with object metadata
+    /// recording (see [`StatsHolder::with_object_meta`]) and is empty otherwise.
+    ///
+    /// Like [`Self::requests`], only the most recent records are kept if the store was
+    /// created with a record capacity.
+    pub object_meta: VecDeque<ObjectMetaRecord>,
+}
+
+impl IoStats {
+    /// Add the counts of `other` to these stats and append its requests.
+    ///
+    /// `num_hops` is summed like the other counters.  Hops in different stores
+    /// that overlapped in time are counted separately so this is an upper bound
+    /// on the number of hops across all of the stores, not a true global count.
+    pub fn merge(&mut self, other: &Self) {
+        let Self {
+            read_iops,
+            read_bytes,
+            metadata_iops,
+            write_iops,
+            write_bytes,
+            multipart_uploads,
+            multipart_parts,
+            multipart_completed,
+            multipart_aborted,
+            total_write_latency,
+            multipart_part_latency,
+            num_hops,
+            requests,
+            recorded_requests,
+            object_meta,
+        } = other;
+        self.read_iops += read_iops;
+        self.read_bytes += read_bytes;
+        self.metadata_iops += metadata_iops;
+        self.write_iops += write_iops;
+        self.write_bytes += write_bytes;
+        self.multipart_uploads += multipart_uploads;
+        self.multipart_parts += multipart_parts;
+        self.multipart_completed += multipart_completed;
+        self.multipart_aborted += multipart_aborted;
+        self.total_write_latency += *total_write_latency;
+        self.multipart_part_latency += *multipart_part_latency;
+        self.num_hops += num_hops;
+        self.requests.extend(requests.iter().cloned());
+        self.recorded_requests += recorded_requests;
+        self.object_meta.extend(object_meta.iter().cloned());
+    }
+
+    /// The most recently returned metadata of the object at `path`, if any.
+    ///
+    /// Comparing the etag of this before and after an operation shows whether the
+    /// object was rewritten in between.
+    pub fn latest_object_meta(&self, path: &Path) -> Option<&ObjectMeta> {
+        self.object_meta
+            .iter()
+            .rev()
+            .map(|record| &record.meta)
+            .find(|meta| &meta.location == path)
+    }
+
+    /// Write throughput in MB/s, `write_bytes` over [`Self::total_write_latency`]
+    ///
+    /// This is `None` if no write latency was measured.
+    pub fn write_throughput_mbps(&self) -> Option<f64> {
+        if self.total_write_latency.is_zero() {
+            return None;
+        }
+        Some(self.write_bytes as f64 / 1_000_000.0 / self.total_write_latency.as_secs_f64())
+    }
+
+    /// Write amplification, `write_bytes` over the `logical_bytes` of data written
+    ///
+    /// `logical_bytes` is the size of the data the operation needed to write, e.g. the
+    /// row count times the width of each row for fixed-width columns.  A value well above
+    /// 1.0 means the operation rewrote more than necessary.  This is `None` if
+    /// `logical_bytes` is zero.
+    pub fn write_amplification(&self, logical_bytes: u64) -> Option<f64> {
+        if logical_bytes == 0 {
+            return None;
+        }
+        Some(self.write_bytes as f64 / logical_bytes as f64)
+    }
+
+    /// Group the reads of data files by the id of the fragment that owns them.
+    ///
+    /// Reads are matched to the fragments of `dataset` by path, so only the reads
+    /// in [`Self::requests`] are counted (see `min_record_bytes`).  Reads of other
+    /// files, such as manifests and indices, are left out.  Each fragment's stats
+    /// have `read_iops`, `read_bytes` and `requests` set.
+    pub fn by_fragment(&self, dataset: &Dataset) -> HashMap<u64, Self> {
+        let data_dir = dataset.data_dir();
+        let owners = dataset
+            .get_fragments()
+            .iter()
+            .flat_map(|fragment| {
+                let id = fragment.id() as u64;
+                let data_dir = &data_dir;
+                fragment
+                    .metadata()
+                    .files
+                    .iter()
+                    .map(move |file| (data_dir.child(file.path.as_str()), id))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut by_fragment = HashMap::<u64, Self>::new();
+        for request in &self.requests {
+            if request.method == "head" || request.method.starts_with("list") {
+                continue;
+            }
+            let Some(id) = owners.get(&request.path) else {
+                continue;
+            };
+            let stats = by_fragment.entry(*id).or_default();
+            stats.read_iops += 1;
+            stats.read_bytes += request.num_bytes;
+            stats.requests.push_back(request.clone());
+            stats.recorded_requests += 1;
+        }
+        by_fragment
+    }
+}
+
+/// Write the reads recorded in `stats` to a trace file at `path`.
+///
+/// Only requests recorded with their data, see
+/// [`StatsHolder::with_recorded_data`], are written.  The trace
+/// can be served back with a [`ReplayStore`].
+pub fn record_io_trace(stats: &IoStats, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    for request in &stats.requests {
+        let (Some(range), Some(data)) = (&request.range, &request.data) else {
+            continue;
+        };
+        let location = request.path.as_ref().as_bytes();
+        writer.write_u32::<LittleEndian>(location.len() as u32)?;
+        writer.write_all(location)?;
+        writer.write_u64::<LittleEndian>(range.start)?;
+        writer.write_u64::<LittleEndian>(range.end)?;
+        writer.write_all(data)?;
+    }
+    writer.flush()
 }
 
 // These fields are "dead code" because we just use them right now to display
@@ -292,11 +979,26 @@ pub struct IoRequestRecord {
     pub method: &'static str,
     pub path: Path,
     pub range: Option<Range<u64>>,
+    /// Number of bytes read, zero for metadata requests.
+    pub num_bytes: u64,
+    /// The bytes returned by the request, only recorded if requested.
+    pub data: Option<Bytes>,
+}
+
+/// The metadata of an object returned by a request, see [`IoStats::object_meta`].
+#[derive(Debug, Clone)]
+pub struct ObjectMetaRecord {
+    pub method: &'static str,
+    pub meta: ObjectMeta,
 }
 
 impl Display for IoStats {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:#?}", self)
+        write!(f, "{:#?}", self)?;
+        if let Some(throughput) = self.write_throughput_mbps() {
+            write!(f, "\nwrite throughput: {:.2} MB/s", throughput)?;
+        }
+        Ok(())
     }
 }
 
@@ -304,6 +1006,12 @@ impl Display for IoStats {
 pub struct IoTrackingStore {
     target: Arc<dyn ObjectStore>,
     stats: Arc<Mutex<IoStats>>,
+    min_record_bytes: u64,
+    record_data: bool,
+    skip_hops: bool,
+    time_writes: bool,
+    record_capacity: Option<usize>,
+    record_object_meta: bool,
     active_requests: Arc<AtomicU16>,
 }
 
@@ -314,11 +1022,139 @@ impl Display for IoTrackingStore {
 }
 
 #[derive(Debug, Default, Clone)]
-pub struct StatsHolder(Arc<Mutex<IoStats>>);
+pub struct StatsHolder {
+    stats: Arc<Mutex<IoStats>>,
+    min_record_bytes: u64,
+    record_data: bool,
+    skip_hops: bool,
+    time_writes: bool,
+    record_capacity: Option<usize>,
+    record_object_meta: bool,
+    // Shared by all of the wrapped stores since they share the stats
+    active_requests: Arc<AtomicU16>,
+}
 
 impl StatsHolder {
+    /// Only record requests of at least `min_record_bytes` in [`IoStats::requests`].
+    ///
+    /// This keeps memory bounded for large scans.  The details of smaller requests,
+    /// including all metadata requests, are dropped but they are still counted in
+    /// the IOPS and byte totals.
+    pub fn with_min_record_bytes(mut self, min_record_bytes: u64) -> Self {
+        self.min_record_bytes = min_record_bytes;
+        self
+    }
+
+    /// Also record the bytes returned by each read in [`IoStats::requests`], so the
+    /// reads can be saved with [`record_io_trace`].
+    ///
+    /// Every byte read is held in memory so this should only be used for small
+    /// datasets.
+    pub fn with_recorded_data(mut self) -> Self {
+        self.record_data = true;
+        self
+    }
+
+    /// Do not track [`IoStats::num_hops`], which will always be zero.
+    ///
+    /// Hop tracking touches a shared atomic and the stats lock at the start and end
+    /// of every request.  Disabling it trims that contention in micro-benchmarks
+    /// that only care about the IOPS and byte totals.
+    pub fn without_hop_tracking(mut self) -> Self {
+        self.skip_hops = true;
+        self
+    }
+
+    /// Also measure the latency of write requests, see [`IoStats::total_write_latency`].
+    ///
+    /// Timing is opt-in since it adds a clock read, and a lock, to every write.
+    pub fn with_write_timing(mut self) -> Self {
+        self.time_writes = true;
+        self
+    }
+
+    /// Keep at most `capacity` records in [`IoStats::requests`] and
+    /// [`IoStats::object_meta`], evicting the oldest.
+    ///
+    /// This bounds the memory of long running tests while keeping the most recent
+    /// requests around to diagnose failures.  Evicted requests are still counted in
+    /// the totals and in [`IoStats::recorded_requests`].
+    pub fn with_record_capacity(mut self, capacity: usize) -> Self {
+        self.record_capacity = Some(capacity);
+        self
+    }
+
+    /// Also record the metadata of the objects returned by `head`, `get`, `get_opts`
+    /// and `list*` requests, see [`IoStats::object_meta`].
+    ///
+    /// This is opt-in since it clones the metadata, and takes the stats lock once
+    /// per listed object.
+    pub fn with_object_meta(mut self) -> Self {
+        self.record_object_meta = true;
+        self
+    }
+
     pub fn incremental_stats(&self) -> IoStats {
-        std::mem::take(&mut *self.0.lock().unwrap())
+        std::mem::take(&mut *self.stats.lock().unwrap())
+    }
+
+    /// Take the incremental stats of each holder and merge them, see [`IoStats::merge`].
+    ///
+    /// Each holder is reset as if [`Self::incremental_stats`] was called on it.
+    /// Holders that share stats (e.g. clones) are only counted once.
+    pub fn merged(holders: &[&Self]) -> IoStats {
+        let mut merged = IoStats::default();
+        let mut seen = Vec::with_capacity(holders.len());
+        for holder in holders {
+            if seen.iter().any(|stats| Arc::ptr_eq(stats, &holder.stats)) {
+                continue;
+            }
+            seen.push(holder.stats.clone());
+            merged.merge(&holder.incremental_stats());
+        }
+        merged
+    }
+
+    /// Like [`Self::incremental_stats`] but waits until no requests are in flight.
+    ///
+    /// The stats are swapped while nothing is in flight so every request, and every
+    /// hop, is counted entirely in one phase.  This may wait briefly for in-flight
+    /// requests (and open multipart uploads) to finish.
+    ///
+    /// Without hop tracking in-flight requests are not known so this returns
+    /// immediately, like [`Self::incremental_stats`].
+    ///
+    /// Panics if requests are still in flight after 30 seconds, for example because a
+    /// multipart upload was never completed or aborted.
+    pub async fn reset_quiescent(&self) -> IoStats {
+        self.reset_quiescent_with_timeout(Duration::from_secs(30))
+            .await
+    }
+
+    /// Like [`Self::reset_quiescent`] but panics if requests are still in flight
+    /// after `timeout`.
+    pub async fn reset_quiescent_with_timeout(&self, timeout: Duration) -> IoStats {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let active_requests = {
+                // Requests start and finish while holding the stats lock
+                let mut stats = self.stats.lock().unwrap();
+                let active_requests = self
+                    .active_requests
+                    .load(std::sync::atomic::Ordering::SeqCst);
+                if active_requests == 0 {
+                    return std::mem::take(&mut *stats);
+                }
+                active_requests
+            };
+            if Instant::now() >= deadline {
+                panic!(
+                    "{} requests were still in flight after waiting {:?} for the store to be quiescent",
+                    active_requests, timeout
+                );
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
     }
 }
 
@@ -326,16 +1162,52 @@ impl WrappingObjectStore for StatsHolder {
     fn wrap(&self, target: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
         Arc::new(IoTrackingStore {
             target,
-            stats: self.0.clone(),
-            active_requests: Arc::new(AtomicU16::new(0)),
+            stats: self.stats.clone(),
+            min_record_bytes: self.min_record_bytes,
+            record_data: self.record_data,
+            skip_hops: self.skip_hops,
+            time_writes: self.time_writes,
+            record_capacity: self.record_capacity,
+            record_object_meta: self.record_object_meta,
+            active_requests: self.active_requests.clone(),
+        })
+    }
+
+    fn io_counts(&self) -> Option<IoCounts> {
+        let stats = self.stats.lock().unwrap();
+        Some(IoCounts {
+            read_iops: stats.read_iops,
+            read_bytes: stats.read_bytes,
         })
     }
 }
 
 impl IoTrackingStore {
     pub fn new_wrapper() -> (Arc<dyn WrappingObjectStore>, Arc<Mutex<IoStats>>) {
-        let stats = Arc::new(Mutex::new(IoStats::default()));
-        (Arc::new(StatsHolder(stats.clone())), stats)
+        Self::new_wrapper_with(StatsHolder::default())
+    }
+
+    /// Like [`Self::new_wrapper`] but the stores record what `holder` was configured
+    /// to record, e.g. `StatsHolder::default().with_write_timing()`.
+    pub fn new_wrapper_with(
+        holder: StatsHolder,
+    ) -> (Arc<dyn WrappingObjectStore>, Arc<Mutex<IoStats>>) {
+        let stats = holder.stats.clone();
+        (Arc::new(holder), stats)
+    }
+
+    // Add a record, evicting the oldest record if the capacity is reached
+    fn push_record(&self, stats: &mut IoStats, record: IoRequestRecord) {
+        stats.recorded_requests += 1;
+        if let Some(capacity) = self.record_capacity {
+            if capacity == 0 {
+                return;
+            }
+            while stats.requests.len() >= capacity {
+                stats.requests.pop_front();
+            }
+        }
+        stats.requests.push_back(record);
     }
 
     fn record_read(
@@ -348,22 +1220,133 @@ impl IoTrackingStore {
         let mut stats = self.stats.lock().unwrap();
         stats.read_iops += 1;
         stats.read_bytes += num_bytes;
-        stats.requests.push(IoRequestRecord {
-            method,
-            path,
-            range,
-        });
+        if num_bytes >= self.min_record_bytes {
+            self.push_record(
+                &mut stats,
+                IoRequestRecord {
+                    method,
+                    path,
+                    range,
+                    num_bytes,
+                    data: None,
+                },
+            );
+        }
     }
 
-    fn record_write(&self, num_bytes: u64) {
+    // Like `record_read` but keeps the bytes of each range that was read
+    fn record_read_data(&self, method: &'static str, path: Path, reads: Vec<(Range<u64>, Bytes)>) {
         let mut stats = self.stats.lock().unwrap();
-        stats.write_iops += 1;
-        stats.write_bytes += num_bytes;
-    }
+        stats.read_iops += 1;
+        for (range, data) in reads {
+            stats.read_bytes += data.len() as u64;
+            if data.len() as u64 >= self.min_record_bytes {
+                self.push_record(
+                    &mut stats,
+                    IoRequestRecord {
+                        method,
+                        path: path.clone(),
+                        range: Some(range),
+                        num_bytes: data.len() as u64,
+                        data: Some(data),
+                    },
+                );
+            }
+        }
+    }
+
+    fn record_metadata(&self, method: &'static str, path: Path) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.metadata_iops += 1;
+        if self.min_record_bytes == 0 {
+            self.push_record(
+                &mut stats,
+                IoRequestRecord {
+                    method,
+                    path,
+                    range: None,
+                    num_bytes: 0,
+                    data: None,
+                },
+            );
+        }
+    }
+
+    fn record_object_meta(&self, method: &'static str, meta: &ObjectMeta) {
+        if self.record_object_meta {
+            record_object_meta(&self.stats, self.record_capacity, method, meta);
+        }
+    }
+
+    // Records the metadata of each object in a listing as it is returned
+    fn record_listing(
+        &self,
+        method: &'static str,
+        listing: BoxStream<'static, OSResult<ObjectMeta>>,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        if !self.record_object_meta {
+            return listing;
+        }
+        let stats = self.stats.clone();
+        let record_capacity = self.record_capacity;
+        listing
+            .inspect(move |meta| {
+                if let Ok(meta) = meta {
+                    record_object_meta(&stats, record_capacity, method, meta);
+                }
+            })
+            .boxed()
+    }
+
+    fn record_write(&self, num_bytes: u64) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.write_iops += 1;
+        stats.write_bytes += num_bytes;
+    }
+
+    // Await a write request, adding its latency to the stats if writes are timed
+    async fn time_write<T>(&self, write: impl Future<Output = T>) -> T {
+        if !self.time_writes {
+            return write.await;
+        }
+        let start = Instant::now();
+        let result = write.await;
+        self.stats.lock().unwrap().total_write_latency += start.elapsed();
+        result
+    }
 
-    fn hop_guard(&self) -> HopGuard {
-        HopGuard::new(self.active_requests.clone(), self.stats.clone())
+    fn hop_guard(&self) -> Option<HopGuard> {
+        if self.skip_hops {
+            None
+        } else {
+            Some(HopGuard::new(
+                self.active_requests.clone(),
+                self.stats.clone(),
+            ))
+        }
+    }
+}
+
+// Add an object meta record, evicting the oldest record if the capacity is reached
+fn record_object_meta(
+    stats: &Mutex<IoStats>,
+    record_capacity: Option<usize>,
+    method: &'static str,
+    meta: &ObjectMeta,
+) {
+    let mut stats = stats.lock().unwrap();
+    if let Some(capacity) = record_capacity {
+        if capacity == 0 {
+            return;
+        }
+        while stats.object_meta.len() >= capacity {
+            stats.object_meta.pop_front();
+        }
     }
+    stats.object_meta.push_back(ObjectMetaRecord {
+        method,
+        meta: meta.clone(),
+    });
 }
 
 #[async_trait::async_trait]
@@ -372,7 +1355,7 @@ impl ObjectStore for IoTrackingStore {
     async fn put(&self, location: &Path, bytes: PutPayload) -> OSResult<PutResult> {
         let _guard = self.hop_guard();
         self.record_write(bytes.content_length() as u64);
-        self.target.put(location, bytes).await
+        self.time_write(self.target.put(location, bytes)).await
     }
 
     async fn put_opts(
@@ -383,15 +1366,18 @@ impl ObjectStore for IoTrackingStore {
     ) -> OSResult<PutResult> {
         let _guard = self.hop_guard();
         self.record_write(bytes.content_length() as u64);
-        self.target.put_opts(location, bytes, opts).await
+        self.time_write(self.target.put_opts(location, bytes, opts))
+            .await
     }
 
     async fn put_multipart(&self, location: &Path) -> OSResult<Box<dyn MultipartUpload>> {
         let _guard = self.hop_guard();
         let target = self.target.put_multipart(location).await?;
+        self.stats.lock().unwrap().multipart_uploads += 1;
         Ok(Box::new(IoTrackingMultipartUpload {
             target,
             stats: self.stats.clone(),
+            time_writes: self.time_writes,
             _guard,
         }))
     }
@@ -403,19 +1389,32 @@ impl ObjectStore for IoTrackingStore {
     ) -> OSResult<Box<dyn MultipartUpload>> {
         let _guard = self.hop_guard();
         let target = self.target.put_multipart_opts(location, opts).await?;
+        self.stats.lock().unwrap().multipart_uploads += 1;
         Ok(Box::new(IoTrackingMultipartUpload {
             target,
             stats: self.stats.clone(),
+            time_writes: self.time_writes,
             _guard,
         }))
     }
 
     async fn get(&self, location: &Path) -> OSResult<GetResult> {
         let _guard = self.hop_guard();
+        if self.record_data {
+            let (result, data) = buffer_get_result(self.target.get(location).await?).await?;
+            self.record_object_meta("get", &result.meta);
+            self.record_read_data(
+                "get",
+                location.to_owned(),
+                vec![(result.range.clone(), data)],
+            );
+            return Ok(result);
+        }
         let result = self.target.get(location).await;
         if let Ok(result) = &result {
             let num_bytes = result.range.end - result.range.start;
             self.record_read("get", location.to_owned(), num_bytes, None);
+            self.record_object_meta("get", &result.meta);
         }
         result
     }
@@ -426,11 +1425,23 @@ impl ObjectStore for IoTrackingStore {
             Some(GetRange::Bounded(range)) => Some(range.clone()),
             _ => None, // TODO: fill in other options.
         };
+        if self.record_data {
+            let result = self.target.get_opts(location, options).await?;
+            let (result, data) = buffer_get_result(result).await?;
+            self.record_object_meta("get_opts", &result.meta);
+            self.record_read_data(
+                "get_opts",
+                location.to_owned(),
+                vec![(result.range.clone(), data)],
+            );
+            return Ok(result);
+        }
         let result = self.target.get_opts(location, options).await;
         if let Ok(result) = &result {
             let num_bytes = result.range.end - result.range.start;
 
             self.record_read("get_opts", location.to_owned(), num_bytes, range);
+            self.record_object_meta("get_opts", &result.meta);
         }
         result
     }
@@ -438,6 +1449,16 @@ impl ObjectStore for IoTrackingStore {
     async fn get_range(&self, location: &Path, range: Range<u64>) -> OSResult<Bytes> {
         let _guard = self.hop_guard();
         let result = self.target.get_range(location, range.clone()).await;
+        if self.record_data {
+            if let Ok(result) = &result {
+                self.record_read_data(
+                    "get_range",
+                    location.to_owned(),
+                    vec![(range, result.clone())],
+                );
+            }
+            return result;
+        }
         if let Ok(result) = &result {
             self.record_read(
                 "get_range",
@@ -452,6 +1473,13 @@ impl ObjectStore for IoTrackingStore {
     async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> OSResult<Vec<Bytes>> {
         let _guard = self.hop_guard();
         let result = self.target.get_ranges(location, ranges).await;
+        if self.record_data {
+            if let Ok(result) = &result {
+                let reads = ranges.iter().cloned().zip(result.iter().cloned()).collect();
+                self.record_read_data("get_ranges", location.to_owned(), reads);
+            }
+            return result;
+        }
         if let Ok(result) = &result {
             self.record_read(
                 "get_ranges",
@@ -465,8 +1493,10 @@ impl ObjectStore for IoTrackingStore {
 
     async fn head(&self, location: &Path) -> OSResult<ObjectMeta> {
         let _guard = self.hop_guard();
-        self.record_read("head", location.to_owned(), 0, None);
-        self.target.head(location).await
+        self.record_metadata("head", location.to_owned());
+        let meta = self.target.head(location).await?;
+        self.record_object_meta("head", &meta);
+        Ok(meta)
     }
 
     async fn delete(&self, location: &Path) -> OSResult<()> {
@@ -484,8 +1514,8 @@ impl ObjectStore for IoTrackingStore {
 
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
         let _guard = self.hop_guard();
-        self.record_read("list", prefix.cloned().unwrap_or_default(), 0, None);
-        self.target.list(prefix)
+        self.record_metadata("list", prefix.cloned().unwrap_or_default());
+        self.record_listing("list", self.target.list(prefix))
     }
 
     fn list_with_offset(
@@ -493,48 +1523,47 @@ impl ObjectStore for IoTrackingStore {
         prefix: Option<&Path>,
         offset: &Path,
     ) -> BoxStream<'static, OSResult<ObjectMeta>> {
-        self.record_read(
+        self.record_metadata("list_with_offset", prefix.cloned().unwrap_or_default());
+        self.record_listing(
             "list_with_offset",
-            prefix.cloned().unwrap_or_default(),
-            0,
-            None,
-        );
-        self.target.list_with_offset(prefix, offset)
+            self.target.list_with_offset(prefix, offset),
+        )
     }
 
     async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
         let _guard = self.hop_guard();
-        self.record_read(
-            "list_with_delimiter",
-            prefix.cloned().unwrap_or_default(),
-            0,
-            None,
-        );
-        self.target.list_with_delimiter(prefix).await
+        self.record_metadata("list_with_delimiter", prefix.cloned().unwrap_or_default());
+        let result = self.target.list_with_delimiter(prefix).await?;
+        for meta in &result.objects {
+            self.record_object_meta("list_with_delimiter", meta);
+        }
+        Ok(result)
     }
 
     async fn copy(&self, from: &Path, to: &Path) -> OSResult<()> {
         let _guard = self.hop_guard();
         self.record_write(0);
-        self.target.copy(from, to).await
+        self.time_write(self.target.copy(from, to)).await
     }
 
     async fn rename(&self, from: &Path, to: &Path) -> OSResult<()> {
         let _guard = self.hop_guard();
         self.record_write(0);
-        self.target.rename(from, to).await
+        self.time_write(self.target.rename(from, to)).await
     }
 
     async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> OSResult<()> {
         let _guard = self.hop_guard();
         self.record_write(0);
-        self.target.rename_if_not_exists(from, to).await
+        self.time_write(self.target.rename_if_not_exists(from, to))
+            .await
     }
 
     async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> OSResult<()> {
         let _guard = self.hop_guard();
         self.record_write(0);
-        self.target.copy_if_not_exists(from, to).await
+        self.time_write(self.target.copy_if_not_exists(from, to))
+            .await
     }
 }
 
@@ -542,17 +1571,26 @@ impl ObjectStore for IoTrackingStore {
 struct IoTrackingMultipartUpload {
     target: Box<dyn MultipartUpload>,
     stats: Arc<Mutex<IoStats>>,
-    _guard: HopGuard,
+    time_writes: bool,
+    _guard: Option<HopGuard>,
 }
 
 #[async_trait::async_trait]
 impl MultipartUpload for IoTrackingMultipartUpload {
     async fn abort(&mut self) -> OSResult<()> {
-        self.target.abort().await
+        let result = self.target.abort().await;
+        if result.is_ok() {
+            self.stats.lock().unwrap().multipart_aborted += 1;
+        }
+        result
     }
 
     async fn complete(&mut self) -> OSResult<PutResult> {
-        self.target.complete().await
+        let result = self.target.complete().await;
+        if result.is_ok() {
+            self.stats.lock().unwrap().multipart_completed += 1;
+        }
+        result
     }
 
     fn put_part(&mut self, payload: PutPayload) -> UploadPart {
@@ -560,8 +1598,193 @@ impl MultipartUpload for IoTrackingMultipartUpload {
             let mut stats = self.stats.lock().unwrap();
             stats.write_iops += 1;
             stats.write_bytes += payload.content_length() as u64;
+            stats.multipart_parts += 1;
+        }
+        let part = self.target.put_part(payload);
+        if !self.time_writes {
+            return part;
         }
-        self.target.put_part(payload)
+        let stats = self.stats.clone();
+        let start = Instant::now();
+        async move {
+            let result = part.await;
+            let elapsed = start.elapsed();
+            let mut stats = stats.lock().unwrap();
+            stats.total_write_latency += elapsed;
+            stats.multipart_part_latency += elapsed;
+            result
+        }
+        .boxed()
+    }
+}
+
+// Read the whole payload of a get so it can be recorded, the returned result
+// streams the buffered bytes.
+async fn buffer_get_result(result: GetResult) -> OSResult<(GetResult, Bytes)> {
+    let meta = result.meta.clone();
+    let range = result.range.clone();
+    let attributes = result.attributes.clone();
+    let data = result.bytes().await?;
+    let result = GetResult {
+        payload: GetResultPayload::Stream(stream::once(future::ready(Ok(data.clone()))).boxed()),
+        meta,
+        range,
+        attributes,
+    };
+    Ok((result, data))
+}
+
+/// Serves reads from a trace written by [`record_io_trace`].
+///
+/// Reads are answered from the recorded bytes and never reach the wrapped
+/// store.  A read that is not covered by a single recorded read fails.  All
+/// other requests (`head`, `list`, writes...) are passed to the wrapped store.
+#[derive(Debug, Clone)]
+pub struct ReplayStore {
+    reads: Arc<HashMap<Path, Vec<(Range<u64>, Bytes)>>>,
+}
+
+impl ReplayStore {
+    /// Load a trace written by [`record_io_trace`]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut reads = HashMap::<Path, Vec<(Range<u64>, Bytes)>>::new();
+        loop {
+            let location_len = match reader.read_u32::<LittleEndian>() {
+                Ok(len) => len as usize,
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            };
+            let mut location = vec![0; location_len];
+            reader.read_exact(&mut location)?;
+            let location = String::from_utf8(location)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+            let start = reader.read_u64::<LittleEndian>()?;
+            let end = reader.read_u64::<LittleEndian>()?;
+            let mut data = vec![0; (end - start) as usize];
+            reader.read_exact(&mut data)?;
+            reads
+                .entry(Path::from(location))
+                .or_default()
+                .push((start..end, Bytes::from(data)));
+        }
+        Ok(Self {
+            reads: Arc::new(reads),
+        })
+    }
+}
+
+impl WrappingObjectStore for ReplayStore {
+    fn wrap(&self, target: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(ReplayObjectStore {
+            target,
+            reads: self.reads.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ReplayObjectStore {
+    target: Arc<dyn ObjectStore>,
+    reads: Arc<HashMap<Path, Vec<(Range<u64>, Bytes)>>>,
+}
+
+impl Display for ReplayObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReplayObjectStore({})", self.target)
+    }
+}
+
+impl ReplayObjectStore {
+    fn replay(&self, location: &Path, range: Option<Range<u64>>) -> OSResult<GetResult> {
+        let reads = self
+            .reads
+            .get(location)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        // The size of the object is not recorded, the furthest read is the best guess
+        let size = reads.iter().map(|(range, _)| range.end).max().unwrap_or(0);
+        let range = range.unwrap_or(0..size);
+        let Some((recorded, data)) = reads
+            .iter()
+            .find(|(recorded, _)| recorded.start <= range.start && range.end <= recorded.end)
+        else {
+            return Err(object_store::Error::Generic {
+                store: "ReplayStore",
+                source: format!("No recorded read of {:?} from {}", range, location).into(),
+            });
+        };
+        let data = data
+            .slice((range.start - recorded.start) as usize..(range.end - recorded.start) as usize);
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream::once(future::ready(Ok(data))).boxed()),
+            meta: ObjectMeta {
+                location: location.clone(),
+                last_modified: Default::default(),
+                size,
+                e_tag: None,
+                version: None,
+            },
+            range,
+            attributes: Default::default(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for ReplayObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        bytes: PutPayload,
+        opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        self.target.put_opts(location, bytes, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        self.target.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        let range = match options.range {
+            None => None,
+            Some(GetRange::Bounded(range)) => Some(range),
+            Some(range) => {
+                return Err(object_store::Error::NotSupported {
+                    source: format!("ReplayStore cannot replay a read of {:?}", range).into(),
+                })
+            }
+        };
+        self.replay(location, range)
+    }
+
+    async fn head(&self, location: &Path) -> OSResult<ObjectMeta> {
+        self.target.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> OSResult<()> {
+        self.target.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.target.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        self.target.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> OSResult<()> {
+        self.target.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> OSResult<()> {
+        self.target.copy_if_not_exists(from, to).await
     }
 }
 
@@ -573,7 +1796,11 @@ struct HopGuard {
 
 impl HopGuard {
     fn new(active_requests: Arc<AtomicU16>, stats: Arc<Mutex<IoStats>>) -> Self {
-        active_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        {
+            // Hold the stats lock so StatsHolder::reset_quiescent sees a consistent count
+            let _stats = stats.lock().unwrap();
+            active_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
         Self {
             active_requests,
             stats,
@@ -583,17 +1810,45 @@ impl HopGuard {
 
 impl Drop for HopGuard {
     fn drop(&mut self) {
+        let mut stats = self.stats.lock().unwrap();
         if self
             .active_requests
             .fetch_sub(1, std::sync::atomic::Ordering::SeqCst)
             == 1
         {
-            let mut stats = self.stats.lock().unwrap();
             stats.num_hops += 1;
         }
     }
 }
 
+/// A [`Clock`] that only moves when told to, see [`crate::dataset::builder::DatasetBuilder::with_clock`]
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, delta: TimeDelta) {
+        *self.now.lock().unwrap() += delta;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
 pub struct FragmentCount(pub u32);
 
 impl From<u32> for FragmentCount {
@@ -610,6 +1865,29 @@ impl From<u32> for FragmentRowCount {
     }
 }
 
+/// How the vectors made by [`gen_vectors`] are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorDistribution {
+    /// Each value is sampled independently
+    Random,
+    /// Vectors are random but scaled to unit length (e.g. for cosine / dot distance)
+    Normalized,
+}
+
+/// Create a generator for a typical vector search dataset
+///
+/// The data has an `id` column (Int64, counting up from 0) and a `vector` column
+/// (`FixedSizeList<Float32, dim>`).  Use [`DatagenExt`] to turn it into a dataset.
+pub fn gen_vectors(dim: u32, distribution: VectorDistribution) -> BatchGeneratorBuilder {
+    let vectors = match distribution {
+        VectorDistribution::Random => array::rand_vec::<Float32Type>(Dimension::from(dim)),
+        VectorDistribution::Normalized => array::rand_unit_vec(Dimension::from(dim)),
+    };
+    lance_datagen::gen()
+        .col("id", array::step::<Int64Type>())
+        .col("vector", vectors)
+}
+
 #[async_trait::async_trait]
 pub trait DatagenExt {
     async fn into_dataset(
@@ -666,6 +1944,39 @@ pub trait DatagenExt {
         self.into_dataset("memory://", frag_count, rows_per_fragment)
             .await
     }
+
+    /// Like [`Self::into_ram_dataset`] but the dataset's object store is wrapped with an
+    /// [`IoTrackingStore`] so the IO of later operations can be asserted on.
+    ///
+    /// The writes (and reads) made while creating the dataset are also recorded in the
+    /// returned stats.  Reset them (e.g. with `std::mem::take`) before the operation
+    /// being measured.
+    async fn into_tracked_ram_dataset(
+        self,
+        frag_count: FragmentCount,
+        rows_per_fragment: FragmentRowCount,
+    ) -> crate::Result<(Dataset, Arc<Mutex<IoStats>>)>
+    where
+        Self: Sized,
+    {
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let rows_per_fragment_val = rows_per_fragment.0;
+        let dataset = self
+            .into_ram_dataset_with_params(
+                frag_count,
+                rows_per_fragment,
+                Some(WriteParams {
+                    max_rows_per_file: rows_per_fragment_val as usize,
+                    store_params: Some(ObjectStoreParams {
+                        object_store_wrapper: Some(io_stats_wrapper),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        Ok((dataset, io_stats))
+    }
 }
 
 #[async_trait::async_trait]
@@ -805,13 +2116,47 @@ pub async fn assert_plan_node_equals(
     Ok(())
 }
 
+/// Assert that every file read in `stats` belongs to the version of `dataset`.
+///
+/// The paths of the recorded requests are compared with [`Dataset::referenced_paths`]
+/// to catch reads that leak into the files of another version, e.g. through a cache
+/// or a commit handler.  Listings are ignored since they are of directories.  The
+/// stats must be recorded without a `min_record_bytes` threshold or small reads will
+/// not be checked.
+pub async fn assert_reads_within_version(
+    dataset: &Dataset,
+    stats: &IoStats,
+) -> lance_core::Result<()> {
+    let referenced = dataset.referenced_paths().await?;
+    let mut unexpected = stats
+        .requests
+        .iter()
+        .filter(|request| !request.method.starts_with("list"))
+        .filter(|request| referenced.binary_search(&request.path).is_err())
+        .map(|request| request.path.to_string())
+        .collect::<Vec<_>>();
+    unexpected.sort();
+    unexpected.dedup();
+    if !unexpected.is_empty() {
+        panic!(
+            "Expected only the files of version {} to be read but these were also read: {:?}",
+            dataset.version().version,
+            unexpected
+        );
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use super::*;
+    use arrow_array::cast::AsArray;
     use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int32Array, StringArray, StructArray};
     use arrow_schema::{DataType, Field as ArrowField, Fields as ArrowFields};
+    use futures::FutureExt;
+    use lance_core::ROW_ID;
     use rstest::rstest;
 
     #[rstest]
@@ -859,6 +2204,584 @@ mod tests {
         assert!(num_holes > 0, "Expected at least one hole in the field ids");
     }
 
+    #[tokio::test]
+    async fn test_io_tracking_min_record_bytes() {
+        let (wrapper, stats) =
+            IoTrackingStore::new_wrapper_with(StatsHolder::default().with_min_record_bytes(50));
+        let store = wrapper.wrap(Arc::new(object_store::memory::InMemory::new()));
+        let path = Path::from("data");
+        store
+            .put(&path, PutPayload::from(vec![0_u8; 100]))
+            .await
+            .unwrap();
+
+        store.get_range(&path, 0..10).await.unwrap();
+        store.get_range(&path, 0..100).await.unwrap();
+        store.head(&path).await.unwrap();
+
+        let stats = stats.lock().unwrap();
+        assert_eq!(stats.read_iops, 2);
+        assert_eq!(stats.read_bytes, 110);
+        assert_eq!(stats.metadata_iops, 1);
+        assert_eq!(stats.write_iops, 1);
+        assert_eq!(stats.requests.len(), 1);
+        assert_eq!(stats.requests[0].range, Some(0..100));
+    }
+
+    #[tokio::test]
+    async fn test_io_tracking_record_capacity() {
+        // The options compose, the capacity also bounds the object metadata
+        let holder = StatsHolder::default()
+            .with_record_capacity(3)
+            .with_object_meta();
+        let store = holder.wrap(Arc::new(object_store::memory::InMemory::new()));
+        let path = Path::from("data");
+        store
+            .put(&path, PutPayload::from(vec![0_u8; 100]))
+            .await
+            .unwrap();
+        for _ in 0..5 {
+            store.head(&path).await.unwrap();
+        }
+        let stats = holder.incremental_stats();
+        assert_eq!(stats.metadata_iops, 5);
+        assert_eq!(stats.requests.len(), 3);
+        assert_eq!(stats.object_meta.len(), 3);
+
+        for i in 0..10 {
+            store.get_range(&path, i..(i + 1)).await.unwrap();
+        }
+
+        let stats = holder.incremental_stats();
+        assert_eq!(stats.read_iops, 10);
+        assert_eq!(stats.read_bytes, 10);
+        assert_eq!(stats.recorded_requests, 10);
+        // Only the last 3 requests are kept
+        let ranges = stats
+            .requests
+            .iter()
+            .map(|request| request.range.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![7..8, 8..9, 9..10]);
+    }
+
+    #[tokio::test]
+    async fn test_io_tracking_object_meta() {
+        let path = Path::from("data");
+        let target = Arc::new(object_store::memory::InMemory::new());
+
+        // Not recorded by default
+        let holder = StatsHolder::default();
+        let store = holder.wrap(target.clone());
+        store
+            .put(&path, PutPayload::from(vec![0_u8; 100]))
+            .await
+            .unwrap();
+        store.head(&path).await.unwrap();
+        assert!(holder.incremental_stats().object_meta.is_empty());
+
+        let holder = StatsHolder::default().with_object_meta();
+        let store = holder.wrap(target);
+        let head = store.head(&path).await.unwrap();
+        store.list(None).collect::<Vec<_>>().await;
+        store.list_with_delimiter(None).await.unwrap();
+        store.get_opts(&path, GetOptions::default()).await.unwrap();
+        let stats = holder.incremental_stats();
+        let methods = stats
+            .object_meta
+            .iter()
+            .map(|record| record.method)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            methods,
+            vec!["head", "list", "list_with_delimiter", "get_opts"]
+        );
+        assert!(stats
+            .object_meta
+            .iter()
+            .all(|record| record.meta.size == 100 && record.meta.e_tag == head.e_tag));
+
+        // Rewriting the object changes the etag
+        store
+            .put(&path, PutPayload::from(vec![1_u8; 10]))
+            .await
+            .unwrap();
+        store.head(&path).await.unwrap();
+        let stats = holder.incremental_stats();
+        let latest = stats.latest_object_meta(&path).unwrap();
+        assert_eq!(latest.size, 10);
+        assert_ne!(latest.e_tag, head.e_tag);
+        assert!(stats.latest_object_meta(&Path::from("other")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reset_quiescent() {
+        let throttle = ThrottledStoreWrapper {
+            config: object_store::throttle::ThrottleConfig {
+                wait_get_per_call: std::time::Duration::from_millis(50),
+                ..Default::default()
+            },
+        };
+        let holder = StatsHolder::default();
+        let store = holder.wrap(throttle.wrap(Arc::new(object_store::memory::InMemory::new())));
+        let path = Path::from("data");
+        store
+            .put(&path, PutPayload::from(vec![0_u8; 100]))
+            .await
+            .unwrap();
+        holder.incremental_stats();
+
+        let read = {
+            let store = store.clone();
+            let path = path.clone();
+            tokio::spawn(async move { store.get_range(&path, 0..10).await.unwrap() })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        // The in-flight read, and its hop, are counted in this phase
+        let stats = holder.reset_quiescent().await;
+        assert_eq!(stats.read_iops, 1);
+        assert_eq!(stats.num_hops, 1);
+        read.await.unwrap();
+
+        let stats = holder.incremental_stats();
+        assert_eq!(stats.read_iops, 0);
+        assert_eq!(stats.num_hops, 0);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "still in flight")]
+    async fn test_reset_quiescent_timeout() {
+        let holder = StatsHolder::default();
+        let store = holder.wrap(Arc::new(object_store::memory::InMemory::new()));
+        // An open multipart upload keeps the store busy until it is completed or aborted
+        let _upload = store.put_multipart(&Path::from("data")).await.unwrap();
+        holder
+            .reset_quiescent_with_timeout(Duration::from_millis(10))
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_without_hop_tracking() {
+        let holder = StatsHolder::default().without_hop_tracking();
+        let store = holder.wrap(Arc::new(object_store::memory::InMemory::new()));
+        let path = Path::from("data");
+        store
+            .put(&path, PutPayload::from(vec![0_u8; 100]))
+            .await
+            .unwrap();
+        store.get_range(&path, 0..10).await.unwrap();
+        store.head(&path).await.unwrap();
+
+        let stats = holder.reset_quiescent().await;
+        assert_eq!(stats.write_iops, 1);
+        assert_eq!(stats.read_iops, 1);
+        assert_eq!(stats.read_bytes, 10);
+        assert_eq!(stats.metadata_iops, 1);
+        assert_eq!(stats.num_hops, 0);
+    }
+
+    #[tokio::test]
+    async fn test_merged_stats() {
+        let holders = [StatsHolder::default(), StatsHolder::default()];
+        let data = Path::from("data");
+        for (i, holder) in holders.iter().enumerate() {
+            let store = holder.wrap(Arc::new(object_store::memory::InMemory::new()));
+            store
+                .put(&data, PutPayload::from(vec![0_u8; 100]))
+                .await
+                .unwrap();
+            for _ in 0..=i {
+                store.get_range(&data, 0..10).await.unwrap();
+            }
+            store.head(&data).await.unwrap();
+        }
+
+        let holder_clone = holders[0].clone();
+        let stats = StatsHolder::merged(&[&holders[0], &holders[1], &holder_clone]);
+        assert_eq!(stats.write_iops, 2);
+        assert_eq!(stats.write_bytes, 200);
+        assert_eq!(stats.read_iops, 3);
+        assert_eq!(stats.read_bytes, 30);
+        assert_eq!(stats.metadata_iops, 2);
+        // Every sequential request is its own hop in each store
+        assert_eq!(stats.num_hops, 7);
+        assert_eq!(stats.requests.len(), 5);
+
+        // Merging takes the stats from each holder
+        let stats = StatsHolder::merged(&[&holders[0], &holders[1]]);
+        assert_eq!(stats.read_iops, 0);
+        assert!(stats.requests.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_io_tracking_multipart() {
+        let (wrapper, stats) = IoTrackingStore::new_wrapper();
+        let store = wrapper.wrap(Arc::new(object_store::memory::InMemory::new()));
+
+        let mut upload = store.put_multipart(&Path::from("complete")).await.unwrap();
+        for _ in 0..3 {
+            upload
+                .put_part(PutPayload::from(vec![0_u8; 10]))
+                .await
+                .unwrap();
+        }
+        upload.complete().await.unwrap();
+
+        let mut upload = store.put_multipart(&Path::from("abort")).await.unwrap();
+        upload
+            .put_part(PutPayload::from(vec![0_u8; 10]))
+            .await
+            .unwrap();
+        upload.abort().await.unwrap();
+
+        let stats = stats.lock().unwrap();
+        assert_eq!(stats.multipart_uploads, 2);
+        assert_eq!(stats.multipart_parts, 4);
+        assert_eq!(stats.multipart_completed, 1);
+        assert_eq!(stats.multipart_aborted, 1);
+        assert_eq!(stats.write_iops, 4);
+        assert_eq!(stats.write_bytes, 40);
+        assert!(stats.to_string().contains("multipart_parts: 4"));
+    }
+
+    #[tokio::test]
+    async fn test_write_amplification() {
+        let (wrapper, stats) = IoTrackingStore::new_wrapper();
+        let store = wrapper.wrap(Arc::new(object_store::memory::InMemory::new()));
+        for path in ["a", "b"] {
+            store
+                .put(&Path::from(path), PutPayload::from(vec![0_u8; 100]))
+                .await
+                .unwrap();
+        }
+        let stats = std::mem::take(&mut *stats.lock().unwrap());
+        assert_eq!(stats.write_amplification(100), Some(2.0));
+        assert_eq!(stats.write_amplification(200), Some(1.0));
+        assert_eq!(stats.write_amplification(0), None);
+
+        // Writing a dataset only adds a small amount of metadata to the data
+        let num_rows = 100_000;
+        let (_, stats) = lance_datagen::gen()
+            .col("x", lance_datagen::array::step::<Int64Type>())
+            .into_tracked_ram_dataset(FragmentCount::from(1), FragmentRowCount::from(num_rows))
+            .await
+            .unwrap();
+        let amplification = stats
+            .lock()
+            .unwrap()
+            .write_amplification(num_rows as u64 * 8)
+            .unwrap();
+        assert!(
+            (1.0..1.1).contains(&amplification),
+            "write amplification was {}",
+            amplification
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_timing() {
+        let put_delay = Duration::from_millis(20);
+        let throttle = ThrottledStoreWrapper {
+            config: object_store::throttle::ThrottleConfig {
+                wait_put_per_call: put_delay,
+                ..Default::default()
+            },
+        };
+        let inner = throttle.wrap(Arc::new(object_store::memory::InMemory::new()));
+
+        // Timing is off by default
+        let (wrapper, stats) = IoTrackingStore::new_wrapper();
+        let store = wrapper.wrap(inner.clone());
+        store
+            .put(&Path::from("untimed"), PutPayload::from(vec![0_u8; 100]))
+            .await
+            .unwrap();
+        assert!(stats.lock().unwrap().total_write_latency.is_zero());
+        assert!(stats.lock().unwrap().write_throughput_mbps().is_none());
+
+        let (wrapper, stats) =
+            IoTrackingStore::new_wrapper_with(StatsHolder::default().with_write_timing());
+        let store = wrapper.wrap(inner);
+        store
+            .put(&Path::from("data"), PutPayload::from(vec![0_u8; 100]))
+            .await
+            .unwrap();
+        let mut upload = store.put_multipart(&Path::from("multi")).await.unwrap();
+        for _ in 0..2 {
+            upload
+                .put_part(PutPayload::from(vec![0_u8; 10]))
+                .await
+                .unwrap();
+        }
+        upload.complete().await.unwrap();
+        store
+            .copy(&Path::from("data"), &Path::from("copy"))
+            .await
+            .unwrap();
+
+        let stats = stats.lock().unwrap();
+        assert_eq!(stats.write_bytes, 120);
+        assert!(stats.multipart_part_latency >= 2 * put_delay);
+        assert!(stats.total_write_latency >= stats.multipart_part_latency + put_delay);
+        assert!(stats.write_throughput_mbps().unwrap() > 0.0);
+        let display = stats.to_string();
+        assert!(display.contains("total_write_latency"), "{}", display);
+        assert!(display.contains("write throughput:"), "{}", display);
+    }
+
+    #[tokio::test]
+    async fn test_into_tracked_ram_dataset() {
+        let (dataset, io_stats) = lance_datagen::gen()
+            .col(
+                "ints",
+                lance_datagen::array::step::<arrow_array::types::Int32Type>(),
+            )
+            .into_tracked_ram_dataset(FragmentCount::from(2), FragmentRowCount::from(50))
+            .await
+            .unwrap();
+        assert_eq!(dataset.get_fragments().len(), 2);
+
+        // Creating the dataset is recorded too
+        let write_stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert!(write_stats.write_iops > 0);
+
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 100);
+        let scan_stats = io_stats.lock().unwrap();
+        assert!(scan_stats.read_iops > 0);
+        assert_eq!(scan_stats.write_iops, 0);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_corrupt_buffer(#[values(false, true)] use_memory: bool) {
+        let test_dir = tempdir().unwrap();
+        let uri = if use_memory {
+            "memory://corrupt".to_string()
+        } else {
+            test_dir.path().to_str().unwrap().to_string()
+        };
+        let dataset = lance_datagen::gen()
+            .col(
+                "ints",
+                lance_datagen::array::step::<arrow_array::types::Int32Type>(),
+            )
+            .into_dataset_with_params(
+                &uri,
+                FragmentCount::from(2),
+                FragmentRowCount::from(50),
+                Some(WriteParams {
+                    max_rows_per_file: 50,
+                    data_storage_version: Some(LanceFileVersion::V2_0),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        // The second value of the second fragment, 51, becomes !51
+        corrupt_buffer(&dataset, 1, "ints", 0, 4..8).await;
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        let ints = batch["ints"].as_primitive::<arrow_array::types::Int32Type>();
+        let expected = (0..100)
+            .map(|i| if i == 51 { !51 } else { i })
+            .collect::<Vec<_>>();
+        assert_eq!(ints.values().to_vec(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_io_stats_by_fragment() {
+        let (dataset, io_stats) = lance_datagen::gen()
+            .col(
+                "ints",
+                lance_datagen::array::step::<arrow_array::types::Int32Type>(),
+            )
+            .into_tracked_ram_dataset(FragmentCount::from(3), FragmentRowCount::from(50))
+            .await
+            .unwrap();
+        *io_stats.lock().unwrap() = Default::default();
+
+        dataset.scan().try_into_batch().await.unwrap();
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        let by_fragment = stats.by_fragment(&dataset);
+        assert_eq!(by_fragment.len(), 3);
+        for fragment_stats in by_fragment.values() {
+            assert!(fragment_stats.read_iops > 0);
+            assert!(fragment_stats.read_bytes > 0);
+        }
+        assert!(by_fragment.values().map(|s| s.read_bytes).sum::<u64>() <= stats.read_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_assert_reads_within_version() {
+        let (mut dataset, io_stats) = lance_datagen::gen()
+            .col(
+                "ints",
+                lance_datagen::array::step::<arrow_array::types::Int32Type>(),
+            )
+            .into_tracked_ram_dataset(FragmentCount::from(2), FragmentRowCount::from(50))
+            .await
+            .unwrap();
+        let batches = lance_datagen::gen()
+            .col(
+                "ints",
+                lance_datagen::array::step::<arrow_array::types::Int32Type>(),
+            )
+            .into_reader_rows(RowCount::from(50), BatchCount::from(1));
+        dataset.append(batches, None).await.unwrap();
+        let version_one = dataset.checkout_version(1).await.unwrap();
+
+        std::mem::take(&mut *io_stats.lock().unwrap());
+        let batch = version_one.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 100);
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert_reads_within_version(&version_one, &stats)
+            .await
+            .unwrap();
+
+        // Reading the appended fragment is not part of version one
+        std::mem::take(&mut *io_stats.lock().unwrap());
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 150);
+        let stats = std::mem::take(&mut *io_stats.lock().unwrap());
+        assert_reads_within_version(&dataset, &stats).await.unwrap();
+        let result =
+            std::panic::AssertUnwindSafe(assert_reads_within_version(&version_one, &stats))
+                .catch_unwind()
+                .await;
+        let message = result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("version 1"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_io_trace() {
+        let (wrapper, stats) =
+            IoTrackingStore::new_wrapper_with(StatsHolder::default().with_recorded_data());
+        let store = wrapper.wrap(Arc::new(object_store::memory::InMemory::new()));
+        let path = Path::from("data");
+        let data = (0..100_u8).collect::<Vec<_>>();
+        store
+            .put(&path, PutPayload::from(data.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(store.get_range(&path, 10..20).await.unwrap(), data[10..20]);
+        let ranges = store.get_ranges(&path, &[0..5, 50..60]).await.unwrap();
+        assert_eq!(ranges[1], data[50..60]);
+        assert_eq!(store.get(&path).await.unwrap().bytes().await.unwrap(), data);
+        assert_eq!(stats.lock().unwrap().read_iops, 3);
+
+        let trace_dir = tempdir().unwrap();
+        let trace_path = trace_dir.path().join("trace");
+        record_io_trace(&stats.lock().unwrap(), &trace_path).unwrap();
+
+        // Replay against an empty store
+        let replay = ReplayStore::load(&trace_path)
+            .unwrap()
+            .wrap(Arc::new(object_store::memory::InMemory::new()));
+        assert_eq!(replay.get_range(&path, 12..15).await.unwrap(), data[12..15]);
+        assert_eq!(replay.get_range(&path, 50..60).await.unwrap(), data[50..60]);
+        let result = replay.get(&path).await.unwrap();
+        assert_eq!(result.meta.size, 100);
+        assert_eq!(result.bytes().await.unwrap(), data);
+
+        assert!(replay.get_range(&Path::from("other"), 0..10).await.is_err());
+    }
+
+    #[test]
+    fn test_field_id_mapping() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new(
+                "b",
+                DataType::Struct(
+                    vec![
+                        ArrowField::new("f1", DataType::Utf8, true),
+                        ArrowField::new("f2", DataType::Boolean, false),
+                    ]
+                    .into(),
+                ),
+                true,
+            ),
+            ArrowField::new("c", DataType::Float64, false),
+        ]));
+        let data = vec![RecordBatch::new_empty(arrow_schema)];
+        let path = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<FieldPath>();
+        let mapping = HashMap::from([
+            (path(&["a"]), 5),
+            (path(&["b"]), 0),
+            (path(&["b", "f1"]), 2),
+            (path(&["b", "f2"]), 1),
+            (path(&["c"]), 3),
+        ]);
+
+        let generator = TestDatasetGenerator::new(data.clone(), LanceFileVersion::Stable)
+            .with_field_id_mapping(mapping.clone())
+            .unwrap();
+        let schema = generator.make_schema(&mut rand::thread_rng());
+        let field_ids = schema.fields_pre_order().map(|f| f.id).collect::<Vec<_>>();
+        assert_eq!(field_ids, vec![5, 0, 2, 1, 3]);
+
+        let mut missing = mapping.clone();
+        missing.remove(&path(&["b", "f1"]));
+        let err = TestDatasetGenerator::new(data.clone(), LanceFileVersion::Stable)
+            .with_field_id_mapping(missing)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("missing the field"), "{}", err);
+
+        let mut duplicate = mapping.clone();
+        duplicate.insert(path(&["c"]), 5);
+        let err = TestDatasetGenerator::new(data.clone(), LanceFileVersion::Stable)
+            .with_field_id_mapping(duplicate)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("more than once"), "{}", err);
+
+        let sorted = HashMap::from([
+            (path(&["a"]), 0),
+            (path(&["b"]), 1),
+            (path(&["b", "f1"]), 2),
+            (path(&["b", "f2"]), 4),
+            (path(&["c"]), 5),
+        ]);
+        let err = TestDatasetGenerator::new(data, LanceFileVersion::Stable)
+            .with_field_id_mapping(sorted)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not hostile"), "{}", err);
+    }
+
+    #[rstest]
+    #[test]
+    fn test_make_schema_small(#[values(1, 2, 3)] num_fields: usize) {
+        let arrow_schema = Arc::new(ArrowSchema::new(
+            (0..num_fields)
+                .map(|i| ArrowField::new(format!("f{}", i), DataType::Int32, false))
+                .collect::<Vec<_>>(),
+        ));
+        let data = vec![RecordBatch::new_empty(arrow_schema)];
+        let generator = TestDatasetGenerator::new(data, LanceFileVersion::Stable);
+
+        for seed in 0..20 {
+            let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+            let schema = generator.make_schema(&mut rng);
+            let field_ids = schema.fields_pre_order().map(|f| f.id).collect::<Vec<_>>();
+            assert_eq!(field_ids.len(), num_fields);
+            assert_eq!(check_field_ids(&field_ids), None, "{:?}", field_ids);
+            if num_fields >= 2 {
+                let mut sorted_ids = field_ids.clone();
+                sorted_ids.sort_unstable();
+                assert!(
+                    sorted_ids.windows(2).any(|w| w[1] - w[0] > 1),
+                    "Expected a hole in {:?}",
+                    field_ids
+                );
+            }
+        }
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_make_fragment(
@@ -994,7 +2917,274 @@ mod tests {
                 if num_cols > 1 {
                     assert_ne!(field_structure[0], field_structure[1]);
                 }
+                assert_eq!(TestDatasetGenerator::why_not(&dataset), None);
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_make_hostile_with_store_params() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new("b", DataType::Int32, false),
+        ]));
+        let data = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+            ],
+        )
+        .unwrap();
+
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let dataset = TestDatasetGenerator::new(vec![data], LanceFileVersion::Stable)
+            .with_store_params(ObjectStoreParams {
+                object_store_wrapper: Some(io_stats_wrapper),
+                ..Default::default()
+            })
+            .make_hostile("memory://hostile")
+            .await;
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 3);
+
+        // Every data file and the manifest were written through the wrapper
+        let num_files = dataset.get_fragments()[0].metadata().files.len();
+        assert!(num_files > 1);
+        let stats = io_stats.lock().unwrap();
+        assert!(
+            stats.write_iops as usize > num_files,
+            "{} files, {}",
+            num_files,
+            stats
+        );
+    }
+
+    #[tokio::test]
+    async fn test_make_hostile_with_config() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new("b", DataType::Int32, false),
+        ]));
+        let data = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+            ],
+        )
+        .unwrap();
+
+        let empty_value = HashMap::from([("key".to_string(), String::new())]);
+        assert!(
+            TestDatasetGenerator::new(vec![data.clone()], LanceFileVersion::Stable)
+                .with_config(empty_value)
+                .is_err()
+        );
+
+        let config = HashMap::from([
+            ("owner".to_string(), "tests".to_string()),
+            ("retention".to_string(), "7d".to_string()),
+        ]);
+        let mut dataset = TestDatasetGenerator::new(vec![data.clone()], LanceFileVersion::Stable)
+            .with_config(config.clone())
+            .unwrap()
+            .make_hostile("memory://hostile_config")
+            .await;
+        assert_eq!(dataset.config().unwrap(), config);
+
+        // The config is carried over by later operations
+        let batches = RecordBatchIterator::new(vec![Ok(data)], schema);
+        dataset.append(batches, None).await.unwrap();
+        assert_eq!(dataset.version().version, 2);
+        assert_eq!(dataset.config().unwrap(), config);
+        dataset.delete("a = 1").await.unwrap();
+        assert_eq!(dataset.config().unwrap(), config);
+    }
+
+    #[tokio::test]
+    async fn test_make_hostile_with_shuffled_row_ids() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new("b", DataType::Int32, false),
+        ]));
+        let data = (0..4)
+            .map(|i| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from_iter_values(i * 10..(i + 1) * 10)),
+                        Arc::new(Int32Array::from_iter_values(0..10)),
+                    ],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            TestDatasetGenerator::new(data[..1].to_vec(), LanceFileVersion::Stable)
+                .with_shuffled_row_ids(42)
+                .is_err()
+        );
+
+        let row_ids = |dataset: Dataset| async move {
+            let batch = dataset.scan().with_row_id().try_into_batch().await.unwrap();
+            batch[ROW_ID]
+                .as_primitive::<arrow_array::types::UInt64Type>()
+                .values()
+                .to_vec()
+        };
+        let generator = TestDatasetGenerator::new(data, LanceFileVersion::Stable)
+            .with_shuffled_row_ids(42)
+            .unwrap();
+        let dataset = generator.make_hostile("memory://shuffled_row_ids").await;
+        assert!(dataset.manifest.uses_move_stable_row_ids());
+        let ids = row_ids(dataset).await;
+
+        // The ids are unique but not in fragment order
+        let mut sorted = ids.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..40).collect::<Vec<_>>());
+        assert_ne!(ids, sorted);
+
+        // The same seed gives the same row ids
+        let dataset = generator.make_hostile("memory://shuffled_row_ids_2").await;
+        assert_eq!(row_ids(dataset).await, ids);
+    }
+
+    #[tokio::test]
+    async fn test_make_hostile_with_empty_fragment() {
+        let data = (0..2)
+            .map(|_| {
+                lance_datagen::gen()
+                    .col("a", array::step::<Int64Type>())
+                    .col("b", array::rand_utf8(ByteCount::from(8), false))
+                    .col("c", array::rand::<Float32Type>())
+                    .into_batch_rows(RowCount::from(10))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            TestDatasetGenerator::new(data.clone(), LanceFileVersion::Legacy)
+                .with_empty_fragment()
+                .is_err()
+        );
+
+        let dataset = TestDatasetGenerator::new(data, LanceFileVersion::Stable)
+            .with_empty_fragment()
+            .unwrap()
+            .make_hostile("memory://empty_fragment")
+            .await;
+        let fragments = dataset.get_fragments();
+        assert_eq!(fragments.len(), 3);
+        let empty = fragments[2].metadata();
+        assert_eq!(empty.physical_rows, Some(0));
+        assert!(!empty.files.is_empty());
+        assert!(TestDatasetGenerator::is_hostile(&dataset));
+
+        assert_eq!(fragments[2].count_rows(None).await.unwrap(), 0);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 20);
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_wide() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let generator = TestDatasetGenerator::wide(100, 10, LanceFileVersion::Stable);
+        let dataset = generator
+            .make_hostile(tmp_dir.path().to_str().unwrap())
+            .await;
+
+        assert_eq!(dataset.schema().fields.len(), 100);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 10);
+        assert!(TestDatasetGenerator::is_hostile(&dataset));
+    }
+
+    #[tokio::test]
+    async fn test_gen_vectors() {
+        let dataset = gen_vectors(16, VectorDistribution::Normalized)
+            .into_ram_dataset(FragmentCount::from(2), FragmentRowCount::from(50))
+            .await
+            .unwrap();
+        assert_eq!(dataset.get_fragments().len(), 2);
+        let schema = ArrowSchema::from(dataset.schema());
+        assert_eq!(schema.field(0).name(), "id");
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).name(), "vector");
+        assert!(matches!(
+            schema.field(1).data_type(),
+            DataType::FixedSizeList(item, 16) if item.data_type() == &DataType::Float32
+        ));
+
+        let batch = dataset.scan().try_into_batch().await.unwrap();
+        let ids = batch["id"].as_primitive::<Int64Type>();
+        assert_eq!(ids.values().to_vec(), (0..100).collect::<Vec<_>>());
+        let vectors = batch["vector"].as_fixed_size_list();
+        let values = vectors.values().as_primitive::<Float32Type>().values();
+        for vector in values.chunks(16) {
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 0.0001);
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_is_hostile(
+        #[values(LanceFileVersion::Legacy, LanceFileVersion::Stable)]
+        data_storage_version: LanceFileVersion,
+        #[values(1, 3)] num_cols: usize,
+    ) {
+        let tmp_dir = tempfile::tempdir().unwrap();
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new("b", DataType::Int32, false),
+            ArrowField::new("c", DataType::Float64, false),
+        ]));
+        let data = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+                Arc::new(Float64Array::from(vec![1.1, 2.2, 3.3])),
+            ],
+        )
+        .unwrap();
+        let projection = (0..num_cols).collect::<Vec<_>>();
+        let data = data.project(&projection).unwrap();
+
+        // A single fragment generated with a hostile layout
+        let path = tmp_dir.path().join("hostile");
+        let dataset = TestDatasetGenerator::new(vec![data.clone()], data_storage_version)
+            .make_hostile(path.to_str().unwrap())
+            .await;
+        assert!(TestDatasetGenerator::is_hostile(&dataset));
+        if num_cols > 1 {
+            assert!(dataset.get_fragments()[0].metadata().files.len() > 1);
+        }
+
+        // A dataset written normally has sorted field ids and a single file
+        let path = tmp_dir.path().join("plain");
+        let reader = RecordBatchIterator::new(vec![Ok(data.clone())], data.schema());
+        let dataset = Dataset::write(
+            reader,
+            path.to_str().unwrap(),
+            Some(WriteParams {
+                data_storage_version: Some(data_storage_version),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        if num_cols > 1 {
+            assert_eq!(
+                TestDatasetGenerator::why_not(&dataset),
+                Some(HostileViolation::SortedFieldIds)
+            );
+        } else {
+            // A single column is always trivially hostile
+            assert!(TestDatasetGenerator::is_hostile(&dataset));
+        }
+    }
 }